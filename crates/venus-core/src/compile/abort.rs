@@ -0,0 +1,160 @@
+//! Aborting in-progress compilations.
+//!
+//! Builders given a [`CompileAbortHandle`] spawn rustc and cargo through it,
+//! so another thread can kill the build. Each process is started in its own
+//! process group, which lets an abort take down the rustc processes cargo
+//! spawned as well.
+
+use std::io;
+use std::process::{Child, Command, Output, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Thread-safe handle for killing the compiler process a builder is running.
+///
+/// Clones share the same state, so a clone can be kept outside the lock that
+/// guards the builder.
+#[derive(Clone, Default)]
+pub struct CompileAbortHandle {
+    /// Process ID of the running compiler, if any.
+    pid: Arc<Mutex<Option<u32>>>,
+    /// Whether the last compilation was aborted.
+    aborted: Arc<AtomicBool>,
+}
+
+impl CompileAbortHandle {
+    /// Create a handle with no compilation running.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Kill the running compiler process and its children.
+    ///
+    /// Returns `false` if no compilation is in progress.
+    pub fn abort(&self) -> bool {
+        let Ok(guard) = self.pid.lock() else {
+            return false;
+        };
+        let Some(pid) = *guard else {
+            return false;
+        };
+
+        self.aborted.store(true, Ordering::SeqCst);
+        kill_process_group(pid);
+        true
+    }
+
+    /// Whether a compiler process is currently running.
+    pub fn is_compiling(&self) -> bool {
+        self.pid.lock().map(|g| g.is_some()).unwrap_or(false)
+    }
+
+    /// Whether the last compilation was aborted.
+    pub fn is_aborted(&self) -> bool {
+        self.aborted.load(Ordering::SeqCst)
+    }
+
+    /// Check and clear the aborted flag.
+    pub fn take_aborted(&self) -> bool {
+        self.aborted.swap(false, Ordering::SeqCst)
+    }
+
+    /// Spawn a compiler process and record it as the running one.
+    ///
+    /// Call [`finish`](Self::finish) once the process has exited.
+    pub(crate) fn spawn(&self, cmd: &mut Command) -> io::Result<Child> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            cmd.process_group(0);
+        }
+
+        let mut guard = self
+            .pid
+            .lock()
+            .map_err(|_| io::Error::other("compile abort handle poisoned"))?;
+        let child = cmd.spawn()?;
+        *guard = Some(child.id());
+        Ok(child)
+    }
+
+    /// Forget the running compiler process.
+    pub(crate) fn finish(&self) {
+        if let Ok(mut guard) = self.pid.lock() {
+            *guard = None;
+        }
+    }
+
+    /// Run a compiler process to completion, collecting its output.
+    pub(crate) fn output(&self, cmd: &mut Command) -> io::Result<Output> {
+        let child = self.spawn(
+            cmd.stdin(Stdio::null())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped()),
+        )?;
+        let output = child.wait_with_output();
+        self.finish();
+        output
+    }
+}
+
+/// Kill a process group led by `pid`.
+fn kill_process_group(pid: u32) {
+    tracing::info!("Aborting compilation (process {})", pid);
+
+    #[cfg(unix)]
+    {
+        // SAFETY: `kill` has no memory-safety preconditions. The negative pid
+        // addresses the process group started by `spawn`.
+        let result = unsafe { libc::kill(-(pid as i32), libc::SIGKILL) };
+        if result != 0 {
+            tracing::warn!(
+                "Failed to kill compiler process {}: {}",
+                pid,
+                io::Error::last_os_error()
+            );
+        }
+    }
+
+    #[cfg(windows)]
+    {
+        // /T also terminates the processes started by this one
+        let result = Command::new("taskkill")
+            .args(["/F", "/T", "/PID", &pid.to_string()])
+            .output();
+        if let Err(e) = result {
+            tracing::warn!("Failed to kill compiler process {}: {}", pid, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_abort_without_compilation() {
+        let handle = CompileAbortHandle::new();
+        assert!(!handle.abort());
+        assert!(!handle.is_aborted());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_abort_kills_process() {
+        let handle = CompileAbortHandle::new();
+        let mut child = handle
+            .spawn(Command::new("sleep").arg("30"))
+            .expect("Failed to spawn sleep");
+        assert!(handle.is_compiling());
+
+        assert!(handle.clone().abort());
+        let status = child.wait().expect("Failed to wait for sleep");
+        handle.finish();
+
+        assert!(!status.success());
+        assert!(!handle.is_compiling());
+        assert!(handle.take_aborted());
+        assert!(!handle.is_aborted());
+    }
+}
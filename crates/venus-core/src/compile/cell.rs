@@ -10,9 +10,10 @@ use std::path::PathBuf;
 use std::process::Command;
 use std::time::Instant;
 
-use crate::graph::CellInfo;
+use crate::graph::{CellInfo, OutputFormat, unqualified_name};
 
-use super::errors::ErrorMapper;
+use super::abort::CompileAbortHandle;
+use super::errors::{ErrorLevel, ErrorMapper};
 use super::toolchain::ToolchainManager;
 use super::types::{
     CompilationResult, CompiledCell, CompilerConfig, dylib_extension, dylib_prefix,
@@ -28,6 +29,9 @@ pub struct CellCompiler {
 
     /// Path to the universe library (for linking)
     universe_path: Option<PathBuf>,
+
+    /// Handle through which rustc is spawned, so it can be killed
+    abort_handle: Option<CompileAbortHandle>,
 }
 
 impl CellCompiler {
@@ -37,6 +41,7 @@ impl CellCompiler {
             config,
             toolchain,
             universe_path: None,
+            abort_handle: None,
         }
     }
 
@@ -46,9 +51,17 @@ impl CellCompiler {
         self
     }
 
+    /// Run rustc through an abort handle, so the compilation can be killed.
+    ///
+    /// An aborted compilation fails with a "compilation aborted" error.
+    pub fn with_abort_handle(mut self, handle: CompileAbortHandle) -> Self {
+        self.abort_handle = Some(handle);
+        self
+    }
+
     /// Compile a cell to a dynamic library.
     pub fn compile(&self, cell: &CellInfo, deps_hash: u64) -> CompilationResult {
-        let source_hash = self.hash_source(&cell.source_code);
+        let source_hash = self.hash_source(cell);
 
         // Check cache
         if let Some(cached) = self.check_cache(cell, source_hash, deps_hash) {
@@ -62,17 +75,18 @@ impl CellCompiler {
 
         // Compile - include source_hash in dylib name to force reload on changes
         match self.compile_to_dylib(cell, &wrapper_code, source_hash) {
-            Ok(dylib_path) => {
+            Ok((dylib_path, warnings)) => {
                 let compile_time = start.elapsed().as_millis() as u64;
 
                 let compiled = CompiledCell {
                     cell_id: cell.id,
                     name: cell.name.clone(),
                     dylib_path,
-                    entry_symbol: format!("venus_cell_{}", cell.name),
+                    entry_symbol: format!("venus_cell_{}", artifact_stem(&cell.name)),
                     source_hash,
                     deps_hash,
                     compile_time_ms: compile_time,
+                    warnings,
                 };
 
                 // Save to cache
@@ -126,8 +140,8 @@ impl CellCompiler {
     fn generate_ffi_entry(&self, cell: &CellInfo) -> String {
         let mut code = String::new();
 
-        let fn_name = &cell.name;
-        let entry_name = format!("venus_cell_{}", fn_name);
+        let fn_name = unqualified_name(&cell.name);
+        let entry_name = format!("venus_cell_{}", artifact_stem(&cell.name));
 
         // Determine return handling
         let returns_result = cell.return_type.starts_with("Result<");
@@ -137,6 +151,10 @@ impl CellCompiler {
         code.push_str("/// # Safety\n");
         code.push_str("/// This function is called from the Venus runtime.\n");
         code.push_str("#[no_mangle]\n");
+        if cell.name.contains("::") {
+            // The entry point of a cell in an impl block carries the type name
+            code.push_str("#[allow(non_snake_case)]\n");
+        }
         code.push_str(&format!("pub unsafe extern \"C\" fn {}(\n", entry_name));
 
         // Input parameters (serialized)
@@ -218,6 +236,8 @@ impl CellCompiler {
 
         // Wrap cell execution in catch_unwind for panic safety.
         // This prevents user code panics from crashing the Venus server.
+        // The cell has its own copy of std, so it records its panics itself.
+        code.push_str("    venus_universe::__venus_panic::install_hook();\n\n");
         code.push_str("    // Wrap execution in catch_unwind for panic safety\n");
         code.push_str("    let execution_result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {\n");
 
@@ -239,8 +259,33 @@ impl CellCompiler {
             ));
         }
 
-        // Create debug display string (inside catch_unwind)
-        code.push_str("        let display_str = format!(\"{:?}\", result);\n");
+        // Create display string (inside catch_unwind). The output format hint
+        // picks the `Render` representation; by default it is the debug text
+        // followed by a NUL and the HTML when the return type implements `Render`
+        code.push_str("        let display_str = {\n");
+        match cell.output_format {
+            Some(OutputFormat::Text | OutputFormat::Csv) => {
+                code.push_str("            use venus_universe::__venus_render::{TextProbe, NoTextProbe as _, RenderTextProbe as _};\n");
+                code.push_str("            (&TextProbe(&result)).probe_text().unwrap_or_else(|| format!(\"{:?}\", result))\n");
+            }
+            Some(OutputFormat::Json) => {
+                code.push_str("            use venus_universe::__venus_render::{DataProbe, NoDataProbe as _, RenderDataProbe as _};\n");
+                code.push_str("            match (&DataProbe(&result)).probe_data() {\n");
+                code.push_str("                Some(data) => format!(\"{:#}\", data),\n");
+                code.push_str("                None => format!(\"{:?}\", result),\n");
+                code.push_str("            }\n");
+            }
+            _ => {
+                code.push_str("            use venus_universe::__venus_render::{HtmlProbe, NoHtmlProbe as _, RenderHtmlProbe as _};\n");
+                code.push_str("            match (&HtmlProbe(&result)).probe_html() {\n");
+                code.push_str(
+                    "                Some(html) => format!(\"{:?}\\0{}\", result, html),\n",
+                );
+                code.push_str("                None => format!(\"{:?}\", result),\n");
+                code.push_str("            }\n");
+            }
+        }
+        code.push_str("        };\n");
         code.push_str("        let display_bytes = display_str.as_bytes();\n\n");
 
         // Serialize output with rkyv (inside catch_unwind)
@@ -283,7 +328,20 @@ impl CellCompiler {
         code.push_str("            0 // Success\n");
         code.push_str("        }\n");
         code.push_str("        Ok(Err(code)) => code, // Cell error or serialization error\n");
-        code.push_str("        Err(_) => -4, // Panic occurred\n");
+        code.push_str("        Err(_) => {\n");
+        code.push_str("            // Hand the panic message and backtrace to the runtime\n");
+        code.push_str(
+            "            if let Some(report) = venus_universe::__venus_panic::take_report() {\n",
+        );
+        code.push_str("                let report = report.to_bytes();\n");
+        code.push_str("                let len = report.len();\n");
+        code.push_str("                let ptr = report.as_ptr();\n");
+        code.push_str("                std::mem::forget(report);\n");
+        code.push_str("                *out_ptr = ptr as *mut u8;\n");
+        code.push_str("                *out_len = len;\n");
+        code.push_str("            }\n");
+        code.push_str("            -4 // Panic occurred\n");
+        code.push_str("        }\n");
         code.push_str("    }\n");
         code.push_str("}\n");
 
@@ -296,14 +354,15 @@ impl CellCompiler {
         cell: &CellInfo,
         wrapper_code: &str,
         source_hash: u64,
-    ) -> std::result::Result<PathBuf, Vec<super::CompileError>> {
+    ) -> std::result::Result<(PathBuf, Vec<super::CompileError>), Vec<super::CompileError>> {
         let build_dir = self.config.cell_build_dir();
         fs::create_dir_all(&build_dir).map_err(|e| {
             super::CompileError::simple(format!("Failed to create build directory: {}", e))
         })?;
 
         // Write wrapper source
-        let src_file = build_dir.join(format!("{}.rs", cell.name));
+        let stem = artifact_stem(&cell.name);
+        let src_file = build_dir.join(format!("{}.rs", stem));
         fs::write(&src_file, wrapper_code)
             .map_err(|e| super::CompileError::simple(format!("Failed to write source: {}", e)))?;
 
@@ -312,14 +371,14 @@ impl CellCompiler {
         let dylib_name = format!(
             "{}cell_{}_{:x}.{}",
             dylib_prefix(),
-            cell.name,
+            stem,
             source_hash,
             dylib_extension()
         );
         let dylib_path = build_dir.join(&dylib_name);
 
         // Clean up old dylibs for this cell (they accumulate with different hashes)
-        let cell_prefix = format!("{}cell_{}_", dylib_prefix(), cell.name);
+        let cell_prefix = format!("{}cell_{}_", dylib_prefix(), stem);
         if let Ok(entries) = fs::read_dir(&build_dir) {
             for entry in entries.flatten() {
                 let name = entry.file_name();
@@ -348,7 +407,7 @@ impl CellCompiler {
         }
 
         // Optimization level
-        cmd.arg(format!("-Copt-level={}", self.config.opt_level));
+        cmd.arg(format!("-Copt-level={}", self.opt_level_for(cell)));
 
         // Debug info
         if self.config.debug_info {
@@ -435,16 +494,30 @@ impl CellCompiler {
         }
 
         // Run compilation
-        let output = cmd
-            .output()
-            .map_err(|e| super::CompileError::simple(format!("Failed to run rustc: {}", e)))?;
+        let output = match &self.abort_handle {
+            Some(handle) => handle.output(&mut cmd),
+            None => cmd.output(),
+        }
+        .map_err(|e| super::CompileError::simple(format!("Failed to run rustc: {}", e)))?;
+
+        if self.abort_handle.as_ref().is_some_and(|h| h.is_aborted()) {
+            return Err(super::CompileError::simple(
+                "Compilation aborted".to_string(),
+            ));
+        }
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let mapper = ErrorMapper::new(cell.source_file.clone());
 
         if output.status.success() {
-            Ok(dylib_path)
+            let warnings = mapper
+                .parse_rustc_output(&stderr)
+                .into_iter()
+                .filter(|d| d.level == ErrorLevel::Warning)
+                .collect();
+            Ok((dylib_path, warnings))
         } else {
             // Parse errors
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            let mapper = ErrorMapper::new(cell.source_file.clone());
             let errors = mapper.parse_rustc_output(&stderr);
 
             if errors.is_empty() {
@@ -456,13 +529,109 @@ impl CellCompiler {
         }
     }
 
-    /// Hash the source code.
-    fn hash_source(&self, source: &str) -> u64 {
+    /// Remove all build and cache artifacts for a cell.
+    ///
+    /// Deletes the generated wrapper source, every compiled dylib for the
+    /// cell (regardless of source hash) and its cache metadata. Used for
+    /// ephemeral cells that should not leave files behind.
+    pub fn remove_artifacts(&self, cell_name: &str) {
+        let build_dir = self.config.cell_build_dir();
+        let stem = artifact_stem(cell_name);
+        let _ = fs::remove_file(build_dir.join(format!("{}.rs", stem)));
+
+        let cell_prefix = format!("{}cell_{}_", dylib_prefix(), stem);
+        if let Ok(entries) = fs::read_dir(&build_dir) {
+            for entry in entries.flatten() {
+                if entry
+                    .file_name()
+                    .to_string_lossy()
+                    .starts_with(&cell_prefix)
+                {
+                    let _ = fs::remove_file(entry.path());
+                }
+            }
+        }
+
+        let _ = fs::remove_file(self.cache_meta_path(cell_name));
+    }
+
+    /// Remove build and cache artifacts of cells that no longer exist.
+    ///
+    /// Artifacts are matched to cells by name; anything belonging to a cell in
+    /// `live_cell_names`, and any file not recognised as a cell artifact, is
+    /// kept. Returns the number of bytes reclaimed.
+    pub fn prune_artifacts(&self, live_cell_names: &[String]) -> u64 {
+        let live: std::collections::HashSet<String> = live_cell_names
+            .iter()
+            .map(|name| artifact_stem(name))
+            .collect();
+
+        let mut reclaimed = 0;
+        for (dir, hashed) in [
+            (self.config.cell_build_dir(), true),
+            (self.config.cache_dir.join("cells"), false),
+        ] {
+            let Ok(entries) = fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let file_name = entry.file_name();
+                let Some(cell_name) =
+                    artifact_cell_name(&file_name.to_string_lossy(), hashed).map(str::to_string)
+                else {
+                    continue;
+                };
+                if live.contains(&cell_name) {
+                    continue;
+                }
+
+                let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+                if fs::remove_file(entry.path()).is_ok() {
+                    reclaimed += size;
+                }
+            }
+        }
+        reclaimed
+    }
+
+    /// Remove every cached cell build.
+    ///
+    /// Cached builds are keyed by source and dependencies only, so this is
+    /// needed when the compiler configuration changes (e.g. switching between
+    /// Cranelift and LLVM) to avoid reusing artifacts from the old backend.
+    pub fn clear_cache(&self) {
+        let cache_dir = self.config.cache_dir.join("cells");
+        if let Ok(entries) = fs::read_dir(&cache_dir) {
+            for entry in entries.flatten() {
+                let _ = fs::remove_file(entry.path());
+            }
+        }
+    }
+
+    /// Hash a cell's source code together with the codegen settings.
+    ///
+    /// Including the backend and optimization settings gives each build
+    /// configuration its own dylib path, so a worker never reuses a library
+    /// loaded for a different backend. The output format hint is included
+    /// because it changes the generated entry point but is not part of the
+    /// cell's source.
+    fn hash_source(&self, cell: &CellInfo) -> u64 {
         let mut hasher = DefaultHasher::new();
-        source.hash(&mut hasher);
+        cell.source_code.hash(&mut hasher);
+        cell.output_format.hash(&mut hasher);
+        self.config.use_cranelift.hash(&mut hasher);
+        self.opt_level_for(cell).hash(&mut hasher);
+        self.config.debug_info.hash(&mut hasher);
+        self.config.extra_rustc_flags.hash(&mut hasher);
         hasher.finish()
     }
 
+    /// Optimization level for a cell: its `opt_level` override, or the
+    /// configured level.
+    fn opt_level_for(&self, cell: &CellInfo) -> u8 {
+        cell.opt_level.unwrap_or(self.config.opt_level)
+    }
+
     /// Check if a cached compilation exists.
     fn check_cache(
         &self,
@@ -489,10 +658,11 @@ impl CellCompiler {
                     cell_id: cell.id,
                     name: cell.name.clone(),
                     dylib_path: cache_file,
-                    entry_symbol: format!("venus_cell_{}", cell.name),
+                    entry_symbol: format!("venus_cell_{}", artifact_stem(&cell.name)),
                     source_hash,
                     deps_hash,
                     compile_time_ms: 0,
+                    warnings: Vec::new(),
                 });
             }
         }
@@ -521,7 +691,12 @@ impl CellCompiler {
 
     /// Get the cache path for a cell.
     fn cache_path(&self, cell: &CellInfo) -> PathBuf {
-        let filename = format!("{}cell_{}.{}", dylib_prefix(), cell.name, dylib_extension());
+        let filename = format!(
+            "{}cell_{}.{}",
+            dylib_prefix(),
+            artifact_stem(&cell.name),
+            dylib_extension()
+        );
         self.config.cache_dir.join("cells").join(filename)
     }
 
@@ -530,7 +705,42 @@ impl CellCompiler {
         self.config
             .cache_dir
             .join("cells")
-            .join(format!("{}.meta", name))
+            .join(format!("{}.meta", artifact_stem(name)))
+    }
+}
+
+/// A cell name as used in artifact file names and entry symbols.
+///
+/// Cells in an impl block are named `Type::function`, which is neither a
+/// valid identifier nor a portable file name, so `::` becomes `__`.
+fn artifact_stem(cell_name: &str) -> String {
+    cell_name.replace("::", "__")
+}
+
+/// Name of the cell a build or cache artifact belongs to.
+///
+/// Recognises wrapper sources (`<name>.rs`), cache metadata (`<name>.meta`)
+/// and cell dylibs, which carry a source-hash suffix in the build directory
+/// (`hashed`) but not in the cache directory.
+fn artifact_cell_name(file_name: &str, hashed: bool) -> Option<&str> {
+    if let Some(name) = file_name
+        .strip_suffix(".rs")
+        .or_else(|| file_name.strip_suffix(".meta"))
+    {
+        return Some(name);
+    }
+
+    let stem = file_name
+        .strip_prefix(dylib_prefix())?
+        .strip_prefix("cell_")?
+        .strip_suffix(dylib_extension())?
+        .strip_suffix('.')?;
+
+    if hashed {
+        let (name, hash) = stem.rsplit_once('_')?;
+        hash.chars().all(|c| c.is_ascii_hexdigit()).then_some(name)
+    } else {
+        Some(stem)
     }
 }
 
@@ -549,6 +759,10 @@ mod tests {
             doc_comment: None,
             source_code: "pub fn test_cell() -> i32 { 42 }".to_string(),
             source_file: PathBuf::from("test.rs"),
+            timeout_ms: None,
+            opt_level: None,
+            output_format: None,
+            is_async: false,
             span: SourceSpan {
                 start_line: 1,
                 start_col: 0,
@@ -570,6 +784,40 @@ mod tests {
         assert!(wrapper.contains("venus_cell_test_cell"));
         assert!(wrapper.contains("pub fn test_cell() -> i32"));
         assert!(wrapper.contains("#[no_mangle]"));
+        assert!(wrapper.contains("(&HtmlProbe(&result)).probe_html()"));
+    }
+
+    #[test]
+    fn test_generate_wrapper_json_hint() {
+        let config = CompilerConfig::default();
+        let toolchain = ToolchainManager::new().unwrap();
+        let compiler = CellCompiler::new(config, toolchain);
+
+        let cell = CellInfo {
+            output_format: Some(OutputFormat::Json),
+            is_async: false,
+            ..make_test_cell()
+        };
+        let wrapper = compiler.generate_wrapper(&cell);
+
+        assert!(wrapper.contains("(&DataProbe(&result)).probe_data()"));
+        assert!(!wrapper.contains("probe_html"));
+    }
+
+    #[test]
+    fn test_generate_wrapper_for_impl_cell() {
+        let config = CompilerConfig::default();
+        let toolchain = ToolchainManager::new().unwrap();
+        let compiler = CellCompiler::new(config, toolchain);
+
+        let cell = CellInfo {
+            name: "Cells::test_cell".to_string(),
+            ..make_test_cell()
+        };
+        let wrapper = compiler.generate_wrapper(&cell);
+
+        assert!(wrapper.contains("fn venus_cell_Cells__test_cell("));
+        assert!(wrapper.contains("let result = test_cell();"));
     }
 
     #[test]
@@ -592,6 +840,10 @@ mod tests {
             doc_comment: None,
             source_code: "pub fn process(config: &Config) -> Output { todo!() }".to_string(),
             source_file: PathBuf::from("test.rs"),
+            timeout_ms: None,
+            opt_level: None,
+            output_format: None,
+            is_async: false,
             span: SourceSpan {
                 start_line: 5,
                 start_col: 0,
@@ -607,17 +859,166 @@ mod tests {
         assert!(wrapper.contains("rkyv::access"));
     }
 
+    #[test]
+    fn test_remove_artifacts() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let config = CompilerConfig {
+            build_dir: temp.path().join("build"),
+            cache_dir: temp.path().join("cache"),
+            ..CompilerConfig::default()
+        };
+        let toolchain = ToolchainManager::new().unwrap();
+        let compiler = CellCompiler::new(config.clone(), toolchain);
+
+        let build_dir = config.cell_build_dir();
+        fs::create_dir_all(&build_dir).unwrap();
+        fs::create_dir_all(config.cache_dir.join("cells")).unwrap();
+
+        let dylib = format!("{}cell_scratch_1f.{}", dylib_prefix(), dylib_extension());
+        let other = format!("{}cell_other_1f.{}", dylib_prefix(), dylib_extension());
+        fs::write(build_dir.join("scratch.rs"), "").unwrap();
+        fs::write(build_dir.join(&dylib), "").unwrap();
+        fs::write(build_dir.join(&other), "").unwrap();
+        fs::write(config.cache_dir.join("cells").join("scratch.meta"), "").unwrap();
+
+        compiler.remove_artifacts("scratch");
+
+        assert!(!build_dir.join("scratch.rs").exists());
+        assert!(!build_dir.join(&dylib).exists());
+        assert!(!config.cache_dir.join("cells").join("scratch.meta").exists());
+        assert!(build_dir.join(&other).exists());
+    }
+
+    #[test]
+    fn test_prune_artifacts_keeps_live_cells() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let config = CompilerConfig {
+            build_dir: temp.path().join("build"),
+            cache_dir: temp.path().join("cache"),
+            ..CompilerConfig::default()
+        };
+        let toolchain = ToolchainManager::new().unwrap();
+        let compiler = CellCompiler::new(config.clone(), toolchain);
+
+        let build_dir = config.cell_build_dir();
+        let cells_dir = config.cache_dir.join("cells");
+        fs::create_dir_all(&build_dir).unwrap();
+        fs::create_dir_all(&cells_dir).unwrap();
+
+        let dylib = |name: &str| format!("{}cell_{}.{}", dylib_prefix(), name, dylib_extension());
+        let hashed =
+            |name: &str| format!("{}cell_{}_1f.{}", dylib_prefix(), name, dylib_extension());
+        for name in ["live_cell", "old_cell"] {
+            fs::write(build_dir.join(format!("{}.rs", name)), "fn main() {}").unwrap();
+            fs::write(build_dir.join(hashed(name)), "dylib").unwrap();
+            fs::write(cells_dir.join(format!("{}.meta", name)), "1\n2").unwrap();
+            fs::write(cells_dir.join(dylib(name)), "dylib").unwrap();
+        }
+        fs::write(build_dir.join("notes.txt"), "keep").unwrap();
+
+        let reclaimed = compiler.prune_artifacts(&["live_cell".to_string()]);
+
+        assert_eq!(reclaimed, ("fn main() {}".len() + 5 + 3 + 5) as u64);
+        assert!(build_dir.join("live_cell.rs").exists());
+        assert!(build_dir.join(hashed("live_cell")).exists());
+        assert!(cells_dir.join("live_cell.meta").exists());
+        assert!(cells_dir.join(dylib("live_cell")).exists());
+        assert!(build_dir.join("notes.txt").exists());
+        assert!(!build_dir.join("old_cell.rs").exists());
+        assert!(!build_dir.join(hashed("old_cell")).exists());
+        assert!(!cells_dir.join("old_cell.meta").exists());
+        assert!(!cells_dir.join(dylib("old_cell")).exists());
+    }
+
+    #[test]
+    fn test_clear_cache() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let config = CompilerConfig {
+            build_dir: temp.path().join("build"),
+            cache_dir: temp.path().join("cache"),
+            ..CompilerConfig::default()
+        };
+        let toolchain = ToolchainManager::new().unwrap();
+        let compiler = CellCompiler::new(config.clone(), toolchain);
+
+        let cells_dir = config.cache_dir.join("cells");
+        fs::create_dir_all(&cells_dir).unwrap();
+        fs::write(cells_dir.join("a.meta"), "1\n2").unwrap();
+        fs::write(cells_dir.join("b.meta"), "3\n4").unwrap();
+
+        compiler.clear_cache();
+
+        assert_eq!(fs::read_dir(&cells_dir).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_hash_source_depends_on_backend() {
+        let toolchain = ToolchainManager::new().unwrap();
+        let dev = CellCompiler::new(CompilerConfig::development(), toolchain.clone());
+        let release = CellCompiler::new(CompilerConfig::production(), toolchain);
+
+        let cell = make_test_cell();
+        assert_ne!(dev.hash_source(&cell), release.hash_source(&cell));
+    }
+
     #[test]
     fn test_hash_source() {
         let config = CompilerConfig::default();
         let toolchain = ToolchainManager::new().unwrap();
         let compiler = CellCompiler::new(config, toolchain);
 
-        let hash1 = compiler.hash_source("fn foo() {}");
-        let hash2 = compiler.hash_source("fn foo() {}");
-        let hash3 = compiler.hash_source("fn bar() {}");
+        let cell = make_test_cell();
+        let hash1 = compiler.hash_source(&cell);
+        let hash2 = compiler.hash_source(&make_test_cell());
+        let hash3 = compiler.hash_source(&CellInfo {
+            source_code: "pub fn test_cell() -> i32 { 43 }".to_string(),
+            ..make_test_cell()
+        });
+        let hash4 = compiler.hash_source(&CellInfo {
+            opt_level: Some(2),
+            ..make_test_cell()
+        });
 
         assert_eq!(hash1, hash2);
         assert_ne!(hash1, hash3);
+        assert_ne!(hash1, hash4);
+    }
+
+    #[test]
+    fn test_output_format_change_misses_cache() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let config = CompilerConfig {
+            build_dir: temp.path().join("build"),
+            cache_dir: temp.path().join("cache"),
+            ..CompilerConfig::default()
+        };
+        let toolchain = ToolchainManager::new().unwrap();
+        let compiler = CellCompiler::new(config, toolchain);
+
+        let cell = make_test_cell();
+        let source_hash = compiler.hash_source(&cell);
+        let cached = CompiledCell {
+            cell_id: cell.id,
+            name: cell.name.clone(),
+            dylib_path: compiler.cache_path(&cell),
+            entry_symbol: "venus_cell_test_cell".to_string(),
+            source_hash,
+            deps_hash: 7,
+            compile_time_ms: 0,
+            warnings: Vec::new(),
+        };
+        fs::create_dir_all(cached.dylib_path.parent().unwrap()).unwrap();
+        fs::write(&cached.dylib_path, "dylib").unwrap();
+        compiler.save_to_cache(&cached);
+        assert!(compiler.check_cache(&cell, source_hash, 7).is_some());
+
+        // Same source, different hint: the entry point differs, so recompile
+        let hinted = CellInfo {
+            output_format: Some(OutputFormat::Json),
+            ..make_test_cell()
+        };
+        let hinted_hash = compiler.hash_source(&hinted);
+        assert_ne!(hinted_hash, source_hash);
+        assert!(compiler.check_cache(&hinted, hinted_hash, 7).is_none());
     }
 }
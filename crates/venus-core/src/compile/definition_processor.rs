@@ -11,6 +11,8 @@
 //!   cells instead of staying private to the universe crate).
 //! - Promotes every top-level definition (`struct`, `enum`, `type`, `fn`,
 //!   `trait`, `const`, `static`, `union`, `mod`) to `pub` visibility.
+//! - Drops `#[venus::cell]` functions from inherent impl blocks: like other
+//!   cells they are compiled on their own, not into the universe.
 //! - Rewrites `#[derive(...)]` on structs/enums to carry rkyv's `Archive`,
 //!   `Serialize`, and `Deserialize` derives (re-exported by the universe as
 //!   `Archive`/`RkyvSerialize`/`RkyvDeserialize`) so cell return values can be
@@ -22,7 +24,9 @@
 
 use proc_macro2::TokenStream;
 use quote::ToTokens;
-use syn::{Attribute, Item, Visibility, parse_file, parse_quote};
+use syn::{Attribute, ImplItem, Item, Visibility, parse_file, parse_quote};
+
+use crate::graph::{CellParser, strip_doc_attributes};
 
 /// The result of processing a notebook's definition cells.
 #[derive(Debug, Default)]
@@ -125,6 +129,11 @@ fn classify_item(item: Item) -> ProcessedItem {
             m.vis = public;
             ProcessedItem::Definition(render(&m))
         }
+        Item::Impl(mut i) if CellParser::inherent_impl_type(&i).is_some() => {
+            i.items
+                .retain(|item| !matches!(item, ImplItem::Fn(f) if CellParser::is_cell_method(f)));
+            ProcessedItem::Definition(render(&i))
+        }
         // Impl blocks, macro invocations, extern crates, etc. carry no
         // visibility of their own; keep them as-is.
         other => ProcessedItem::Definition(render(&other)),
@@ -132,8 +141,11 @@ fn classify_item(item: Item) -> ProcessedItem {
 }
 
 /// Render a `syn` item back to source text.
+///
+/// Doc comments are dropped: they do not affect the compiled universe, and
+/// leaving them out keeps documentation edits from invalidating its cache.
 fn render<T: ToTokens>(item: &T) -> String {
-    let tokens: TokenStream = item.to_token_stream();
+    let tokens: TokenStream = strip_doc_attributes(item.to_token_stream());
     tokens.to_string()
 }
 
@@ -263,6 +275,20 @@ mod tests {
         assert!(squish(&out.type_definitions).starts_with("pub struct Foo"));
     }
 
+    #[test]
+    fn cell_methods_are_dropped_from_impl() {
+        let out = process_one(
+            "impl Cells {\n    fn helper() -> i32 { 1 }\n\n    #[venus::cell]\n    pub fn load() -> i32 { 2 }\n}",
+        );
+        let squished = squish(&out.type_definitions);
+        assert!(squished.contains("fn helper"));
+        assert!(!squished.contains("fn load"));
+
+        // Trait impls are left alone
+        let out = process_one("impl Trait for Cells {\n    #[venus::cell]\n    fn load() {}\n}");
+        assert!(squish(&out.type_definitions).contains("fn load"));
+    }
+
     #[test]
     fn non_pub_helper_fn_is_promoted_to_pub() {
         let out = process_one("fn helper(x: i32) -> i32 { x + 1 }");
@@ -3,6 +3,7 @@
 //! This module provides:
 //! - Toolchain management (Cranelift nightly installation)
 //! - Universe building (dependency crate compilation)
+//! - Build progress (streamed cargo output)
 //! - Cell compilation (individual cell → dylib)
 //! - Error mapping (rustc errors → source locations)
 //! - Dependency parsing (cargo-style specs from doc comments)
@@ -19,22 +20,26 @@
 //!                                   └── Links against Universe
 //! ```
 
+mod abort;
 mod cargo_generator;
 mod cell;
 mod definition_processor;
 mod dependency_parser;
 mod errors;
 mod production;
+mod progress;
 mod source_processor;
 mod toolchain;
 mod types;
 mod universe;
 
+pub use abort::CompileAbortHandle;
 pub use cargo_generator::{ManifestConfig, ReleaseProfile, generate_cargo_toml};
 pub use cell::CellCompiler;
 pub use dependency_parser::{DependencyParser, ExternalDependency};
-pub use errors::{CompileError, ErrorMapper};
+pub use errors::{CompileError, ErrorLevel, ErrorMapper};
 pub use production::ProductionBuilder;
+pub use progress::{BuildEvent, CargoProgress};
 pub use source_processor::NotebookSourceProcessor;
 pub use toolchain::ToolchainManager;
 pub use types::{CompilationResult, CompiledCell, CompilerConfig};
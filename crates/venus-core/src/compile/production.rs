@@ -1,6 +1,7 @@
 //! Production builder for Venus notebooks.
 //!
-//! Generates standalone binaries that execute all cells.
+//! Generates standalone binaries that execute all cells, or library crates
+//! that expose them.
 
 use std::collections::HashSet;
 use std::fs;
@@ -8,18 +9,22 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use crate::error::{Error, Result};
-use crate::graph::{CellInfo, CellParser, GraphEngine};
+use crate::graph::{CellId, CellInfo, CellParser, GraphEngine};
+use crate::state::{OutputIndex, StateManager, output_fingerprints};
 
 use super::CompilerConfig;
 use super::cargo_generator::{ManifestConfig, ReleaseProfile, generate_cargo_toml};
-use super::dependency_parser::DependencyParser;
+use super::dependency_parser::{DependencyParser, ExternalDependency};
+use super::progress::{BuildEvent, run_cargo};
 use super::source_processor::NotebookSourceProcessor;
 
 /// Builder for standalone production binaries.
 ///
 /// Unlike interactive execution (via `venus run` or `venus serve`), production
-/// builds do not use [`crate::state::StateManager`] since the output is a
-/// self-contained executable with its own execution flow.
+/// builds do not use [`StateManager`] since the output is a self-contained
+/// executable with its own execution flow. The one exception is
+/// [`with_embedded_outputs`](Self::with_embedded_outputs), which reads cached
+/// outputs once at build time and bakes them into the binary.
 pub struct ProductionBuilder {
     /// Compiler configuration
     config: CompilerConfig,
@@ -38,6 +43,12 @@ pub struct ProductionBuilder {
 
     /// Notebook file path (for error messages)
     notebook_path: PathBuf,
+
+    /// State directory to embed cached outputs from, if enabled
+    embedded_outputs: Option<PathBuf>,
+
+    /// Target triple to cross-compile for (host if `None`)
+    target: Option<String>,
 }
 
 impl ProductionBuilder {
@@ -50,9 +61,36 @@ impl ProductionBuilder {
             parser: DependencyParser::new(),
             source: String::new(),
             notebook_path: PathBuf::new(),
+            embedded_outputs: None,
+            target: None,
         }
     }
 
+    /// Cross-compile for the given target triple.
+    ///
+    /// Forces the LLVM backend, since Cranelift only supports a few targets.
+    /// The whole generated crate, including all notebook dependencies, is
+    /// built for the target. Use [`ToolchainManager::ensure_target`] first to
+    /// check that the target's standard library is installed.
+    ///
+    /// [`ToolchainManager::ensure_target`]: super::ToolchainManager::ensure_target
+    pub fn with_target(mut self, target: impl Into<String>) -> Self {
+        self.target = Some(target.into());
+        self.config.use_cranelift = false;
+        self
+    }
+
+    /// Embed the cached outputs from `state_dir` into the binary.
+    ///
+    /// The built executable prints these outputs instead of running the
+    /// cells when invoked with `--cached`, provided their recorded types
+    /// still match the compiled cell types. Every cell must have a cached
+    /// output (see `venus run`).
+    pub fn with_embedded_outputs(mut self, state_dir: impl Into<PathBuf>) -> Self {
+        self.embedded_outputs = Some(state_dir.into());
+        self
+    }
+
     /// Load a notebook from the given path.
     ///
     /// # Errors
@@ -121,13 +159,23 @@ impl ProductionBuilder {
     /// - Cargo fails to compile the project
     /// - The binary cannot be copied to the output path
     pub fn build(&self, output_path: impl AsRef<Path>, release: bool) -> Result<PathBuf> {
+        self.build_with_progress(output_path, release, |_| {})
+    }
+
+    /// Build the standalone binary, reporting cargo's progress to `on_event`.
+    pub fn build_with_progress(
+        &self,
+        output_path: impl AsRef<Path>,
+        release: bool,
+        mut on_event: impl FnMut(&BuildEvent),
+    ) -> Result<PathBuf> {
         let output_path = output_path.as_ref();
         let build_dir = self.config.build_dir.join("production");
 
         fs::create_dir_all(&build_dir)?;
 
         // Generate Cargo.toml
-        let cargo_toml = self.generate_cargo_toml()?;
+        let cargo_toml = self.generate_cargo_toml(false)?;
         fs::write(build_dir.join("Cargo.toml"), cargo_toml)?;
 
         // Generate main.rs
@@ -144,20 +192,24 @@ impl ProductionBuilder {
             cmd.arg("--release");
         }
 
-        // Capture output
+        if let Some(target) = &self.target {
+            cmd.arg("--target").arg(target);
+        }
+
+        // Short diagnostics keep the streamed output readable
         cmd.arg("--message-format=short");
 
-        let output = cmd.output().map_err(|e| Error::Compilation {
-            cell_id: None,
-            message: format!(
-                "Failed to run cargo (working dir: {}): {}",
-                build_dir.display(),
-                e
-            ),
-        })?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
+        let (status, stderr) =
+            run_cargo(&mut cmd, &mut on_event, None).map_err(|e| Error::Compilation {
+                cell_id: None,
+                message: format!(
+                    "Failed to run cargo (working dir: {}): {}",
+                    build_dir.display(),
+                    e
+                ),
+            })?;
+
+        if !status.success() {
             return Err(Error::Compilation {
                 cell_id: None,
                 message: format!(
@@ -171,7 +223,11 @@ impl ProductionBuilder {
         // Copy the binary to output path
         let profile = if release { "release" } else { "debug" };
         let binary_name = self.binary_name();
-        let built_binary = build_dir.join("target").join(profile).join(&binary_name);
+        let mut target_dir = build_dir.join("target");
+        if let Some(target) = &self.target {
+            target_dir = target_dir.join(target);
+        }
+        let built_binary = target_dir.join(profile).join(&binary_name);
 
         fs::copy(&built_binary, output_path)?;
 
@@ -187,6 +243,40 @@ impl ProductionBuilder {
         Ok(output_path.to_path_buf())
     }
 
+    /// Generate a library crate exposing the notebook's cells.
+    ///
+    /// Writes `Cargo.toml` and `src/lib.rs` to `output_dir`. Every cell
+    /// becomes a `pub fn`, and a generated `run()` calls them in dependency
+    /// order and returns their outputs. The crate is not compiled; it builds
+    /// with a stable `cargo build`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `output_dir` already contains a `Cargo.toml` or
+    /// the files cannot be written.
+    pub fn build_library(&self, output_dir: impl AsRef<Path>) -> Result<PathBuf> {
+        let output_dir = output_dir.as_ref();
+        if output_dir.join("Cargo.toml").exists() {
+            return Err(Error::Compilation {
+                cell_id: None,
+                message: format!(
+                    "Refusing to overwrite existing crate at {}",
+                    output_dir.display()
+                ),
+            });
+        }
+
+        let src_dir = output_dir.join("src");
+        fs::create_dir_all(&src_dir)?;
+        fs::write(
+            output_dir.join("Cargo.toml"),
+            self.generate_cargo_toml(true)?,
+        )?;
+        fs::write(src_dir.join("lib.rs"), self.generate_lib_rs()?)?;
+
+        Ok(output_dir.to_path_buf())
+    }
+
     /// Get the number of cells.
     pub fn cell_count(&self) -> usize {
         self.cells.len()
@@ -197,8 +287,12 @@ impl ProductionBuilder {
         self.parser.dependencies().len()
     }
 
-    /// Generate Cargo.toml for the production binary.
-    fn generate_cargo_toml(&self) -> Result<String> {
+    /// Generate Cargo.toml for the production binary or library crate.
+    ///
+    /// Library crates depend on exactly the notebook's dependencies plus
+    /// `venus` and `rkyv`, since notebooks import the prelude (whose derives
+    /// need `rkyv` in scope) and the crate is meant to build outside of Venus.
+    fn generate_cargo_toml(&self, library: bool) -> Result<String> {
         // Derive binary name from notebook filename
         let name = self
             .notebook_path
@@ -242,18 +336,148 @@ impl ProductionBuilder {
             version: "0.1.0",
             edition: "2021",
             lib_crate_types: None,
-            release_profile: Some(ReleaseProfile::production()),
+            release_profile: (!library).then(ReleaseProfile::production),
             standalone_workspace: true,
         };
 
+        let mut dependencies = self.parser.dependencies().to_vec();
+        if library && !dependencies.iter().any(|d| d.name == "venus") {
+            dependencies.push(match &self.config.venus_crate_path {
+                Some(path) => ExternalDependency {
+                    name: "venus".to_string(),
+                    version: None,
+                    features: Vec::new(),
+                    path: Some(path.clone()),
+                },
+                None => ExternalDependency::simple("venus", "0.1"),
+            });
+        }
+        // The prelude's rkyv derives expand to `::rkyv` paths
+        if library && !dependencies.iter().any(|d| d.name == "rkyv") {
+            dependencies.push(
+                ExternalDependency::simple("rkyv", "0.8")
+                    .with_features(vec!["std".to_string(), "bytecheck".to_string()]),
+            );
+        }
+
         Ok(generate_cargo_toml(
             &config,
-            self.parser.dependencies(),
-            true, // Always include serde for consistency
+            &dependencies,
+            !library, // Binaries always include serde; libraries only what they declare
             Some(notebook_dir),
         ))
     }
 
+    /// Generate lib.rs with public cells and a `run()` orchestrator.
+    ///
+    /// `run()` returns an `Outputs` struct with one field per cell. Cells
+    /// whose output is moved into a dependent (taken by value) or whose type
+    /// cannot be named (`impl Trait`) are computed but not included.
+    fn generate_lib_rs(&self) -> Result<String> {
+        let mut code = String::new();
+
+        code.push_str("//! Generated by Venus - notebook library crate.\n");
+        code.push_str("//!\n");
+        code.push_str(&format!("//! Source: {}\n", self.notebook_path.display()));
+        code.push('\n');
+        code.push_str("#![allow(unused_imports)]\n");
+        code.push_str("#![allow(dead_code)]\n");
+        code.push_str("#![allow(clippy::ptr_arg)]\n");
+        code.push('\n');
+
+        let processed_source =
+            NotebookSourceProcessor::process_for_library(&self.source).map_err(|e| {
+                Error::Compilation {
+                    cell_id: None,
+                    message: format!(
+                        "Failed to parse notebook source '{}': {}",
+                        self.notebook_path.display(),
+                        e
+                    ),
+                }
+            })?;
+        code.push_str(&processed_source);
+        code.push_str("\n\n");
+
+        let order = self.graph.topological_order()?;
+        let ordered: Vec<&CellInfo> = order
+            .iter()
+            .map(|id| {
+                self.cells
+                    .iter()
+                    .find(|c| c.id == *id)
+                    .ok_or_else(|| Error::CellNotFound(format!("{:?}", id)))
+            })
+            .collect::<Result<_>>()?;
+
+        // Outputs that are mutated or moved by dependents
+        let mut mutated = HashSet::new();
+        let mut moved = HashSet::new();
+        for cell in &ordered {
+            for dep in &cell.dependencies {
+                if !dep.is_ref {
+                    moved.insert(dep.param_name.as_str());
+                } else if dep.is_mut {
+                    mutated.insert(dep.param_name.as_str());
+                }
+            }
+        }
+
+        let exposed: Vec<&&CellInfo> = ordered
+            .iter()
+            .filter(|c| !moved.contains(c.output_name()) && !c.return_type.contains("impl "))
+            .collect();
+
+        code.push_str("/// Outputs of every cell, as returned by [`run`].\n");
+        code.push_str("pub struct Outputs {\n");
+        for cell in &exposed {
+            code.push_str(&format!("    /// Output of [`{}`].\n", cell.name));
+            code.push_str(&format!(
+                "    pub {}: {},\n",
+                cell.output_name(),
+                cell.return_type
+            ));
+        }
+        code.push_str("}\n\n");
+
+        code.push_str("/// Run all cells in dependency order.\n");
+        code.push_str("pub fn run() -> Outputs {\n");
+        for cell in &ordered {
+            let args: Vec<String> = cell
+                .dependencies
+                .iter()
+                .map(|dep| {
+                    if dep.is_ref {
+                        if dep.is_mut {
+                            format!("&mut {}", dep.param_name)
+                        } else {
+                            format!("&{}", dep.param_name)
+                        }
+                    } else {
+                        dep.param_name.clone()
+                    }
+                })
+                .collect();
+            let binding = if mutated.contains(cell.output_name()) {
+                "let mut"
+            } else {
+                "let"
+            };
+            code.push_str(&format!(
+                "    {} {} = {}({});\n",
+                binding,
+                cell.output_name(),
+                cell.name,
+                args.join(", ")
+            ));
+        }
+        let fields: Vec<&str> = exposed.iter().map(|c| c.output_name()).collect();
+        code.push_str(&format!("    Outputs {{ {} }}\n", fields.join(", ")));
+        code.push_str("}\n");
+
+        Ok(code)
+    }
+
     /// Generate main.rs with all cells and execution logic.
     fn generate_main_rs(&self) -> Result<String> {
         let mut code = String::new();
@@ -281,6 +505,20 @@ impl ProductionBuilder {
         code.push_str(&processed_source);
         code.push('\n');
 
+        // Execute cells in topological order
+        let order = self.graph.topological_order()?;
+
+        let footer = format!(
+            "    println!(\"═══════════════════════════════════════════════════\");\n    \
+             println!(\"  Completed {} cell(s)\");\n    \
+             println!(\"═══════════════════════════════════════════════════\");\n",
+            order.len()
+        );
+
+        if let Some(state_dir) = &self.embedded_outputs {
+            code.push_str(&self.generate_embedded_outputs(state_dir, &order)?);
+        }
+
         // Main function
         code.push_str("fn main() {\n");
         code.push_str("    println!(\"═══════════════════════════════════════════════════\");\n");
@@ -294,8 +532,14 @@ impl ProductionBuilder {
         code.push_str("    println!(\"═══════════════════════════════════════════════════\");\n");
         code.push_str("    println!();\n\n");
 
-        // Execute cells in topological order
-        let order = self.graph.topological_order()?;
+        if self.embedded_outputs.is_some() {
+            code.push_str(
+                "    if std::env::args().any(|arg| arg == \"--cached\") && venus_print_embedded_outputs() {\n",
+            );
+            code.push_str(&footer);
+            code.push_str("        return;\n");
+            code.push_str("    }\n\n");
+        }
 
         for cell_id in &order {
             let cell = self
@@ -327,28 +571,103 @@ impl ProductionBuilder {
             // Call the cell function and store result
             code.push_str(&format!(
                 "    let {} = {}({});\n",
-                cell.name,
+                cell.output_name(),
                 cell.name,
                 args.join(", ")
             ));
 
             // Print output
-            code.push_str(&format!("    println!(\"  → {{:?}}\", {});\n", cell.name));
+            code.push_str(&format!(
+                "    println!(\"  → {{:?}}\", {});\n",
+                cell.output_name()
+            ));
             code.push_str("    println!();\n");
         }
 
-        code.push_str("    println!(\"═══════════════════════════════════════════════════\");\n");
-        code.push_str(&format!(
-            "    println!(\"  Completed {} cell(s)\");\n",
-            order.len()
-        ));
-        code.push_str("    println!(\"═══════════════════════════════════════════════════\");\n");
+        code.push_str(&footer);
         code.push_str("}\n");
 
         Ok(code)
     }
 
+    /// Generate the embedded output table and the function that prints it.
+    ///
+    /// Outputs are looked up by cell name in the [`OutputIndex`] written by
+    /// `venus run`, and refused if the cell or anything upstream of it has
+    /// changed since. Each entry also records the type the output was
+    /// produced with, which is compared at runtime against the compiled
+    /// cell's return type, ignoring module paths.
+    fn generate_embedded_outputs(&self, state_dir: &Path, order: &[CellId]) -> Result<String> {
+        let mut state = StateManager::new(state_dir)?;
+        state.restore()?;
+        let index = OutputIndex::load(state_dir)?;
+        let fingerprints = output_fingerprints(&self.graph)?;
+
+        let mut entries = String::new();
+        let mut compiled_types = String::new();
+
+        for cell_id in order {
+            let cell = self
+                .cells
+                .iter()
+                .find(|c| c.id == *cell_id)
+                .ok_or_else(|| Error::CellNotFound(format!("{:?}", cell_id)))?;
+
+            // Outputs are stored by the IDs of the run that produced them,
+            // so find this cell's by name and check it is still current
+            let fingerprint = fingerprints.get(&cell.name).copied().unwrap_or_default();
+            let output = index
+                .lookup(&cell.name, fingerprint)
+                .and_then(|stored_id| state.get_output(stored_id));
+            let display = output.as_ref().and_then(|o| o.display_text());
+            let (Some(output), Some(display)) = (&output, display) else {
+                let problem = if index.contains(&cell.name) {
+                    format!("The cached output of cell '{}' is out of date", cell.name)
+                } else {
+                    format!("No cached output for cell '{}' to embed", cell.name)
+                };
+                return Err(Error::Compilation {
+                    cell_id: Some(cell.name.clone()),
+                    message: format!(
+                        "{}; run `venus run {}` first",
+                        problem,
+                        self.notebook_path.display()
+                    ),
+                });
+            };
+
+            entries.push_str(&format!(
+                "    ({:?}, {:?}, {:?}),\n",
+                cell.name,
+                output.type_name(),
+                display
+            ));
+            compiled_types.push_str(&format!(
+                "        std::any::type_name::<{}>(),\n",
+                cell.return_type
+            ));
+        }
+
+        let mut code = String::new();
+        code.push_str("/// Cell outputs embedded at build time: (cell, type, display).\n");
+        code.push_str("static VENUS_EMBEDDED_OUTPUTS: &[(&str, &str, &str)] = &[\n");
+        code.push_str(&entries);
+        code.push_str("];\n\n");
+        code.push_str(EMBEDDED_OUTPUT_RUNTIME);
+        code.push_str("/// Print the embedded outputs if they match the compiled cell types.\n");
+        code.push_str("fn venus_print_embedded_outputs() -> bool {\n");
+        code.push_str("    let compiled_types: &[&str] = &[\n");
+        code.push_str(&compiled_types);
+        code.push_str("    ];\n");
+        code.push_str("    venus_check_embedded_types(compiled_types)\n");
+        code.push_str("}\n\n");
+
+        Ok(code)
+    }
+
     /// Get the platform-specific binary name.
+    ///
+    /// When cross-compiling, the extension follows the target, not the host.
     fn binary_name(&self) -> String {
         let name = self
             .notebook_path
@@ -357,17 +676,64 @@ impl ProductionBuilder {
             .unwrap_or("notebook")
             .replace('-', "_");
 
-        #[cfg(target_os = "windows")]
-        {
+        let windows = match &self.target {
+            Some(target) => target.contains("windows"),
+            None => cfg!(target_os = "windows"),
+        };
+
+        if windows {
             format!("{}.exe", name)
-        }
-        #[cfg(not(target_os = "windows"))]
-        {
+        } else {
             name
         }
     }
 }
 
+/// Runtime support for embedded outputs, emitted verbatim into `main.rs`.
+const EMBEDDED_OUTPUT_RUNTIME: &str = r#"/// Strip module paths and whitespace from a type name.
+fn venus_short_type_name(name: &str) -> String {
+    let mut short = String::new();
+    let mut ident = String::new();
+    let mut chars = name.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_alphanumeric() || c == '_' {
+            ident.push(c);
+        } else if c == ':' && chars.peek() == Some(&':') {
+            chars.next();
+            ident.clear();
+        } else {
+            short.push_str(&ident);
+            ident.clear();
+            if !c.is_whitespace() {
+                short.push(c);
+            }
+        }
+    }
+    short.push_str(&ident);
+    short
+}
+
+/// Validate embedded output types and print the outputs if all match.
+fn venus_check_embedded_types(compiled_types: &[&str]) -> bool {
+    for ((name, cached, _), compiled) in VENUS_EMBEDDED_OUTPUTS.iter().zip(compiled_types) {
+        if venus_short_type_name(cached) != venus_short_type_name(compiled) {
+            eprintln!(
+                "Embedded output for '{}' has type {}, but the cell returns {}; running cells instead",
+                name, cached, compiled
+            );
+            return false;
+        }
+    }
+    for (name, _, display) in VENUS_EMBEDDED_OUTPUTS {
+        println!("▶ Cached: {}", name);
+        println!("  → {}", display);
+        println!();
+    }
+    true
+}
+
+"#;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -387,6 +753,117 @@ mod tests {
         assert_eq!(name, "my_notebook");
     }
 
+    #[test]
+    fn test_binary_name_for_target() {
+        let mut builder =
+            ProductionBuilder::new(CompilerConfig::default()).with_target("x86_64-pc-windows-gnu");
+        builder.notebook_path = PathBuf::from("my-notebook.rs");
+        assert_eq!(builder.binary_name(), "my_notebook.exe");
+        assert!(!builder.config.use_cranelift);
+
+        let mut builder = ProductionBuilder::new(CompilerConfig::default())
+            .with_target("aarch64-unknown-linux-gnu");
+        builder.notebook_path = PathBuf::from("my-notebook.rs");
+        assert_eq!(builder.binary_name(), "my_notebook");
+    }
+
+    #[test]
+    fn test_generate_lib_rs() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let notebook = temp.path().join("nb.rs");
+        fs::write(
+            &notebook,
+            "#[venus::cell]\nfn base() -> Vec<i32> { vec![1, 2] }\n\n\
+             #[venus::cell]\npub fn total(base: &Vec<i32>) -> i32 { base.iter().sum() }\n\n\
+             #[venus::cell]\npub fn owned(total: i32) -> i32 { total }\n",
+        )
+        .unwrap();
+
+        let mut builder = ProductionBuilder::new(CompilerConfig::default());
+        builder.load(&notebook).unwrap();
+        let lib_rs = builder.generate_lib_rs().unwrap();
+
+        assert!(lib_rs.contains("pub fn base"));
+        assert!(lib_rs.contains("pub base: "));
+        // `total` is moved into `owned`, so it is not exposed
+        assert!(!lib_rs.contains("pub total: i32,"));
+        assert!(lib_rs.contains("pub fn run() -> Outputs"));
+        assert!(lib_rs.contains("let total = total(&base);"));
+        assert!(lib_rs.contains("Outputs { base, owned }"));
+
+        let out = temp.path().join("lib");
+        builder.build_library(&out).unwrap();
+        let manifest = fs::read_to_string(out.join("Cargo.toml")).unwrap();
+        assert!(manifest.contains("venus"));
+        assert!(!manifest.contains("[profile.release]"));
+        assert!(out.join("src").join("lib.rs").exists());
+        assert!(builder.build_library(&out).is_err());
+    }
+
+    #[test]
+    fn test_embedded_outputs() {
+        use crate::state::BoxedOutput;
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let notebook = temp.path().join("nb.rs");
+        fs::write(
+            &notebook,
+            "#[venus::cell]\npub fn answer() -> i32 { 42 }\n\n\
+             #[venus::cell]\npub fn doubled(answer: &i32) -> i32 { answer * 2 }\n",
+        )
+        .unwrap();
+        let state_dir = temp.path().join("state");
+
+        let mut builder =
+            ProductionBuilder::new(CompilerConfig::default()).with_embedded_outputs(&state_dir);
+        builder.load(&notebook).unwrap();
+        let order = builder.graph.topological_order().unwrap();
+
+        // Nothing cached yet
+        assert!(
+            builder
+                .generate_embedded_outputs(&state_dir, &order)
+                .is_err()
+        );
+
+        // Stored under the IDs of an earlier run, in which the cells were swapped
+        let fingerprints = output_fingerprints(&builder.graph).unwrap();
+        let mut state = StateManager::new(&state_dir).unwrap();
+        let mut index = OutputIndex::load(&state_dir).unwrap();
+        for (stored_id, (name, display)) in [("doubled", "84"), ("answer", "42")]
+            .into_iter()
+            .enumerate()
+        {
+            let output = BoxedOutput::from_raw_bytes_with_display(Vec::new(), display.into())
+                .with_type_name("i32");
+            state.store_output(CellId::new(stored_id), output);
+            index.record(name, CellId::new(stored_id), fingerprints[name] ^ 1);
+        }
+        state.flush().unwrap();
+        index.save().unwrap();
+
+        // Produced by other code
+        let err = builder
+            .generate_embedded_outputs(&state_dir, &order)
+            .unwrap_err();
+        assert!(err.to_string().contains("out of date"));
+
+        for (stored_id, name) in ["doubled", "answer"].into_iter().enumerate() {
+            index.record(name, CellId::new(stored_id), fingerprints[name]);
+        }
+        index.save().unwrap();
+
+        let code = builder
+            .generate_embedded_outputs(&state_dir, &order)
+            .unwrap();
+        assert!(code.contains(r#"("answer", "i32", "42"),"#));
+        assert!(code.contains(r#"("doubled", "i32", "84"),"#));
+        assert!(code.contains("std::any::type_name::<i32>()"));
+
+        let main_rs = builder.generate_main_rs().unwrap();
+        assert!(main_rs.contains("venus_print_embedded_outputs()"));
+    }
+
     #[test]
     fn test_validate_unique_cell_names() {
         use crate::graph::{CellId, SourceSpan};
@@ -413,6 +890,10 @@ mod tests {
                 doc_comment: None,
                 source_code: String::new(),
                 source_file: PathBuf::new(),
+                timeout_ms: None,
+                opt_level: None,
+                output_format: None,
+                is_async: false,
                 span: span.clone(),
             },
             CellInfo {
@@ -424,6 +905,10 @@ mod tests {
                 doc_comment: None,
                 source_code: String::new(),
                 source_file: PathBuf::new(),
+                timeout_ms: None,
+                opt_level: None,
+                output_format: None,
+                is_async: false,
                 span: span.clone(),
             },
         ];
@@ -439,6 +924,10 @@ mod tests {
             doc_comment: None,
             source_code: String::new(),
             source_file: PathBuf::new(),
+            timeout_ms: None,
+            opt_level: None,
+            output_format: None,
+            is_async: false,
             span,
         });
         assert!(builder.validate_unique_cell_names().is_err());
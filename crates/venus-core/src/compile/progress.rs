@@ -0,0 +1,181 @@
+//! Progress reporting for cargo builds.
+//!
+//! Dependency builds can take minutes the first time. Rather than waiting on
+//! `Command::output()`, builders stream cargo's stderr through [`run_cargo`]
+//! and report a [`BuildEvent`] per line, plus a [`BuildEvent::Compiling`]
+//! whenever cargo starts on a new crate.
+
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::process::{Command, ExitStatus, Stdio};
+
+use super::abort::CompileAbortHandle;
+
+/// An event emitted while cargo is building.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildEvent {
+    /// A raw line of cargo output.
+    Output(String),
+
+    /// Cargo started compiling a crate.
+    Compiling {
+        /// Crate name.
+        name: String,
+        /// Number of crates compiled so far, including this one.
+        current: usize,
+        /// Number of packages in the lock file, if known.
+        total: Option<usize>,
+    },
+}
+
+/// Tracks how far a cargo build has progressed from its output lines.
+#[derive(Debug, Default)]
+pub struct CargoProgress {
+    compiled: usize,
+    total: Option<usize>,
+}
+
+impl CargoProgress {
+    /// Create a tracker, optionally knowing the total number of crates.
+    pub fn new(total: Option<usize>) -> Self {
+        Self { compiled: 0, total }
+    }
+
+    /// Set the total number of crates once it becomes known.
+    pub fn set_total(&mut self, total: usize) {
+        self.total = Some(total);
+    }
+
+    /// Parse a line of cargo output, returning an event if it starts a crate.
+    pub fn parse_line(&mut self, line: &str) -> Option<BuildEvent> {
+        let rest = line.trim_start().strip_prefix("Compiling ")?;
+        let name = rest.split_whitespace().next()?;
+
+        self.compiled += 1;
+        Some(BuildEvent::Compiling {
+            name: name.to_string(),
+            current: self.total.map_or(self.compiled, |t| self.compiled.min(t)),
+            total: self.total,
+        })
+    }
+}
+
+/// Count the packages recorded in a `Cargo.lock`.
+fn lock_package_count(lock: &str) -> usize {
+    lock.lines()
+        .filter(|line| line.trim() == "[[package]]")
+        .count()
+}
+
+/// Run a cargo command, streaming its stderr to `on_event`.
+///
+/// The total crate count is read from `Cargo.lock` in the command's working
+/// directory when the first crate starts compiling, since cargo writes the
+/// lock file before building. Returns the exit status and the full stderr.
+///
+/// With an abort handle, cargo is spawned through it so the build can be
+/// killed from another thread.
+pub(crate) fn run_cargo(
+    cmd: &mut Command,
+    on_event: &mut dyn FnMut(&BuildEvent),
+    abort: Option<&CompileAbortHandle>,
+) -> std::io::Result<(ExitStatus, String)> {
+    let lock_path = cmd
+        .get_current_dir()
+        .map(|dir| dir.join("Cargo.lock"))
+        .unwrap_or_else(|| Path::new("Cargo.lock").to_path_buf());
+
+    cmd.arg("--color")
+        .arg("never")
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
+    let mut child = match abort {
+        Some(handle) => handle.spawn(cmd)?,
+        None => cmd.spawn()?,
+    };
+
+    let mut progress = CargoProgress::new(None);
+    let mut stderr = String::new();
+
+    if let Some(pipe) = child.stderr.take() {
+        for line in BufReader::new(pipe).lines() {
+            // Stop on a read error; waiting below still reaps cargo and
+            // releases the abort handle
+            let Ok(line) = line else {
+                break;
+            };
+
+            // Cargo has resolved the lock file by the time it starts compiling
+            if progress.total.is_none()
+                && line.trim_start().starts_with("Compiling ")
+                && let Ok(lock) = fs::read_to_string(&lock_path)
+            {
+                progress.set_total(lock_package_count(&lock));
+            }
+
+            if let Some(event) = progress.parse_line(&line) {
+                on_event(&event);
+            }
+            on_event(&BuildEvent::Output(line.clone()));
+            stderr.push_str(&line);
+            stderr.push('\n');
+        }
+    }
+
+    let status = child.wait();
+    if let Some(handle) = abort {
+        handle.finish();
+    }
+    Ok((status?, stderr))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_compiling_lines() {
+        let mut progress = CargoProgress::new(Some(2));
+
+        assert_eq!(progress.parse_line("    Updating crates.io index"), None);
+        assert_eq!(
+            progress.parse_line("   Compiling serde v1.0.200"),
+            Some(BuildEvent::Compiling {
+                name: "serde".to_string(),
+                current: 1,
+                total: Some(2),
+            })
+        );
+        assert_eq!(
+            progress.parse_line("   Compiling venus_universe v0.1.0 (/tmp/universe)"),
+            Some(BuildEvent::Compiling {
+                name: "venus_universe".to_string(),
+                current: 2,
+                total: Some(2),
+            })
+        );
+        assert_eq!(progress.parse_line("    Finished `release` profile"), None);
+    }
+
+    #[test]
+    fn test_current_never_exceeds_total() {
+        let mut progress = CargoProgress::new(Some(1));
+        progress.parse_line("Compiling a v0.1.0");
+        let event = progress.parse_line("Compiling b v0.1.0");
+        assert!(matches!(
+            event,
+            Some(BuildEvent::Compiling {
+                current: 1,
+                total: Some(1),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_lock_package_count() {
+        let lock = "version = 4\n\n[[package]]\nname = \"a\"\n\n[[package]]\nname = \"b\"\n";
+        assert_eq!(lock_package_count(lock), 2);
+    }
+}
@@ -5,13 +5,14 @@
 
 use proc_macro2::TokenStream;
 use quote::ToTokens;
-use syn::{Attribute, File, Item, parse_file};
+use syn::{Attribute, File, ImplItem, Item, Visibility, parse_file};
 
 /// Process notebook source code for production builds.
 ///
 /// This processor transforms notebook source by:
 /// - Removing module-level doc comments (`//!`)
-/// - Stripping `#[venus::cell]` attributes from functions
+/// - Stripping `#[venus::cell]` attributes from functions, including
+///   associated functions in impl blocks
 /// - Removing the `main` function (to be replaced with generated one)
 ///
 /// Uses proper syntax parsing via `syn` to handle edge cases like:
@@ -30,16 +31,30 @@ impl NotebookSourceProcessor {
     /// Returns an error if the source cannot be parsed as valid Rust.
     pub fn process_for_production(source: &str) -> Result<String, syn::Error> {
         let file = parse_file(source)?;
-        let processed = Self::process_file(file);
+        let processed = Self::process_file(file, false);
+        Ok(Self::tokens_to_string(processed))
+    }
+
+    /// Process notebook source for a library crate.
+    ///
+    /// Same as [`process_for_production`](Self::process_for_production), but
+    /// cell functions are also made `pub` so the library exposes them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the source cannot be parsed as valid Rust.
+    pub fn process_for_library(source: &str) -> Result<String, syn::Error> {
+        let file = parse_file(source)?;
+        let processed = Self::process_file(file, true);
         Ok(Self::tokens_to_string(processed))
     }
 
     /// Process a parsed file, filtering and transforming items.
-    fn process_file(file: File) -> File {
+    fn process_file(file: File, public_cells: bool) -> File {
         let items = file
             .items
             .into_iter()
-            .filter_map(Self::process_item)
+            .filter_map(|item| Self::process_item(item, public_cells))
             .collect();
 
         File {
@@ -63,7 +78,7 @@ impl NotebookSourceProcessor {
     }
 
     /// Process a single item, returning None to remove it.
-    fn process_item(item: Item) -> Option<Item> {
+    fn process_item(item: Item, public_cells: bool) -> Option<Item> {
         match item {
             Item::Fn(mut func) => {
                 // Remove main function
@@ -71,11 +86,29 @@ impl NotebookSourceProcessor {
                     return None;
                 }
 
+                let is_cell = func.attrs.iter().any(Self::is_venus_cell_attr);
+                if is_cell && public_cells {
+                    func.vis = Visibility::Public(Default::default());
+                }
+
                 // Strip #[venus::cell] attribute
                 func.attrs.retain(|attr| !Self::is_venus_cell_attr(attr));
 
                 Some(Item::Fn(func))
             }
+            Item::Impl(mut item_impl) => {
+                for item in &mut item_impl.items {
+                    if let ImplItem::Fn(func) = item
+                        && func.attrs.iter().any(Self::is_venus_cell_attr)
+                    {
+                        if public_cells && item_impl.trait_.is_none() {
+                            func.vis = Visibility::Public(Default::default());
+                        }
+                        func.attrs.retain(|attr| !Self::is_venus_cell_attr(attr));
+                    }
+                }
+                Some(Item::Impl(item_impl))
+            }
             // Keep all other items unchanged
             other => Some(other),
         }
@@ -144,6 +177,27 @@ struct MyStruct { x: i32 }
         assert!(result.contains("struct MyStruct"));
     }
 
+    #[test]
+    fn test_library_makes_cells_public() {
+        let source = r#"
+#[venus::cell]
+fn private_cell() -> i32 { 42 }
+
+fn helper() -> i32 { 1 }
+
+impl Cells {
+    #[venus::cell]
+    fn method_cell() -> i32 { 7 }
+}
+"#;
+        let result = NotebookSourceProcessor::process_for_library(source).unwrap();
+
+        assert!(result.contains("pub fn private_cell"));
+        assert!(result.contains("pub fn method_cell"));
+        assert!(!result.contains("pub fn helper"));
+        assert!(!result.contains("venus :: cell"));
+    }
+
     #[test]
     fn test_removes_main_function() {
         let source = r#"
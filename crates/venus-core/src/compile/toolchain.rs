@@ -156,6 +156,52 @@ impl ToolchainManager {
         Ok(PathBuf::from(libdir))
     }
 
+    /// Get the host target triple (e.g. `x86_64-unknown-linux-gnu`).
+    pub fn host_target(&self) -> Result<String> {
+        let output = Command::new(&self.rustc_path)
+            .args(["-vV"])
+            .output()
+            .map_err(|e| Error::Toolchain(format!("Failed to run rustc: {}", e)))?;
+
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .find_map(|line| line.strip_prefix("host: "))
+            .map(|host| host.trim().to_string())
+            .ok_or_else(|| Error::Toolchain("Failed to determine host target".to_string()))
+    }
+
+    /// Check that the standard library for `target` is installed.
+    ///
+    /// Looks for the target in the sysroot, which works for both rustup and
+    /// standalone installations. Unknown triples and missing targets get
+    /// distinct errors, the latter with the `rustup target add` command.
+    pub fn ensure_target(&self, target: &str) -> Result<()> {
+        let rustlib = self.sysroot()?.join("lib").join("rustlib").join(target);
+        if rustlib.exists() {
+            return Ok(());
+        }
+
+        let output = Command::new(&self.rustc_path)
+            .args(["--print", "target-list"])
+            .output()
+            .map_err(|e| Error::Toolchain(format!("Failed to run rustc: {}", e)))?;
+        let known = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .any(|line| line.trim() == target);
+
+        if known {
+            Err(Error::Toolchain(format!(
+                "target '{}' is not installed; run `rustup target add {}`",
+                target, target
+            )))
+        } else {
+            Err(Error::Toolchain(format!(
+                "unknown target triple '{}' (see `rustc --print target-list`)",
+                target
+            )))
+        }
+    }
+
     /// Find rustup in PATH.
     fn find_rustup() -> Option<PathBuf> {
         which::which("rustup").ok()
@@ -222,6 +268,17 @@ mod tests {
         assert!(!manager.version().is_empty());
     }
 
+    #[test]
+    fn test_ensure_target() {
+        let manager = ToolchainManager::new().unwrap();
+
+        let host = manager.host_target().unwrap();
+        assert!(manager.ensure_target(&host).is_ok());
+
+        let err = manager.ensure_target("not-a-real-triple").unwrap_err();
+        assert!(err.to_string().contains("unknown target"));
+    }
+
     #[test]
     fn test_cranelift_flags() {
         let manager = ToolchainManager::new().unwrap();
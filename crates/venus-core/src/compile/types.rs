@@ -154,6 +154,9 @@ pub struct CompiledCell {
 
     /// Compilation time in milliseconds
     pub compile_time_ms: u64,
+
+    /// Warnings emitted by rustc (empty for cache hits)
+    pub warnings: Vec<crate::compile::CompileError>,
 }
 
 /// Result of a compilation operation.
@@ -10,8 +10,10 @@ use std::process::Command;
 use crate::error::{Error, Result};
 use crate::graph::DefinitionCell;
 
+use super::abort::CompileAbortHandle;
 use super::definition_processor::process_definitions;
 use super::dependency_parser::{DependencyParser, ExternalDependency};
+use super::progress::{BuildEvent, run_cargo};
 use super::toolchain::ToolchainManager;
 use super::types::{CompilerConfig, dylib_extension, dylib_prefix};
 
@@ -47,6 +49,12 @@ pub struct UniverseBuilder {
 
     /// Path to workspace Cargo.toml (for copying dependencies)
     workspace_cargo_toml: Option<PathBuf>,
+
+    /// Handle through which cargo is spawned, so the build can be killed
+    abort_handle: Option<CompileAbortHandle>,
+
+    /// Whether the crate declares `pub mod notebook;` for LSP analysis
+    notebook_module: bool,
 }
 
 impl UniverseBuilder {
@@ -63,9 +71,26 @@ impl UniverseBuilder {
             type_definitions: String::new(),
             imports: String::new(),
             workspace_cargo_toml,
+            abort_handle: None,
+            notebook_module: true,
         }
     }
 
+    /// Set whether the crate includes the `notebook` module that the server
+    /// fills with cell sources for rust-analyzer. On by default.
+    pub fn with_notebook_module(mut self, enabled: bool) -> Self {
+        self.notebook_module = enabled;
+        self
+    }
+
+    /// Run cargo through an abort handle, so the build can be killed.
+    ///
+    /// An aborted build fails with [`Error::CompilationAborted`].
+    pub fn with_abort_handle(mut self, handle: CompileAbortHandle) -> Self {
+        self.abort_handle = Some(handle);
+        self
+    }
+
     /// Parse dependencies and process definition cells from notebook source.
     ///
     /// Delegates to [`DependencyParser`] for dependency parsing and to
@@ -91,6 +116,10 @@ impl UniverseBuilder {
     }
 
     /// Get the dependencies hash (includes imports and type definitions).
+    ///
+    /// When a workspace Cargo.toml is configured, its contents (and those of
+    /// the sibling Cargo.lock, if present) are hashed too, so editing shared
+    /// workspace dependencies invalidates the cached universe.
     pub fn deps_hash(&self) -> u64 {
         use std::collections::hash_map::DefaultHasher;
         use std::hash::{Hash, Hasher};
@@ -99,6 +128,13 @@ impl UniverseBuilder {
         self.parser.calculate_hash().hash(&mut hasher);
         self.imports.hash(&mut hasher);
         self.type_definitions.hash(&mut hasher);
+
+        if let Some(cargo_toml_path) = &self.workspace_cargo_toml {
+            fs::read(cargo_toml_path).ok().hash(&mut hasher);
+            let lockfile = cargo_toml_path.with_file_name("Cargo.lock");
+            fs::read(lockfile).ok().hash(&mut hasher);
+        }
+
         hasher.finish()
     }
 
@@ -124,6 +160,15 @@ impl UniverseBuilder {
         false
     }
 
+    /// Discard the cached build, so the next build reruns cargo.
+    pub fn invalidate_cache(&self) -> Result<()> {
+        let cache_file = self.cache_hash_file();
+        if cache_file.exists() {
+            fs::remove_file(cache_file)?;
+        }
+        Ok(())
+    }
+
     /// Get the path to the compiled universe library.
     pub fn universe_path(&self) -> PathBuf {
         let build_dir = self.config.universe_build_dir();
@@ -133,6 +178,11 @@ impl UniverseBuilder {
 
     /// Build the universe library.
     pub fn build(&self) -> Result<PathBuf> {
+        self.build_with_progress(|_| {})
+    }
+
+    /// Build the universe library, reporting cargo's progress to `on_event`.
+    pub fn build_with_progress(&self, mut on_event: impl FnMut(&BuildEvent)) -> Result<PathBuf> {
         // Check cache first
         if self.is_cache_valid() && self.universe_path().exists() {
             tracing::info!("Using cached universe library");
@@ -164,20 +214,25 @@ impl UniverseBuilder {
         let notebook_rs = "//! Notebook cells module.\n\
                           //! This file is populated by the server for LSP analysis.\n\
                           //! For CLI builds, this is a stub to satisfy the module declaration.\n";
-        fs::write(src_dir.join("notebook.rs"), notebook_rs)?;
+        if self.notebook_module {
+            fs::write(src_dir.join("notebook.rs"), notebook_rs)?;
+        }
 
         // Build with cargo
-        let output = Command::new("cargo")
-            .current_dir(&build_dir)
-            .args(["build", "--release", "--lib"])
-            .output()
+        let mut cmd = Command::new("cargo");
+        cmd.current_dir(&build_dir)
+            .args(["build", "--release", "--lib"]);
+        let (status, stderr) = run_cargo(&mut cmd, &mut on_event, self.abort_handle.as_ref())
             .map_err(|e| Error::Compilation {
                 cell_id: None,
                 message: format!("Failed to run cargo: {}", e),
             })?;
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
+        if self.abort_handle.as_ref().is_some_and(|h| h.is_aborted()) {
+            return Err(Error::CompilationAborted);
+        }
+
+        if !status.success() {
             return Err(Error::Compilation {
                 cell_id: None,
                 message: format!("Universe build failed:\n{}", stderr),
@@ -367,6 +422,12 @@ impl UniverseBuilder {
         lib.push_str("pub use venus::widgets::{WidgetContext, WidgetValue, WidgetDef};\n");
         lib.push_str("pub use venus::widgets::{set_widget_context, take_widget_context};\n\n");
 
+        // Rich output rendering, used by cell wrappers to produce HTML
+        lib.push_str("#[doc(hidden)]\npub use venus::render as __venus_render;\n\n");
+
+        // Panic reports, used by cell wrappers to pass on panic backtraces
+        lib.push_str("#[doc(hidden)]\npub use venus::panic_report as __venus_panic;\n\n");
+
         for dep in self.dependencies() {
             // Convert crate name to valid Rust identifier
             let ident = dep.name.replace('-', "_");
@@ -392,8 +453,10 @@ impl UniverseBuilder {
 
         // Include notebook module for LSP analysis
         // This module is written by the server with current cell content
-        lib.push_str("\n// Notebook cells (for LSP analysis)\n");
-        lib.push_str("pub mod notebook;\n");
+        if self.notebook_module {
+            lib.push_str("\n// Notebook cells (for LSP analysis)\n");
+            lib.push_str("pub mod notebook;\n");
+        }
 
         lib
     }
@@ -497,6 +560,18 @@ mod tests {
         assert_import_reexported(&lib, "venus::prelude::*");
     }
 
+    #[test]
+    fn universe_omits_notebook_module_when_disabled() {
+        assert!(
+            make_builder()
+                .generate_lib_rs()
+                .contains("pub mod notebook;")
+        );
+
+        let builder = make_builder().with_notebook_module(false);
+        assert!(!builder.generate_lib_rs().contains("pub mod notebook;"));
+    }
+
     #[test]
     fn universe_reexports_aliased_import_from_definition_cell() {
         let mut builder = make_builder();
@@ -646,4 +721,50 @@ pub fn hello() -> i32 { 42 }
 
         assert_ne!(hash1, hash2);
     }
+
+    #[test]
+    fn test_hash_changes_with_workspace_manifest() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let cargo_toml = dir.path().join("Cargo.toml");
+        fs::write(&cargo_toml, "[workspace.dependencies]\nserde = \"1.0\"\n").unwrap();
+
+        let toolchain = ToolchainManager::new().unwrap();
+        let mut builder = UniverseBuilder::new(
+            CompilerConfig::default(),
+            toolchain,
+            Some(cargo_toml.clone()),
+        );
+        builder.parse_dependencies("", &[]).unwrap();
+        let hash1 = builder.deps_hash();
+
+        fs::write(
+            &cargo_toml,
+            "[workspace.dependencies]\nserde = \"1.0\"\nanyhow = \"1.0\"\n",
+        )
+        .unwrap();
+        let hash2 = builder.deps_hash();
+        assert_ne!(hash1, hash2);
+
+        fs::write(dir.path().join("Cargo.lock"), "version = 4\n").unwrap();
+        let hash3 = builder.deps_hash();
+        assert_ne!(hash2, hash3);
+    }
+
+    #[test]
+    fn test_invalidate_cache() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let config = CompilerConfig {
+            cache_dir: dir.path().to_path_buf(),
+            ..CompilerConfig::default()
+        };
+        let mut builder = UniverseBuilder::new(config, ToolchainManager::new().unwrap(), None);
+        builder.parse_dependencies("", &[]).unwrap();
+
+        builder.save_cache_hash().unwrap();
+        assert!(builder.is_cache_valid());
+
+        builder.invalidate_cache().unwrap();
+        assert!(!builder.is_cache_valid());
+        builder.invalidate_cache().unwrap();
+    }
 }
@@ -0,0 +1,126 @@
+//! Environment files for cell execution.
+//!
+//! Reads `KEY=VALUE` pairs in dotenv format so notebooks can get
+//! configuration (API keys, paths) from the environment. The variables only
+//! affect the process that runs cells; they are not part of any compilation
+//! input or cache key.
+
+use std::fs;
+use std::path::Path;
+
+use crate::error::{Error, Result};
+
+/// Load variables from a dotenv file, in file order.
+pub fn load_env_file(path: impl AsRef<Path>) -> Result<Vec<(String, String)>> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path)?;
+    parse_env_file(&content).map_err(|e| match e {
+        Error::Parse(msg) => Error::Parse(format!("{}: {}", path.display(), msg)),
+        other => other,
+    })
+}
+
+/// Parse dotenv content.
+///
+/// Supports blank lines, `#` comments, an optional `export ` prefix, single
+/// or double quoted values, and trailing ` #` comments on unquoted values.
+/// Inside double quotes, `\n`, `\"` and `\\` are unescaped.
+pub fn parse_env_file(content: &str) -> Result<Vec<(String, String)>> {
+    let mut vars = Vec::new();
+
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(Error::Parse(format!(
+                "line {}: expected KEY=VALUE",
+                index + 1
+            )));
+        };
+        let key = key.trim();
+        if key.is_empty()
+            || key.starts_with(|c: char| c.is_ascii_digit())
+            || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            return Err(Error::Parse(format!(
+                "line {}: invalid variable name '{}'",
+                index + 1,
+                key
+            )));
+        }
+
+        vars.push((key.to_string(), parse_value(value.trim())));
+    }
+
+    Ok(vars)
+}
+
+/// Unquote a value, or strip a trailing comment from an unquoted one.
+fn parse_value(value: &str) -> String {
+    if let Some(inner) = value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) {
+        return inner.to_string();
+    }
+
+    if let Some(inner) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+        let mut unescaped = String::with_capacity(inner.len());
+        let mut chars = inner.chars();
+        while let Some(c) = chars.next() {
+            if c == '\\' {
+                match chars.next() {
+                    Some('n') => unescaped.push('\n'),
+                    Some(other) => unescaped.push(other),
+                    None => unescaped.push('\\'),
+                }
+            } else {
+                unescaped.push(c);
+            }
+        }
+        return unescaped;
+    }
+
+    match value.find(" #") {
+        Some(pos) => value[..pos].trim_end().to_string(),
+        None => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_env_file() {
+        let content = r#"
+# API settings
+API_KEY=abc123
+export DATA_DIR = /tmp/data # where inputs live
+GREETING="hello \"world\"\nbye"
+RAW='a # b'
+EMPTY=
+"#;
+        let vars = parse_env_file(content).unwrap();
+        assert_eq!(
+            vars,
+            vec![
+                ("API_KEY".to_string(), "abc123".to_string()),
+                ("DATA_DIR".to_string(), "/tmp/data".to_string()),
+                ("GREETING".to_string(), "hello \"world\"\nbye".to_string()),
+                ("RAW".to_string(), "a # b".to_string()),
+                ("EMPTY".to_string(), String::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_env_file_errors() {
+        let err = parse_env_file("OK=1\nnot a pair\n").unwrap_err();
+        assert!(matches!(&err, Error::Parse(msg) if msg.starts_with("line 2")));
+
+        assert!(parse_env_file("1ABC=x").is_err());
+        assert!(parse_env_file("A-B=x").is_err());
+    }
+}
@@ -59,10 +59,21 @@ pub enum Error {
     #[error("execution error: {0}")]
     Execution(String),
 
+    /// A cell panicked during execution.
+    #[error("cell panicked: {message}")]
+    CellPanic {
+        message: String,
+        backtrace: Option<String>,
+    },
+
     /// Execution was aborted by user request.
     #[error("execution aborted")]
     Aborted,
 
+    /// Compilation was aborted by user request.
+    #[error("compilation aborted")]
+    CompilationAborted,
+
     /// Invalid operation (e.g., moving first cell up).
     #[error("invalid operation: {0}")]
     InvalidOperation(String),
@@ -113,7 +124,11 @@ impl Error {
                 }
             }
             Error::Toolchain(msg) => {
-                if msg.contains("rustc") || msg.contains("not found") {
+                if msg.contains("rustup target add") {
+                    Some("Cross-compilation needs the target's standard library. Install it with the `rustup target add` command above.".to_string())
+                } else if msg.contains("unknown target triple") {
+                    Some("Check the spelling of the target triple against `rustc --print target-list`.".to_string())
+                } else if msg.contains("rustc") || msg.contains("not found") {
                     Some("Install Rust from https://rustup.rs if not already installed. Ensure 'rustc' is in your PATH.".to_string())
                 } else if msg.contains("cranelift") {
                     Some("Cranelift backend is optional. Venus will fall back to standard rustc compilation.".to_string())
@@ -130,6 +145,13 @@ impl Error {
                     None
                 }
             }
+            Error::CellPanic { backtrace, .. } => {
+                if backtrace.is_some() {
+                    Some("Check your cell code for unwrap() calls on None/Err values, array out-of-bounds access, or other panic sources. Add proper error handling.".to_string())
+                } else {
+                    Some("Check your cell code for unwrap() calls on None/Err values, array out-of-bounds access, or other panic sources. Set RUST_BACKTRACE=1 before starting Venus to get a backtrace.".to_string())
+                }
+            }
             Error::Io(io_err) => {
                 match io_err.kind() {
                     std::io::ErrorKind::NotFound => {
@@ -153,7 +175,7 @@ impl Error {
             }
             // These errors are self-explanatory or context-specific
             Error::Parse(_) | Error::LibraryLoad(_) | Error::Serialization(_) |
-            Error::Aborted | Error::InvalidOperation(_) => None,
+            Error::Aborted | Error::CompilationAborted | Error::InvalidOperation(_) => None,
         }
     }
 
@@ -12,6 +12,7 @@ use tracing::{debug, trace, warn};
 use crate::compile::CompiledCell;
 use crate::error::{Error, Result};
 use crate::graph::CellId;
+use crate::panic_report::PanicReport;
 use crate::state::{BoxedOutput, StateManager};
 
 use super::context::{AbortHandle, ExecutionCallback};
@@ -132,6 +133,19 @@ impl LinearExecutor {
         cell_id: CellId,
         inputs: &[Arc<BoxedOutput>],
     ) -> Result<BoxedOutput> {
+        self.execute_cell_with_widgets(cell_id, inputs, Vec::new())
+            .map(|(output, _widgets_json)| output)
+    }
+
+    /// Execute a single cell with the given inputs and widget values.
+    ///
+    /// Returns the cell output and any registered widget definitions as JSON.
+    pub fn execute_cell_with_widgets(
+        &mut self,
+        cell_id: CellId,
+        inputs: &[Arc<BoxedOutput>],
+        widget_values_json: Vec<u8>,
+    ) -> Result<(BoxedOutput, Vec<u8>)> {
         // Check for abort before starting
         if self.is_aborted() {
             return Err(Error::Aborted);
@@ -148,7 +162,7 @@ impl LinearExecutor {
         }
 
         // Execute the cell
-        let result = self.call_cell_ffi(loaded, inputs);
+        let result = self.call_cell_ffi(loaded, inputs, &widget_values_json);
 
         // Check for abort after execution (cell may have been aborted mid-flight)
         if self.is_aborted() {
@@ -238,7 +252,8 @@ impl LinearExecutor {
         &self,
         loaded: &LoadedCell,
         inputs: &[Arc<BoxedOutput>],
-    ) -> Result<BoxedOutput> {
+        widget_values: &[u8],
+    ) -> Result<(BoxedOutput, Vec<u8>)> {
         // Verify input count matches
         if inputs.len() != loaded.dep_count {
             return Err(Error::Execution(format!(
@@ -251,16 +266,20 @@ impl LinearExecutor {
 
         // For cells with no dependencies, use the simple path
         if loaded.dep_count == 0 {
-            return self.call_cell_no_deps(loaded);
+            return self.call_cell_no_deps(loaded, widget_values);
         }
 
         // For cells with dependencies, we need to construct the FFI call dynamically
         // This is complex because the number of parameters varies
-        self.call_cell_with_deps(loaded, inputs)
+        self.call_cell_with_deps(loaded, inputs, widget_values)
     }
 
     /// Call a cell with no dependencies.
-    fn call_cell_no_deps(&self, loaded: &LoadedCell) -> Result<BoxedOutput> {
+    fn call_cell_no_deps(
+        &self,
+        loaded: &LoadedCell,
+        widget_values: &[u8],
+    ) -> Result<(BoxedOutput, Vec<u8>)> {
         let symbol_name = loaded.entry_symbol();
 
         // Safety: We trust the symbol exists and has the correct signature
@@ -272,9 +291,6 @@ impl LinearExecutor {
         let mut out_ptr: *mut u8 = std::ptr::null_mut();
         let mut out_len: usize = 0;
 
-        // Empty widget values (LinearExecutor doesn't support widgets)
-        let widget_values: &[u8] = &[];
-
         // Safety: We're calling a function generated by our compiler
         let result_code = unsafe {
             func(
@@ -296,12 +312,10 @@ impl LinearExecutor {
         &self,
         loaded: &LoadedCell,
         inputs: &[Arc<BoxedOutput>],
-    ) -> Result<BoxedOutput> {
+        widget_values: &[u8],
+    ) -> Result<(BoxedOutput, Vec<u8>)> {
         let symbol_name = loaded.entry_symbol();
 
-        // Empty widget values (LinearExecutor doesn't support widgets)
-        let widget_values: &[u8] = &[];
-
         debug!(
             cell = %loaded.compiled.name,
             dep_count = inputs.len(),
@@ -424,7 +438,8 @@ impl LinearExecutor {
         }
     }
 
-    /// Process the FFI result and convert output to BoxedOutput.
+    /// Process the FFI result and convert output to BoxedOutput, returning
+    /// the widget definitions JSON alongside it.
     ///
     /// Output format from cells:
     /// - display_len (8 bytes, u64 LE): length of display string
@@ -438,7 +453,7 @@ impl LinearExecutor {
         out_ptr: *mut u8,
         out_len: usize,
         cell_name: &str,
-    ) -> Result<BoxedOutput> {
+    ) -> Result<(BoxedOutput, Vec<u8>)> {
         let result = ExecutionResult::from(result_code);
 
         match result {
@@ -505,11 +520,12 @@ impl LinearExecutor {
 
                 // Format is: display_len | display_bytes | widgets_len | widgets_json | rkyv_data
                 let display_text = String::from_utf8_lossy(&bytes[8..display_end]).to_string();
+                let widgets_json = bytes[display_end + 8..widgets_end].to_vec();
                 let rkyv_data = bytes[widgets_end..].to_vec();
 
-                Ok(BoxedOutput::from_raw_bytes_with_display(
-                    rkyv_data,
-                    display_text,
+                Ok((
+                    BoxedOutput::from_raw_bytes_with_display(rkyv_data, display_text),
+                    widgets_json,
                 ))
             }
             ExecutionResult::DeserializationError => {
@@ -530,10 +546,26 @@ impl LinearExecutor {
                 "Cell {} failed to serialize output",
                 cell_name
             ))),
-            ExecutionResult::Panic => Err(Error::Execution(format!(
-                "Cell {} panicked during execution. Check for unwrap() on None/Err, out-of-bounds access, or other panic sources.",
-                cell_name
-            ))),
+            ExecutionResult::Panic => {
+                // The cell hands over its panic report, if it has one
+                let report = (!out_ptr.is_null() && out_len > 0)
+                    .then(|| {
+                        // Safety: The cell allocated this memory via libc malloc
+                        let memory_guard = unsafe { FfiMemoryGuard::new(out_ptr) };
+                        PanicReport::from_bytes(memory_guard.as_slice(out_len))
+                    })
+                    .flatten();
+                Err(match report {
+                    Some(report) => Error::CellPanic {
+                        message: report.message,
+                        backtrace: report.backtrace,
+                    },
+                    None => Error::CellPanic {
+                        message: format!("Cell {} panicked during execution", cell_name),
+                        backtrace: None,
+                    },
+                })
+            }
         }
     }
 }
@@ -54,6 +54,7 @@
 //! - `parallel` - ParallelExecutor for concurrent execution
 //! - `process` - ProcessExecutor for isolated execution
 //! - `reload` - Hot-reload support
+//! - `resources` - CPU and memory sampling of worker processes
 //! - `windows_dll` - Windows DLL hot-reload handler
 
 mod context;
@@ -63,6 +64,7 @@ mod loaded_cell;
 mod parallel;
 mod process;
 mod reload;
+mod resources;
 mod windows_dll;
 
 pub use context::{AbortHandle, CellContext, ExecutionCallback};
@@ -70,6 +72,7 @@ pub use executor::LinearExecutor;
 pub use ffi::ExecutionResult;
 pub use loaded_cell::LoadedCell;
 pub use parallel::ParallelExecutor;
-pub use process::{ExecutorKillHandle, ProcessExecutor};
+pub use process::{ExecutionProfile, ExecutorKillHandle, ProcessExecutor, ResourceMonitor};
 pub use reload::HotReloader;
+pub use resources::{ResourceSample, sample_process};
 pub use windows_dll::WindowsDllHandler;
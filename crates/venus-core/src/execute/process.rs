@@ -6,14 +6,16 @@
 use std::collections::HashMap;
 use std::path::Path;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use crate::compile::CompiledCell;
 use crate::error::{Error, Result};
 use crate::graph::CellId;
-use crate::ipc::{WorkerKillHandle, WorkerPool};
-use crate::state::{BoxedOutput, StateManager};
+use crate::ipc::{WorkerKillHandle, WorkerPool, WorkerSpawnConfig};
+use crate::state::{BoxedOutput, OutputAssembler, StateManager};
 
 use super::context::{AbortHandle, ExecutionCallback};
+use super::resources::{ResourceSample, sample_process};
 
 /// Process-based executor that runs cells in isolated worker processes.
 ///
@@ -37,6 +39,20 @@ pub struct ProcessExecutor {
     /// This is wrapped in Arc<Mutex<>> so it can be cloned and killed from
     /// another thread while execute_cell is running.
     current_worker_kill: Arc<Mutex<Option<WorkerKillHandle>>>,
+    /// Phase timings of the last successful execution.
+    last_profile: Option<ExecutionProfile>,
+}
+
+/// Where the time of a cell execution went.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExecutionProfile {
+    /// Worker round trips (loading the cell and executing it), excluding the
+    /// time the cell itself ran.
+    pub ipc: Duration,
+    /// Time the cell ran inside the worker, including serializing its output.
+    pub exec: Duration,
+    /// Time spent copying inputs and decoding the output in this process.
+    pub serialize: Duration,
 }
 
 /// Info about a compiled cell (without the loaded library)
@@ -77,6 +93,23 @@ impl ExecutorKillHandle {
     }
 }
 
+/// Thread-safe handle for sampling the resource usage of an executor's
+/// current worker from another thread.
+#[derive(Clone)]
+pub struct ResourceMonitor {
+    inner: Arc<Mutex<Option<WorkerKillHandle>>>,
+}
+
+impl ResourceMonitor {
+    /// Sample the worker that is currently executing a cell.
+    ///
+    /// Returns `None` if no cell is executing or sampling is unsupported.
+    pub fn sample(&self) -> Option<ResourceSample> {
+        let pid = self.inner.lock().ok()?.as_ref()?.pid();
+        sample_process(pid)
+    }
+}
+
 impl ProcessExecutor {
     /// Create a new process executor.
     pub fn new(state_dir: impl AsRef<Path>) -> Result<Self> {
@@ -87,6 +120,7 @@ impl ProcessExecutor {
             abort_handle: None,
             worker_pool: WorkerPool::new(4), // Pool of up to 4 workers
             current_worker_kill: Arc::new(Mutex::new(None)),
+            last_profile: None,
         })
     }
 
@@ -99,6 +133,7 @@ impl ProcessExecutor {
             abort_handle: None,
             worker_pool: WorkerPool::new(4),
             current_worker_kill: Arc::new(Mutex::new(None)),
+            last_profile: None,
         }
     }
 
@@ -111,6 +146,7 @@ impl ProcessExecutor {
             abort_handle: None,
             worker_pool: WorkerPool::with_warm_workers(pool_size, pool_size.min(2))?,
             current_worker_kill: Arc::new(Mutex::new(None)),
+            last_profile: None,
         })
     }
 
@@ -180,6 +216,22 @@ impl ProcessExecutor {
         cell_id: CellId,
         inputs: &[Arc<BoxedOutput>],
         widget_values_json: Vec<u8>,
+    ) -> Result<(BoxedOutput, Vec<u8>)> {
+        self.execute_cell_streamed(cell_id, inputs, widget_values_json, &mut |_, _| {})
+    }
+
+    /// Execute a single cell, streaming the display text of a large output.
+    ///
+    /// Outputs too large for a single IPC message arrive in segments. As
+    /// they do, `on_text` is called with the display text they complete and
+    /// whether the text is now complete, and the output is rebuilt from
+    /// them. `on_text` is not called for outputs that arrive in one piece.
+    pub fn execute_cell_streamed(
+        &mut self,
+        cell_id: CellId,
+        inputs: &[Arc<BoxedOutput>],
+        widget_values_json: Vec<u8>,
+        on_text: &mut dyn FnMut(&str, bool),
     ) -> Result<(BoxedOutput, Vec<u8>)> {
         // Check for abort before starting
         if self.is_aborted() {
@@ -209,6 +261,7 @@ impl ProcessExecutor {
         }
 
         // Load the cell in the worker
+        let load_start = Instant::now();
         worker.load_cell(
             compiled.dylib_path.clone(),
             dep_count,
@@ -216,11 +269,15 @@ impl ProcessExecutor {
             compiled.name.clone(),
         )?;
 
+        let load_time = load_start.elapsed();
+
         // Prepare inputs as raw bytes
+        let serialize_start = Instant::now();
         let input_bytes: Vec<Vec<u8>> = inputs
             .iter()
             .map(|output| output.bytes().to_vec())
             .collect();
+        let mut serialize_time = serialize_start.elapsed();
 
         // Check for abort after load
         if self.is_aborted() {
@@ -237,7 +294,18 @@ impl ProcessExecutor {
         }
 
         // Execute the cell with widget values
-        let result = worker.execute_with_widgets(input_bytes, widget_values_json);
+        let execute_start = Instant::now();
+        let mut assembler = OutputAssembler::new();
+        let mut streaming = false;
+        let result =
+            worker.execute_streamed(input_bytes, widget_values_json, &mut |segment, last| {
+                let text = assembler.push(&segment);
+                streaming |= !last;
+                if streaming && let Some((text, done)) = text {
+                    on_text(&text, done);
+                }
+            });
+        let round_trip = execute_start.elapsed();
 
         // Clear kill handle
         {
@@ -258,9 +326,17 @@ impl ProcessExecutor {
 
         // Process result
         match result {
-            Ok((bytes, widgets_json)) => {
+            Ok((widgets_json, exec_time)) => {
                 // Parse the output bytes into BoxedOutput
-                let output = self.parse_output_bytes(&bytes, &compiled.name)?;
+                let parse_start = Instant::now();
+                let output = assembler.finish(&compiled.name)?;
+                serialize_time += parse_start.elapsed();
+
+                self.last_profile = Some(ExecutionProfile {
+                    ipc: load_time + round_trip.saturating_sub(exec_time),
+                    exec: exec_time,
+                    serialize: serialize_time,
+                });
 
                 if let Some(ref callback) = self.callback {
                     callback.on_cell_completed(cell_id, &compiled.name);
@@ -277,41 +353,11 @@ impl ProcessExecutor {
         }
     }
 
-    /// Parse output bytes from worker into BoxedOutput.
+    /// Take the phase timings of the last successful execution.
     ///
-    /// Output format (after worker strips widget data):
-    /// - display_len (8 bytes, u64 LE): length of display string
-    /// - display_bytes (N bytes): display string (UTF-8)
-    /// - rkyv_data (remaining bytes): rkyv-serialized data
-    fn parse_output_bytes(&self, bytes: &[u8], cell_name: &str) -> Result<BoxedOutput> {
-        if bytes.len() < 8 {
-            return Err(Error::Execution(format!(
-                "Cell {} output too short: {} bytes (need at least 8 for header)",
-                cell_name,
-                bytes.len()
-            )));
-        }
-
-        // Read display_len
-        let display_len = u64::from_le_bytes(bytes[0..8].try_into().unwrap()) as usize;
-        let display_end = 8 + display_len;
-
-        if bytes.len() < display_end {
-            return Err(Error::Execution(format!(
-                "Cell {} output too short for display data",
-                cell_name
-            )));
-        }
-
-        // Worker already stripped widgets_len and widgets_json
-        // Format is: display_len | display_bytes | rkyv_data
-        let display_text = String::from_utf8_lossy(&bytes[8..display_end]).to_string();
-        let rkyv_data = bytes[display_end..].to_vec();
-
-        Ok(BoxedOutput::from_raw_bytes_with_display(
-            rkyv_data,
-            display_text,
-        ))
+    /// Returns `None` if no execution has succeeded since the last call.
+    pub fn take_last_profile(&mut self) -> Option<ExecutionProfile> {
+        self.last_profile.take()
     }
 
     /// Execute a cell and store the output in the state manager.
@@ -383,6 +429,13 @@ impl ProcessExecutor {
         })
     }
 
+    /// Get a handle for sampling the resource usage of the executing worker.
+    pub fn resource_monitor(&self) -> ResourceMonitor {
+        ResourceMonitor {
+            inner: self.current_worker_kill.clone(),
+        }
+    }
+
     /// Abort execution and kill any running cell.
     ///
     /// Sets the abort flag and kills the current worker.
@@ -403,6 +456,15 @@ impl ProcessExecutor {
         &mut self.state
     }
 
+    /// Set the environment variables and working directory of the worker
+    /// processes that run cells.
+    ///
+    /// They only affect execution, not compilation or cache keys. Idle
+    /// workers are restarted to pick them up.
+    pub fn set_worker_config(&mut self, config: WorkerSpawnConfig) {
+        self.worker_pool.set_spawn_config(config);
+    }
+
     /// Shutdown the executor and all workers.
     pub fn shutdown(&mut self) {
         self.worker_pool.shutdown();
@@ -0,0 +1,71 @@
+//! Resource usage sampling for worker processes.
+//!
+//! On Linux, samples are read from `/proc/<pid>/stat` and `/proc/<pid>/statm`.
+//! Other platforms report no samples.
+
+/// A point-in-time measurement of a process's resource usage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResourceSample {
+    /// Resident set size in bytes.
+    pub rss_bytes: u64,
+    /// User plus system CPU time consumed so far, in milliseconds.
+    pub cpu_ms: u64,
+}
+
+/// Sample the resource usage of a process.
+///
+/// Returns `None` if the process does not exist or the platform is not
+/// supported.
+#[cfg(target_os = "linux")]
+pub fn sample_process(pid: u32) -> Option<ResourceSample> {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    let statm = std::fs::read_to_string(format!("/proc/{}/statm", pid)).ok()?;
+
+    // The command name (field 2) may contain spaces, so fields are counted
+    // from the closing parenthesis. utime and stime are fields 14 and 15.
+    let fields: Vec<&str> = stat.rsplit_once(')')?.1.split_whitespace().collect();
+    let utime: u64 = fields.get(11)?.parse().ok()?;
+    let stime: u64 = fields.get(12)?.parse().ok()?;
+    let resident_pages: u64 = statm.split_whitespace().nth(1)?.parse().ok()?;
+
+    // SAFETY: sysconf has no preconditions
+    let (ticks_per_sec, page_size) = unsafe {
+        (
+            libc::sysconf(libc::_SC_CLK_TCK),
+            libc::sysconf(libc::_SC_PAGESIZE),
+        )
+    };
+    if ticks_per_sec <= 0 || page_size <= 0 {
+        return None;
+    }
+
+    Some(ResourceSample {
+        rss_bytes: resident_pages * page_size as u64,
+        cpu_ms: (utime + stime) * 1000 / ticks_per_sec as u64,
+    })
+}
+
+/// Sample the resource usage of a process.
+///
+/// Returns `None` if the process does not exist or the platform is not
+/// supported.
+#[cfg(not(target_os = "linux"))]
+pub fn sample_process(_pid: u32) -> Option<ResourceSample> {
+    None
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_current_process() {
+        let sample = sample_process(std::process::id()).unwrap();
+        assert!(sample.rss_bytes > 0);
+    }
+
+    #[test]
+    fn test_sample_missing_process() {
+        assert_eq!(sample_process(u32::MAX), None);
+    }
+}
@@ -0,0 +1,311 @@
+//! Impact analysis for edits to definition cells.
+//!
+//! Every definition cell is compiled into the universe crate, so historically
+//! any edit marked every cell dirty. Comparing the items before and after an
+//! edit narrows that down: comment and doc edits affect nothing, and a change
+//! to the body of an existing item only affects the cells that use it.
+
+use std::collections::{BTreeMap, HashSet};
+
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::{Item, UseTree};
+
+use super::subgraph::{collect_idents, defined_names};
+
+/// The effect of editing a definition cell.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DefinitionChange {
+    /// Only comments, doc comments or formatting changed.
+    Unchanged,
+    /// The same items are defined, and the named ones changed.
+    Changed(HashSet<String>),
+    /// Items were added or removed, or the content could not be analyzed.
+    Unknown,
+}
+
+/// Compare the content of a definition cell before and after an edit.
+pub fn classify_definition_change(old: &str, new: &str) -> DefinitionChange {
+    let (Some(old_items), Some(new_items)) = (item_tokens(old), item_tokens(new)) else {
+        return DefinitionChange::Unknown;
+    };
+
+    if !old_items.keys().eq(new_items.keys()) {
+        return DefinitionChange::Unknown;
+    }
+
+    let changed: HashSet<String> = old_items
+        .iter()
+        .filter(|(key, tokens)| new_items[*key] != **tokens)
+        .flat_map(|((_, names), _)| names.iter().cloned())
+        .collect();
+
+    if changed.is_empty() {
+        DefinitionChange::Unchanged
+    } else {
+        DefinitionChange::Changed(changed)
+    }
+}
+
+/// Extend `symbols` with every definition that refers to one of them,
+/// directly or through other definitions.
+pub fn affected_symbols(definitions: &[String], mut symbols: HashSet<String>) -> HashSet<String> {
+    let mut candidates: Vec<(Vec<String>, HashSet<String>)> = Vec::new();
+    for content in definitions {
+        let Ok(file) = syn::parse_file(content) else {
+            continue;
+        };
+        for item in &file.items {
+            if let Some(names) = defined_names(item) {
+                let mut idents = HashSet::new();
+                collect_idents(item.to_token_stream(), &mut idents);
+                candidates.push((names, idents));
+            }
+        }
+    }
+
+    loop {
+        let (hit, rest): (Vec<_>, Vec<_>) = candidates
+            .into_iter()
+            .partition(|(_, idents)| idents.iter().any(|i| symbols.contains(i)));
+        candidates = rest;
+        if hit.is_empty() {
+            return symbols;
+        }
+        symbols.extend(hit.into_iter().flat_map(|(names, _)| names));
+    }
+}
+
+/// Names a definition cell makes available to cells: its named items, the
+/// types its `impl` blocks are for, and the names its `use` items import.
+///
+/// Returns `None` if the content does not parse.
+pub fn defined_symbols(content: &str) -> Option<HashSet<String>> {
+    let file = syn::parse_file(content).ok()?;
+    let mut symbols = HashSet::new();
+
+    for item in &file.items {
+        match item {
+            Item::Use(item_use) => imported_names(&item_use.tree, &mut symbols),
+            _ => symbols.extend(defined_names(item).unwrap_or_default()),
+        }
+    }
+
+    Some(symbols)
+}
+
+/// Names brought into scope by a `use` tree; globs contribute nothing.
+fn imported_names(tree: &UseTree, names: &mut HashSet<String>) {
+    match tree {
+        UseTree::Path(path) => imported_names(&path.tree, names),
+        UseTree::Name(name) if name.ident != "self" => {
+            names.insert(name.ident.to_string());
+        }
+        UseTree::Name(_) | UseTree::Glob(_) => {}
+        UseTree::Rename(rename) => {
+            names.insert(rename.rename.to_string());
+        }
+        UseTree::Group(group) => {
+            for tree in &group.items {
+                imported_names(tree, names);
+            }
+        }
+    }
+}
+
+/// Whether a cell's source mentions any of `symbols`.
+///
+/// Sources that do not parse are assumed to.
+pub fn references_any(source: &str, symbols: &HashSet<String>) -> bool {
+    let Ok(tokens) = source.parse::<TokenStream>() else {
+        return true;
+    };
+    let mut idents = HashSet::new();
+    collect_idents(tokens, &mut idents);
+    idents.iter().any(|i| symbols.contains(i))
+}
+
+/// Remove `#[doc = ...]` and `#![doc = ...]` attributes (i.e. doc comments)
+/// from a token stream, at every nesting level.
+pub(crate) fn strip_doc_attributes(tokens: TokenStream) -> TokenStream {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    let mut output = Vec::with_capacity(tokens.len());
+    let mut i = 0;
+
+    while i < tokens.len() {
+        if let TokenTree::Punct(p) = &tokens[i]
+            && p.as_char() == '#'
+        {
+            let bang =
+                matches!(&tokens.get(i + 1), Some(TokenTree::Punct(p)) if p.as_char() == '!');
+            let group = i + 1 + usize::from(bang);
+            if tokens.get(group).is_some_and(is_doc_group) {
+                i = group + 1;
+                continue;
+            }
+        }
+
+        output.push(match &tokens[i] {
+            TokenTree::Group(g) => {
+                let mut group =
+                    proc_macro2::Group::new(g.delimiter(), strip_doc_attributes(g.stream()));
+                group.set_span(g.span());
+                TokenTree::Group(group)
+            }
+            other => other.clone(),
+        });
+        i += 1;
+    }
+
+    output.into_iter().collect()
+}
+
+/// Whether a token is the `[doc ...]` part of an attribute.
+fn is_doc_group(token: &TokenTree) -> bool {
+    match token {
+        TokenTree::Group(g) if g.delimiter() == Delimiter::Bracket => matches!(
+            g.stream().into_iter().next(),
+            Some(TokenTree::Ident(ident)) if ident == "doc"
+        ),
+        _ => false,
+    }
+}
+
+/// Items keyed by what they define, with their doc-free tokens.
+///
+/// Items that define nothing (`use`, `extern crate`, ...) are keyed by their
+/// own tokens, so editing one shows up as a removal plus an addition.
+fn item_tokens(content: &str) -> Option<BTreeMap<(String, Vec<String>), String>> {
+    let file = syn::parse_file(content).ok()?;
+    let mut items: BTreeMap<(String, Vec<String>), String> = BTreeMap::new();
+
+    for item in &file.items {
+        let tokens = strip_doc_attributes(item.to_token_stream()).to_string();
+        let key = match (item, defined_names(item)) {
+            (Item::Impl(i), Some(mut names)) => {
+                names.sort();
+                let trait_ = i
+                    .trait_
+                    .as_ref()
+                    .map(|(_, path, _)| path.to_token_stream().to_string())
+                    .unwrap_or_default();
+                let key = format!("impl {} {}", trait_, i.self_ty.to_token_stream());
+                (key, names)
+            }
+            (_, Some(names)) => (names.join(","), names),
+            (_, None) => (tokens.clone(), Vec::new()),
+        };
+        // Several impls of the same trait for a type share a key
+        items.entry(key).or_default().push_str(&tokens);
+    }
+
+    Some(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(names: &[&str]) -> HashSet<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    const DEFS: &str = r#"use std::collections::HashMap;
+
+/// A point
+pub struct Point {
+    pub x: f64,
+}
+
+fn norm(p: &Point) -> f64 {
+    p.x.abs()
+}
+
+impl Point {
+    pub fn scaled(&self) -> f64 {
+        norm(self) * 2.0
+    }
+}
+"#;
+
+    #[test]
+    fn test_comment_and_doc_edits_are_unchanged() {
+        let edited = DEFS
+            .replace("/// A point", "/// A point in space\n// TODO: y")
+            .replace("    pub x: f64,", "    /// Horizontal\n    pub x:   f64,");
+        assert_eq!(
+            classify_definition_change(DEFS, &edited),
+            DefinitionChange::Unchanged
+        );
+    }
+
+    #[test]
+    fn test_body_edit_reports_changed_item() {
+        let edited = DEFS.replace("p.x.abs()", "p.x * p.x");
+        assert_eq!(
+            classify_definition_change(DEFS, &edited),
+            DefinitionChange::Changed(set(&["norm"]))
+        );
+
+        let edited = DEFS.replace("* 2.0", "* 3.0");
+        assert_eq!(
+            classify_definition_change(DEFS, &edited),
+            DefinitionChange::Changed(set(&["Point"]))
+        );
+    }
+
+    #[test]
+    fn test_added_removed_or_invalid_items_are_unknown() {
+        let added = format!("{}\npub struct Other;\n", DEFS);
+        assert_eq!(
+            classify_definition_change(DEFS, &added),
+            DefinitionChange::Unknown
+        );
+
+        let renamed = DEFS.replace("HashMap;", "BTreeMap;");
+        assert_eq!(
+            classify_definition_change(DEFS, &renamed),
+            DefinitionChange::Unknown
+        );
+
+        assert_eq!(
+            classify_definition_change(DEFS, "pub struct {"),
+            DefinitionChange::Unknown
+        );
+    }
+
+    #[test]
+    fn test_affected_symbols_follow_references() {
+        let affected = affected_symbols(&[DEFS.to_string()], set(&["norm"]));
+        assert_eq!(affected, set(&["norm", "Point"]));
+
+        assert!(references_any(
+            "pub fn a(p: &Point) -> f64 { p.scaled() }",
+            &affected
+        ));
+        assert!(!references_any("pub fn b() -> i32 { 1 }", &affected));
+    }
+
+    #[test]
+    fn test_defined_symbols() {
+        let symbols = defined_symbols(&format!(
+            "{}\nuse std::io::{{self, Read as R}};\nuse std::fmt::*;",
+            DEFS
+        ))
+        .unwrap();
+        assert_eq!(symbols, set(&["HashMap", "Point", "norm", "R"]));
+        assert_eq!(defined_symbols("pub struct {"), None);
+    }
+
+    #[test]
+    fn test_strip_doc_attributes() {
+        let tokens: TokenStream =
+            "#![doc = \"m\"] #[doc = \"a\"] #[derive(Debug)] struct A { #[doc = \"f\"] x: u8 }"
+                .parse()
+                .unwrap();
+        let stripped = strip_doc_attributes(tokens).to_string();
+        assert!(!stripped.contains("doc"));
+        assert!(stripped.contains("derive"));
+        assert!(stripped.contains("x : u8"));
+    }
+}
@@ -6,14 +6,23 @@
 //! - Topological ordering for execution
 //! - Cycle detection with helpful error messages
 //! - Source file editing for cell insertion, deletion, and reordering
+//! - Extraction of self-contained subsets of a notebook
+//! - Impact analysis for definition cell edits
 
+mod definition_change;
 mod parser;
 mod source_editor;
+mod subgraph;
 mod types;
 
-pub use parser::{CellParser, ParseResult};
-pub use source_editor::{MoveDirection, SourceEditor};
+pub(crate) use definition_change::strip_doc_attributes;
+pub use definition_change::{
+    DefinitionChange, affected_symbols, classify_definition_change, defined_symbols, references_any,
+};
+pub use parser::{CellParser, ParseResult, SyntaxError};
+pub use source_editor::{BlockMove, CellConversion, MoveDirection, SourceEditor};
+pub use subgraph::extract_subgraph;
 pub use types::{
     CellId, CellInfo, CellType, DefinitionCell, DefinitionType, Dependency, GraphEngine,
-    MarkdownCell, SourceSpan,
+    MarkdownCell, OutputFormat, SourceSpan, UnparsedRegion, unqualified_name,
 };
@@ -3,9 +3,15 @@
 use std::path::Path;
 use syn::spanned::Spanned;
 use syn::visit::Visit;
-use syn::{Attribute, File, FnArg, ItemFn, Pat, ReturnType, Type};
-
-use super::types::{CellId, CellInfo, DefinitionCell, Dependency, MarkdownCell, SourceSpan};
+use syn::{
+    Attribute, Block, File, FnArg, ImplItemFn, Item, ItemFn, ItemImpl, Pat, ReturnType, Signature,
+    Type,
+};
+
+use super::types::{
+    CellId, CellInfo, DefinitionCell, Dependency, MarkdownCell, OutputFormat, SourceSpan,
+    UnparsedRegion,
+};
 use crate::error::{Error, Result};
 
 /// Result of parsing a notebook file.
@@ -17,6 +23,19 @@ pub struct ParseResult {
     pub markdown_cells: Vec<MarkdownCell>,
     /// Definition cells (imports, types, helper functions).
     pub definition_cells: Vec<DefinitionCell>,
+    /// Top-level items skipped by [`CellParser::parse_str_partial`].
+    pub unparsed_regions: Vec<UnparsedRegion>,
+}
+
+/// A Rust syntax error and where it occurred.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxError {
+    /// Error message from the Rust parser.
+    pub message: String,
+    /// Line of the error (1-based).
+    pub line: usize,
+    /// Column of the error (1-based).
+    pub column: usize,
 }
 
 /// Parser for extracting cells from Rust source files.
@@ -31,6 +50,8 @@ pub struct CellParser {
     source_file: std::path::PathBuf,
     /// Source code (for extracting spans)
     source_code: String,
+    /// Type of the inherent impl block being visited, if any
+    impl_type: Option<String>,
 }
 
 impl CellParser {
@@ -42,9 +63,22 @@ impl CellParser {
             definition_cells: Vec::new(),
             source_file: std::path::PathBuf::new(),
             source_code: String::new(),
+            impl_type: None,
         }
     }
 
+    /// Check that source code is valid Rust, locating the first syntax error.
+    pub fn check_syntax(source: &str) -> std::result::Result<(), SyntaxError> {
+        syn::parse_file(source).map(|_| ()).map_err(|e| {
+            let start = e.span().start();
+            SyntaxError {
+                message: e.to_string(),
+                line: start.line,
+                column: start.column + 1,
+            }
+        })
+    }
+
     /// Parse a source file and extract all cells.
     pub fn parse_file(&mut self, path: &Path) -> Result<ParseResult> {
         let source = std::fs::read_to_string(path)
@@ -74,17 +108,82 @@ impl CellParser {
         self.extract_standalone_doc_comments(source);
 
         // Extract definition blocks (imports, types, helpers)
-        self.extract_definition_blocks(&file);
+        self.extract_definition_cells(&file);
 
         Ok(ParseResult {
             code_cells: std::mem::take(&mut self.cells),
             markdown_cells: std::mem::take(&mut self.markdown_cells),
             definition_cells: std::mem::take(&mut self.definition_cells),
+            unparsed_regions: Vec::new(),
+        })
+    }
+
+    /// Parse source code, skipping top-level items that don't parse.
+    ///
+    /// If the whole file parses this is [`parse_str`](Self::parse_str).
+    /// Otherwise the file is split into top-level items, and items that fail
+    /// to parse on their own are blanked out and reported as
+    /// [`UnparsedRegion`]s so the rest of the notebook stays usable. Line
+    /// numbers of the remaining cells are unchanged.
+    ///
+    /// The split is heuristic: it relies on items starting in column 0, as
+    /// rustfmt leaves them. If the remaining source still doesn't parse, the
+    /// original error is returned.
+    pub fn parse_str_partial(&mut self, source: &str, path: &Path) -> Result<ParseResult> {
+        let error = match self.parse_str(source, path) {
+            Ok(result) => return Ok(result),
+            Err(e) => e,
+        };
+
+        let lines: Vec<&str> = source.lines().collect();
+        let (kept, regions) = blank_broken_items(&lines);
+
+        // Nothing was skipped, or nothing is left
+        if regions.is_empty() || kept.iter().all(|line| line.trim().is_empty()) {
+            return Err(error);
+        }
+
+        let mut result = self.parse_str(&kept.join("\n"), path).map_err(|_| error)?;
+        result.unparsed_regions = regions;
+        Ok(result)
+    }
+
+    /// Parse source as a Rust file, blanking out top-level items that don't
+    /// parse as [`parse_str_partial`](Self::parse_str_partial) does.
+    ///
+    /// Line numbers in the returned file match `source`, so editors can
+    /// locate cells while part of the notebook is broken.
+    pub fn parse_file_partial(source: &str) -> Result<File> {
+        let error = match syn::parse_file(source) {
+            Ok(file) => return Ok(file),
+            Err(e) => Error::Parse(format!("Failed to parse source: {}", e)),
+        };
+
+        let lines: Vec<&str> = source.lines().collect();
+        let (kept, regions) = blank_broken_items(&lines);
+        if regions.is_empty() {
+            return Err(error);
+        }
+        syn::parse_file(&kept.join("\n")).map_err(|_| error)
+    }
+
+    /// Line of a top-level `fn main` among the definition cells, if any.
+    ///
+    /// Venus never calls `main`; it is compiled like any other helper.
+    pub fn main_function_line(definitions: &[DefinitionCell]) -> Option<usize> {
+        definitions.iter().find_map(|def| {
+            let file: File = syn::parse_str(&def.content).ok()?;
+            file.items.iter().find_map(|item| match item {
+                Item::Fn(func) if func.sig.ident == "main" => {
+                    Some(def.span.start_line + func.sig.ident.span().start().line - 1)
+                }
+                _ => None,
+            })
         })
     }
 
     /// Check if a function has the #[venus::cell] attribute.
-    fn has_cell_attribute(attrs: &[Attribute]) -> bool {
+    pub(crate) fn has_cell_attribute(attrs: &[Attribute]) -> bool {
         attrs.iter().any(|attr| {
             let path = attr.path();
             let segments: Vec<_> = path.segments.iter().map(|s| s.ident.to_string()).collect();
@@ -95,6 +194,118 @@ impl CellParser {
         })
     }
 
+    /// Name of the type of an inherent impl block, e.g. `Foo` for
+    /// `impl Foo { ... }`.
+    ///
+    /// `None` for trait impls and types that aren't a plain path.
+    pub(crate) fn inherent_impl_type(item: &ItemImpl) -> Option<String> {
+        if item.trait_.is_some() {
+            return None;
+        }
+        match &*item.self_ty {
+            Type::Path(path) if path.qself.is_none() => {
+                path.path.segments.last().map(|s| s.ident.to_string())
+            }
+            _ => None,
+        }
+    }
+
+    /// Check if an associated function is a cell: it has the cell
+    /// attribute and no `self` receiver, since no instance exists to call
+    /// it on.
+    pub(crate) fn is_cell_method(func: &ImplItemFn) -> bool {
+        Self::has_cell_attribute(&func.attrs) && func.sig.receiver().is_none()
+    }
+
+    /// Record a code cell for a cell function.
+    fn push_cell(&mut self, name: String, attrs: &[Attribute], sig: &Signature, block: &Block) {
+        let dependencies: Vec<Dependency> = sig
+            .inputs
+            .iter()
+            .filter_map(Self::extract_dependency)
+            .collect();
+
+        let return_type = Self::extract_return_type(&sig.output);
+
+        let doc_comment = Self::extract_doc_comment(attrs);
+
+        let display_name = Self::extract_display_name(&doc_comment, &name);
+
+        let span = self.span_to_source_span(sig.ident.span());
+
+        let source_code = self.extract_source_code(sig, block);
+
+        let cell = CellInfo {
+            id: CellId::new(0), // Assigned later by GraphEngine
+            name,
+            display_name,
+            dependencies,
+            return_type,
+            is_async: sig.asyncness.is_some(),
+            doc_comment,
+            source_code,
+            span,
+            source_file: self.source_file.clone(),
+            timeout_ms: Self::extract_int_argument(attrs, "timeout_ms"),
+            opt_level: Self::extract_int_argument(attrs, "opt_level"),
+            output_format: Self::extract_str_argument(attrs, "output")
+                .map(|hint| OutputFormat::from_hint(&hint)),
+        };
+
+        self.cells.push(cell);
+    }
+
+    /// Extract an integer argument such as `timeout_ms = N` from the cell
+    /// attribute, if present.
+    ///
+    /// Other attribute arguments are ignored.
+    fn extract_int_argument<N>(attrs: &[Attribute], name: &str) -> Option<N>
+    where
+        N: std::str::FromStr,
+        N::Err: std::fmt::Display,
+    {
+        let attr = attrs
+            .iter()
+            .find(|attr| Self::has_cell_attribute(std::slice::from_ref(attr)))?;
+
+        let mut result = None;
+        if matches!(attr.meta, syn::Meta::List(_)) {
+            let _ = attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident(name) {
+                    let value: syn::LitInt = meta.value()?.parse()?;
+                    result = value.base10_parse().ok();
+                } else if meta.input.peek(syn::Token![=]) {
+                    // Skip the value of other arguments
+                    let _: syn::Expr = meta.value()?.parse()?;
+                }
+                Ok(())
+            });
+        }
+        result
+    }
+
+    /// Extract a string argument such as `output = "json"` from the cell
+    /// attribute, if present.
+    fn extract_str_argument(attrs: &[Attribute], name: &str) -> Option<String> {
+        let attr = attrs
+            .iter()
+            .find(|attr| Self::has_cell_attribute(std::slice::from_ref(attr)))?;
+
+        let mut result = None;
+        if matches!(attr.meta, syn::Meta::List(_)) {
+            let _ = attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident(name) {
+                    let value: syn::LitStr = meta.value()?.parse()?;
+                    result = Some(value.value());
+                } else if meta.input.peek(syn::Token![=]) {
+                    let _: syn::Expr = meta.value()?.parse()?;
+                }
+                Ok(())
+            });
+        }
+        result
+    }
+
     /// Extract doc comments from attributes.
     fn extract_doc_comment(attrs: &[Attribute]) -> Option<String> {
         let docs: Vec<String> = attrs
@@ -212,10 +423,10 @@ impl CellParser {
 
     /// Extract the source code for a function.
     /// Includes the full function signature and body, excluding doc comments and attributes.
-    fn extract_source_code(&self, func: &ItemFn) -> String {
+    fn extract_source_code(&self, sig: &Signature, block: &Block) -> String {
         // Use the function signature span to get the full function including parameters
-        let sig_span = func.sig.span();
-        let body_span = func.block.brace_token.span.join();
+        let sig_span = sig.span();
+        let body_span = block.brace_token.span.join();
 
         let start = sig_span.start();
         let end = body_span.end();
@@ -225,7 +436,7 @@ impl CellParser {
 
         if start.line == 0 || end.line == 0 || start.line > lines.len() {
             // Fallback: use quote to regenerate the function
-            return quote::quote!(#func).to_string();
+            return quote::quote!(#sig #block).to_string();
         }
 
         // Extract the function source from signature to end of body (1-indexed lines)
@@ -430,116 +641,51 @@ impl CellParser {
         })
     }
 
-    /// Extract definition blocks (imports, types, helper functions).
+    /// Extract definition cells (imports, types, helper functions).
     ///
-    /// Definitions are split when markdown cells or executable cells appear between them.
-    /// Blank lines within definitions do NOT split the block.
+    /// Each top-level item becomes its own cell, so it can be edited, moved
+    /// or deleted without touching its neighbours. The span includes the
+    /// item's doc comments and attributes.
     /// Skips items with #[venus::hide] attribute.
-    fn extract_definition_blocks(&mut self, file: &File) {
+    fn extract_definition_cells(&mut self, file: &File) {
+        use super::types::DefinitionType;
         use syn::Item;
 
-        let mut current_block: Vec<String> = Vec::new();
-        let mut block_start_line: Option<usize> = None;
-        let mut block_end_line: usize = 0;
-
         for item in &file.items {
-            // Check if this is a definition item (not executable cell or other)
-            let is_definition = matches!(
-                item,
-                Item::Use(_)
-                    | Item::Struct(_)
-                    | Item::Enum(_)
-                    | Item::Type(_)
-                    | Item::Fn(_)
-                    | Item::Impl(_)
-            );
-
-            // Skip items with #[venus::hide]
-            let has_hide = match item {
-                Item::Use(item_use) => Self::has_hide_attribute(&item_use.attrs),
-                Item::Struct(item_struct) => Self::has_hide_attribute(&item_struct.attrs),
-                Item::Enum(item_enum) => Self::has_hide_attribute(&item_enum.attrs),
-                Item::Type(item_type) => Self::has_hide_attribute(&item_type.attrs),
-                Item::Impl(item_impl) => Self::has_hide_attribute(&item_impl.attrs),
-                Item::Fn(item_fn) => {
-                    Self::has_hide_attribute(&item_fn.attrs)
-                        || Self::has_cell_attribute(&item_fn.attrs) // Also skip executable cells
+            let (definition_type, attrs, span) = match item {
+                Item::Use(u) => (DefinitionType::Import, &u.attrs, u.span()),
+                Item::Struct(s) => (DefinitionType::Struct, &s.attrs, s.span()),
+                Item::Enum(e) => (DefinitionType::Enum, &e.attrs, e.span()),
+                Item::Type(t) => (DefinitionType::TypeAlias, &t.attrs, t.span()),
+                Item::Impl(i) => (DefinitionType::Impl, &i.attrs, i.span()),
+                // Executable cells are extracted separately
+                Item::Fn(f) if !Self::has_cell_attribute(&f.attrs) => {
+                    (DefinitionType::HelperFunction, &f.attrs, f.span())
                 }
-                _ => false,
+                _ => continue,
             };
 
-            if has_hide || !is_definition {
-                // This item breaks the definition block - flush any accumulated definitions
-                if !current_block.is_empty() {
-                    self.flush_definition_block(
-                        &mut current_block,
-                        block_start_line.unwrap(),
-                        block_end_line,
-                    );
-                    block_start_line = None;
-                }
+            if Self::has_hide_attribute(attrs) {
                 continue;
             }
 
-            // Get the span and extract original source text (preserves formatting)
-            let span = match item {
-                Item::Use(u) => self.span_to_source_span(u.span()),
-                Item::Struct(s) => self.span_to_source_span(s.span()),
-                Item::Enum(e) => self.span_to_source_span(e.span()),
-                Item::Type(t) => self.span_to_source_span(t.span()),
-                Item::Impl(i) => self.span_to_source_span(i.span()),
-                Item::Fn(f) => self.span_to_source_span(f.span()),
-                _ => unreachable!(),
-            };
-
             // Extract the original source text with proper formatting
-            let source_text = self.extract_source_text(span.start_line, span.end_line);
-
-            // Check if there are any markdown or code cells between the last definition and this one
-            // If so, we need to split the definition block here
-            let should_split = if let Some(prev_end) = block_end_line
-                .checked_sub(0)
-                .filter(|_| !current_block.is_empty())
-            {
-                // Check if any markdown cells fall between prev_end and span.start_line
-                let has_markdown_between = self.markdown_cells.iter().any(|md| {
-                    md.span.start_line > prev_end && md.span.start_line < span.start_line
-                });
-
-                // Check if any code cells fall between prev_end and span.start_line
-                let has_code_between = self.cells.iter().any(|cell| {
-                    cell.span.start_line > prev_end && cell.span.start_line < span.start_line
-                });
-
-                has_markdown_between || has_code_between
-            } else {
-                false
-            };
-
-            if should_split {
-                // Flush current block before starting a new one
-                self.flush_definition_block(
-                    &mut current_block,
-                    block_start_line.unwrap(),
-                    block_end_line,
-                );
-                current_block.clear();
-                block_start_line = Some(span.start_line);
-            } else if block_start_line.is_none() {
-                block_start_line = Some(span.start_line);
-            }
-
-            block_end_line = span.end_line;
-            current_block.push(source_text);
-        }
-
-        // Flush any remaining block
-        if !current_block.is_empty() {
-            self.flush_definition_block(
-                &mut current_block,
-                block_start_line.unwrap(),
-                block_end_line,
-            );
+            let span = self.span_to_source_span(span);
+            let content = self.extract_source_text(span.start_line, span.end_line);
+
+            self.definition_cells.push(DefinitionCell {
+                id: CellId::new(0), // Assigned later by GraphEngine
+                content,
+                definition_type,
+                span: SourceSpan {
+                    start_line: span.start_line,
+                    start_col: 0,
+                    end_line: span.end_line,
+                    end_col: 0,
+                },
+                source_file: self.source_file.clone(),
+                doc_comment: Self::extract_doc_comment(attrs),
+            });
         }
     }
 
@@ -558,128 +704,6 @@ impl CellParser {
         let end_idx = end_idx.min(lines.len());
         lines[start_idx..end_idx].join("\n")
     }
-
-    /// Flush accumulated definition block into a single DefinitionCell.
-    fn flush_definition_block(
-        &mut self,
-        block: &mut Vec<String>,
-        start_line: usize,
-        end_line: usize,
-    ) {
-        let combined_content = block.join("\n\n");
-
-        // Determine definition type based on content
-        let definition_type = self.infer_definition_type(&combined_content);
-
-        let definition_cell = DefinitionCell {
-            id: CellId::new(0), // Assigned later by GraphEngine
-            content: combined_content,
-            definition_type,
-            span: SourceSpan {
-                start_line,
-                start_col: 0,
-                end_line,
-                end_col: 0,
-            },
-            source_file: self.source_file.clone(),
-            doc_comment: None, // Combined blocks don't have individual doc comments
-        };
-
-        self.definition_cells.push(definition_cell);
-        block.clear();
-    }
-
-    /// Infer the definition type from the content.
-    /// Returns the specific type if the block contains only one type of definition,
-    /// otherwise returns Mixed.
-    fn infer_definition_type(&self, content: &str) -> super::types::DefinitionType {
-        use super::types::DefinitionType;
-
-        // Strip out doc comments to avoid false positives
-        let content_no_docs: String = content
-            .lines()
-            .filter(|line| {
-                let trimmed = line.trim();
-                !trimmed.starts_with("///") && !trimmed.starts_with("//!")
-            })
-            .collect::<Vec<_>>()
-            .join("\n");
-
-        // Look for definition keywords at word boundaries (not inside strings/comments)
-        let has_use = content_no_docs.lines().any(|line| {
-            let trimmed = line.trim();
-            trimmed.starts_with("use ") || trimmed.starts_with("pub use ")
-        });
-
-        let has_struct = content_no_docs.lines().any(|line| {
-            let trimmed = line.trim();
-            trimmed.starts_with("struct ") || trimmed.starts_with("pub struct ")
-        });
-
-        let has_enum = content_no_docs.lines().any(|line| {
-            let trimmed = line.trim();
-            trimmed.starts_with("enum ") || trimmed.starts_with("pub enum ")
-        });
-
-        let has_type = content_no_docs.lines().any(|line| {
-            let trimmed = line.trim();
-            trimmed.starts_with("type ") || trimmed.starts_with("pub type ")
-        });
-
-        let has_impl = content_no_docs.lines().any(|line| {
-            let trimmed = line.trim();
-            trimmed.starts_with("impl ") || trimmed.starts_with("impl<")
-        });
-
-        let has_fn = content_no_docs.lines().any(|line| {
-            let trimmed = line.trim();
-            (trimmed.starts_with("fn ") || trimmed.starts_with("pub fn "))
-                && !content.contains("#[venus::cell]")
-        });
-
-        // Special case: impl blocks contain functions, so if we have impl + fn, that's still just Impl
-        if has_impl && !has_use && !has_struct && !has_enum && !has_type {
-            return DefinitionType::Impl;
-        }
-
-        // Count how many different top-level types we have (excluding fn if impl is present)
-        let type_count = [
-            has_use,
-            has_struct,
-            has_enum,
-            has_type,
-            has_impl,
-            has_fn && !has_impl,
-        ]
-        .iter()
-        .filter(|&&x| x)
-        .count();
-
-        // If only one type, return that specific type
-        if type_count == 1 {
-            if has_use {
-                return DefinitionType::Import;
-            }
-            if has_struct {
-                return DefinitionType::Struct;
-            }
-            if has_enum {
-                return DefinitionType::Enum;
-            }
-            if has_type {
-                return DefinitionType::TypeAlias;
-            }
-            if has_impl {
-                return DefinitionType::Impl;
-            }
-            if has_fn {
-                return DefinitionType::HelperFunction;
-            }
-        }
-
-        // Multiple types or couldn't determine - use Mixed
-        DefinitionType::Mixed
-    }
 }
 
 impl Default for CellParser {
@@ -695,40 +719,91 @@ impl<'ast> Visit<'ast> for CellParser {
             return;
         }
 
-        // Extract cell information
         let name = func.sig.ident.to_string();
+        self.push_cell(name, &func.attrs, &func.sig, &func.block);
+    }
 
-        let dependencies: Vec<Dependency> = func
-            .sig
-            .inputs
-            .iter()
-            .filter_map(Self::extract_dependency)
-            .collect();
+    fn visit_item_impl(&mut self, item: &'ast ItemImpl) {
+        // Only inherent impls (`impl Foo { ... }`) can hold cells
+        self.impl_type = Self::inherent_impl_type(item);
+        syn::visit::visit_item_impl(self, item);
+        self.impl_type = None;
+    }
 
-        let return_type = Self::extract_return_type(&func.sig.output);
+    fn visit_impl_item_fn(&mut self, func: &'ast ImplItemFn) {
+        if !Self::is_cell_method(func) {
+            return;
+        }
+        let Some(impl_type) = &self.impl_type else {
+            return;
+        };
 
-        let doc_comment = Self::extract_doc_comment(&func.attrs);
+        let name = format!("{}::{}", impl_type, func.sig.ident);
+        self.push_cell(name, &func.attrs, &func.sig, &func.block);
+    }
+}
 
-        let display_name = Self::extract_display_name(&doc_comment, &name);
+/// Blank out the top-level items of `lines` that don't parse on their own,
+/// returning the remaining lines and a region for each blanked item.
+fn blank_broken_items<'a>(lines: &[&'a str]) -> (Vec<&'a str>, Vec<UnparsedRegion>) {
+    let mut kept = lines.to_vec();
+    let mut regions = Vec::new();
 
-        let span = self.span_to_source_span(func.sig.ident.span());
+    for (start, end) in item_chunks(lines) {
+        let Err(e) = syn::parse_file(&lines[start..end].join("\n")) else {
+            continue;
+        };
 
-        let source_code = self.extract_source_code(func);
+        let mut last = end;
+        while last > start + 1 && lines[last - 1].trim().is_empty() {
+            last -= 1;
+        }
+        regions.push(UnparsedRegion {
+            id: CellId::new(0), // Placeholder
+            content: lines[start..last].join("\n"),
+            message: e.to_string(),
+            span: SourceSpan {
+                start_line: start + 1,
+                start_col: 0,
+                end_line: last,
+                end_col: lines[last - 1].len(),
+            },
+        });
+        kept[start..end].fill("");
+    }
 
-        let cell = CellInfo {
-            id: CellId::new(0), // Assigned later by GraphEngine
-            name,
-            display_name,
-            dependencies,
-            return_type,
-            doc_comment,
-            source_code,
-            span,
-            source_file: self.source_file.clone(),
-        };
+    (kept, regions)
+}
 
-        self.cells.push(cell);
+/// Split source lines into top-level items, as `(start, end)` line ranges.
+///
+/// An item starts at an unindented line, unless that line closes a bracket
+/// or directly follows an attribute or comment, which belong to the item
+/// below them.
+fn item_chunks(lines: &[&str]) -> Vec<(usize, usize)> {
+    let mut starts = vec![0];
+    let mut attached = true;
+
+    for (index, line) in lines.iter().enumerate() {
+        if line.trim().is_empty() {
+            attached = false;
+            continue;
+        }
+
+        let unindented = !line.starts_with(char::is_whitespace);
+        let closing = line.starts_with(['}', ')', ']']);
+        if unindented && !closing && !attached {
+            starts.push(index);
+        }
+
+        attached = unindented
+            && ((line.starts_with("#[") || closing && line.trim_end().ends_with(']'))
+                || (line.starts_with("//") && !line.starts_with("//!")));
     }
+
+    starts.dedup();
+    let ends = starts.iter().skip(1).copied().chain([lines.len()]);
+    starts.iter().copied().zip(ends).collect()
 }
 
 #[cfg(test)]
@@ -741,6 +816,93 @@ mod tests {
         parser.parse_str(source, &PathBuf::from("test.rs")).unwrap()
     }
 
+    #[test]
+    fn test_main_function_line() {
+        let result = parse(
+            r#"use std::fmt;
+
+#[venus::cell]
+pub fn main_data() -> i32 {
+    1
+}
+
+/// Entry point
+fn main() {
+    println!("hi");
+}
+"#,
+        );
+        assert_eq!(
+            CellParser::main_function_line(&result.definition_cells),
+            Some(9)
+        );
+
+        let result = parse("fn helper() {}\n");
+        assert_eq!(
+            CellParser::main_function_line(&result.definition_cells),
+            None
+        );
+    }
+
+    #[test]
+    fn test_check_syntax() {
+        assert_eq!(CellParser::check_syntax("pub fn a() -> i32 { 1 }"), Ok(()));
+
+        let error = CellParser::check_syntax("pub fn a() -> i32 {\n    1 +\n}\n").unwrap_err();
+        assert_eq!((error.line, error.column), (3, 1));
+        assert!(!error.message.is_empty());
+    }
+
+    #[test]
+    fn test_parse_partial_skips_broken_items() {
+        let source = r#"//! # Notebook
+
+use std::collections::HashMap;
+
+/// Loads data
+#[venus::cell]
+pub fn data() -> Vec<i32> {
+    vec![1, 2, 3]
+}
+
+#[venus::cell(timeout_ms = 10)]
+pub fn broken(data: &Vec<i32>) -> i32 {
+    data.iter().sum() +
+}
+
+#[venus::cell]
+pub fn total(data: &Vec<i32>) -> usize {
+    data.len()
+}
+"#;
+        let path = PathBuf::from("test.rs");
+        assert!(CellParser::new().parse_str(source, &path).is_err());
+
+        let result = CellParser::new().parse_str_partial(source, &path).unwrap();
+        let names: Vec<&str> = result.code_cells.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["data", "total"]);
+        assert_eq!(result.code_cells[1].span.start_line, 17);
+        assert_eq!(result.definition_cells.len(), 1);
+
+        assert_eq!(result.unparsed_regions.len(), 1);
+        let region = &result.unparsed_regions[0];
+        assert_eq!((region.span.start_line, region.span.end_line), (11, 14));
+        assert!(
+            region
+                .content
+                .starts_with("#[venus::cell(timeout_ms = 10)]")
+        );
+        assert!(region.content.ends_with('}'));
+
+        // Nothing to recover: the original error is returned
+        assert!(CellParser::new().parse_str_partial("fn (", &path).is_err());
+        assert!(
+            CellParser::new()
+                .parse_str_partial("fn a(\n\nfn b(", &path)
+                .is_err()
+        );
+    }
+
     #[test]
     fn test_parse_simple_cell() {
         let source = r#"
@@ -757,6 +919,10 @@ mod tests {
         assert_eq!(result.code_cells[0].name, "config");
         assert!(result.code_cells[0].dependencies.is_empty());
         assert_eq!(result.code_cells[0].return_type, "Config");
+        assert!(!result.code_cells[0].is_async);
+
+        let result = parse("#[venus::cell]\npub async fn fetch() -> i32 { 1 }\n");
+        assert!(result.code_cells[0].is_async);
     }
 
     #[test]
@@ -781,6 +947,50 @@ mod tests {
         assert_eq!(result.code_cells[0].dependencies[1].param_type, "DataFrame");
     }
 
+    #[test]
+    fn test_definition_cells_are_per_item() {
+        use crate::graph::DefinitionType;
+
+        let source = r#"
+use std::collections::HashMap;
+use std::fmt;
+
+/// A point
+#[derive(Debug)]
+pub struct Point {
+    pub x: i32,
+}
+
+fn helper() -> i32 {
+    1
+}
+
+#[venus::cell]
+pub fn cell() -> i32 {
+    helper()
+}
+"#;
+        let result = parse(source);
+        let defs = &result.definition_cells;
+
+        let types: Vec<_> = defs.iter().map(|d| d.definition_type).collect();
+        assert_eq!(
+            types,
+            vec![
+                DefinitionType::Import,
+                DefinitionType::Import,
+                DefinitionType::Struct,
+                DefinitionType::HelperFunction,
+            ]
+        );
+
+        // Spans cover exactly the item, including its doc comment and attributes
+        assert_eq!(defs[1].content, "use std::fmt;");
+        assert_eq!((defs[2].span.start_line, defs[2].span.end_line), (5, 9));
+        assert!(defs[2].content.starts_with("/// A point\n#[derive(Debug)]"));
+        assert_eq!(defs[2].doc_comment.as_deref(), Some("A point"));
+    }
+
     #[test]
     fn test_parse_doc_comments() {
         let source = r#"
@@ -801,6 +1011,47 @@ mod tests {
         assert!(doc.contains("multiple lines"));
     }
 
+    #[test]
+    fn test_parse_timeout_ms() {
+        let source = r#"
+            #[venus::cell(timeout_ms = 500)]
+            pub fn fast() -> i32 { 1 }
+
+            #[venus::cell(label = "x", timeout_ms = 60_000, opt_level = 2)]
+            pub fn slow() -> i32 { 2 }
+
+            #[venus::cell]
+            pub fn default() -> i32 { 3 }
+        "#;
+
+        let result = parse(source);
+        assert_eq!(result.code_cells[0].timeout_ms, Some(500));
+        assert_eq!(result.code_cells[1].timeout_ms, Some(60_000));
+        assert_eq!(result.code_cells[2].timeout_ms, None);
+        assert_eq!(result.code_cells[0].opt_level, None);
+        assert_eq!(result.code_cells[1].opt_level, Some(2));
+    }
+
+    #[test]
+    fn test_parse_output_format() {
+        let source = r#"
+            #[venus::cell(output = "json", timeout_ms = 5)]
+            pub fn data() -> i32 { 1 }
+
+            #[venus::cell(output = "sparkles")]
+            pub fn unknown() -> i32 { 2 }
+
+            #[venus::cell]
+            pub fn plain() -> i32 { 3 }
+        "#;
+
+        let result = parse(source);
+        assert_eq!(result.code_cells[0].output_format, Some(OutputFormat::Json));
+        assert_eq!(result.code_cells[0].timeout_ms, Some(5));
+        assert_eq!(result.code_cells[1].output_format, Some(OutputFormat::Text));
+        assert_eq!(result.code_cells[2].output_format, None);
+    }
+
     #[test]
     fn test_parse_multiple_cells() {
         let source = r#"
@@ -823,6 +1074,37 @@ mod tests {
         assert_eq!(result.code_cells[2].name, "c");
     }
 
+    #[test]
+    fn test_parse_impl_method_cells() {
+        let source = r#"
+            pub struct Cells;
+
+            impl Cells {
+                #[venus::cell]
+                pub fn load() -> i32 { 1 }
+
+                #[venus::cell]
+                pub fn bad(&self) -> i32 { 2 }
+            }
+
+            impl Clone for Cells {
+                #[venus::cell]
+                fn clone() -> Self { Cells }
+            }
+
+            #[venus::cell]
+            pub fn total(load: &i32) -> i32 { *load + 1 }
+        "#;
+
+        let result = parse(source);
+        assert_eq!(result.code_cells.len(), 2);
+        assert_eq!(result.code_cells[0].name, "Cells::load");
+        assert_eq!(result.code_cells[0].output_name(), "load");
+        assert!(result.code_cells[0].source_code.contains("fn load()"));
+        assert_eq!(result.code_cells[1].name, "total");
+        assert_eq!(result.code_cells[1].dependencies[0].param_name, "load");
+    }
+
     #[test]
     fn test_skip_non_cell_functions() {
         let source = r#"
@@ -1028,34 +1310,28 @@ pub fn config() -> i32 {
 
         // Expected structure:
         // Markdown cell: lines 1-11 (module doc)
-        // Definition cell: lines 15-16 (use statements) - Import type
+        // Definition cells: lines 15 and 16 (one per use statement) - Import type
         // Markdown cell: lines 18 (Data Structures separator)
-        // Definition cell: lines 20-67 (struct definitions) - Struct type
+        // Definition cells: lines 20-67 (one per struct) - Struct type
         // Markdown cell: lines 69 (Cells separator)
         // Code cells: 75+
         // Note: impl blocks have #[venus::hide] so they won't appear as definition cells
 
-        // We should have 2 definition cells (imports and structs, impl blocks are hidden)
-        assert_eq!(
-            result.definition_cells.len(),
-            2,
-            "Expected 2 definition cells, got {}",
-            result.definition_cells.len()
-        );
-
-        // First definition cell should be imports (use statements)
+        // We should have 7 definition cells (2 imports and 5 structs, impl blocks are hidden)
         use crate::graph::DefinitionType;
+        let types: Vec<DefinitionType> = result
+            .definition_cells
+            .iter()
+            .map(|d| d.definition_type)
+            .collect();
         assert_eq!(
-            result.definition_cells[0].definition_type,
-            DefinitionType::Import,
-            "First definition should be Import type"
-        );
-
-        // Second definition cell should be structs
-        assert_eq!(
-            result.definition_cells[1].definition_type,
-            DefinitionType::Struct,
-            "Second definition should be Struct type"
+            types,
+            [
+                vec![DefinitionType::Import; 2],
+                vec![DefinitionType::Struct; 5]
+            ]
+            .concat(),
+            "Expected one definition cell per import and struct"
         );
 
         // Check we have the expected code cells
@@ -4,7 +4,7 @@
 //! modify the same notebook file concurrently.
 
 use fs2::FileExt;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs::{self, File};
 use std::path::{Path, PathBuf};
 
@@ -13,6 +13,8 @@ use syn::{Attribute, File as SynFile};
 
 use serde::{Deserialize, Serialize};
 
+use super::parser::CellParser;
+use super::types::CellType;
 use crate::error::{Error, Result};
 
 /// Direction for moving a cell.
@@ -25,6 +27,72 @@ pub enum MoveDirection {
     Down,
 }
 
+/// A block of source replaced when converting a cell between code and
+/// markdown, or when editing cells with [`SourceEditor::edit_cells`].
+///
+/// Line numbers are 1-indexed. Undo replaces `start_line..=new_end_line` with
+/// `old_text`; redo replaces `start_line..=old_end_line` with `new_text`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CellConversion {
+    /// First line of the block.
+    pub start_line: usize,
+    /// Last line of the block before conversion.
+    pub old_end_line: usize,
+    /// Last line of the block after conversion.
+    pub new_end_line: usize,
+    /// Source text before conversion.
+    pub old_text: String,
+    /// Source text after conversion.
+    pub new_text: String,
+}
+
+/// Where a block moved by [`SourceEditor::move_block`] ended up.
+///
+/// Line numbers are 1-indexed and refer to the source after the move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockMove {
+    /// First line of the block.
+    pub new_start_line: usize,
+    /// Last line of the block.
+    pub new_end_line: usize,
+    /// Line to move the block before to put it back (None = end of file).
+    pub undo_before_line: Option<usize>,
+}
+
+/// A cell function found in the parsed source.
+struct CellFn<'a> {
+    /// Cell name, `Type::method` for cells in impl blocks.
+    name: String,
+    attrs: &'a [Attribute],
+    vis: &'a syn::Visibility,
+    sig: &'a syn::Signature,
+    block: &'a syn::Block,
+}
+
+impl CellFn<'_> {
+    /// First line of the cell, including its attributes and doc comments.
+    fn start_line(&self) -> usize {
+        self.attrs
+            .iter()
+            .map(|a| a.span().start().line)
+            .min()
+            .unwrap_or(self.sig.fn_token.span.start().line)
+    }
+
+    /// First line of the function itself (visibility or signature).
+    fn function_start_line(&self) -> usize {
+        match self.vis {
+            syn::Visibility::Inherited => self.sig.span().start().line,
+            vis => vis.span().start().line,
+        }
+    }
+
+    /// Last line of the cell (closing brace).
+    fn end_line(&self) -> usize {
+        self.block.brace_token.span.close().end().line
+    }
+}
+
 /// Editor for modifying .rs notebook source files.
 ///
 /// Holds an exclusive file lock for the duration of the edit session
@@ -81,8 +149,7 @@ impl SourceEditor {
     /// Returns the name of the newly created cell.
     pub fn insert_cell(&mut self, after_cell_id: Option<&str>) -> Result<String> {
         // Parse the file to find cell positions and existing names
-        let file: SynFile = syn::parse_str(&self.content)
-            .map_err(|e| Error::Parse(format!("Failed to parse source: {}", e)))?;
+        let file: SynFile = self.parse()?;
 
         // Collect existing cell names for unique name generation
         let existing_names = self.collect_cell_names(&file);
@@ -102,21 +169,223 @@ impl SourceEditor {
         Ok(new_name)
     }
 
+    /// Insert a new cell before the item at a line.
+    ///
+    /// If `line` falls inside an item (including its doc comments and
+    /// attributes), the cell goes before the whole item. Module docs and
+    /// inner attributes always stay first. If `line` is None, inserts at the
+    /// end of the file. Returns the name of the newly created cell.
+    pub fn insert_cell_before_line(&mut self, line: Option<usize>) -> Result<String> {
+        let file: SynFile = self.parse()?;
+
+        let new_name = self.generate_unique_name(&self.collect_cell_names(&file));
+        let cell_code = self.generate_cell_code(&new_name);
+        let cell_code = cell_code.trim();
+
+        let Some(line) = line else {
+            let separator = if self.content.trim().is_empty() {
+                ""
+            } else if self.content.ends_with('\n') {
+                "\n"
+            } else {
+                "\n\n"
+            };
+            self.content = format!("{}{}{}\n", self.content, separator, cell_code);
+            return Ok(new_name);
+        };
+
+        let (line, after_header) = Self::insertion_line(&file, line);
+        let lines: Vec<&str> = self.content.lines().collect();
+        if line > lines.len() {
+            return self.insert_cell_before_line(None);
+        }
+
+        let offset = self.line_start_offset(line);
+        let insert_text = if after_header {
+            format!("\n{}\n", cell_code)
+        } else {
+            format!("{}\n\n", cell_code)
+        };
+        self.content.insert_str(offset, &insert_text);
+
+        Ok(new_name)
+    }
+
+    /// Move a block of lines to before the item at a line.
+    ///
+    /// `before_line` is adjusted like in [`Self::insert_cell_before_line`];
+    /// None moves the block to the end of the file. One blank line after the
+    /// block travels with it. Returns where the block ended up and how to
+    /// move it back.
+    pub fn move_block(
+        &mut self,
+        start_line: usize,
+        end_line: usize,
+        before_line: Option<usize>,
+    ) -> Result<BlockMove> {
+        let file: SynFile = self.parse()?;
+        let mut lines: Vec<String> = self.content.lines().map(str::to_string).collect();
+
+        if start_line == 0
+            || start_line > lines.len()
+            || end_line > lines.len()
+            || start_line > end_line
+        {
+            return Err(Error::InvalidOperation(format!(
+                "Invalid line range: {}-{}",
+                start_line, end_line
+            )));
+        }
+
+        let target = before_line.map(|line| Self::insertion_line(&file, line));
+        if let Some((line, _)) = target
+            && start_line < line
+            && line <= end_line
+        {
+            return Err(Error::InvalidOperation(
+                "Cannot move a block into itself".to_string(),
+            ));
+        }
+
+        // Take the block and the blank line separating it from the next one
+        let removed_end = if lines.get(end_line).is_some_and(|l| l.trim().is_empty()) {
+            end_line + 1
+        } else {
+            end_line
+        };
+        let block: Vec<String> = lines
+            .drain(start_line - 1..removed_end)
+            .take(end_line - start_line + 1)
+            .collect();
+        let removed = removed_end - start_line + 1;
+
+        // The line that followed the block is now at `start_line`
+        let mut undo_before_line = (removed_end < lines.len() + removed).then_some(start_line);
+
+        // Separate the block from the lines around it with one blank line
+        let (insert_at, leading_blank) = match target {
+            Some((line, after_header)) if line <= lines.len() + removed => {
+                let line = if line > removed_end {
+                    line - removed
+                } else {
+                    line.min(start_line)
+                };
+                (line - 1, after_header)
+            }
+            _ => {
+                while lines.last().is_some_and(|l| l.trim().is_empty()) {
+                    lines.pop();
+                }
+                (lines.len(), !lines.is_empty())
+            }
+        };
+        let trailing_blank = !leading_blank && insert_at < lines.len();
+
+        let new_start_line = insert_at + 1 + usize::from(leading_blank);
+        let new_end_line = new_start_line + block.len() - 1;
+
+        let mut text = block;
+        if leading_blank {
+            text.insert(0, String::new());
+        }
+        if trailing_blank {
+            text.push(String::new());
+        }
+        if let Some(line) = undo_before_line.as_mut()
+            && *line > insert_at
+        {
+            *line += text.len();
+        }
+
+        lines.splice(insert_at..insert_at, text);
+        while lines.last().is_some_and(|l| l.trim().is_empty()) {
+            lines.pop();
+        }
+        let mut content = lines.join("\n");
+        content.push('\n');
+        self.content = content;
+
+        Ok(BlockMove {
+            new_start_line,
+            new_end_line,
+            undo_before_line,
+        })
+    }
+
+    /// Rearrange blocks of lines into the given order.
+    ///
+    /// `blocks` are non-overlapping `(start_line, end_line)` ranges. Lines
+    /// before the first block and after the last one stay where they are;
+    /// any other lines between blocks travel with the block after them.
+    /// The rearranged blocks are separated by one blank line.
+    pub fn reorder_blocks(&mut self, blocks: &[(usize, usize)]) -> Result<()> {
+        let lines: Vec<&str> = self.content.lines().collect();
+
+        let mut sorted = blocks.to_vec();
+        sorted.sort();
+        let mut prev_end = 0;
+        for &(start_line, end_line) in &sorted {
+            if start_line <= prev_end || start_line > end_line || end_line > lines.len() {
+                return Err(Error::InvalidOperation(format!(
+                    "Invalid line range: {}-{}",
+                    start_line, end_line
+                )));
+            }
+            prev_end = end_line;
+        }
+        let Some(&(first_start, _)) = sorted.first() else {
+            return Ok(());
+        };
+
+        // Each block with the lines between it and the block before it
+        let mut chunks: HashMap<(usize, usize), &[&str]> = HashMap::new();
+        let mut chunk_start = first_start - 1;
+        for &(start_line, end_line) in &sorted {
+            let mut chunk = &lines[chunk_start..end_line];
+            while chunk.first().is_some_and(|l| l.trim().is_empty()) {
+                chunk = &chunk[1..];
+            }
+            chunks.insert((start_line, end_line), chunk);
+            chunk_start = end_line;
+        }
+
+        let mut new_lines: Vec<&str> = lines[..first_start - 1].to_vec();
+        while new_lines.last().is_some_and(|l| l.trim().is_empty()) {
+            new_lines.pop();
+        }
+        for block in blocks {
+            if !new_lines.is_empty() {
+                new_lines.push("");
+            }
+            new_lines.extend_from_slice(chunks[block]);
+        }
+        let tail = &lines[prev_end..];
+        if tail.iter().any(|l| !l.trim().is_empty()) {
+            new_lines.push("");
+            new_lines.extend(tail.iter().skip_while(|l| l.trim().is_empty()));
+        }
+        while new_lines.last().is_some_and(|l| l.trim().is_empty()) {
+            new_lines.pop();
+        }
+
+        let mut content = new_lines.join("\n");
+        content.push('\n');
+        self.replace_content(content)
+    }
+
     /// Delete a cell by name.
     ///
     /// Returns the name of the deleted cell.
     pub fn delete_cell(&mut self, cell_name: &str) -> Result<String> {
         // Parse the file to find cell positions
-        let file: SynFile = syn::parse_str(&self.content)
-            .map_err(|e| Error::Parse(format!("Failed to parse source: {}", e)))?;
+        let file: SynFile = self.parse()?;
 
         // Find the cell's span (including doc comments and attributes)
         let (start_line, end_line) = self.find_cell_span(&file, cell_name)?;
 
         // Convert line numbers to byte offsets
-        let lines: Vec<&str> = self.content.lines().collect();
-        let start_offset = self.line_start_offset(start_line, &lines);
-        let end_offset = self.line_to_byte_offset(end_line, &lines);
+        let start_offset = self.line_start_offset(start_line);
+        let end_offset = self.line_to_byte_offset(end_line);
 
         // Remove the cell from content
         self.content = format!(
@@ -138,8 +407,7 @@ impl SourceEditor {
     /// Returns the name of the new cell.
     pub fn duplicate_cell(&mut self, cell_name: &str) -> Result<String> {
         // Parse the file to find cell positions and existing names
-        let file: SynFile = syn::parse_str(&self.content)
-            .map_err(|e| Error::Parse(format!("Failed to parse source: {}", e)))?;
+        let file: SynFile = self.parse()?;
 
         // Find the cell's span
         let (start_line, end_line) = self.find_cell_span(&file, cell_name)?;
@@ -147,18 +415,23 @@ impl SourceEditor {
         // Collect existing names to generate unique copy name
         let existing_names = self.collect_cell_names(&file);
 
-        // Generate a unique name for the copy
-        let new_name = self.generate_copy_name(cell_name, &existing_names);
+        // Generate a unique name for the copy, keeping the `Type::` of a
+        // cell in an impl block
+        let (prefix, fn_name) = match cell_name.rsplit_once("::") {
+            Some((ty, fn_name)) => (format!("{}::", ty), fn_name),
+            None => (String::new(), cell_name),
+        };
+        let new_fn_name = self.generate_copy_name(fn_name, &existing_names);
+        let new_name = format!("{}{}", prefix, new_fn_name);
 
         // Extract the cell's source code
-        let lines: Vec<&str> = self.content.lines().collect();
-        let start_offset = self.line_start_offset(start_line, &lines);
-        let end_offset = self.line_to_byte_offset(end_line, &lines);
+        let start_offset = self.line_start_offset(start_line);
+        let end_offset = self.line_to_byte_offset(end_line);
         let cell_source = &self.content[start_offset..end_offset];
 
         // Replace the function name in the duplicated code
         let new_cell_source =
-            cell_source.replace(&format!("fn {}(", cell_name), &format!("fn {}(", new_name));
+            cell_source.replace(&format!("fn {}(", fn_name), &format!("fn {}(", new_fn_name));
 
         // Insert the new cell after the original
         let insert_code = format!("\n{}", new_cell_source);
@@ -172,8 +445,7 @@ impl SourceEditor {
     /// Returns Ok(()) on success.
     pub fn move_cell(&mut self, cell_name: &str, direction: MoveDirection) -> Result<()> {
         // Parse the file to find all cells in order
-        let file: SynFile = syn::parse_str(&self.content)
-            .map_err(|e| Error::Parse(format!("Failed to parse source: {}", e)))?;
+        let file: SynFile = self.parse()?;
 
         // Collect all cells with their spans in order
         let cells = self.collect_cell_spans(&file);
@@ -211,15 +483,23 @@ impl SourceEditor {
             (neighbor_idx, cell_idx)
         };
 
-        let (_, first_start, first_end) = cells[first_idx];
-        let (_, second_start, second_end) = cells[second_idx];
+        let (first_name, first_start, first_end) = &cells[first_idx];
+        let (second_name, second_start, second_end) = &cells[second_idx];
+        if first_name.rsplit_once("::").map(|(ty, _)| ty)
+            != second_name.rsplit_once("::").map(|(ty, _)| ty)
+        {
+            return Err(Error::InvalidOperation(
+                "Cannot move a cell out of its impl block".to_string(),
+            ));
+        }
+        let (first_start, first_end) = (*first_start, *first_end);
+        let (second_start, second_end) = (*second_start, *second_end);
 
         // Extract source code for both cells
-        let lines: Vec<&str> = self.content.lines().collect();
-        let first_start_offset = self.line_start_offset(first_start, &lines);
-        let first_end_offset = self.line_to_byte_offset(first_end, &lines);
-        let second_start_offset = self.line_start_offset(second_start, &lines);
-        let second_end_offset = self.line_to_byte_offset(second_end, &lines);
+        let first_start_offset = self.line_start_offset(first_start);
+        let first_end_offset = self.line_to_byte_offset(first_end);
+        let second_start_offset = self.line_start_offset(second_start);
+        let second_end_offset = self.line_to_byte_offset(second_end);
 
         let first_source = self.content[first_start_offset..first_end_offset].to_string();
         let second_source = self.content[second_start_offset..second_end_offset].to_string();
@@ -242,107 +522,101 @@ impl SourceEditor {
     /// Updates or adds a `# Display Name` heading to the cell's doc comment.
     pub fn rename_cell(&mut self, cell_name: &str, new_display_name: &str) -> Result<()> {
         // Parse the file to find the cell
-        let file: SynFile = syn::parse_str(&self.content)
-            .map_err(|e| Error::Parse(format!("Failed to parse source: {}", e)))?;
+        let file: SynFile = self.parse()?;
 
         // Find the cell
-        for item in &file.items {
-            if let syn::Item::Fn(func) = item
-                && Self::has_cell_attribute(&func.attrs)
-            {
-                let name = func.sig.ident.to_string();
-                if name == cell_name {
-                    // Extract existing doc comments (excluding # heading lines)
-                    let mut doc_lines: Vec<String> = Vec::new();
-
-                    for attr in &func.attrs {
-                        if attr.path().is_ident("doc")
-                            && let syn::Meta::NameValue(nv) = &attr.meta
-                            && let syn::Expr::Lit(syn::ExprLit {
-                                lit: syn::Lit::Str(s),
-                                ..
-                            }) = &nv.value
-                        {
-                            let line = s.value();
-                            let trimmed = line.trim_start();
-
-                            // Skip existing # heading (we'll add new one)
-                            if trimmed.starts_with('#') {
-                                continue;
-                            }
-
-                            doc_lines.push(line);
+        for func in Self::cell_fns(&file) {
+            if func.name == cell_name {
+                // Extract existing doc comments (excluding # heading lines)
+                let mut doc_lines: Vec<String> = Vec::new();
+
+                for attr in func.attrs {
+                    if attr.path().is_ident("doc")
+                        && let syn::Meta::NameValue(nv) = &attr.meta
+                        && let syn::Expr::Lit(syn::ExprLit {
+                            lit: syn::Lit::Str(s),
+                            ..
+                        }) = &nv.value
+                    {
+                        let line = s.value();
+                        let trimmed = line.trim_start();
+
+                        // Skip existing # heading (we'll add new one)
+                        if trimmed.starts_with('#') {
+                            continue;
                         }
-                    }
 
-                    // Build new doc comment with display name heading
-                    let mut new_doc_lines = vec![format!("# {}", new_display_name)];
-                    if !doc_lines.is_empty() {
-                        // Add blank line between heading and description
-                        new_doc_lines.push(String::new());
-                        new_doc_lines.extend(doc_lines);
+                        doc_lines.push(line);
                     }
+                }
 
-                    // Find the span for doc comments and attributes
-                    let doc_start_line = if !func.attrs.is_empty() {
-                        func.attrs
-                            .iter()
-                            .filter(|a| a.path().is_ident("doc"))
-                            .map(|a| a.span().start().line)
-                            .min()
-                            .unwrap_or(func.attrs[0].span().start().line)
-                    } else {
-                        func.span().start().line
-                    };
+                // Build new doc comment with display name heading
+                let mut new_doc_lines = vec![format!("# {}", new_display_name)];
+                if !doc_lines.is_empty() {
+                    // Add blank line between heading and description
+                    new_doc_lines.push(String::new());
+                    new_doc_lines.extend(doc_lines);
+                }
 
-                    // Find the function declaration line (pub fn ...)
-                    let fn_start_line = func.sig.fn_token.span.start().line;
+                // Find the span for doc comments and attributes
+                let doc_start_line = if !func.attrs.is_empty() {
+                    func.attrs
+                        .iter()
+                        .filter(|a| a.path().is_ident("doc"))
+                        .map(|a| a.span().start().line)
+                        .min()
+                        .unwrap_or(func.attrs[0].span().start().line)
+                } else {
+                    func.function_start_line()
+                };
 
-                    // Reconstruct the cell with new doc comments
-                    let lines: Vec<&str> = self.content.lines().collect();
+                // Find the function declaration line (pub fn ...)
+                let fn_start_line = func.sig.fn_token.span.start().line;
 
-                    // Get the indentation of the original doc comments or function
-                    let indent = if !func.attrs.is_empty() {
-                        Self::get_line_indent(&lines, doc_start_line)
-                    } else {
-                        Self::get_line_indent(&lines, fn_start_line)
-                    };
+                // Reconstruct the cell with new doc comments
+                let lines: Vec<&str> = self.content.lines().collect();
 
-                    // Build new doc comment block
-                    let new_doc_comment = new_doc_lines
-                        .iter()
-                        .map(|line| format!("{}/// {}", indent, line))
-                        .collect::<Vec<_>>()
-                        .join("\n");
-
-                    // Find where to replace
-                    let replace_start = self.line_start_offset(doc_start_line, &lines);
-                    let replace_end = self.line_start_offset(fn_start_line, &lines);
-
-                    // Build new content
-                    let mut new_content = String::new();
-                    new_content.push_str(&self.content[..replace_start]);
-                    new_content.push_str(&new_doc_comment);
-                    new_content.push('\n');
-
-                    // Add the #[venus::cell] attribute if it's not a doc comment
-                    let mut added_cell_attr = false;
-                    for attr in &func.attrs {
-                        if !attr.path().is_ident("doc") && !added_cell_attr {
-                            new_content.push_str(&format!("{}#[venus::cell]\n", indent));
-                            added_cell_attr = true;
-                        }
-                    }
+                // Get the indentation of the original doc comments or function
+                let indent = if !func.attrs.is_empty() {
+                    Self::get_line_indent(&lines, doc_start_line)
+                } else {
+                    Self::get_line_indent(&lines, fn_start_line)
+                };
 
-                    if !added_cell_attr {
+                // Build new doc comment block
+                let new_doc_comment = new_doc_lines
+                    .iter()
+                    .map(|line| format!("{}/// {}", indent, line))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+
+                // Find where to replace
+                let replace_start = self.line_start_offset(doc_start_line);
+                let replace_end = self.line_start_offset(fn_start_line);
+
+                // Build new content
+                let mut new_content = String::new();
+                new_content.push_str(&self.content[..replace_start]);
+                new_content.push_str(&new_doc_comment);
+                new_content.push('\n');
+
+                // Add the #[venus::cell] attribute if it's not a doc comment
+                let mut added_cell_attr = false;
+                for attr in func.attrs {
+                    if !attr.path().is_ident("doc") && !added_cell_attr {
                         new_content.push_str(&format!("{}#[venus::cell]\n", indent));
+                        added_cell_attr = true;
                     }
+                }
 
-                    new_content.push_str(&self.content[replace_end..]);
-
-                    self.content = new_content;
-                    return Ok(());
+                if !added_cell_attr {
+                    new_content.push_str(&format!("{}#[venus::cell]\n", indent));
                 }
+
+                new_content.push_str(&self.content[replace_end..]);
+
+                self.content = new_content;
+                return Ok(());
             }
         }
 
@@ -428,20 +702,10 @@ impl SourceEditor {
 
         let mut brace_depth = 0;
         let mut found_opening = false;
-        let mut offset = 0;
-
-        for (i, line) in lines.iter().enumerate() {
-            let line_num = i + 1;
-
-            // Calculate offset for this line
-            if line_num < start_line {
-                offset += line.len() + 1; // +1 for newline
-                continue;
-            }
 
+        for (i, line) in lines.iter().enumerate().skip(start_line - 1) {
             // Count braces
             for ch in line.chars() {
-                offset += ch.len_utf8();
                 match ch {
                     '{' => {
                         brace_depth += 1;
@@ -451,15 +715,13 @@ impl SourceEditor {
                         brace_depth -= 1;
                         // If we're back to 0 and we found an opening brace, we're done
                         if found_opening && brace_depth == 0 {
-                            offset += 1; // Include the newline after closing brace
-                            return offset.min(self.content.len());
+                            // Include the rest of the line and its newline
+                            return self.line_to_byte_offset(i + 1);
                         }
                     }
                     _ => {}
                 }
             }
-
-            offset += 1; // newline
         }
 
         // If we didn't find a complete block, return end of content
@@ -469,7 +731,7 @@ impl SourceEditor {
     /// Edit an existing markdown cell by line range.
     ///
     /// Replaces the comment block at the given line range with new content.
-    /// If `is_module_doc` is true, uses `//!` syntax; otherwise uses `///`.
+    /// If `is_module_doc` is true, uses `//!` syntax; otherwise uses `//`.
     pub fn edit_markdown_cell(
         &mut self,
         start_line: usize,
@@ -477,50 +739,21 @@ impl SourceEditor {
         new_content: &str,
         is_module_doc: bool,
     ) -> Result<()> {
-        let lines: Vec<&str> = self.content.lines().collect();
-
-        if start_line == 0
-            || start_line > lines.len()
-            || end_line > lines.len()
-            || start_line > end_line
-        {
-            return Err(Error::InvalidOperation(format!(
-                "Invalid line range: {}-{}",
-                start_line, end_line
-            )));
-        }
-
-        // Format new content as comment block (either //! or ///)
-        let comment_prefix = if is_module_doc { "//!" } else { "///" };
+        // Format new content as comment block (either //! or //)
+        let comment_prefix = if is_module_doc { "//!" } else { "//" };
         let markdown_block = new_content
             .lines()
-            .map(|line| format!("{} {}", comment_prefix, line))
+            .map(|line| {
+                if line.is_empty() {
+                    comment_prefix.to_string()
+                } else {
+                    format!("{} {}", comment_prefix, line)
+                }
+            })
             .collect::<Vec<_>>()
             .join("\n");
 
-        // Calculate byte offsets
-        let start_offset = self.line_start_offset(start_line, &lines);
-        let end_offset = self.line_to_byte_offset(end_line, &lines);
-
-        // Replace the old block with new content
-        // Note: end_offset already points past the newline of end_line
-        // and markdown_block has internal newlines but no trailing newline
-        let needs_newline = end_offset < self.content.len();
-        self.content = if needs_newline {
-            format!(
-                "{}{}\n{}",
-                &self.content[..start_offset],
-                markdown_block,
-                &self.content[end_offset..]
-            )
-        } else {
-            // Last line of file - no trailing newline needed
-            format!("{}{}", &self.content[..start_offset], markdown_block)
-        };
-
-        eprintln!("  needs_newline={}", needs_newline);
-
-        Ok(())
+        self.replace_lines(start_line, end_line, &markdown_block)
     }
 
     /// Edit raw Rust code by line range (for definition cells, etc.) without any formatting.
@@ -531,11 +764,20 @@ impl SourceEditor {
         end_line: usize,
         new_content: &str,
     ) -> Result<()> {
-        let lines: Vec<&str> = self.content.lines().collect();
+        self.replace_lines(start_line, end_line, new_content)
+    }
+
+    /// Replace a range of lines (1-indexed, inclusive) with text.
+    ///
+    /// The line break ending the range is kept, and trailing line breaks in
+    /// `text` are dropped, so the file's last newline is neither lost nor
+    /// duplicated when the range ends the file.
+    fn replace_lines(&mut self, start_line: usize, end_line: usize, text: &str) -> Result<()> {
+        let line_count = self.content.lines().count();
 
         if start_line == 0
-            || start_line > lines.len()
-            || end_line > lines.len()
+            || start_line > line_count
+            || end_line > line_count
             || start_line > end_line
         {
             return Err(Error::InvalidOperation(format!(
@@ -544,23 +786,27 @@ impl SourceEditor {
             )));
         }
 
-        // Calculate byte offsets
-        let start_offset = self.line_start_offset(start_line, &lines);
-        let end_offset = self.line_to_byte_offset(end_line, &lines);
-
-        // Replace with raw content (no formatting)
-        let needs_newline = end_offset < self.content.len();
-        self.content = if needs_newline {
-            format!(
-                "{}{}\n{}",
-                &self.content[..start_offset],
-                new_content,
-                &self.content[end_offset..]
-            )
+        let start_offset = self.line_start_offset(start_line);
+        let end_offset = self.line_to_byte_offset(end_line);
+
+        // Keep the range's own terminator (`\n` or `\r\n`), if it had one
+        let replaced = &self.content[start_offset..end_offset];
+        let line_end = if replaced.ends_with("\r\n") {
+            "\r\n"
+        } else if replaced.ends_with('\n') {
+            "\n"
         } else {
-            format!("{}{}", &self.content[..start_offset], new_content)
+            ""
         };
 
+        self.content = format!(
+            "{}{}{}{}",
+            &self.content[..start_offset],
+            text.trim_end_matches(['\r', '\n']),
+            line_end,
+            &self.content[end_offset..]
+        );
+
         Ok(())
     }
 
@@ -580,8 +826,8 @@ impl SourceEditor {
         }
 
         // Calculate byte offsets
-        let start_offset = self.line_start_offset(start_line, &lines);
-        let end_offset = self.line_to_byte_offset(end_line, &lines);
+        let start_offset = self.line_start_offset(start_line);
+        let end_offset = self.line_to_byte_offset(end_line);
 
         // Remove the markdown block
         self.content = format!(
@@ -596,121 +842,115 @@ impl SourceEditor {
         Ok(())
     }
 
-    /// Move a markdown cell up or down.
+    /// Move a markdown (or definition) cell up or down.
     ///
-    /// Swaps the markdown block with the adjacent one.
+    /// The block moves past the adjacent block: another markdown cell or a
+    /// top-level item with its doc comments, attributes and attached
+    /// comment. A definition cell moves with its attached comment. Blocks are
+    /// separated by exactly one blank line afterwards, so moving down and
+    /// back up restores the original text.
     pub fn move_markdown_cell(
         &mut self,
         start_line: usize,
         end_line: usize,
         direction: MoveDirection,
-    ) -> Result<()> {
-        let lines: Vec<&str> = self.content.lines().collect();
-
-        if start_line == 0
-            || start_line > lines.len()
-            || end_line > lines.len()
-            || start_line > end_line
-        {
-            return Err(Error::InvalidOperation(format!(
-                "Invalid line range: {}-{}",
-                start_line, end_line
-            )));
-        }
-
-        // Extract the markdown block
-        let start_offset = self.line_start_offset(start_line, &lines);
-        let end_offset = self.line_to_byte_offset(end_line, &lines);
-        let markdown_block = self.content[start_offset..end_offset].to_string();
+    ) -> Result<BlockMove> {
+        let blocks = self.block_spans()?;
+        let index = blocks
+            .iter()
+            .position(|&(start, end)| start <= start_line && end_line <= end)
+            .ok_or_else(|| {
+                Error::InvalidOperation(format!("No block at lines {}-{}", start_line, end_line))
+            })?;
+        let (start_line, end_line) = blocks[index];
 
-        // Find the adjacent block to swap with
-        let (swap_start_line, swap_end_line) = match direction {
+        let before_line = match direction {
             MoveDirection::Up => {
-                // Find the previous block (scan backwards)
-                if start_line == 1 {
+                if index == 0 {
                     return Err(Error::InvalidOperation(
                         "Cannot move first block up".to_string(),
                     ));
                 }
-
-                // Simple heuristic: find previous non-empty line group
-                let mut search_line = start_line - 1;
-                while search_line > 0 && lines[search_line - 1].trim().is_empty() {
-                    search_line -= 1;
-                }
-
-                if search_line == 0 {
-                    return Err(Error::InvalidOperation("No block found above".to_string()));
-                }
-
-                // Find the start of this block
-                let mut block_start = search_line;
-                while block_start > 1 && !lines[block_start - 2].trim().is_empty() {
-                    block_start -= 1;
-                }
-
-                (block_start, search_line)
+                Some(blocks[index - 1].0)
             }
             MoveDirection::Down => {
-                // Find the next block (scan forwards)
-                if end_line >= lines.len() {
+                if index + 1 >= blocks.len() {
                     return Err(Error::InvalidOperation(
                         "Cannot move last block down".to_string(),
                     ));
                 }
+                blocks.get(index + 2).map(|&(start, _)| start)
+            }
+        };
 
-                // Skip blank lines
-                let mut search_line = end_line + 1;
-                while search_line <= lines.len() && lines[search_line - 1].trim().is_empty() {
-                    search_line += 1;
-                }
+        self.move_block(start_line, end_line, before_line)
+    }
 
-                if search_line > lines.len() {
-                    return Err(Error::InvalidOperation("No block found below".to_string()));
-                }
+    /// Line spans of the blocks cells can move past, in source order:
+    /// markdown cells outside the module docs, and top-level items.
+    ///
+    /// An item's block starts at its doc comments and attributes, and
+    /// includes a `//` comment above it that the parser attaches to it
+    /// rather than treating as a markdown cell.
+    fn block_spans(&self) -> Result<Vec<(usize, usize)>> {
+        let file: SynFile = self.parse()?;
+        let parsed = CellParser::new().parse_str_partial(&self.content, &self.path)?;
+        let lines: Vec<&str> = self.content.lines().collect();
 
-                // Find the end of this block
-                let block_start = search_line;
-                let mut block_end = search_line;
-                while block_end < lines.len() && !lines[block_end].trim().is_empty() {
-                    block_end += 1;
-                }
+        let mut blocks: Vec<(usize, usize)> = parsed
+            .markdown_cells
+            .iter()
+            .filter(|md| !md.is_module_doc)
+            .map(|md| (md.span.start_line, md.span.end_line))
+            .collect();
+        let in_markdown = |line: usize| blocks.iter().any(|&(s, e)| s <= line && line <= e);
 
-                (block_start, block_end)
+        let mut items = Vec::new();
+        for item in &file.items {
+            let span = item.span();
+            let mut start = span.start().line;
+            let mut line = start;
+            while line > 1 && !in_markdown(line - 1) {
+                let above = lines[line - 2].trim();
+                if above.is_empty() {
+                    line -= 1;
+                } else if above.starts_with("//")
+                    && !above.starts_with("//!")
+                    && !above.starts_with("///")
+                {
+                    line -= 1;
+                    start = line;
+                } else {
+                    break;
+                }
             }
-        };
+            items.push((start, span.end().line));
+        }
 
-        // Extract the swap block
-        let swap_start_offset = self.line_start_offset(swap_start_line, &lines);
-        let swap_end_offset = self.line_to_byte_offset(swap_end_line, &lines);
-        let swap_block = self.content[swap_start_offset..swap_end_offset].to_string();
+        blocks.extend(items);
+        blocks.extend(
+            parsed
+                .unparsed_regions
+                .iter()
+                .map(|region| (region.span.start_line, region.span.end_line)),
+        );
+        blocks.sort();
+        Ok(blocks)
+    }
 
-        // Perform the swap based on direction
-        match direction {
-            MoveDirection::Up => {
-                // Swap block goes after markdown block
-                self.content = format!(
-                    "{}{}{}{}{}",
-                    &self.content[..swap_start_offset],
-                    markdown_block,
-                    &self.content[swap_end_offset..start_offset],
-                    swap_block,
-                    &self.content[end_offset..]
-                );
-            }
-            MoveDirection::Down => {
-                // Markdown block goes after swap block
-                self.content = format!(
-                    "{}{}{}{}{}",
-                    &self.content[..start_offset],
-                    swap_block,
-                    &self.content[end_offset..swap_start_offset],
-                    markdown_block,
-                    &self.content[swap_end_offset..]
-                );
-            }
-        }
+    /// Get the current source content.
+    pub fn content(&self) -> &str {
+        &self.content
+    }
 
+    /// Replace the whole source content.
+    ///
+    /// Used for edits outside any cell, such as the module doc. The new
+    /// content must still parse as Rust, apart from top-level items that
+    /// don't parse on their own.
+    pub fn replace_content(&mut self, content: String) -> Result<()> {
+        CellParser::parse_file_partial(&content)?;
+        self.content = content;
         Ok(())
     }
 
@@ -727,14 +967,12 @@ impl SourceEditor {
     ///
     /// Used for undo operations to capture cell content before deletion.
     pub fn get_cell_source(&self, cell_name: &str) -> Result<String> {
-        let file: SynFile = syn::parse_str(&self.content)
-            .map_err(|e| Error::Parse(format!("Failed to parse source: {}", e)))?;
+        let file: SynFile = self.parse()?;
 
         let (start_line, end_line) = self.find_cell_span(&file, cell_name)?;
 
-        let lines: Vec<&str> = self.content.lines().collect();
-        let start_offset = self.line_start_offset(start_line, &lines);
-        let end_offset = self.line_to_byte_offset(end_line, &lines);
+        let start_offset = self.line_start_offset(start_line);
+        let end_offset = self.line_to_byte_offset(end_line);
 
         Ok(self.content[start_offset..end_offset].to_string())
     }
@@ -744,8 +982,7 @@ impl SourceEditor {
     /// Returns None if the cell is the first one.
     /// Used for undo operations to track position for restoration.
     pub fn get_previous_cell_name(&self, cell_name: &str) -> Result<Option<String>> {
-        let file: SynFile = syn::parse_str(&self.content)
-            .map_err(|e| Error::Parse(format!("Failed to parse source: {}", e)))?;
+        let file: SynFile = self.parse()?;
 
         let cells = self.collect_cell_spans(&file);
 
@@ -766,8 +1003,7 @@ impl SourceEditor {
     /// If `after_cell_name` is None, inserts at the beginning (before all cells).
     /// Used for undo delete operations.
     pub fn restore_cell(&mut self, source: &str, after_cell_name: Option<&str>) -> Result<()> {
-        let file: SynFile = syn::parse_str(&self.content)
-            .map_err(|e| Error::Parse(format!("Failed to parse source: {}", e)))?;
+        let file: SynFile = self.parse()?;
 
         let insert_pos = if let Some(after_name) = after_cell_name {
             // Insert after the specified cell
@@ -780,8 +1016,7 @@ impl SourceEditor {
                 self.content.len()
             } else {
                 // Insert before the first cell
-                let lines: Vec<&str> = self.content.lines().collect();
-                self.line_start_offset(cells[0].1, &lines)
+                self.line_start_offset(cells[0].1)
             }
         };
 
@@ -798,88 +1033,210 @@ impl SourceEditor {
         Ok(())
     }
 
-    /// Find the span of a cell (start line to end line, 1-indexed).
-    /// Includes doc comments and attributes above the function.
-    pub fn find_cell_span(&self, file: &SynFile, cell_name: &str) -> Result<(usize, usize)> {
-        for item in &file.items {
-            if let syn::Item::Fn(func) = item
-                && Self::has_cell_attribute(&func.attrs)
-            {
-                let name = func.sig.ident.to_string();
-                if name == cell_name {
-                    // Start from the first attribute or doc comment
-                    let start_line = if !func.attrs.is_empty() {
-                        // Find earliest attribute/doc comment line
-                        func.attrs
-                            .iter()
-                            .map(|a| a.span().start().line)
-                            .min()
-                            .unwrap_or(func.sig.fn_token.span.start().line)
-                    } else {
-                        func.sig.fn_token.span.start().line
-                    };
+    /// Convert a code cell into a markdown cell by commenting out its source.
+    ///
+    /// Every line of the cell, including doc comments and attributes, is
+    /// prefixed with `//`. Fails if the commented block would not be parsed
+    /// as a markdown cell (e.g. because it attaches to the following item).
+    pub fn convert_cell_to_markdown(&mut self, cell_name: &str) -> Result<CellConversion> {
+        let file: SynFile = self.parse()?;
 
-                    let end_line = func.block.brace_token.span.close().end().line;
+        let (start_line, end_line) = self.find_cell_span(&file, cell_name)?;
 
-                    return Ok((start_line, end_line));
+        let lines: Vec<&str> = self.content.lines().collect();
+        let old_text = lines[start_line - 1..end_line].join("\n");
+        let new_text = old_text
+            .lines()
+            .map(|line| {
+                if line.trim().is_empty() {
+                    "//".to_string()
+                } else {
+                    format!("// {}", line)
                 }
-            }
-        }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
 
-        Err(Error::CellNotFound(format!(
-            "Cell '{}' not found",
-            cell_name
-        )))
+        self.replace_converted(start_line, end_line, old_text, new_text, CellType::Markdown)
     }
 
-    /// Find just the function span (NOT doc comments) for editing.
-    pub fn find_function_span(&self, file: &SynFile, cell_name: &str) -> Result<(usize, usize)> {
-        for item in &file.items {
-            if let syn::Item::Fn(func) = item
-                && Self::has_cell_attribute(&func.attrs)
-            {
-                let name = func.sig.ident.to_string();
-                if name == cell_name {
-                    // Start from pub fn, NOT doc comments
-                    let start_line = func.sig.fn_token.span.start().line;
-                    let end_line = func.block.brace_token.span.close().end().line;
-                    return Ok((start_line, end_line));
-                }
+    /// Convert a `//` markdown block into a code cell returning its text.
+    ///
+    /// The text is embedded in a raw string literal, so any markdown yields
+    /// valid Rust. Doc comment blocks (`//!`, `///`) are refused since they
+    /// cannot be moved into a function body without changing their meaning.
+    /// Returns the conversion and the name of the new cell.
+    pub fn convert_markdown_to_cell(
+        &mut self,
+        start_line: usize,
+        end_line: usize,
+    ) -> Result<(CellConversion, String)> {
+        let lines: Vec<&str> = self.content.lines().collect();
+
+        if start_line == 0
+            || start_line > lines.len()
+            || end_line > lines.len()
+            || start_line > end_line
+        {
+            return Err(Error::InvalidOperation(format!(
+                "Invalid line range: {}-{}",
+                start_line, end_line
+            )));
+        }
+
+        let old_text = lines[start_line - 1..end_line].join("\n");
+        let mut content_lines = Vec::new();
+        for line in old_text.lines() {
+            let trimmed = line.trim();
+            if trimmed.starts_with("//!") || trimmed.starts_with("///") {
+                return Err(Error::InvalidOperation(
+                    "Cannot convert doc comments to a code cell".to_string(),
+                ));
             }
+            let text = trimmed.strip_prefix("//").ok_or_else(|| {
+                Error::InvalidOperation(format!("Line {} is not a comment", start_line))
+            })?;
+            content_lines.push(text.strip_prefix(' ').unwrap_or(text));
         }
+        let content = content_lines.join("\n");
 
-        Err(Error::CellNotFound(format!(
-            "Cell '{}' not found",
-            cell_name
-        )))
+        let file: SynFile = self.parse()?;
+        let name = self.generate_unique_name(&self.collect_cell_names(&file));
+
+        // Use enough hashes that the text cannot terminate the literal early
+        let hashes = "#".repeat(Self::max_hash_run(&content) + 1);
+        let new_text = format!(
+            "#[venus::cell]\npub fn {}() -> String {{\n    r{}\"{}\"{}.to_string()\n}}",
+            name, hashes, content, hashes
+        );
+
+        let conversion =
+            self.replace_converted(start_line, end_line, old_text, new_text, CellType::Code)?;
+        Ok((conversion, name))
+    }
+
+    /// Replace a block with converted text, rolling back if the result is
+    /// not valid Rust or does not contain a cell of type `to` at the block.
+    fn replace_converted(
+        &mut self,
+        start_line: usize,
+        end_line: usize,
+        old_text: String,
+        new_text: String,
+        to: CellType,
+    ) -> Result<CellConversion> {
+        let original = self.content.clone();
+        self.edit_raw_code(start_line, end_line, &new_text)?;
+
+        let new_end_line = start_line + new_text.lines().count().max(1) - 1;
+        let block = start_line..=new_end_line;
+
+        let converted = CellParser::new()
+            .parse_str_partial(&self.content, &self.path)
+            .map(|result| match to {
+                CellType::Code => result
+                    .code_cells
+                    .iter()
+                    .any(|c| block.contains(&c.span.start_line)),
+                CellType::Markdown => result
+                    .markdown_cells
+                    .iter()
+                    .any(|m| m.span.start_line == start_line),
+                CellType::Definition => false,
+            });
+
+        match converted {
+            Ok(true) => Ok(CellConversion {
+                start_line,
+                old_end_line: end_line,
+                new_end_line,
+                old_text,
+                new_text,
+            }),
+            Ok(false) => {
+                self.content = original;
+                Err(Error::InvalidOperation(
+                    "Conversion would not produce a standalone cell".to_string(),
+                ))
+            }
+            Err(e) => {
+                self.content = original;
+                Err(Error::InvalidOperation(format!(
+                    "Conversion would produce invalid Rust: {}",
+                    e
+                )))
+            }
+        }
+    }
+
+    /// Length of the longest run of `#` directly following a `"`.
+    fn max_hash_run(text: &str) -> usize {
+        text.split('"')
+            .skip(1)
+            .map(|rest| rest.chars().take_while(|&c| c == '#').count())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Find the span of a cell (start line to end line, 1-indexed).
+    /// Includes doc comments and attributes above the function.
+    pub fn find_cell_span(&self, file: &SynFile, cell_name: &str) -> Result<(usize, usize)> {
+        if let Some(func) = Self::cell_fns(file)
+            .into_iter()
+            .find(|f| f.name == cell_name)
+        {
+            return Ok((func.start_line(), func.end_line()));
+        }
+
+        Err(Error::CellNotFound(format!(
+            "Cell '{}' not found",
+            cell_name
+        )))
+    }
+
+    /// Lines of a cell, including its doc comments and attributes.
+    pub fn cell_line_range(&self, cell_name: &str) -> Result<(usize, usize)> {
+        let file: SynFile = self.parse()?;
+        self.find_cell_span(&file, cell_name)
+    }
+
+    /// Find just the function span (NOT doc comments) for editing.
+    pub fn find_function_span(&self, file: &SynFile, cell_name: &str) -> Result<(usize, usize)> {
+        if let Some(func) = Self::cell_fns(file)
+            .into_iter()
+            .find(|f| f.name == cell_name)
+        {
+            // Start from pub fn, NOT doc comments
+            let start_line = func.sig.fn_token.span.start().line;
+            return Ok((start_line, func.end_line()));
+        }
+
+        Err(Error::CellNotFound(format!(
+            "Cell '{}' not found",
+            cell_name
+        )))
     }
 
     /// Extract existing doc comments for a cell.
     /// Returns them in "/// comment" format, preserving original formatting.
     pub fn extract_doc_comments(&self, cell_name: &str) -> Result<Vec<String>> {
-        let file: SynFile =
-            syn::parse_str(&self.content).map_err(|e| Error::Parse(e.to_string()))?;
-
-        for item in &file.items {
-            if let syn::Item::Fn(func) = item
-                && Self::has_cell_attribute(&func.attrs)
-            {
-                let name = func.sig.ident.to_string();
-                if name == cell_name {
-                    let mut doc_lines = Vec::new();
-                    for attr in &func.attrs {
-                        if attr.path().is_ident("doc")
-                            && let syn::Meta::NameValue(meta) = &attr.meta
-                            && let syn::Expr::Lit(lit) = &meta.value
-                            && let syn::Lit::Str(s) = &lit.lit
-                        {
-                            // syn stores doc comments without the leading space
-                            // e.g., /// Hello -> doc = " Hello"
-                            doc_lines.push(format!("///{}", s.value()));
-                        }
+        let file: SynFile = self.parse()?;
+
+        for func in Self::cell_fns(&file) {
+            if func.name == cell_name {
+                let mut doc_lines = Vec::new();
+                for attr in func.attrs {
+                    if attr.path().is_ident("doc")
+                        && let syn::Meta::NameValue(meta) = &attr.meta
+                        && let syn::Expr::Lit(lit) = &meta.value
+                        && let syn::Lit::Str(s) = &lit.lit
+                    {
+                        // syn stores doc comments without the leading space
+                        // e.g., /// Hello -> doc = " Hello"
+                        doc_lines.push(format!("///{}", s.value()));
                     }
-                    return Ok(doc_lines);
                 }
+                return Ok(doc_lines);
             }
         }
 
@@ -891,7 +1248,23 @@ impl SourceEditor {
 
     /// Reconstruct a complete cell including doc comments and attributes.
     /// Returns the full cell text: doc comments + #[venus::cell] + function.
+    ///
+    /// The lines above the function (doc comments and attributes) are kept
+    /// verbatim, and surrounding blank lines are trimmed from the function,
+    /// so reconstructing a cell from its own source reproduces it exactly.
     pub fn reconstruct_cell(&self, cell_name: &str, new_function: &str) -> Result<String> {
+        let file: SynFile = self.parse()?;
+        let (start_line, _) = self.find_cell_span(&file, cell_name)?;
+        let function_line = Self::function_start_line(&file, cell_name)?;
+        let new_function = Self::trim_blank_lines(new_function);
+
+        if start_line < function_line {
+            let lines: Vec<&str> = self.content.lines().collect();
+            let header = lines[start_line - 1..function_line - 1].join("\n");
+            return Ok(format!("{}\n{}", header, new_function));
+        }
+
+        // Attributes on the same line as the function cannot be split off
         let doc_comments = self.extract_doc_comments(cell_name)?;
 
         if !doc_comments.is_empty() {
@@ -912,8 +1285,7 @@ impl SourceEditor {
         cell_name: &str,
         new_function: &str,
     ) -> Result<(String, usize, usize)> {
-        let file: SynFile =
-            syn::parse_str(&self.content).map_err(|e| Error::Parse(e.to_string()))?;
+        let file: SynFile = self.parse()?;
 
         let reconstructed = self.reconstruct_cell(cell_name, new_function)?;
         let (start_line, end_line) = self.find_cell_span(&file, cell_name)?;
@@ -921,22 +1293,84 @@ impl SourceEditor {
         Ok((reconstructed, start_line, end_line))
     }
 
-    /// Get the byte offset at the start of a line (1-indexed).
-    fn line_start_offset(&self, line: usize, lines: &[&str]) -> usize {
-        if line <= 1 {
-            return 0;
+    /// Replace several cells' functions in one pass.
+    ///
+    /// Each edit is `(cell_name, new_function)`. All spans are resolved
+    /// against the current content before anything changes, then applied
+    /// from the bottom of the file up so earlier spans stay valid.
+    ///
+    /// Returns the replaced blocks from the top of the file down, with line
+    /// numbers from before the edit. Undoing them in that order, or redoing
+    /// them in reverse, keeps each block's lines valid.
+    pub fn edit_cells(&mut self, edits: &[(String, String)]) -> Result<Vec<CellConversion>> {
+        let mut spans = Vec::with_capacity(edits.len());
+        for (cell_name, new_function) in edits {
+            if spans.iter().any(|(name, _, _, _)| *name == cell_name) {
+                return Err(Error::InvalidOperation(format!(
+                    "Cell '{}' is edited more than once",
+                    cell_name
+                )));
+            }
+            let (reconstructed, start_line, end_line) =
+                self.reconstruct_and_get_span(cell_name, new_function)?;
+            spans.push((cell_name, reconstructed, start_line, end_line));
         }
 
-        let mut offset = 0;
-        for (i, l) in lines.iter().enumerate() {
-            if i + 1 >= line {
-                break;
-            }
-            offset += l.len();
-            offset += 1; // newline
+        spans.sort_by_key(|(_, _, start_line, _)| std::cmp::Reverse(*start_line));
+        let lines: Vec<&str> = self.content.lines().collect();
+        let mut blocks: Vec<CellConversion> = spans
+            .into_iter()
+            .map(|(_, new_text, start_line, end_line)| CellConversion {
+                start_line,
+                old_end_line: end_line,
+                new_end_line: start_line + new_text.lines().count().max(1) - 1,
+                old_text: lines[start_line - 1..end_line].join("\n"),
+                new_text,
+            })
+            .collect();
+        for block in &blocks {
+            self.edit_raw_code(block.start_line, block.old_end_line, &block.new_text)?;
         }
 
-        offset.min(self.content.len())
+        blocks.reverse();
+        Ok(blocks)
+    }
+
+    /// Line where a cell's function starts, after its attributes (at the
+    /// visibility or `fn` keyword).
+    fn function_start_line(file: &SynFile, cell_name: &str) -> Result<usize> {
+        if let Some(func) = Self::cell_fns(file)
+            .into_iter()
+            .find(|f| f.name == cell_name)
+        {
+            return Ok(func.function_start_line());
+        }
+
+        Err(Error::CellNotFound(format!(
+            "Cell '{}' not found",
+            cell_name
+        )))
+    }
+
+    /// Remove leading blank lines and trailing whitespace.
+    fn trim_blank_lines(text: &str) -> &str {
+        let text = text.trim_end();
+        let first_line = text
+            .find(|c: char| !c.is_whitespace())
+            .map(|i| text[..i].rfind('\n').map_or(0, |n| n + 1))
+            .unwrap_or(text.len());
+        &text[first_line..]
+    }
+
+    /// Get the byte offset at the start of a line (1-indexed).
+    ///
+    /// Offsets are measured on the actual text, so `\r\n` line endings and
+    /// multibyte characters are accounted for.
+    fn line_start_offset(&self, line: usize) -> usize {
+        if line <= 1 {
+            return 0;
+        }
+        self.line_to_byte_offset(line - 1)
     }
 
     /// Get the indentation (leading whitespace) of a line (1-indexed).
@@ -977,50 +1411,25 @@ impl SourceEditor {
         self.content = result;
     }
 
-    /// Collect all cell function names from the file.
+    /// Collect all cell function names from the file, without the `Type::`
+    /// of cells in impl blocks (dependents refer to cells by these names).
     fn collect_cell_names(&self, file: &SynFile) -> HashSet<String> {
-        let mut names = HashSet::new();
-
-        for item in &file.items {
-            if let syn::Item::Fn(func) = item
-                && Self::has_cell_attribute(&func.attrs)
-            {
-                names.insert(func.sig.ident.to_string());
-            }
-        }
-
-        names
+        Self::cell_fns(file)
+            .into_iter()
+            .map(|func| func.sig.ident.to_string())
+            .collect()
     }
 
     /// Collect all cells with their spans in source order.
     /// Returns Vec of (name, start_line, end_line).
     fn collect_cell_spans(&self, file: &SynFile) -> Vec<(String, usize, usize)> {
-        let mut cells = Vec::new();
-
-        for item in &file.items {
-            if let syn::Item::Fn(func) = item
-                && Self::has_cell_attribute(&func.attrs)
-            {
-                let name = func.sig.ident.to_string();
-
-                // Start from the first attribute or doc comment
-                let start_line = if !func.attrs.is_empty() {
-                    func.attrs
-                        .iter()
-                        .map(|a| a.span().start().line)
-                        .min()
-                        .unwrap_or(func.sig.fn_token.span.start().line)
-                } else {
-                    func.sig.fn_token.span.start().line
-                };
-
-                let end_line = func.block.brace_token.span.close().end().line;
-
-                cells.push((name, start_line, end_line));
-            }
-        }
-
-        cells
+        Self::cell_fns(file)
+            .into_iter()
+            .map(|func| {
+                let (start_line, end_line) = (func.start_line(), func.end_line());
+                (func.name, start_line, end_line)
+            })
+            .collect()
     }
 
     /// Generate a unique cell name (new_cell_1, new_cell_2, etc.).
@@ -1052,32 +1461,47 @@ impl SourceEditor {
         unreachable!()
     }
 
+    /// Adjust a line to insert before: lines inside an item move to the start
+    /// of the item (its first attribute or doc comment), and lines in the
+    /// module docs and inner attributes move below them. Returns the line and
+    /// whether it was moved below the module header.
+    fn insertion_line(file: &SynFile, line: usize) -> (usize, bool) {
+        let header_end = file
+            .attrs
+            .iter()
+            .map(|a| a.span().end().line)
+            .max()
+            .unwrap_or(0);
+        let after_header = line <= header_end;
+        let line = line.max(header_end + 1);
+
+        for item in &file.items {
+            let span = item.span();
+            if span.start().line <= line && line <= span.end().line {
+                return (span.start().line, after_header);
+            }
+        }
+        (line, after_header)
+    }
+
     /// Find the byte position where the new cell should be inserted.
     fn find_insert_position(&self, file: &SynFile, after_cell_id: Option<&str>) -> Result<usize> {
-        let lines: Vec<&str> = self.content.lines().collect();
-
         // Track the end position of cells
         let mut last_cell_end_line = 0;
         let mut target_end_line = None;
 
-        for item in &file.items {
-            if let syn::Item::Fn(func) = item
-                && Self::has_cell_attribute(&func.attrs)
-            {
-                let name = func.sig.ident.to_string();
-
-                // Get the end line of this function
-                let end_line = func.block.brace_token.span.close().end().line;
-
-                if let Some(target) = after_cell_id
-                    && name == target
-                {
-                    target_end_line = Some(end_line);
-                    break;
-                }
+        for func in Self::cell_fns(file) {
+            // Get the end line of this function
+            let end_line = func.end_line();
 
-                last_cell_end_line = end_line;
+            if let Some(target) = after_cell_id
+                && func.name == target
+            {
+                target_end_line = Some(end_line);
+                break;
             }
+
+            last_cell_end_line = end_line;
         }
 
         // Determine which line to insert after
@@ -1094,29 +1518,26 @@ impl SourceEditor {
         };
 
         // Convert line number to byte offset (lines are 1-indexed from syn)
-        let byte_offset = self.line_to_byte_offset(insert_after_line, &lines);
+        let byte_offset = self.line_to_byte_offset(insert_after_line);
 
         Ok(byte_offset)
     }
 
-    /// Convert a 1-indexed line number to a byte offset (end of that line).
-    fn line_to_byte_offset(&self, line: usize, lines: &[&str]) -> usize {
-        if line == 0 || line > lines.len() {
+    /// Convert a 1-indexed line number to a byte offset (end of that line,
+    /// after its line terminator).
+    fn line_to_byte_offset(&self, line: usize) -> usize {
+        if line == 0 {
             return self.content.len();
         }
 
-        // Sum the bytes of all lines up to and including the target line
-        let mut offset = 0;
-        for (i, l) in lines.iter().enumerate() {
-            offset += l.len();
-            offset += 1; // newline character
-
-            if i + 1 == line {
-                break;
-            }
-        }
-
-        offset.min(self.content.len())
+        let offset = self
+            .content
+            .split_inclusive('\n')
+            .take(line)
+            .map(str::len)
+            .sum::<usize>();
+        debug_assert!(self.content.is_char_boundary(offset));
+        offset
     }
 
     /// Generate the code for a new cell.
@@ -1134,6 +1555,52 @@ pub fn {}() -> String {{
         )
     }
 
+    /// Collect the cell functions of the file in source order, including
+    /// cells defined as associated functions of inherent impl blocks.
+    fn cell_fns(file: &SynFile) -> Vec<CellFn<'_>> {
+        let mut cells = Vec::new();
+        for item in &file.items {
+            match item {
+                syn::Item::Fn(func) if Self::has_cell_attribute(&func.attrs) => {
+                    cells.push(CellFn {
+                        name: func.sig.ident.to_string(),
+                        attrs: &func.attrs,
+                        vis: &func.vis,
+                        sig: &func.sig,
+                        block: &func.block,
+                    });
+                }
+                syn::Item::Impl(item_impl) => {
+                    let Some(ty) = CellParser::inherent_impl_type(item_impl) else {
+                        continue;
+                    };
+                    for impl_item in &item_impl.items {
+                        if let syn::ImplItem::Fn(func) = impl_item
+                            && CellParser::is_cell_method(func)
+                        {
+                            cells.push(CellFn {
+                                name: format!("{}::{}", ty, func.sig.ident),
+                                attrs: &func.attrs,
+                                vis: &func.vis,
+                                sig: &func.sig,
+                                block: &func.block,
+                            });
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        cells
+    }
+
+    /// Parse the current content, skipping top-level items that don't parse
+    /// so cells can still be edited while another part of the notebook is
+    /// broken. Line numbers match the content.
+    fn parse(&self) -> Result<SynFile> {
+        CellParser::parse_file_partial(&self.content)
+    }
+
     /// Check if a function has the #[venus::cell] attribute.
     fn has_cell_attribute(attrs: &[Attribute]) -> bool {
         attrs.iter().any(|attr| {
@@ -1224,6 +1691,537 @@ pub fn second(first: &i32) -> i32 {
         assert!(new_pos < second_pos);
     }
 
+    #[test]
+    fn test_insert_cell_before_line() {
+        let source = r#"//! # Notebook
+
+use venus::prelude::*;
+
+/// First cell
+#[venus::cell]
+pub fn first() -> i32 {
+    1
+}
+
+// Some notes
+
+/// Second cell
+#[venus::cell]
+pub fn second(first: &i32) -> i32 {
+    *first + 1
+}
+"#;
+
+        let file = create_temp_file(source);
+
+        // Before the first item, but after the module docs
+        let mut editor = SourceEditor::load(file.path()).unwrap();
+        let name = editor.insert_cell_before_line(Some(1)).unwrap();
+        assert_eq!(name, "new_cell_1");
+        assert!(
+            editor
+                .content
+                .starts_with("//! # Notebook\n\n/// New cell\n#[venus::cell]\npub fn new_cell_1()")
+        );
+        assert!(editor.content.contains("}\n\nuse venus::prelude::*;"));
+        drop(editor);
+
+        // A line inside a cell's doc comments goes before the whole cell
+        let mut editor = SourceEditor::load(file.path()).unwrap();
+        editor.insert_cell_before_line(Some(7)).unwrap();
+        let new_pos = editor.content.find("pub fn new_cell_1()").unwrap();
+        assert!(editor.content.find("use venus").unwrap() < new_pos);
+        assert!(new_pos < editor.content.find("/// First cell").unwrap());
+        drop(editor);
+
+        // Between a markdown block and the next cell
+        let mut editor = SourceEditor::load(file.path()).unwrap();
+        editor.insert_cell_before_line(Some(13)).unwrap();
+        let new_pos = editor.content.find("pub fn new_cell_1()").unwrap();
+        assert!(editor.content.find("// Some notes").unwrap() < new_pos);
+        assert!(new_pos < editor.content.find("/// Second cell").unwrap());
+        syn::parse_file(&editor.content).unwrap();
+        drop(editor);
+
+        // At the end of the file
+        let mut editor = SourceEditor::load(file.path()).unwrap();
+        editor.insert_cell_before_line(None).unwrap();
+        assert!(
+            editor
+                .content
+                .ends_with("pub fn new_cell_1() -> String {\n    \"Hello\".to_string()\n}\n")
+        );
+        assert!(editor.content.contains("*first + 1\n}\n\n/// New cell"));
+    }
+
+    #[test]
+    fn test_move_block() {
+        let source = r#"//! # Notebook
+
+use venus::prelude::*;
+
+// Notes
+
+/// First cell
+#[venus::cell]
+pub fn first() -> i32 {
+    1
+}
+
+pub struct Config {
+    pub n: i32,
+}
+"#;
+
+        let file = create_temp_file(source);
+
+        // To the top, below the module docs, and back
+        let mut editor = SourceEditor::load(file.path()).unwrap();
+        let moved = editor.move_block(5, 5, Some(1)).unwrap();
+        assert!(
+            editor.content.starts_with(
+                "//! # Notebook\n\n// Notes\n\nuse venus::prelude::*;\n\n/// First cell"
+            )
+        );
+        assert_eq!((moved.new_start_line, moved.new_end_line), (3, 3));
+        assert_eq!(moved.undo_before_line, Some(7));
+        editor
+            .move_block(
+                moved.new_start_line,
+                moved.new_end_line,
+                moved.undo_before_line,
+            )
+            .unwrap();
+        assert_eq!(editor.content, source);
+
+        // Before a cell's doc comments, from the end of the file, and back
+        let moved = editor.move_block(13, 15, Some(9)).unwrap();
+        assert_eq!((moved.new_start_line, moved.new_end_line), (7, 9));
+        assert_eq!(moved.undo_before_line, None);
+        assert!(
+            editor
+                .content
+                .contains("// Notes\n\npub struct Config {\n    pub n: i32,\n}\n\n/// First cell")
+        );
+        assert!(editor.content.ends_with("    1\n}\n"));
+        editor
+            .move_block(
+                moved.new_start_line,
+                moved.new_end_line,
+                moved.undo_before_line,
+            )
+            .unwrap();
+        assert_eq!(editor.content, source);
+
+        // To the end of the file
+        let moved = editor.move_block(5, 5, None).unwrap();
+        assert!(editor.content.ends_with("}\n\n// Notes\n"));
+        assert_eq!((moved.new_start_line, moved.new_end_line), (15, 15));
+        assert_eq!(moved.undo_before_line, Some(5));
+
+        assert!(editor.move_block(0, 1, None).is_err());
+    }
+
+    #[test]
+    fn test_reorder_blocks() {
+        let source = r#"//! # Notebook
+
+use venus::prelude::*;
+
+/// Second
+#[venus::cell]
+pub fn second(first: &i32) -> i32 {
+    *first + 1
+}
+
+// About the first cell
+
+/// First
+#[venus::cell]
+pub fn first() -> i32 {
+    1
+}
+"#;
+
+        let file = create_temp_file(source);
+        let mut editor = SourceEditor::load(file.path()).unwrap();
+        editor.reorder_blocks(&[(3, 3), (11, 17), (5, 9)]).unwrap();
+        assert_eq!(
+            editor.content,
+            "//! # Notebook\n\nuse venus::prelude::*;\n\n// About the first cell\n\n\
+             /// First\n#[venus::cell]\npub fn first() -> i32 {\n    1\n}\n\n\
+             /// Second\n#[venus::cell]\npub fn second(first: &i32) -> i32 {\n    *first + 1\n}\n"
+        );
+
+        assert!(editor.reorder_blocks(&[(3, 5), (5, 9)]).is_err());
+    }
+
+    /// Apply an edit the way `Session::edit_cell` does.
+    fn edit_cell(editor: &mut SourceEditor, cell_name: &str, new_source: &str) {
+        let (reconstructed, start_line, end_line) = editor
+            .reconstruct_and_get_span(cell_name, new_source)
+            .unwrap();
+        editor
+            .edit_raw_code(start_line, end_line, &reconstructed)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_repeated_cell_edit_is_idempotent() {
+        let source = r#"use venus::prelude::*;
+
+#[venus::cell]
+pub fn plain() -> i32 {
+    1
+}
+
+/// Documented
+#[venus::cell(timeout_ms = 500)]
+pub fn documented() -> i32 {
+    2
+}
+
+#[venus::cell] pub fn inline() -> i32 { 3 }
+
+pub fn helper() {}
+"#;
+        let file = create_temp_file(source);
+        let mut editor = SourceEditor::load(file.path()).unwrap();
+
+        for (name, body) in [
+            ("plain", "pub fn plain() -> i32 {\n    10\n}\n"),
+            (
+                "documented",
+                "\npub fn documented() -> i32 {\n    20\n}\n\n",
+            ),
+            ("inline", "pub fn inline() -> i32 {\n    30\n}"),
+        ] {
+            edit_cell(&mut editor, name, body);
+            let once = editor.content.clone();
+            edit_cell(&mut editor, name, body);
+            assert_eq!(editor.content, once, "editing '{}' twice drifted", name);
+        }
+
+        assert!(
+            editor.content.contains(
+                "\n\n#[venus::cell]\npub fn plain() -> i32 {\n    10\n}\n\n/// Documented"
+            )
+        );
+        assert!(editor.content.contains(
+            "/// Documented\n#[venus::cell(timeout_ms = 500)]\npub fn documented() -> i32 {\n    20\n}\n\n"
+        ));
+        assert!(
+            editor
+                .content
+                .ends_with("    30\n}\n\npub fn helper() {}\n")
+        );
+    }
+
+    #[test]
+    fn test_edit_cells_matches_sequential_edits() {
+        let source = r#"use venus::prelude::*;
+
+#[venus::cell]
+pub fn a() -> i32 {
+    1
+}
+
+/// Middle
+#[venus::cell]
+pub fn b(a: &i32) -> i32 {
+    *a + 1
+}
+
+#[venus::cell]
+pub fn c(b: &i32) -> i32 {
+    *b * 2
+}
+"#;
+        let edits = vec![
+            (
+                "a".to_string(),
+                "pub fn a() -> i32 {\n    let x = 1;\n    x + 9\n}".to_string(),
+            ),
+            (
+                "c".to_string(),
+                "pub fn c(b: &i32) -> i32 {\n    *b * 3\n}".to_string(),
+            ),
+        ];
+
+        let file = create_temp_file(source);
+        let mut batched_editor = SourceEditor::load(file.path()).unwrap();
+        let blocks = batched_editor.edit_cells(&edits).unwrap();
+        let duplicate = vec![edits[0].clone(), edits[0].clone()];
+        assert!(batched_editor.edit_cells(&duplicate).is_err());
+        let batched = batched_editor.content.clone();
+
+        // Undo top-down and redo bottom-up through the returned blocks
+        for block in &blocks {
+            batched_editor
+                .edit_raw_code(block.start_line, block.new_end_line, &block.old_text)
+                .unwrap();
+        }
+        assert_eq!(batched_editor.content, source);
+        for block in blocks.iter().rev() {
+            batched_editor
+                .edit_raw_code(block.start_line, block.old_end_line, &block.new_text)
+                .unwrap();
+        }
+        assert_eq!(batched_editor.content, batched);
+        drop(batched_editor);
+        assert!(batched.contains("/// Middle\n#[venus::cell]\npub fn b"));
+
+        let mut sequential = SourceEditor::load(file.path()).unwrap();
+        for (name, body) in &edits {
+            edit_cell(&mut sequential, name, body);
+        }
+        assert_eq!(batched, sequential.content);
+    }
+
+    #[test]
+    fn test_edits_around_unparsed_item() {
+        let source = r#"use venus::prelude::*;
+
+#[venus::cell]
+pub fn first() -> i32 {
+    1
+}
+
+#[venus::cell]
+pub fn broken() -> i32 {
+    1 +
+}
+
+#[venus::cell]
+pub fn second() -> i32 {
+    2
+}
+"#;
+
+        let file = create_temp_file(source);
+        let mut editor = SourceEditor::load(file.path()).unwrap();
+        edit_cell(&mut editor, "second", "pub fn second() -> i32 {\n    3\n}");
+        editor.move_cell("first", MoveDirection::Down).unwrap();
+        assert!(editor.content.contains("    1 +\n"));
+
+        // Fix the broken item by its line range
+        let start = editor
+            .content
+            .lines()
+            .position(|l| l.contains("fn broken"))
+            .unwrap();
+        editor
+            .edit_raw_code(
+                start + 1,
+                start + 3,
+                "pub fn broken() -> i32 {\n    1 + 1\n}",
+            )
+            .unwrap();
+        let result = CellParser::new()
+            .parse_str(&editor.content, file.path())
+            .unwrap();
+        let names: Vec<_> = result.code_cells.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["second", "broken", "first"]);
+    }
+
+    #[test]
+    fn test_impl_method_cells() {
+        let source = r#"use venus::prelude::*;
+
+pub struct Cells;
+
+impl Cells {
+    /// Loads data
+    #[venus::cell]
+    pub fn load() -> i32 {
+        1
+    }
+
+    #[venus::cell]
+    pub fn scale(load: &i32) -> i32 {
+        *load * 2
+    }
+}
+
+#[venus::cell]
+pub fn total(scale: &i32) -> i32 {
+    *scale + 1
+}
+"#;
+
+        let file = create_temp_file(source);
+        let mut editor = SourceEditor::load(file.path()).unwrap();
+        assert_eq!(editor.cell_line_range("Cells::load").unwrap(), (6, 10));
+        assert_eq!(
+            editor.extract_doc_comments("Cells::load").unwrap(),
+            vec!["/// Loads data".to_string()]
+        );
+
+        edit_cell(
+            &mut editor,
+            "Cells::scale",
+            "pub fn scale(load: &i32) -> i32 {\n    *load * 3\n}",
+        );
+        assert!(editor.content.contains("*load * 3"));
+
+        let name = editor.duplicate_cell("Cells::load").unwrap();
+        assert_eq!(name, "Cells::load_copy");
+        assert!(editor.content.contains("    pub fn load_copy() -> i32 {"));
+
+        assert!(
+            editor
+                .move_cell("Cells::scale", MoveDirection::Down)
+                .is_err()
+        );
+        editor.move_cell("Cells::scale", MoveDirection::Up).unwrap();
+        let result = CellParser::new()
+            .parse_str(&editor.content, file.path())
+            .unwrap();
+        let names: Vec<_> = result.code_cells.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["Cells::load", "Cells::scale", "Cells::load_copy", "total"]
+        );
+    }
+
+    const MULTIBYTE_NOTEBOOK: &str = "//! # ノートブック 📓\n\nuse venus::prelude::*;\n\n// ## 概要\n// データを読み込む 🚀\n\n/// 最初のセル\n#[venus::cell]\npub fn first() -> String {\n    \"こんにちは\".to_string()\n} // 終わり\n\n// 中間のメモ ✨\n\n#[venus::cell]\npub fn second(first: &String) -> usize {\n    first.chars().count()\n}\n";
+
+    /// Insert, edit and delete around multibyte text without panicking.
+    fn exercise_multibyte_edits(source: &str) -> String {
+        let file = create_temp_file(source);
+        let mut editor = SourceEditor::load(file.path()).unwrap();
+
+        editor.insert_markdown_cell("追加 🎉", Some(10)).unwrap();
+        editor.insert_cell(Some("first")).unwrap();
+        edit_cell(
+            &mut editor,
+            "first",
+            "pub fn first() -> String {\n    \"さようなら 👋\".to_string()\n}",
+        );
+
+        let parsed = CellParser::new()
+            .parse_str(&editor.content, file.path())
+            .unwrap();
+        let notes = parsed
+            .markdown_cells
+            .iter()
+            .find(|md| md.content.contains("追加"))
+            .unwrap();
+        editor
+            .edit_markdown_cell(
+                notes.span.start_line,
+                notes.span.end_line,
+                "編集済み",
+                false,
+            )
+            .unwrap();
+
+        let parsed = CellParser::new()
+            .parse_str(&editor.content, file.path())
+            .unwrap();
+        let intro = parsed
+            .markdown_cells
+            .iter()
+            .find(|md| md.content.contains("概要"))
+            .unwrap();
+        editor
+            .delete_markdown_cell(intro.span.start_line, intro.span.end_line)
+            .unwrap();
+        editor.delete_cell("new_cell_1").unwrap();
+
+        syn::parse_file(&editor.content).unwrap();
+        editor.content
+    }
+
+    #[test]
+    fn test_multibyte_notebook_edits() {
+        let content = exercise_multibyte_edits(MULTIBYTE_NOTEBOOK);
+        assert!(content.contains("\"さようなら 👋\""));
+        assert!(!content.contains("追加"));
+        assert!(content.contains("編集済み\n"));
+        assert!(content.contains("// 中間のメモ ✨"));
+        assert!(!content.contains("概要"));
+        assert!(!content.contains("new_cell_1"));
+        assert!(content.contains("first.chars().count()"));
+    }
+
+    #[test]
+    fn test_multibyte_notebook_edits_with_crlf() {
+        let content = exercise_multibyte_edits(&MULTIBYTE_NOTEBOOK.replace('\n', "\r\n"));
+        assert!(content.contains("\"さようなら 👋\""));
+        assert!(content.contains("編集済み"));
+        assert!(!content.contains("概要"));
+        assert!(!content.contains("new_cell_1"));
+    }
+
+    const MARKDOWN_CELLS: &str = "// # First\n\n#[venus::cell]\npub fn a() -> i32 {\n    1\n}\n\n// ## Middle\n// text\n\n#[venus::cell]\npub fn b() -> i32 {\n    2\n}\n\n// Last\n";
+
+    #[test]
+    fn test_edit_first_markdown_cell() {
+        let file = create_temp_file(MARKDOWN_CELLS);
+        let mut editor = SourceEditor::load(file.path()).unwrap();
+        editor
+            .edit_markdown_cell(1, 1, "# Title\n\nIntro", false)
+            .unwrap();
+        assert_eq!(
+            editor.content,
+            MARKDOWN_CELLS.replace("// # First\n", "// # Title\n//\n// Intro\n")
+        );
+    }
+
+    #[test]
+    fn test_edit_middle_markdown_cell() {
+        let file = create_temp_file(MARKDOWN_CELLS);
+        let mut editor = SourceEditor::load(file.path()).unwrap();
+        editor
+            .edit_markdown_cell(8, 9, "## Changed\n", false)
+            .unwrap();
+        assert_eq!(
+            editor.content,
+            MARKDOWN_CELLS.replace("// ## Middle\n// text\n", "// ## Changed\n")
+        );
+    }
+
+    #[test]
+    fn test_edit_last_markdown_cell_keeps_final_newline() {
+        let file = create_temp_file(MARKDOWN_CELLS);
+        let mut editor = SourceEditor::load(file.path()).unwrap();
+        editor
+            .edit_markdown_cell(16, 16, "Very last", false)
+            .unwrap();
+        assert_eq!(
+            editor.content,
+            MARKDOWN_CELLS.replace("// Last\n", "// Very last\n")
+        );
+        // Still a markdown cell, not a dangling doc comment
+        let parsed = CellParser::new()
+            .parse_str(&editor.content, file.path())
+            .unwrap();
+        assert!(
+            parsed
+                .markdown_cells
+                .iter()
+                .any(|md| md.content == "Very last")
+        );
+
+        // A file without a final newline does not gain one
+        let trimmed = MARKDOWN_CELLS.trim_end();
+        let file = create_temp_file(trimmed);
+        let mut editor = SourceEditor::load(file.path()).unwrap();
+        editor
+            .edit_markdown_cell(16, 16, "Very last\n", false)
+            .unwrap();
+        assert_eq!(editor.content, trimmed.replace("// Last", "// Very last"));
+    }
+
+    #[test]
+    fn test_edit_raw_code_at_end_of_file() {
+        let file = create_temp_file("// Notes\n\npub struct A;\n");
+        let mut editor = SourceEditor::load(file.path()).unwrap();
+        editor.edit_raw_code(3, 3, "pub struct B;\n").unwrap();
+        assert_eq!(editor.content, "// Notes\n\npub struct B;\n");
+    }
+
     #[test]
     fn test_unique_name_generation() {
         let source = r#"use venus::prelude::*;
@@ -1568,4 +2566,189 @@ pub fn second() -> i32 {
         let first_doc_pos = editor.content.find("This is the first cell").unwrap();
         assert!(second_doc_pos < first_doc_pos);
     }
+
+    const MARKDOWN_NOTEBOOK: &str = r#"//! # Notebook
+
+use venus::prelude::*;
+
+// # Intro
+// Some text
+
+/// First cell
+#[venus::cell]
+pub fn first() -> i32 {
+    1
+}
+
+// ## Notes
+
+pub struct Config {
+    pub n: i32,
+}
+"#;
+
+    #[test]
+    fn test_move_markdown_cell_round_trip() {
+        let file = create_temp_file(MARKDOWN_NOTEBOOK);
+        let mut editor = SourceEditor::load(file.path()).unwrap();
+
+        let moved = editor
+            .move_markdown_cell(5, 6, MoveDirection::Down)
+            .unwrap();
+        assert!(
+            editor
+                .content
+                .contains("    1\n}\n\n// # Intro\n// Some text\n\n// ## Notes")
+        );
+        editor
+            .move_markdown_cell(moved.new_start_line, moved.new_end_line, MoveDirection::Up)
+            .unwrap();
+        assert_eq!(editor.content, MARKDOWN_NOTEBOOK);
+
+        let moved = editor
+            .move_markdown_cell(16, 18, MoveDirection::Up)
+            .unwrap();
+        // The comment attached to the struct moves with it
+        assert!(editor.content.contains(
+            "use venus::prelude::*;\n\n// # Intro\n// Some text\n\n// ## Notes\n\npub struct Config"
+        ));
+        editor
+            .move_markdown_cell(
+                moved.new_start_line,
+                moved.new_end_line,
+                MoveDirection::Down,
+            )
+            .unwrap();
+        assert_eq!(editor.content, MARKDOWN_NOTEBOOK);
+    }
+
+    #[test]
+    fn test_move_markdown_cell_repeatedly() {
+        let file = create_temp_file(MARKDOWN_NOTEBOOK);
+        let mut editor = SourceEditor::load(file.path()).unwrap();
+
+        // Down to the end of the file, one block at a time
+        let mut span = (5, 6);
+        for _ in 0..2 {
+            let moved = editor
+                .move_markdown_cell(span.0, span.1, MoveDirection::Down)
+                .unwrap();
+            span = (moved.new_start_line, moved.new_end_line);
+        }
+        assert!(editor.content.ends_with("}\n\n// # Intro\n// Some text\n"));
+        assert!(
+            editor
+                .move_markdown_cell(span.0, span.1, MoveDirection::Down)
+                .is_err()
+        );
+
+        // And back up, past the struct and the first cell
+        for _ in 0..2 {
+            let moved = editor
+                .move_markdown_cell(span.0, span.1, MoveDirection::Up)
+                .unwrap();
+            span = (moved.new_start_line, moved.new_end_line);
+        }
+        assert_eq!(span, (5, 6));
+        assert_eq!(editor.content, MARKDOWN_NOTEBOOK);
+    }
+
+    #[test]
+    fn test_convert_cell_to_markdown_and_back() {
+        let source = r#"use venus::prelude::*;
+
+/// Notes
+#[venus::cell]
+pub fn notes() -> i32 {
+    1
+}
+
+/// Second
+#[venus::cell]
+pub fn second() -> i32 {
+    2
+}
+"#;
+
+        let file = create_temp_file(source);
+        let mut editor = SourceEditor::load(file.path()).unwrap();
+
+        let conversion = editor.convert_cell_to_markdown("notes").unwrap();
+        assert_eq!((conversion.start_line, conversion.old_end_line), (3, 7));
+        assert_eq!(conversion.new_end_line, 7);
+        assert!(
+            editor
+                .content
+                .contains("// #[venus::cell]\n// pub fn notes() -> i32 {")
+        );
+
+        // Undo restores the original source exactly
+        editor
+            .edit_raw_code(
+                conversion.start_line,
+                conversion.new_end_line,
+                &conversion.old_text,
+            )
+            .unwrap();
+        assert_eq!(editor.content, source);
+    }
+
+    #[test]
+    fn test_convert_markdown_to_cell() {
+        let source = r##"use venus::prelude::*;
+
+// # Results
+// Say "#hi"
+
+#[venus::cell]
+pub fn new_cell_1() -> i32 {
+    1
+}
+"##;
+
+        let file = create_temp_file(source);
+        let mut editor = SourceEditor::load(file.path()).unwrap();
+
+        let (conversion, name) = editor.convert_markdown_to_cell(3, 4).unwrap();
+        assert_eq!(name, "new_cell_2");
+        assert_eq!(conversion.new_end_line, 7);
+        assert!(
+            editor
+                .content
+                .contains("r##\"# Results\nSay \"#hi\"\"##.to_string()")
+        );
+
+        let result = CellParser::new()
+            .parse_str(&editor.content, file.path())
+            .unwrap();
+        assert_eq!(result.code_cells.len(), 2);
+        assert!(result.markdown_cells.is_empty());
+    }
+
+    #[test]
+    fn test_convert_refuses_invalid_results() {
+        let source = r#"//! Module docs
+
+/// Attached
+#[venus::cell]
+pub fn first() -> i32 {
+    1
+}
+#[venus::cell]
+pub fn second() -> i32 {
+    2
+}
+"#;
+
+        let file = create_temp_file(source);
+        let mut editor = SourceEditor::load(file.path()).unwrap();
+
+        // Doc comments can't become a code cell
+        assert!(editor.convert_markdown_to_cell(1, 1).is_err());
+
+        // Commented-out `first` would attach to `second` rather than
+        // becoming a markdown cell
+        assert!(editor.convert_cell_to_markdown("first").is_err());
+        assert_eq!(editor.content, source);
+    }
 }
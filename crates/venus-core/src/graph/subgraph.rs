@@ -0,0 +1,365 @@
+//! Extraction of a self-contained notebook from a subset of cells.
+//!
+//! The selected cells are closed over their transitive dependencies, and the
+//! definitions they reference (directly or through other definitions) are
+//! carried along with every `use` item, the `cargo` dependency block and the
+//! crate-level attributes. The result is a notebook that parses and compiles
+//! on its own, e.g. for a minimal reproduction of a bug.
+//!
+//! Cells in an impl block are kept together with the whole block, so
+//! selecting one of them selects the others too.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::Path;
+
+use proc_macro2::{TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::spanned::Spanned;
+use syn::{File, Item};
+
+use super::parser::CellParser;
+use crate::error::{Error, Result};
+
+/// Build a notebook containing `cell_names`, their upstream cells and the
+/// definitions they need.
+///
+/// Fails if a selected cell does not exist, or if a cell in the closure
+/// depends on a name that is not a cell.
+pub fn extract_subgraph(source: &str, cell_names: &[String]) -> Result<String> {
+    let file: File =
+        syn::parse_file(source).map_err(|e| Error::Parse(format!("Invalid notebook: {}", e)))?;
+    let parsed = CellParser::new().parse_str(source, Path::new("notebook.rs"))?;
+
+    let dependencies: HashMap<&str, Vec<&str>> = parsed
+        .code_cells
+        .iter()
+        .map(|cell| {
+            let deps = cell
+                .dependencies
+                .iter()
+                .map(|d| d.param_name.as_str())
+                .collect();
+            (cell.name.as_str(), deps)
+        })
+        .collect();
+    // Dependents refer to cells in impl blocks by the method name
+    let producers: HashMap<&str, &str> = parsed
+        .code_cells
+        .iter()
+        .map(|cell| (cell.output_name(), cell.name.as_str()))
+        .collect();
+
+    // Close the selection over upstream cells
+    let mut selected: HashSet<&str> = HashSet::new();
+    let mut pending: Vec<&str> = Vec::new();
+    for name in cell_names {
+        let (name, _) = dependencies
+            .get_key_value(name.as_str())
+            .ok_or_else(|| Error::CellNotFound(format!("No cell named `{}`", name)))?;
+        pending.push(name);
+    }
+    while let Some(name) = pending.pop() {
+        if !selected.insert(name) {
+            continue;
+        }
+        for &dep in &dependencies[name] {
+            let producer = producers.get(dep).ok_or_else(|| {
+                Error::InvalidOperation(format!(
+                    "Cell `{}` depends on `{}`, which is not a cell",
+                    name, dep
+                ))
+            })?;
+            pending.push(*producer);
+        }
+        if let Some((ty, _)) = name.rsplit_once("::") {
+            pending.extend(
+                dependencies
+                    .keys()
+                    .copied()
+                    .filter(|other| other.rsplit_once("::").is_some_and(|(t, _)| t == ty)),
+            );
+        }
+    }
+
+    let keep = select_items(&file, &selected);
+
+    // Assemble: dependency block, crate attributes, then items in source order
+    let lines: Vec<&str> = source.lines().collect();
+    let mut sections: Vec<String> = Vec::new();
+
+    if let Some(block) = cargo_block(&lines) {
+        sections.push(block);
+    }
+
+    let crate_attrs: Vec<String> = file
+        .attrs
+        .iter()
+        .filter(|attr| !attr.path().is_ident("doc"))
+        .map(|attr| line_range(&lines, attr.span()))
+        .collect();
+    if !crate_attrs.is_empty() {
+        sections.push(crate_attrs.join("\n"));
+    }
+
+    for (index, item) in file.items.iter().enumerate() {
+        if keep.contains(&index) {
+            sections.push(line_range(&lines, item.span()));
+        }
+    }
+
+    let mut output = sections.join("\n\n");
+    output.push('\n');
+
+    validate(&output)?;
+    Ok(output)
+}
+
+/// Indices of the items to keep: the selected cells, every `use`, and the
+/// named definitions reachable from them by identifier.
+fn select_items(file: &File, cells: &HashSet<&str>) -> BTreeSet<usize> {
+    let mut keep = BTreeSet::new();
+    let mut referenced: HashSet<String> = HashSet::new();
+    let mut candidates: Vec<(usize, Vec<String>)> = Vec::new();
+
+    for (index, item) in file.items.iter().enumerate() {
+        match item {
+            Item::Fn(func) if CellParser::has_cell_attribute(&func.attrs) => {
+                if cells.contains(func.sig.ident.to_string().as_str()) {
+                    keep.insert(index);
+                    collect_idents(item.to_token_stream(), &mut referenced);
+                }
+            }
+            Item::Impl(item_impl) if has_selected_cell(item_impl, cells) => {
+                keep.insert(index);
+                collect_idents(item.to_token_stream(), &mut referenced);
+            }
+            Item::Use(_) => {
+                keep.insert(index);
+            }
+            _ => match defined_names(item) {
+                Some(names) => candidates.push((index, names)),
+                // Items without a name (e.g. `extern` blocks) are always kept
+                None => {
+                    keep.insert(index);
+                    collect_idents(item.to_token_stream(), &mut referenced);
+                }
+            },
+        }
+    }
+
+    // Pull in definitions until no new names are referenced
+    loop {
+        let (used, rest): (Vec<_>, Vec<_>) = candidates
+            .into_iter()
+            .partition(|(_, names)| names.iter().any(|name| referenced.contains(name)));
+        candidates = rest;
+        if used.is_empty() {
+            break;
+        }
+        for (index, _) in used {
+            keep.insert(index);
+            collect_idents(file.items[index].to_token_stream(), &mut referenced);
+        }
+    }
+
+    keep
+}
+
+/// Whether an inherent impl block defines one of the selected cells.
+fn has_selected_cell(item_impl: &syn::ItemImpl, cells: &HashSet<&str>) -> bool {
+    let Some(ty) = CellParser::inherent_impl_type(item_impl) else {
+        return false;
+    };
+    item_impl.items.iter().any(|item| {
+        matches!(item, syn::ImplItem::Fn(func)
+            if CellParser::is_cell_method(func)
+                && cells.contains(format!("{}::{}", ty, func.sig.ident).as_str()))
+    })
+}
+
+/// Names an item makes available; an `impl` is named after its self type.
+pub(super) fn defined_names(item: &Item) -> Option<Vec<String>> {
+    let ident = match item {
+        Item::Struct(i) => &i.ident,
+        Item::Enum(i) => &i.ident,
+        Item::Union(i) => &i.ident,
+        Item::Type(i) => &i.ident,
+        Item::Fn(i) => &i.sig.ident,
+        Item::Const(i) => &i.ident,
+        Item::Static(i) => &i.ident,
+        Item::Trait(i) => &i.ident,
+        Item::Mod(i) => &i.ident,
+        Item::Macro(i) => i.ident.as_ref()?,
+        Item::Impl(i) => {
+            let mut names = HashSet::new();
+            collect_idents(i.self_ty.to_token_stream(), &mut names);
+            return Some(names.into_iter().collect());
+        }
+        _ => return None,
+    };
+    Some(vec![ident.to_string()])
+}
+
+/// Collect every identifier in a token stream.
+pub(super) fn collect_idents(tokens: TokenStream, idents: &mut HashSet<String>) {
+    for token in tokens {
+        match token {
+            TokenTree::Ident(ident) => {
+                idents.insert(ident.to_string());
+            }
+            TokenTree::Group(group) => collect_idents(group.stream(), idents),
+            _ => {}
+        }
+    }
+}
+
+/// The `//! ```cargo` block, if the notebook has one.
+fn cargo_block(lines: &[&str]) -> Option<String> {
+    let is_doc = |line: &&str, content: &str| {
+        line.trim()
+            .strip_prefix("//!")
+            .is_some_and(|rest| rest.trim() == content)
+    };
+
+    let start = lines.iter().position(|line| is_doc(line, "```cargo"))?;
+    let end = start + lines[start..].iter().position(|line| is_doc(line, "```"))?;
+    Some(lines[start..=end].join("\n"))
+}
+
+/// Source lines covered by a span.
+fn line_range(lines: &[&str], span: proc_macro2::Span) -> String {
+    let start = span.start().line.saturating_sub(1);
+    let end = span.end().line.min(lines.len());
+    lines[start..end].join("\n")
+}
+
+/// Check that an extracted notebook parses and every dependency is a cell.
+fn validate(source: &str) -> Result<()> {
+    let parsed = CellParser::new().parse_str(source, Path::new("subgraph.rs"))?;
+    let names: HashSet<&str> = parsed.code_cells.iter().map(|c| c.output_name()).collect();
+
+    for cell in &parsed.code_cells {
+        if let Some(dep) = cell
+            .dependencies
+            .iter()
+            .find(|d| !names.contains(d.param_name.as_str()))
+        {
+            return Err(Error::InvalidOperation(format!(
+                "Extracted notebook is incomplete: `{}` depends on missing `{}`",
+                cell.name, dep.param_name
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"//! # Sales
+//!
+//! ```cargo
+//! [dependencies]
+//! serde = "1"
+//! ```
+
+#![allow(clippy::ptr_arg)]
+
+use std::collections::HashMap;
+
+/// A sale
+pub struct Sale {
+    pub amount: f64,
+}
+
+pub struct Unused;
+
+fn total_of(sales: &[Sale]) -> f64 {
+    sales.iter().map(|s| s.amount).sum()
+}
+
+/// # Sales
+#[venus::cell]
+pub fn sales() -> Vec<Sale> {
+    vec![Sale { amount: 1.0 }]
+}
+
+#[venus::cell]
+pub fn total(sales: &Vec<Sale>) -> f64 {
+    total_of(sales)
+}
+
+#[venus::cell]
+pub fn unrelated() -> Unused {
+    Unused
+}
+"#;
+
+    fn names(cells: &[&str]) -> Vec<String> {
+        cells.iter().map(|c| c.to_string()).collect()
+    }
+
+    #[test]
+    fn test_extracts_closure_and_referenced_definitions() {
+        let extracted = extract_subgraph(SOURCE, &names(&["total"])).unwrap();
+
+        assert!(extracted.starts_with("//! ```cargo\n//! [dependencies]"));
+        assert!(extracted.contains("#![allow(clippy::ptr_arg)]"));
+        assert!(extracted.contains("use std::collections::HashMap;"));
+        assert!(extracted.contains("/// A sale\npub struct Sale"));
+        assert!(extracted.contains("fn total_of"));
+        assert!(extracted.contains("/// # Sales\n#[venus::cell]\npub fn sales()"));
+        assert!(extracted.contains("pub fn total("));
+
+        assert!(!extracted.contains("Unused"));
+        assert!(!extracted.contains("unrelated"));
+    }
+
+    #[test]
+    fn test_keeps_impl_block_of_selected_cell() {
+        let source = r#"pub struct Cells;
+
+impl Cells {
+    #[venus::cell]
+    pub fn base() -> i32 {
+        1
+    }
+
+    #[venus::cell]
+    pub fn other() -> i32 {
+        2
+    }
+}
+
+#[venus::cell]
+pub fn doubled(base: &i32) -> i32 {
+    *base * 2
+}
+
+#[venus::cell]
+pub fn unrelated() -> i32 {
+    3
+}
+"#;
+        let extracted = extract_subgraph(source, &names(&["doubled"])).unwrap();
+        assert!(extracted.contains("pub struct Cells;"));
+        assert!(extracted.contains("impl Cells {"));
+        assert!(extracted.contains("pub fn doubled("));
+        assert!(!extracted.contains("unrelated"));
+    }
+
+    #[test]
+    fn test_rejects_unknown_and_dangling_cells() {
+        assert!(matches!(
+            extract_subgraph(SOURCE, &names(&["missing"])),
+            Err(Error::CellNotFound(_))
+        ));
+
+        let dangling = "#[venus::cell]\npub fn b(a: &i32) -> i32 {\n    *a\n}\n";
+        assert!(matches!(
+            extract_subgraph(dangling, &names(&["b"])),
+            Err(Error::InvalidOperation(_))
+        ));
+    }
+}
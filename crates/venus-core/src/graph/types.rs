@@ -50,7 +50,8 @@ pub struct SourceSpan {
 }
 
 /// Type of cell in the notebook.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum CellType {
     /// Code cell (executable Rust function with #[venus::cell]).
     Code,
@@ -65,7 +66,8 @@ pub enum CellType {
 pub struct CellInfo {
     /// Unique identifier
     pub id: CellId,
-    /// Function name (also serves as the output variable name)
+    /// Function name, as `Type::function` for a cell defined in an impl
+    /// block (see [`CellInfo::output_name`])
     pub name: String,
     /// Human-readable display name (extracted from doc comment heading or defaults to function name)
     pub display_name: String,
@@ -73,6 +75,8 @@ pub struct CellInfo {
     pub dependencies: Vec<Dependency>,
     /// Return type (as a string for display)
     pub return_type: String,
+    /// Whether the function is declared `async`
+    pub is_async: bool,
     /// Documentation comments (markdown)
     pub doc_comment: Option<String>,
     /// Source code of the cell
@@ -81,6 +85,57 @@ pub struct CellInfo {
     pub span: SourceSpan,
     /// Source file path
     pub source_file: PathBuf,
+    /// Execution timeout from `#[venus::cell(timeout_ms = N)]`
+    pub timeout_ms: Option<u64>,
+    /// Optimization level override from `#[venus::cell(opt_level = N)]`
+    pub opt_level: Option<u8>,
+    /// Output format hint from `#[venus::cell(output = "json")]`
+    pub output_format: Option<OutputFormat>,
+}
+
+impl CellInfo {
+    /// Name dependents use as the parameter name for this cell's output:
+    /// the function name, without the `Type::` of a cell in an impl block.
+    pub fn output_name(&self) -> &str {
+        unqualified_name(&self.name)
+    }
+}
+
+/// Strip the `Type::` qualifier from a cell name.
+pub fn unqualified_name(cell_name: &str) -> &str {
+    cell_name.rsplit("::").next().unwrap_or(cell_name)
+}
+
+/// Preferred display format of a cell's output.
+///
+/// Clients use it to pick a viewer, and the generated cell wrapper to pick
+/// which `Render` representation to produce.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputFormat {
+    /// Plain text (the `Debug` representation).
+    Text,
+    /// JSON, from `Render::render_data`.
+    Json,
+    /// Comma-separated values in the text output.
+    Csv,
+    /// HTML, from `Render::render_html`.
+    Html,
+    /// An image.
+    Image,
+}
+
+impl OutputFormat {
+    /// Parse an `output = "..."` hint. Unknown hints fall back to text.
+    pub fn from_hint(hint: &str) -> Self {
+        match hint.trim().to_ascii_lowercase().as_str() {
+            "json" => Self::Json,
+            "csv" => Self::Csv,
+            "html" => Self::Html,
+            "image" | "png" | "svg" => Self::Image,
+            _ => Self::Text,
+        }
+    }
 }
 
 /// Complete information about a markdown cell.
@@ -98,6 +153,20 @@ pub struct MarkdownCell {
     pub is_module_doc: bool,
 }
 
+/// A top-level item that failed to parse, kept so the rest of the notebook
+/// stays usable.
+#[derive(Debug, Clone)]
+pub struct UnparsedRegion {
+    /// Unique identifier
+    pub id: CellId,
+    /// Source text of the item
+    pub content: String,
+    /// Parser error for the item
+    pub message: String,
+    /// Location in source file
+    pub span: SourceSpan,
+}
+
 /// Type of definition in a definition cell.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -145,6 +214,9 @@ pub struct GraphEngine {
     cells: FxHashMap<CellId, CellInfo>,
     /// Output name to producing cell mapping
     outputs: FxHashMap<String, CellId>,
+    /// Output names produced by more than one cell (e.g. `Foo::load` and
+    /// `Bar::load`), which cannot be depended on
+    ambiguous_outputs: FxHashMap<String, Vec<String>>,
     /// Definition cells by ID (imports, types, helpers)
     definition_cells: FxHashMap<CellId, DefinitionCell>,
     /// Next cell ID to assign
@@ -159,6 +231,7 @@ impl GraphEngine {
             node_indices: FxHashMap::default(),
             cells: FxHashMap::default(),
             outputs: FxHashMap::default(),
+            ambiguous_outputs: FxHashMap::default(),
             definition_cells: FxHashMap::default(),
             next_id: 0,
         }
@@ -174,7 +247,13 @@ impl GraphEngine {
         self.node_indices.insert(id, node_idx);
 
         // Register the output (function name)
-        self.outputs.insert(cell.name.clone(), id);
+        if let Some(previous) = self.outputs.insert(cell.output_name().to_string(), id) {
+            let producers = self
+                .ambiguous_outputs
+                .entry(cell.output_name().to_string())
+                .or_insert_with(|| vec![self.cells[&previous].name.clone()]);
+            producers.push(cell.name.clone());
+        }
         self.cells.insert(id, cell);
 
         id
@@ -187,6 +266,15 @@ impl GraphEngine {
 
         for (cell_id, cell) in &self.cells {
             for dep in &cell.dependencies {
+                if let Some(producers) = self.ambiguous_outputs.get(&dep.param_name) {
+                    return Err(Error::InvalidOperation(format!(
+                        "Cell '{}' depends on '{}', which is ambiguous between {}",
+                        cell.name,
+                        dep.param_name,
+                        producers.join(", ")
+                    )));
+                }
+
                 // Find the cell that produces this dependency
                 if let Some(&producer_id) = self.outputs.get(&dep.param_name) {
                     edges_to_add.push((producer_id, *cell_id));
@@ -339,7 +427,7 @@ impl GraphEngine {
 
     /// Get a cell by name.
     pub fn get_cell_by_name(&self, name: &str) -> Option<&CellInfo> {
-        self.outputs.get(name).and_then(|id| self.cells.get(id))
+        self.cells.values().find(|cell| cell.name == name)
     }
 
     /// Get all cells.
@@ -447,6 +535,10 @@ mod tests {
                 end_col: 0,
             },
             source_file: PathBuf::new(),
+            timeout_ms: None,
+            opt_level: None,
+            output_format: None,
+            is_async: false,
         }
     }
 
@@ -571,4 +663,20 @@ mod tests {
         let err = result.unwrap_err();
         assert!(matches!(err, Error::CellNotFound(_)));
     }
+
+    #[test]
+    fn test_qualified_cell_names() {
+        let mut graph = GraphEngine::new();
+        let load = graph.add_cell(make_cell("Data::load", &[]));
+        let total = graph.add_cell(make_cell("total", &["load"]));
+        graph.resolve_dependencies().unwrap();
+        assert_eq!(graph.dependencies(total), vec![load]);
+        assert_eq!(graph.get_cell(load).unwrap().output_name(), "load");
+        assert_eq!(graph.get_cell_by_name("Data::load").unwrap().id, load);
+
+        // Two impls providing `load` make depending on it ambiguous
+        graph.add_cell(make_cell("Other::load", &[]));
+        let err = graph.resolve_dependencies().unwrap_err();
+        assert!(err.to_string().contains("Data::load, Other::load"));
+    }
 }
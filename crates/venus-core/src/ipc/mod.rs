@@ -6,5 +6,5 @@
 pub mod protocol;
 mod worker;
 
-pub use protocol::{WorkerCommand, WorkerResponse, read_message, write_message};
-pub use worker::{WorkerHandle, WorkerKillHandle, WorkerPool};
+pub use protocol::{OUTPUT_CHUNK_SIZE, WorkerCommand, WorkerResponse, read_message, write_message};
+pub use worker::{WorkerHandle, WorkerKillHandle, WorkerPool, WorkerSpawnConfig};
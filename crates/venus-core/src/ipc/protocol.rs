@@ -9,6 +9,11 @@ use rkyv::{Archive, Deserialize, Serialize};
 
 use crate::error::{Error, Result};
 
+/// Outputs larger than this are sent as a series of `OutputChunk` messages.
+///
+/// Small enough that the host can show a large output while it arrives.
+pub const OUTPUT_CHUNK_SIZE: usize = 64 * 1024;
+
 /// Command sent from parent to worker process.
 #[derive(Debug, Clone, Archive, Serialize, Deserialize)]
 pub enum WorkerCommand {
@@ -53,6 +58,17 @@ pub enum WorkerResponse {
         /// Widget definitions as JSON.
         /// Empty if no widgets were registered.
         widgets_json: Vec<u8>,
+        /// Time spent in the cell's entry point, in microseconds.
+        exec_us: u64,
+    },
+
+    /// A leading segment of a large output.
+    ///
+    /// Followed by further chunks and a final `Output` carrying the last
+    /// segment. The receiver concatenates the segments in order.
+    OutputChunk {
+        /// Segment of the serialized output bytes.
+        bytes: Vec<u8>,
     },
 
     /// Execution failed with an error.
@@ -65,6 +81,8 @@ pub enum WorkerResponse {
     Panic {
         /// Panic message if available.
         message: String,
+        /// Backtrace of the panic, if `RUST_BACKTRACE` was set.
+        backtrace: Option<String>,
     },
 
     /// Response to Ping command.
@@ -74,6 +92,38 @@ pub enum WorkerResponse {
     ShuttingDown,
 }
 
+impl WorkerResponse {
+    /// Split a large `Output` into `OutputChunk`s followed by a final `Output`.
+    ///
+    /// Other responses, and outputs of at most `chunk_size` bytes, are
+    /// returned unchanged.
+    pub fn into_chunks(self, chunk_size: usize) -> Vec<WorkerResponse> {
+        match self {
+            WorkerResponse::Output {
+                bytes,
+                widgets_json,
+                exec_us,
+            } if bytes.len() > chunk_size => {
+                let mut chunks: Vec<WorkerResponse> = bytes
+                    .chunks(chunk_size)
+                    .map(|chunk| WorkerResponse::OutputChunk {
+                        bytes: chunk.to_vec(),
+                    })
+                    .collect();
+                if let Some(WorkerResponse::OutputChunk { bytes }) = chunks.pop() {
+                    chunks.push(WorkerResponse::Output {
+                        bytes,
+                        widgets_json,
+                        exec_us,
+                    });
+                }
+                chunks
+            }
+            other => vec![other],
+        }
+    }
+}
+
 /// Write a message to a writer using length-prefixed rkyv encoding.
 pub fn write_message<W: Write>(
     writer: &mut W,
@@ -181,6 +231,7 @@ mod tests {
         let resp = WorkerResponse::Output {
             bytes: vec![1, 2, 3, 4, 5],
             widgets_json: vec![],
+            exec_us: 0,
         };
 
         let mut buf = Vec::new();
@@ -193,6 +244,7 @@ mod tests {
             WorkerResponse::Output {
                 bytes,
                 widgets_json,
+                ..
             } => {
                 assert_eq!(bytes, vec![1, 2, 3, 4, 5]);
                 assert!(widgets_json.is_empty());
@@ -297,6 +349,7 @@ mod tests {
     fn test_panic_response_roundtrip() {
         let resp = WorkerResponse::Panic {
             message: "thread 'main' panicked at 'assertion failed'".to_string(),
+            backtrace: Some("0: cell::main".to_string()),
         };
 
         let mut buf = Vec::new();
@@ -306,8 +359,9 @@ mod tests {
         let decoded: WorkerResponse = read_message(&mut cursor).unwrap();
 
         match decoded {
-            WorkerResponse::Panic { message } => {
+            WorkerResponse::Panic { message, backtrace } => {
                 assert!(message.contains("panicked"));
+                assert_eq!(backtrace.as_deref(), Some("0: cell::main"));
             }
             _ => panic!("Wrong response type"),
         }
@@ -365,6 +419,7 @@ mod tests {
         let resp = WorkerResponse::Output {
             bytes: vec![1, 2, 3, 4, 5],
             widgets_json: b"{\"slider_1\": {\"type\": \"slider\", \"value\": 50}}".to_vec(),
+            exec_us: 0,
         };
 
         let mut buf = Vec::new();
@@ -377,6 +432,7 @@ mod tests {
             WorkerResponse::Output {
                 bytes,
                 widgets_json,
+                ..
             } => {
                 assert_eq!(bytes, vec![1, 2, 3, 4, 5]);
                 assert!(!widgets_json.is_empty());
@@ -428,6 +484,7 @@ mod tests {
         let resp = WorkerResponse::Output {
             bytes: large_bytes.clone(),
             widgets_json: vec![],
+            exec_us: 0,
         };
 
         let mut buf = Vec::new();
@@ -493,4 +550,40 @@ mod tests {
             _ => panic!("Wrong command type"),
         }
     }
+
+    #[test]
+    fn test_output_chunks_reassemble() {
+        let resp = WorkerResponse::Output {
+            bytes: (0..10).collect(),
+            widgets_json: b"[]".to_vec(),
+            exec_us: 0,
+        };
+
+        let chunks = resp.into_chunks(4);
+        assert_eq!(chunks.len(), 3);
+
+        let mut assembled = Vec::new();
+        for chunk in chunks {
+            match chunk {
+                WorkerResponse::OutputChunk { bytes } => assembled.extend(bytes),
+                WorkerResponse::Output {
+                    bytes,
+                    widgets_json,
+                    ..
+                } => {
+                    assembled.extend(bytes);
+                    assert_eq!(widgets_json, b"[]");
+                }
+                other => panic!("Unexpected response: {:?}", other),
+            }
+        }
+        assert_eq!(assembled, (0..10).collect::<Vec<u8>>());
+
+        let small = WorkerResponse::Output {
+            bytes: vec![1],
+            widgets_json: vec![],
+            exec_us: 0,
+        };
+        assert_eq!(small.into_chunks(4).len(), 1);
+    }
 }
@@ -13,6 +13,15 @@ use crate::error::{Error, Result};
 
 use super::protocol::{WorkerCommand, WorkerResponse, read_message, write_message};
 
+/// Process settings applied when spawning workers.
+#[derive(Debug, Clone, Default)]
+pub struct WorkerSpawnConfig {
+    /// Extra environment variables, added to the inherited environment.
+    pub env: Vec<(String, String)>,
+    /// Working directory, or the parent's when `None`.
+    pub current_dir: Option<PathBuf>,
+}
+
 /// Handle to a worker process.
 ///
 /// Provides methods to send commands, receive responses, and kill the process.
@@ -35,9 +44,21 @@ impl WorkerHandle {
     /// 2. Same directory as the current executable
     /// 3. System PATH
     pub fn spawn() -> Result<Self> {
+        Self::spawn_with(&WorkerSpawnConfig::default())
+    }
+
+    /// Spawn a worker process with the given environment and working
+    /// directory.
+    pub fn spawn_with(config: &WorkerSpawnConfig) -> Result<Self> {
         let worker_path = Self::find_worker_binary()?;
 
-        let mut child = Command::new(&worker_path)
+        let mut command = Command::new(&worker_path);
+        command.envs(config.env.iter().map(|(k, v)| (k, v)));
+        if let Some(dir) = &config.current_dir {
+            command.current_dir(dir);
+        }
+
+        let mut child = command
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::inherit()) // Let worker stderr pass through for debugging
@@ -198,21 +219,63 @@ impl WorkerHandle {
         inputs: Vec<Vec<u8>>,
         widget_values_json: Vec<u8>,
     ) -> Result<(Vec<u8>, Vec<u8>)> {
+        self.execute_timed(inputs, widget_values_json)
+            .map(|(bytes, widgets_json, _)| (bytes, widgets_json))
+    }
+
+    /// Execute the loaded cell, also returning the time the cell itself ran
+    /// inside the worker (excluding IPC).
+    pub fn execute_timed(
+        &mut self,
+        inputs: Vec<Vec<u8>>,
+        widget_values_json: Vec<u8>,
+    ) -> Result<(Vec<u8>, Vec<u8>, Duration)> {
+        let mut bytes = Vec::new();
+        let (widgets_json, exec_time) =
+            self.execute_streamed(inputs, widget_values_json, &mut |segment, _| {
+                bytes.extend(segment)
+            })?;
+        Ok((bytes, widgets_json, exec_time))
+    }
+
+    /// Execute the loaded cell, handing each segment of the output to
+    /// `on_segment` as it arrives.
+    ///
+    /// Large outputs arrive in several segments; `on_segment` is told
+    /// whether each one is the last. Returns the widget definitions JSON and
+    /// the time the cell itself ran inside the worker.
+    pub fn execute_streamed(
+        &mut self,
+        inputs: Vec<Vec<u8>>,
+        widget_values_json: Vec<u8>,
+        on_segment: &mut dyn FnMut(Vec<u8>, bool),
+    ) -> Result<(Vec<u8>, Duration)> {
         self.send_command(&WorkerCommand::Execute {
             inputs,
             widget_values_json,
         })?;
 
-        match self.recv_response()? {
+        // Large outputs arrive as chunks ahead of the final `Output`
+        let response = loop {
+            match self.recv_response()? {
+                WorkerResponse::OutputChunk { bytes } => on_segment(bytes, false),
+                other => break other,
+            }
+        };
+
+        match response {
             WorkerResponse::Output {
                 bytes,
                 widgets_json,
-            } => Ok((bytes, widgets_json)),
+                exec_us,
+            } => {
+                on_segment(bytes, true);
+                Ok((widgets_json, Duration::from_micros(exec_us)))
+            }
             WorkerResponse::Error { message } => Err(Error::Execution(message)),
-            WorkerResponse::Panic { message } => Err(Error::Execution(format!(
-                "Cell panicked: {}. Check for unwrap() on None/Err, out-of-bounds access, or other panic sources.",
-                message
-            ))),
+            WorkerResponse::Panic { message, backtrace } => {
+                Err(Error::CellPanic { message, backtrace })
+            }
             other => Err(Error::Ipc(format!(
                 "Unexpected response when executing: {:?}",
                 other
@@ -310,6 +373,8 @@ pub struct WorkerPool {
     available: Vec<WorkerHandle>,
     /// Maximum pool size.
     max_size: usize,
+    /// Settings for newly spawned workers.
+    spawn_config: WorkerSpawnConfig,
 }
 
 impl WorkerPool {
@@ -318,6 +383,7 @@ impl WorkerPool {
         Self {
             available: Vec::with_capacity(max_size),
             max_size,
+            spawn_config: WorkerSpawnConfig::default(),
         }
     }
 
@@ -342,7 +408,16 @@ impl WorkerPool {
         }
 
         // No available workers, spawn a new one
-        WorkerHandle::spawn()
+        WorkerHandle::spawn_with(&self.spawn_config)
+    }
+
+    /// Set the settings for spawned workers.
+    ///
+    /// Idle workers were started with the old settings, so they are killed;
+    /// new ones are spawned on demand.
+    pub fn set_spawn_config(&mut self, config: WorkerSpawnConfig) {
+        self.spawn_config = config;
+        self.shutdown();
     }
 
     /// Return a worker to the pool for reuse.
@@ -444,6 +519,11 @@ impl WorkerKillHandle {
         }
     }
 
+    /// Get the process ID of the worker.
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
     /// Check if kill has been requested.
     pub fn is_killed(&self) -> bool {
         self.killed.load(std::sync::atomic::Ordering::SeqCst)
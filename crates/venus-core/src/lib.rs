@@ -31,10 +31,14 @@
 //! - Cell execution and hot-reload
 
 pub mod compile;
+pub mod env_file;
 pub mod error;
 pub mod execute;
 pub mod graph;
 pub mod ipc;
+pub mod lint;
+pub mod markdown;
+pub mod panic_report;
 pub mod paths;
 pub mod salsa_db;
 pub mod state;
@@ -0,0 +1,363 @@
+//! Static checks for notebook anti-patterns.
+//!
+//! Lints run over the parsed dependency graph and never compile or execute
+//! anything. Each [`LintRule`] can be turned off through [`LintConfig`].
+
+use std::collections::{HashMap, HashSet};
+
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use syn::visit::Visit;
+
+use crate::graph::{CellId, CellInfo, GraphEngine};
+
+/// Number of dependents at which an owned collection output is flagged.
+pub const SHARED_OUTPUT_DEPENDENTS: usize = 3;
+
+/// Owned collection types whose bytes are copied to every dependent.
+const OWNED_COLLECTIONS: &[&str] = &[
+    "Vec", "VecDeque", "HashMap", "HashSet", "BTreeMap", "BTreeSet", "String",
+];
+
+/// Identifiers that suggest a cell body does IO, so its `()` result is not
+/// the point of running it.
+const IO_MARKERS: &[&str] = &[
+    "fs",
+    "io",
+    "File",
+    "OpenOptions",
+    "Command",
+    "net",
+    "TcpStream",
+    "UdpSocket",
+    "stdout",
+    "stderr",
+    "stdin",
+    "write",
+    "write_all",
+    "flush",
+    "set_var",
+    "remove_var",
+    "spawn",
+];
+
+/// A lint rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LintRule {
+    /// A cell returns an owned collection that many cells depend on.
+    SharedOwnedOutput,
+    /// Two cells have identical bodies.
+    DuplicateBody,
+    /// A cell returns `()`, has no dependents and does no visible IO.
+    UnusedCell,
+}
+
+impl LintRule {
+    /// All rules, in reporting order.
+    pub const ALL: &'static [LintRule] = &[
+        LintRule::SharedOwnedOutput,
+        LintRule::DuplicateBody,
+        LintRule::UnusedCell,
+    ];
+}
+
+/// Which rules are enabled. All rules are enabled by default.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LintConfig {
+    disabled: HashSet<LintRule>,
+}
+
+impl LintConfig {
+    /// Create a config with every rule enabled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a config with the given rules disabled.
+    pub fn without(rules: impl IntoIterator<Item = LintRule>) -> Self {
+        Self {
+            disabled: rules.into_iter().collect(),
+        }
+    }
+
+    /// Turn a rule on or off.
+    pub fn set_enabled(&mut self, rule: LintRule, enabled: bool) {
+        if enabled {
+            self.disabled.remove(&rule);
+        } else {
+            self.disabled.insert(rule);
+        }
+    }
+
+    /// Check whether a rule is enabled.
+    pub fn is_enabled(&self, rule: LintRule) -> bool {
+        !self.disabled.contains(&rule)
+    }
+}
+
+/// A lint finding for a cell.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LintWarning {
+    /// Cell the warning is about.
+    pub cell_id: CellId,
+    /// Rule that produced the warning.
+    pub rule: LintRule,
+    /// Human-readable explanation.
+    pub message: String,
+}
+
+/// Run the enabled rules over a graph.
+///
+/// Warnings are ordered by rule, then by the cell's position in the source.
+pub fn lint(graph: &GraphEngine, config: &LintConfig) -> Vec<LintWarning> {
+    let mut cells: Vec<&CellInfo> = graph.cells().collect();
+    cells.sort_by_key(|cell| (cell.span.start_line, cell.id.as_usize()));
+
+    let mut warnings = Vec::new();
+    for &rule in LintRule::ALL {
+        if !config.is_enabled(rule) {
+            continue;
+        }
+        match rule {
+            LintRule::SharedOwnedOutput => check_shared_owned_output(graph, &cells, &mut warnings),
+            LintRule::DuplicateBody => check_duplicate_body(&cells, &mut warnings),
+            LintRule::UnusedCell => check_unused_cell(graph, &cells, &mut warnings),
+        }
+    }
+    warnings
+}
+
+/// Flag owned collections that are copied into many dependents.
+fn check_shared_owned_output(
+    graph: &GraphEngine,
+    cells: &[&CellInfo],
+    warnings: &mut Vec<LintWarning>,
+) {
+    for cell in cells {
+        let return_type: String = cell.return_type.split_whitespace().collect();
+        let Some(collection) = OWNED_COLLECTIONS.iter().find(|name| {
+            return_type
+                .strip_prefix(**name)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('<'))
+        }) else {
+            continue;
+        };
+
+        let dependents = graph.dependents(cell.id).len();
+        if dependents >= SHARED_OUTPUT_DEPENDENTS {
+            warnings.push(LintWarning {
+                cell_id: cell.id,
+                rule: LintRule::SharedOwnedOutput,
+                message: format!(
+                    "`{}` returns an owned `{}` that is copied into each of its {} dependents; \
+                     consider returning a smaller summary or splitting the data",
+                    cell.name, collection, dependents
+                ),
+            });
+        }
+    }
+}
+
+/// Flag cells whose body repeats an earlier cell's body.
+fn check_duplicate_body(cells: &[&CellInfo], warnings: &mut Vec<LintWarning>) {
+    let mut first_with_body: HashMap<String, &CellInfo> = HashMap::new();
+
+    for cell in cells {
+        let Some(body) = normalized_body(&cell.source_code) else {
+            continue;
+        };
+
+        match first_with_body.get(&body) {
+            Some(original) => warnings.push(LintWarning {
+                cell_id: cell.id,
+                rule: LintRule::DuplicateBody,
+                message: format!(
+                    "`{}` has the same body as `{}`; consider depending on `{}` instead",
+                    cell.name, original.name, original.name
+                ),
+            }),
+            None => {
+                first_with_body.insert(body, cell);
+            }
+        }
+    }
+}
+
+/// Flag cells whose result nobody can see or use.
+///
+/// A `()` cell without dependents only matters for its side effects, so it is
+/// left alone when the body calls a macro (`println!`, `write!`, ...) or
+/// mentions anything that looks like IO.
+fn check_unused_cell(graph: &GraphEngine, cells: &[&CellInfo], warnings: &mut Vec<LintWarning>) {
+    for cell in cells {
+        let return_type: String = cell.return_type.split_whitespace().collect();
+        if return_type != "()" || !graph.dependents(cell.id).is_empty() {
+            continue;
+        }
+        let Ok(func) = syn::parse_str::<syn::ItemFn>(&cell.source_code) else {
+            continue;
+        };
+
+        let mut finder = SideEffectFinder::default();
+        finder.visit_block(&func.block);
+        if finder.found {
+            continue;
+        }
+
+        warnings.push(LintWarning {
+            cell_id: cell.id,
+            rule: LintRule::UnusedCell,
+            message: format!(
+                "`{}` returns `()`, has no dependents and does no visible IO; \
+                 consider returning a value or removing it",
+                cell.name
+            ),
+        });
+    }
+}
+
+/// Looks for macro calls, `unsafe` blocks and IO-like identifiers.
+#[derive(Default)]
+struct SideEffectFinder {
+    found: bool,
+}
+
+impl<'ast> Visit<'ast> for SideEffectFinder {
+    fn visit_macro(&mut self, _mac: &'ast syn::Macro) {
+        self.found = true;
+    }
+
+    fn visit_expr_unsafe(&mut self, _expr: &'ast syn::ExprUnsafe) {
+        self.found = true;
+    }
+
+    fn visit_ident(&mut self, ident: &'ast syn::Ident) {
+        if IO_MARKERS.iter().any(|marker| ident == marker) {
+            self.found = true;
+        }
+    }
+}
+
+/// Token-level form of a cell's body, ignoring comments and formatting.
+///
+/// Returns `None` for empty bodies, which are too common to be interesting.
+fn normalized_body(source: &str) -> Option<String> {
+    let func: syn::ItemFn = syn::parse_str(source).ok()?;
+    if func.block.stmts.is_empty() {
+        return None;
+    }
+    Some(func.block.to_token_stream().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::CellParser;
+
+    fn graph(source: &str) -> GraphEngine {
+        let mut parser = CellParser::new();
+        let result = parser
+            .parse_str(source, std::path::Path::new("test.rs"))
+            .unwrap();
+
+        let mut graph = GraphEngine::new();
+        for cell in result.code_cells {
+            graph.add_cell(cell);
+        }
+        graph.resolve_dependencies().unwrap();
+        graph
+    }
+
+    const SOURCE: &str = r#"
+#[venus::cell]
+pub fn data() -> Vec<f64> {
+    vec![1.0, 2.0, 3.0]
+}
+
+#[venus::cell]
+pub fn sum(data: &Vec<f64>) -> f64 {
+    data.iter().sum()
+}
+
+#[venus::cell]
+pub fn total(data: &Vec<f64>) -> f64 {
+    // Same computation, different formatting
+    data.iter()
+        .sum()
+}
+
+#[venus::cell]
+pub fn count(data: &Vec<f64>) -> usize {
+    data.len()
+}
+"#;
+
+    #[test]
+    fn test_lint_reports_enabled_rules() {
+        let graph = graph(SOURCE);
+        let warnings = lint(&graph, &LintConfig::new());
+
+        let found: Vec<(LintRule, &str)> = warnings
+            .iter()
+            .map(|w| (w.rule, graph.get_cell(w.cell_id).unwrap().name.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (LintRule::SharedOwnedOutput, "data"),
+                (LintRule::DuplicateBody, "total"),
+            ]
+        );
+        assert!(warnings[1].message.contains("`sum`"));
+    }
+
+    #[test]
+    fn test_unused_cell() {
+        let graph = graph(
+            r#"
+#[venus::cell]
+pub fn base() -> i32 {
+    1
+}
+
+#[venus::cell]
+pub fn scratch(base: &i32) {
+    let _doubled = base * 2;
+}
+
+#[venus::cell]
+pub fn save(base: &i32) {
+    std::fs::write("out.txt", base.to_string()).unwrap();
+}
+
+#[venus::cell]
+pub fn show(base: &i32) -> () {
+    dbg!(base);
+}
+"#,
+        );
+        let config = LintConfig::without([LintRule::SharedOwnedOutput, LintRule::DuplicateBody]);
+
+        let flagged: Vec<&str> = lint(&graph, &config)
+            .iter()
+            .map(|w| graph.get_cell(w.cell_id).unwrap().name.as_str())
+            .collect();
+        assert_eq!(flagged, vec!["scratch"]);
+    }
+
+    #[test]
+    fn test_rules_can_be_disabled() {
+        let graph = graph(SOURCE);
+        let config = LintConfig::without([LintRule::SharedOwnedOutput]);
+
+        let warnings = lint(&graph, &config);
+        assert!(warnings.iter().all(|w| w.rule == LintRule::DuplicateBody));
+
+        let mut config = LintConfig::new();
+        for &rule in LintRule::ALL {
+            config.set_enabled(rule, false);
+        }
+        assert!(lint(&graph, &config).is_empty());
+    }
+}
@@ -0,0 +1,380 @@
+//! Markdown rendering for cell documentation.
+//!
+//! A small CommonMark renderer covering what doc comments use in practice:
+//! ATX headings, paragraphs, emphasis, inline code, fenced code blocks,
+//! block quotes, flat lists, links, images and thematic breaks. Raw HTML
+//! is escaped rather than passed through, so output is safe to embed.
+//!
+//! Both the server and the HTML exporter render through [`to_html`], so a
+//! doc comment looks the same in the web UI and in exported reports.
+
+/// Render markdown to HTML.
+pub fn to_html(source: &str) -> String {
+    let lines: Vec<&str> = source.lines().collect();
+    let mut html = String::new();
+    render_blocks(&lines, &mut html);
+    html
+}
+
+/// Escape text for inclusion in HTML content or attributes.
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// Kind of list being rendered.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ListKind {
+    Unordered,
+    Ordered,
+}
+
+/// Render a sequence of lines as block-level HTML.
+fn render_blocks(lines: &[&str], html: &mut String) {
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = lines[i];
+        let trimmed = line.trim();
+
+        // Fenced code block
+        if let Some(fence) = code_fence(trimmed) {
+            flush_paragraph(&mut paragraph, html);
+            let info = trimmed[fence.len()..].trim();
+            let mut code = Vec::new();
+            i += 1;
+            while i < lines.len() && !lines[i].trim().starts_with(fence) {
+                code.push(lines[i]);
+                i += 1;
+            }
+            i += 1; // closing fence
+
+            let class = info
+                .split_whitespace()
+                .next()
+                .map(|lang| format!(" class=\"language-{}\"", escape_html(lang)))
+                .unwrap_or_default();
+            let mut body = escape_html(&code.join("\n"));
+            if !code.is_empty() {
+                body.push('\n');
+            }
+            html.push_str(&format!("<pre><code{}>{}</code></pre>\n", class, body));
+            continue;
+        }
+
+        if trimmed.is_empty() {
+            flush_paragraph(&mut paragraph, html);
+            i += 1;
+            continue;
+        }
+
+        // ATX heading
+        if let Some((level, text)) = heading(trimmed) {
+            flush_paragraph(&mut paragraph, html);
+            html.push_str(&format!(
+                "<h{}>{}</h{}>\n",
+                level,
+                render_inline(text),
+                level
+            ));
+            i += 1;
+            continue;
+        }
+
+        if is_thematic_break(trimmed) {
+            flush_paragraph(&mut paragraph, html);
+            html.push_str("<hr />\n");
+            i += 1;
+            continue;
+        }
+
+        // Block quote: consecutive `>` lines rendered recursively
+        if trimmed.starts_with('>') {
+            flush_paragraph(&mut paragraph, html);
+            let mut quoted = Vec::new();
+            while i < lines.len() && lines[i].trim().starts_with('>') {
+                let rest = &lines[i].trim()[1..];
+                quoted.push(rest.strip_prefix(' ').unwrap_or(rest));
+                i += 1;
+            }
+            html.push_str("<blockquote>\n");
+            render_blocks(&quoted, html);
+            html.push_str("</blockquote>\n");
+            continue;
+        }
+
+        // List: consecutive items of the same kind, with indented continuations
+        if let Some((kind, _)) = list_item(trimmed) {
+            flush_paragraph(&mut paragraph, html);
+            let tag = match kind {
+                ListKind::Unordered => "ul",
+                ListKind::Ordered => "ol",
+            };
+            html.push_str(&format!("<{}>\n", tag));
+            while i < lines.len() {
+                let Some((item_kind, text)) = list_item(lines[i].trim()) else {
+                    break;
+                };
+                if item_kind != kind {
+                    break;
+                }
+                let mut item = vec![text];
+                i += 1;
+                while i < lines.len()
+                    && lines[i].starts_with([' ', '\t'])
+                    && !lines[i].trim().is_empty()
+                    && list_item(lines[i].trim()).is_none()
+                {
+                    item.push(lines[i].trim());
+                    i += 1;
+                }
+                html.push_str(&format!("<li>{}</li>\n", render_inline(&item.join("\n"))));
+            }
+            html.push_str(&format!("</{}>\n", tag));
+            continue;
+        }
+
+        paragraph.push(trimmed);
+        i += 1;
+    }
+
+    flush_paragraph(&mut paragraph, html);
+}
+
+/// Emit accumulated paragraph lines.
+fn flush_paragraph(paragraph: &mut Vec<&str>, html: &mut String) {
+    if !paragraph.is_empty() {
+        html.push_str(&format!(
+            "<p>{}</p>\n",
+            render_inline(&paragraph.join("\n"))
+        ));
+        paragraph.clear();
+    }
+}
+
+/// Return the fence marker if the line opens a fenced code block.
+fn code_fence(line: &str) -> Option<&'static str> {
+    if line.starts_with("```") {
+        Some("```")
+    } else if line.starts_with("~~~") {
+        Some("~~~")
+    } else {
+        None
+    }
+}
+
+/// Parse an ATX heading into its level and text.
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let rest = &line[level..];
+    if !rest.is_empty() && !rest.starts_with(' ') {
+        return None;
+    }
+    Some((level, rest.trim().trim_end_matches('#').trim_end()))
+}
+
+/// Check for a thematic break (`---`, `***`, `___`).
+fn is_thematic_break(line: &str) -> bool {
+    let chars: Vec<char> = line.chars().filter(|c| !c.is_whitespace()).collect();
+    chars.len() >= 3 && matches!(chars[0], '-' | '*' | '_') && chars.iter().all(|&c| c == chars[0])
+}
+
+/// Parse a list item marker, returning the list kind and item text.
+fn list_item(line: &str) -> Option<(ListKind, &str)> {
+    for marker in ["- ", "* ", "+ "] {
+        if let Some(text) = line.strip_prefix(marker) {
+            return Some((ListKind::Unordered, text));
+        }
+    }
+
+    let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+    if (1..=9).contains(&digits) {
+        let rest = &line[digits..];
+        if let Some(text) = rest.strip_prefix(". ").or_else(|| rest.strip_prefix(") ")) {
+            return Some((ListKind::Ordered, text));
+        }
+    }
+
+    None
+}
+
+/// Render inline markdown (code spans, emphasis, links, images).
+fn render_inline(text: &str) -> String {
+    let mut html = String::new();
+    let mut rest = text;
+
+    while let Some(ch) = rest.chars().next() {
+        match ch {
+            '\\' if rest[1..].starts_with(|c: char| c.is_ascii_punctuation()) => {
+                let escaped = &rest[1..2];
+                html.push_str(&escape_html(escaped));
+                rest = &rest[2..];
+            }
+            '`' => {
+                let ticks = rest.chars().take_while(|&c| c == '`').count();
+                let fence = &rest[..ticks];
+                match rest[ticks..].find(fence) {
+                    Some(end) => {
+                        let code = rest[ticks..ticks + end].trim();
+                        html.push_str(&format!("<code>{}</code>", escape_html(code)));
+                        rest = &rest[ticks + end + ticks..];
+                    }
+                    None => {
+                        html.push_str(fence);
+                        rest = &rest[ticks..];
+                    }
+                }
+            }
+            '*' | '_' => {
+                let double = rest[1..].starts_with(ch);
+                let delim = if double { &rest[..2] } else { &rest[..1] };
+                let prev = html.chars().last();
+                // `_` only delimits at word boundaries, so snake_case stays intact
+                let opens = ch == '*' || !prev.is_some_and(|c| c.is_alphanumeric());
+
+                match find_closing(&rest[delim.len()..], delim).filter(|_| opens) {
+                    Some(end) => {
+                        let inner = &rest[delim.len()..delim.len() + end];
+                        let tag = if double { "strong" } else { "em" };
+                        html.push_str(&format!("<{}>{}</{}>", tag, render_inline(inner), tag));
+                        rest = &rest[delim.len() * 2 + end..];
+                    }
+                    None => {
+                        html.push_str(delim);
+                        rest = &rest[delim.len()..];
+                    }
+                }
+            }
+            '!' if rest[1..].starts_with('[') => match link(&rest[1..]) {
+                Some((alt, url, len)) => {
+                    html.push_str(&format!(
+                        "<img src=\"{}\" alt=\"{}\" />",
+                        escape_html(url),
+                        escape_html(alt)
+                    ));
+                    rest = &rest[1 + len..];
+                }
+                None => {
+                    html.push('!');
+                    rest = &rest[1..];
+                }
+            },
+            '[' => match link(rest) {
+                Some((label, url, len)) => {
+                    html.push_str(&format!(
+                        "<a href=\"{}\">{}</a>",
+                        escape_html(url),
+                        render_inline(label)
+                    ));
+                    rest = &rest[len..];
+                }
+                None => {
+                    html.push('[');
+                    rest = &rest[1..];
+                }
+            },
+            '\n' => {
+                html.push('\n');
+                rest = &rest[1..];
+            }
+            _ => {
+                html.push_str(&escape_html(&rest[..ch.len_utf8()]));
+                rest = &rest[ch.len_utf8()..];
+            }
+        }
+    }
+
+    html
+}
+
+/// Find a closing emphasis delimiter with non-empty content before it.
+fn find_closing(text: &str, delim: &str) -> Option<usize> {
+    let end = text.find(delim)?;
+    let inner = &text[..end];
+    let after = text[end + delim.len()..].chars().next();
+    let closes_word = !delim.starts_with('_') || !after.is_some_and(|c| c.is_alphanumeric());
+
+    (!inner.trim().is_empty() && !inner.starts_with(' ') && closes_word).then_some(end)
+}
+
+/// Parse `[label](url)`, returning the label, URL and consumed length.
+///
+/// URLs with a `javascript:` scheme are rejected.
+fn link(text: &str) -> Option<(&str, &str, usize)> {
+    let label_end = text.find("](")?;
+    let label = &text[1..label_end];
+    let url_start = label_end + 2;
+    let url_len = text[url_start..].find(')')?;
+    let url = text[url_start..url_start + url_len].trim();
+
+    if url.to_ascii_lowercase().starts_with("javascript:") {
+        return None;
+    }
+    Some((label, url, url_start + url_len + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocks() {
+        let html = to_html(
+            "# Title\n\nSome *text* and **bold**.\n\n- one\n- two\n\n1. first\n2. second\n\n---",
+        );
+        assert_eq!(
+            html,
+            "<h1>Title</h1>\n\
+             <p>Some <em>text</em> and <strong>bold</strong>.</p>\n\
+             <ul>\n<li>one</li>\n<li>two</li>\n</ul>\n\
+             <ol>\n<li>first</li>\n<li>second</li>\n</ol>\n\
+             <hr />\n"
+        );
+    }
+
+    #[test]
+    fn test_code() {
+        let html = to_html("Use `Vec<i32>`:\n\n```rust\nlet x = a < b;\n```");
+        assert_eq!(
+            html,
+            "<p>Use <code>Vec&lt;i32&gt;</code>:</p>\n\
+             <pre><code class=\"language-rust\">let x = a &lt; b;\n</code></pre>\n"
+        );
+    }
+
+    #[test]
+    fn test_links_and_quotes() {
+        let html = to_html("> See [docs](https://example.com) ![logo](a.png)");
+        assert_eq!(
+            html,
+            "<blockquote>\n<p>See <a href=\"https://example.com\">docs</a> \
+             <img src=\"a.png\" alt=\"logo\" /></p>\n</blockquote>\n"
+        );
+
+        // Script URLs are left as text
+        assert!(!to_html("[x](javascript:alert(1))").contains("<a"));
+    }
+
+    #[test]
+    fn test_snake_case_and_raw_html_are_literal() {
+        assert_eq!(
+            to_html("call my_cell_name with <b>"),
+            "<p>call my_cell_name with &lt;b&gt;</p>\n"
+        );
+        assert_eq!(to_html("_em_ here"), "<p><em>em</em> here</p>\n");
+    }
+}
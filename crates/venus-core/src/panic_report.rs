@@ -0,0 +1,158 @@
+//! Panic reports passed from cells to the runtime.
+//!
+//! Cells are separate dynamic libraries with their own copy of the standard
+//! library, so a panic hook installed by the worker never sees their panics.
+//! Instead the cell wrapper calls [`install_hook`] before running the cell,
+//! and when it catches a panic hands the encoded [`PanicReport`] from
+//! [`take_report`] back through its output buffer.
+//!
+//! Backtraces are only captured when `RUST_BACKTRACE` (or
+//! `RUST_LIB_BACKTRACE`) is set, as for [`std::backtrace::Backtrace::capture`].
+
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::cell::RefCell;
+use std::panic::PanicHookInfo;
+use std::sync::Once;
+
+/// Maximum number of backtrace lines kept in a report.
+pub const MAX_BACKTRACE_LINES: usize = 200;
+
+/// What is known about a panic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PanicReport {
+    /// Panic message, with its source location if known.
+    pub message: String,
+    /// Backtrace of the panicking thread, if capturing was enabled.
+    pub backtrace: Option<String>,
+}
+
+impl PanicReport {
+    /// Build a report from the information passed to a panic hook.
+    pub fn from_hook_info(info: &PanicHookInfo<'_>) -> Self {
+        let payload = info.payload();
+        let text = if let Some(s) = payload.downcast_ref::<&str>() {
+            s.to_string()
+        } else if let Some(s) = payload.downcast_ref::<String>() {
+            s.clone()
+        } else {
+            "Unknown panic".to_string()
+        };
+        let message = match info.location() {
+            Some(location) => format!("{} at {}", text, location),
+            None => text,
+        };
+
+        let backtrace = Backtrace::capture();
+        let backtrace = (backtrace.status() == BacktraceStatus::Captured)
+            .then(|| truncate_backtrace(&backtrace.to_string()));
+
+        Self { message, backtrace }
+    }
+
+    /// Encode as `message_len (8 bytes LE) | message | backtrace`.
+    ///
+    /// An empty backtrace stands for none.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let backtrace = self.backtrace.as_deref().unwrap_or_default();
+        let mut bytes = Vec::with_capacity(8 + self.message.len() + backtrace.len());
+        bytes.extend_from_slice(&(self.message.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(self.message.as_bytes());
+        bytes.extend_from_slice(backtrace.as_bytes());
+        bytes
+    }
+
+    /// Decode a report encoded with [`PanicReport::to_bytes`].
+    ///
+    /// Returns `None` if the bytes are malformed.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let message_len = u64::from_le_bytes(bytes.get(..8)?.try_into().ok()?) as usize;
+        let message_end = 8usize.checked_add(message_len)?;
+        let message = String::from_utf8_lossy(bytes.get(8..message_end)?).into_owned();
+        let backtrace = &bytes[message_end..];
+        let backtrace =
+            (!backtrace.is_empty()).then(|| String::from_utf8_lossy(backtrace).into_owned());
+        Some(Self { message, backtrace })
+    }
+}
+
+/// Keep the first [`MAX_BACKTRACE_LINES`] lines of a backtrace, noting how
+/// many were dropped.
+pub fn truncate_backtrace(backtrace: &str) -> String {
+    let total = backtrace.lines().count();
+    if total <= MAX_BACKTRACE_LINES {
+        return backtrace.to_string();
+    }
+    let mut kept: Vec<&str> = backtrace.lines().take(MAX_BACKTRACE_LINES).collect();
+    let note = format!("... {} more lines", total - MAX_BACKTRACE_LINES);
+    kept.push(&note);
+    kept.join("\n")
+}
+
+thread_local! {
+    /// Report of the last panic on this thread, until taken.
+    static LAST_PANIC: RefCell<Option<PanicReport>> = const { RefCell::new(None) };
+}
+
+static INSTALL_HOOK: Once = Once::new();
+
+/// Install a panic hook that records a [`PanicReport`] of each panic.
+///
+/// The previous hook still runs afterwards. Installing more than once is a
+/// no-op.
+pub fn install_hook() {
+    INSTALL_HOOK.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let report = PanicReport::from_hook_info(info);
+            LAST_PANIC.with(|last| *last.borrow_mut() = Some(report));
+            previous(info);
+        }));
+    });
+}
+
+/// Take the report of the last panic on this thread.
+pub fn take_report() -> Option<PanicReport> {
+    LAST_PANIC.with(|last| last.borrow_mut().take())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_roundtrip() {
+        let report = PanicReport {
+            message: "boom at src/lib.rs:1:1".to_string(),
+            backtrace: Some("0: main".to_string()),
+        };
+        assert_eq!(PanicReport::from_bytes(&report.to_bytes()), Some(report));
+
+        let report = PanicReport {
+            message: "boom".to_string(),
+            backtrace: None,
+        };
+        assert_eq!(PanicReport::from_bytes(&report.to_bytes()), Some(report));
+        assert_eq!(PanicReport::from_bytes(&[1, 0]), None);
+    }
+
+    #[test]
+    fn test_truncate_backtrace() {
+        let long: Vec<String> = (0..MAX_BACKTRACE_LINES + 5)
+            .map(|i| format!("{i}: frame"))
+            .collect();
+        let truncated = truncate_backtrace(&long.join("\n"));
+        assert_eq!(truncated.lines().count(), MAX_BACKTRACE_LINES + 1);
+        assert!(truncated.ends_with("... 5 more lines"));
+        assert_eq!(truncate_backtrace("0: main"), "0: main");
+    }
+
+    #[test]
+    fn test_hook_records_panic() {
+        install_hook();
+        let result = std::panic::catch_unwind(|| panic!("cell failed"));
+        assert!(result.is_err());
+        let report = take_report().unwrap();
+        assert!(report.message.starts_with("cell failed at "));
+        assert!(take_report().is_none());
+    }
+}
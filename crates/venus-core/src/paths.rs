@@ -3,10 +3,17 @@
 //! Provides consistent directory structure for Venus notebooks,
 //! ensuring the same paths are used across CLI and server components.
 
+use std::collections::hash_map::DefaultHasher;
 use std::fs;
+use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
 
-use crate::error::Result;
+use crate::error::{Error, Result};
+
+/// Environment variable that relocates the `.venus` directory.
+///
+/// Useful when the notebook lives on a read-only or networked filesystem.
+pub const BUILD_DIR_ENV: &str = "VENUS_BUILD_DIR";
 
 /// Directory structure for a Venus notebook.
 ///
@@ -22,6 +29,10 @@ use crate::error::Result;
 /// ├── cache/      # Compilation cache metadata
 /// └── state/      # Persistent cell outputs
 /// ```
+///
+/// If a build root is given (or [`BUILD_DIR_ENV`] is set), the `.venus`
+/// directory is replaced by a subdirectory of the root named after the
+/// notebook's directory, so notebooks in different directories stay apart.
 #[derive(Debug, Clone)]
 pub struct NotebookDirs {
     /// The `.venus` directory itself.
@@ -62,11 +73,37 @@ impl NotebookDirs {
     /// # Errors
     /// Returns an error if directory creation fails.
     pub fn from_notebook_dir(notebook_dir: &Path) -> Result<Self> {
-        let venus_dir = notebook_dir.join(".venus");
+        Self::with_build_root(notebook_dir, None)
+    }
+
+    /// Create directory structure for a notebook directory, placing it under
+    /// `build_root` instead of next to the notebook.
+    ///
+    /// Falls back to [`BUILD_DIR_ENV`], then to `.venus` in the notebook's
+    /// directory.
+    ///
+    /// # Errors
+    /// Returns an error if directory creation fails or the build root is
+    /// not writable.
+    pub fn with_build_root(notebook_dir: &Path, build_root: Option<&Path>) -> Result<Self> {
+        let build_root = build_root.map(Path::to_path_buf).or_else(|| {
+            std::env::var_os(BUILD_DIR_ENV)
+                .filter(|v| !v.is_empty())
+                .map(PathBuf::from)
+        });
+
+        let venus_dir = match &build_root {
+            Some(root) => root.join(relocated_dir_name(notebook_dir)),
+            None => notebook_dir.join(".venus"),
+        };
         let build_dir = venus_dir.join("build");
         let cache_dir = venus_dir.join("cache");
         let state_dir = venus_dir.join("state");
 
+        if let Some(root) = &build_root {
+            check_writable(root, &venus_dir)?;
+        }
+
         // Create all directories (Error::Io auto-converts via #[from])
         fs::create_dir_all(&build_dir)?;
         fs::create_dir_all(&cache_dir)?;
@@ -97,6 +134,45 @@ impl NotebookDirs {
     }
 }
 
+/// Name of a notebook directory's subdirectory under a relocated build root.
+///
+/// The directory's name keeps it recognizable; the hash of its full path
+/// keeps same-named directories apart.
+fn relocated_dir_name(notebook_dir: &Path) -> String {
+    // `Path::parent` yields an empty path for bare file names
+    let notebook_dir = if notebook_dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        notebook_dir
+    };
+    let absolute = notebook_dir
+        .canonicalize()
+        .unwrap_or_else(|_| notebook_dir.to_path_buf());
+    let mut hasher = DefaultHasher::new();
+    absolute.hash(&mut hasher);
+
+    let name = absolute
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "notebook".to_string());
+    format!("{}-{:016x}", name, hasher.finish())
+}
+
+/// Make sure build artifacts can be written to a relocated build directory.
+fn check_writable(root: &Path, venus_dir: &Path) -> Result<()> {
+    let probe = venus_dir.join(".write-test");
+    fs::create_dir_all(venus_dir)
+        .and_then(|()| fs::write(&probe, b""))
+        .and_then(|()| fs::remove_file(&probe))
+        .map_err(|e| {
+            Error::InvalidOperation(format!(
+                "build directory {} is not writable: {}",
+                root.display(),
+                e
+            ))
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -115,6 +191,38 @@ mod tests {
         assert!(dirs.state_dir.exists());
     }
 
+    #[test]
+    fn test_with_build_root() {
+        let notebooks = TempDir::new().expect("Failed to create temp dir");
+        let root = TempDir::new().expect("Failed to create temp dir");
+
+        let dirs = NotebookDirs::with_build_root(notebooks.path(), Some(root.path()))
+            .expect("Failed to create dirs");
+
+        assert!(dirs.venus_dir.starts_with(root.path()));
+        assert!(dirs.build_dir.starts_with(&dirs.venus_dir));
+        assert!(dirs.state_dir.exists());
+        assert!(!notebooks.path().join(".venus").exists());
+
+        // Another notebook directory gets its own subdirectory
+        let other = notebooks.path().join("other");
+        fs::create_dir(&other).expect("Failed to create dir");
+        let other_dirs = NotebookDirs::with_build_root(&other, Some(root.path()))
+            .expect("Failed to create dirs");
+        assert_ne!(dirs.venus_dir, other_dirs.venus_dir);
+    }
+
+    #[test]
+    fn test_unwritable_build_root() {
+        let temp = TempDir::new().expect("Failed to create temp dir");
+        // A file can't hold the build directories
+        let root = temp.path().join("not-a-dir");
+        fs::write(&root, "").expect("Failed to write file");
+
+        let err = NotebookDirs::with_build_root(temp.path(), Some(&root)).unwrap_err();
+        assert!(err.to_string().contains("not writable"), "{err}");
+    }
+
     #[test]
     fn test_clean() {
         let temp = TempDir::new().expect("Failed to create temp dir");
@@ -6,7 +6,7 @@
 
 use std::path::PathBuf;
 
-use crate::graph::{CellId, CellInfo, Dependency, SourceSpan};
+use crate::graph::{CellId, CellInfo, Dependency, OutputFormat, SourceSpan};
 
 /// Serializable cell data for Salsa tracking.
 ///
@@ -30,6 +30,8 @@ pub struct CellData {
     pub param_is_mut: Vec<bool>,
     /// Return type
     pub return_type: String,
+    /// Whether the function is `async`
+    pub is_async: bool,
     /// Documentation
     pub doc_comment: Option<String>,
     /// Source code
@@ -38,6 +40,12 @@ pub struct CellData {
     pub source_file: PathBuf,
     /// Source location (start_line, start_col, end_line, end_col)
     pub span: (usize, usize, usize, usize),
+    /// Execution timeout in milliseconds
+    pub timeout_ms: Option<u64>,
+    /// Optimization level override
+    pub opt_level: Option<u8>,
+    /// Output format hint
+    pub output_format: Option<OutputFormat>,
 }
 
 impl From<CellInfo> for CellData {
@@ -59,6 +67,7 @@ impl From<CellInfo> for CellData {
             param_is_ref: info.dependencies.iter().map(|d| d.is_ref).collect(),
             param_is_mut: info.dependencies.iter().map(|d| d.is_mut).collect(),
             return_type: info.return_type,
+            is_async: info.is_async,
             doc_comment: info.doc_comment,
             source_code: info.source_code,
             source_file: info.source_file,
@@ -68,6 +77,9 @@ impl From<CellInfo> for CellData {
                 info.span.end_line,
                 info.span.end_col,
             ),
+            timeout_ms: info.timeout_ms,
+            opt_level: info.opt_level,
+            output_format: info.output_format,
         }
     }
 }
@@ -94,6 +106,7 @@ impl From<CellData> for CellInfo {
             display_name: data.display_name,
             dependencies,
             return_type: data.return_type,
+            is_async: data.is_async,
             doc_comment: data.doc_comment,
             source_code: data.source_code,
             source_file: data.source_file,
@@ -103,6 +116,9 @@ impl From<CellData> for CellInfo {
                 end_line: data.span.2,
                 end_col: data.span.3,
             },
+            timeout_ms: data.timeout_ms,
+            opt_level: data.opt_level,
+            output_format: data.output_format,
         }
     }
 }
@@ -160,6 +176,7 @@ impl CompiledCellData {
             source_hash: self.source_hash,
             deps_hash: self.deps_hash,
             compile_time_ms: self.compile_time_ms,
+            warnings: Vec::new(),
         }
     }
 }
@@ -340,6 +357,10 @@ mod tests {
             doc_comment: Some("Test cell".to_string()),
             source_code: "{ 42 }".to_string(),
             source_file: PathBuf::from("test.rs"),
+            timeout_ms: None,
+            opt_level: None,
+            output_format: None,
+            is_async: false,
             span: SourceSpan {
                 start_line: 1,
                 start_col: 0,
@@ -381,6 +402,7 @@ mod tests {
             source_hash: 12345,
             deps_hash: 67890,
             compile_time_ms: 100,
+            warnings: Vec::new(),
         };
 
         // Convert to CompiledCellData
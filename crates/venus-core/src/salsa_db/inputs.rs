@@ -37,6 +37,12 @@ pub struct CompilerSettings {
     /// Path to the compiled universe library
     pub universe_path: Option<PathBuf>,
 
+    /// Dependency hash the universe was built with.
+    ///
+    /// Overrides the hash computed from the notebook source, so that the
+    /// compile cache is shared with builds driven by `UniverseBuilder`.
+    pub deps_hash: Option<u64>,
+
     /// Use Cranelift backend (fast compilation)
     pub use_cranelift: bool,
 
@@ -141,6 +141,7 @@ impl VenusDatabase {
         build_dir: PathBuf,
         cache_dir: PathBuf,
         universe_path: Option<PathBuf>,
+        deps_hash: Option<u64>,
         use_cranelift: bool,
         opt_level: u8,
     ) -> CompilerSettings {
@@ -149,6 +150,7 @@ impl VenusDatabase {
             build_dir,
             cache_dir,
             universe_path,
+            deps_hash,
             use_cranelift,
             opt_level,
         )
@@ -460,6 +462,7 @@ mod tests {
             PathBuf::from(".venus/build"),
             PathBuf::from(".venus/cache"),
             Some(PathBuf::from(".venus/universe/libvenus_universe.so")),
+            None,
             true,
             0,
         );
@@ -349,7 +349,7 @@ pub fn dependency_hash(db: &dyn salsa::Database, source: SourceFile) -> u64 {
 ///
 /// The compilation depends on:
 /// - The cell's source code (via CellData from parse_cells)
-/// - The dependency hash (via dependency_hash)
+/// - The dependency hash (from the settings, or via dependency_hash)
 /// - The compiler settings (via CompilerSettings input)
 #[salsa::tracked]
 pub fn compiled_cell(
@@ -365,8 +365,10 @@ pub fn compiled_cell(
         return CompilationStatus::Failed(format!("Cell index {} not found", cell_idx));
     };
 
-    // Get dependency hash
-    let deps_hash = dependency_hash(db, source);
+    // Get dependency hash, preferring the one the universe was built with
+    let deps_hash = settings
+        .deps_hash(db)
+        .unwrap_or_else(|| dependency_hash(db, source));
 
     // Convert to CellInfo for the compiler
     let cell_info: CellInfo = cell_data.clone().into();
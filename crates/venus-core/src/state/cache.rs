@@ -0,0 +1,163 @@
+//! Output cache for skipping re-execution of unchanged cells.
+//!
+//! A cell's output is fully determined by its source, the outputs of its
+//! dependencies and the widget values it reads. [`inputs_hash`] folds these
+//! into a single key; when a cell is run again with the same key, the
+//! cached [`BoxedOutput`] can be reused instead of executing the cell.
+
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use rustc_hash::FxHasher;
+
+use super::output::BoxedOutput;
+
+/// Compute the inputs hash for a cell execution.
+///
+/// `source_hash` identifies the cell's code (and anything it was compiled
+/// against), `inputs` are the dependency outputs in parameter order, and
+/// `widget_values` is the serialized widget state passed to the cell.
+pub fn inputs_hash(source_hash: u64, inputs: &[Arc<BoxedOutput>], widget_values: &[u8]) -> u64 {
+    let mut hasher = FxHasher::default();
+    source_hash.hash(&mut hasher);
+    inputs.len().hash(&mut hasher);
+    for input in inputs {
+        input.type_hash().hash(&mut hasher);
+        input.bytes().hash(&mut hasher);
+    }
+    widget_values.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// A cached cell output together with the inputs that produced it.
+#[derive(Debug, Clone)]
+pub struct CachedOutput {
+    /// Inputs hash the output was produced with.
+    pub inputs_hash: u64,
+
+    /// The cell output.
+    pub output: Arc<BoxedOutput>,
+
+    /// Execution time of the run that produced the output.
+    pub execution_time_ms: u64,
+}
+
+/// Cache of the most recent output of each cell, keyed by cell name.
+///
+/// Cell names are used rather than [`CellId`](crate::graph::CellId)s because
+/// IDs are reassigned whenever the notebook is re-parsed.
+#[derive(Debug, Default)]
+pub struct OutputCache {
+    entries: HashMap<String, CachedOutput>,
+}
+
+impl OutputCache {
+    /// Create an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Look up a cached output that is valid for `inputs_hash`.
+    pub fn get(&self, cell_name: &str, inputs_hash: u64) -> Option<&CachedOutput> {
+        self.entries
+            .get(cell_name)
+            .filter(|entry| entry.inputs_hash == inputs_hash)
+    }
+
+    /// Record the output of a cell execution, replacing any previous entry.
+    pub fn insert(
+        &mut self,
+        cell_name: impl Into<String>,
+        inputs_hash: u64,
+        output: Arc<BoxedOutput>,
+        execution_time_ms: u64,
+    ) {
+        self.entries.insert(
+            cell_name.into(),
+            CachedOutput {
+                inputs_hash,
+                output,
+                execution_time_ms,
+            },
+        );
+    }
+
+    /// Drop the cached output of a cell.
+    pub fn remove(&mut self, cell_name: &str) {
+        self.entries.remove(cell_name);
+    }
+
+    /// Names of the cells with a cached output.
+    pub fn cell_names(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(String::as_str)
+    }
+
+    /// Drop all cached outputs.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Number of cached outputs.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output(value: i32) -> Arc<BoxedOutput> {
+        Arc::new(BoxedOutput::new(&value).unwrap())
+    }
+
+    #[test]
+    fn test_rerun_with_identical_inputs_hits_cache() {
+        let mut cache = OutputCache::new();
+        let inputs = vec![output(1), output(2)];
+
+        let hash = inputs_hash(42, &inputs, b"");
+        cache.insert("sum", hash, output(3), 10);
+
+        // Same source, same inputs: recomputed hash matches
+        let rerun = inputs_hash(42, &[output(1), output(2)], b"");
+        let cached = cache.get("sum", rerun).expect("cache hit");
+        assert_eq!(cached.output.deserialize::<i32>().unwrap(), 3);
+        assert_eq!(cached.execution_time_ms, 10);
+    }
+
+    #[test]
+    fn test_changed_inputs_miss_cache() {
+        let mut cache = OutputCache::new();
+        let hash = inputs_hash(42, &[output(1)], b"");
+        cache.insert("double", hash, output(2), 1);
+
+        let changed_input = inputs_hash(42, &[output(5)], b"");
+        let changed_source = inputs_hash(43, &[output(1)], b"");
+        let changed_widgets = inputs_hash(42, &[output(1)], b"{}");
+        assert!(cache.get("double", changed_input).is_none());
+        assert!(cache.get("double", changed_source).is_none());
+        assert!(cache.get("double", changed_widgets).is_none());
+        assert!(cache.get("other", hash).is_none());
+    }
+
+    #[test]
+    fn test_insert_replaces_and_remove() {
+        let mut cache = OutputCache::new();
+        cache.insert("a", 1, output(1), 0);
+        cache.insert("a", 2, output(2), 0);
+        assert_eq!(cache.len(), 1);
+        assert!(cache.get("a", 1).is_none());
+        assert!(cache.get("a", 2).is_some());
+        assert_eq!(cache.cell_names().collect::<Vec<_>>(), ["a"]);
+
+        cache.remove("a");
+        assert!(cache.is_empty());
+    }
+}
@@ -0,0 +1,169 @@
+//! Index of persisted outputs by cell name.
+//!
+//! [`StateManager`](super::StateManager) stores outputs under cell IDs, which
+//! are reassigned whenever the notebook is re-parsed. The index records, for
+//! each cell name, the ID its output was stored under and a fingerprint of
+//! the code that produced it, so a later build can tell whether the output
+//! still belongs to the cell.
+
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use rustc_hash::FxHasher;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::graph::{CellId, GraphEngine};
+
+/// File in the state directory holding the index.
+const INDEX_FILE: &str = "output_index.json";
+
+/// Where a cell's output was persisted and what produced it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct IndexEntry {
+    /// ID the output is stored under.
+    cell_id: CellId,
+    /// Fingerprint of the cell when the output was produced.
+    fingerprint: u64,
+}
+
+/// Persisted outputs keyed by cell name.
+#[derive(Debug, Default)]
+pub struct OutputIndex {
+    path: PathBuf,
+    entries: HashMap<String, IndexEntry>,
+}
+
+impl OutputIndex {
+    /// Load the index from a state directory, or start an empty one.
+    pub fn load(state_dir: impl AsRef<Path>) -> Result<Self> {
+        let path = state_dir.as_ref().join(INDEX_FILE);
+        let entries = match fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+                tracing::warn!("Ignoring unreadable output index: {}", e);
+                HashMap::new()
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self { path, entries })
+    }
+
+    /// Record that a cell's output with `fingerprint` is stored under `cell_id`.
+    ///
+    /// Entries of other cells pointing at the same ID are dropped, since
+    /// their output has been overwritten.
+    pub fn record(&mut self, cell_name: &str, cell_id: CellId, fingerprint: u64) {
+        self.entries.retain(|_, entry| entry.cell_id != cell_id);
+        self.entries.insert(
+            cell_name.to_string(),
+            IndexEntry {
+                cell_id,
+                fingerprint,
+            },
+        );
+    }
+
+    /// ID of a cell's persisted output, if it was produced with `fingerprint`.
+    pub fn lookup(&self, cell_name: &str, fingerprint: u64) -> Option<CellId> {
+        self.entries
+            .get(cell_name)
+            .filter(|entry| entry.fingerprint == fingerprint)
+            .map(|entry| entry.cell_id)
+    }
+
+    /// Whether the index has an entry for a cell, current or not.
+    pub fn contains(&self, cell_name: &str) -> bool {
+        self.entries.contains_key(cell_name)
+    }
+
+    /// Write the index back to the state directory.
+    pub fn save(&self) -> Result<()> {
+        let json = serde_json::to_string_pretty(&self.entries)
+            .map_err(|e| Error::Serialization(e.to_string()))?;
+        let temp_path = self.path.with_extension("tmp");
+        fs::write(&temp_path, json)?;
+        fs::rename(&temp_path, &self.path)?;
+        Ok(())
+    }
+}
+
+/// Fingerprint each cell of a graph by the code its output depends on.
+///
+/// A cell's fingerprint covers its source, its return type, its output
+/// format hint and the fingerprints of the cells it depends on, so editing a
+/// cell changes the fingerprint of every cell downstream of it. Definition
+/// cells are not covered. Keyed by cell name.
+pub fn output_fingerprints(graph: &GraphEngine) -> Result<HashMap<String, u64>> {
+    let mut by_id: HashMap<CellId, u64> = HashMap::new();
+    let mut by_name = HashMap::new();
+    for cell_id in graph.topological_order()? {
+        let Some(cell) = graph.get_cell(cell_id) else {
+            continue;
+        };
+        let mut hasher = FxHasher::default();
+        cell.source_code.hash(&mut hasher);
+        cell.return_type.hash(&mut hasher);
+        cell.output_format.hash(&mut hasher);
+        for dep in graph.dependencies(cell_id) {
+            by_id.get(&dep).hash(&mut hasher);
+        }
+        let fingerprint = hasher.finish();
+        by_id.insert(cell_id, fingerprint);
+        by_name.insert(cell.name.clone(), fingerprint);
+    }
+    Ok(by_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::CellParser;
+
+    fn graph(source: &str) -> GraphEngine {
+        let parsed = CellParser::new()
+            .parse_str(source, Path::new("notebook.rs"))
+            .unwrap();
+        let mut graph = GraphEngine::new();
+        for cell in parsed.code_cells {
+            graph.add_cell(cell);
+        }
+        graph.resolve_dependencies().unwrap();
+        graph
+    }
+
+    #[test]
+    fn test_fingerprints_follow_upstream_edits() {
+        let a = "#[venus::cell]\npub fn a() -> i32 { 1 }\n\n";
+        let b = "#[venus::cell]\npub fn b(a: &i32) -> i32 { *a }\n\n";
+        let c = "#[venus::cell]\npub fn c() -> i32 { 3 }\n";
+        let before = output_fingerprints(&graph(&format!("{a}{b}{c}"))).unwrap();
+
+        let edited = a.replace("{ 1 }", "{ 2 }");
+        let after = output_fingerprints(&graph(&format!("{c}{edited}{b}"))).unwrap();
+
+        assert_ne!(before["a"], after["a"]);
+        assert_ne!(before["b"], after["b"]);
+        // Unaffected by the edit and by its new position
+        assert_eq!(before["c"], after["c"]);
+    }
+
+    #[test]
+    fn test_index_round_trip() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let mut index = OutputIndex::load(temp.path()).unwrap();
+        index.record("a", CellId::new(0), 1);
+        index.record("b", CellId::new(1), 2);
+        // Another cell's output now occupies ID 0
+        index.record("c", CellId::new(0), 3);
+        index.save().unwrap();
+
+        let index = OutputIndex::load(temp.path()).unwrap();
+        assert!(!index.contains("a"));
+        assert_eq!(index.lookup("b", 2), Some(CellId::new(1)));
+        assert_eq!(index.lookup("b", 5), None);
+        assert_eq!(index.lookup("c", 3), Some(CellId::new(0)));
+    }
+}
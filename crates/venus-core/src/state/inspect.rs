@@ -0,0 +1,130 @@
+//! Structured inspection of cell outputs.
+//!
+//! Decodes the rkyv bytes of common output types into JSON so clients can
+//! explore a value, falling back to a byte summary for other types.
+
+use serde::Serialize;
+use serde_json::{Value, json};
+
+/// Number of leading bytes included in the summary of undecodable outputs.
+pub const INSPECT_HEX_BYTES: usize = 64;
+
+/// Decode an output to JSON based on the cell's declared return type.
+///
+/// Supports primitives, `String`, `()`, and `Vec<T>` / `Option<T>` of
+/// those. Other types, or bytes that fail to decode, yield
+/// `{ "length": N, "hex": "..." }` with the first [`INSPECT_HEX_BYTES`]
+/// bytes.
+pub fn inspect_output(type_name: &str, bytes: &[u8]) -> Value {
+    let type_name: String = type_name.split_whitespace().collect();
+    decode_json(&type_name, bytes).unwrap_or_else(|| byte_summary(bytes))
+}
+
+/// Decode bytes as `T` and convert to JSON.
+fn decode_as<T>(bytes: &[u8]) -> Option<Value>
+where
+    T: Serialize + rkyv::Archive,
+    T::Archived: for<'a> rkyv::bytecheck::CheckBytes<rkyv::api::high::HighValidator<'a, rkyv::rancor::Error>>
+        + rkyv::Deserialize<T, rkyv::api::high::HighDeserializer<rkyv::rancor::Error>>,
+{
+    let value = rkyv::from_bytes::<T, rkyv::rancor::Error>(bytes).ok()?;
+    serde_json::to_value(value).ok()
+}
+
+/// Decode a scalar type, wrapped in `$wrap` (`Vec`, `Option` or `Plain`).
+macro_rules! decode_scalar {
+    ($name:expr, $bytes:expr, $wrap:ident) => {
+        match $name {
+            "i8" => decode_as::<$wrap<i8>>($bytes),
+            "i16" => decode_as::<$wrap<i16>>($bytes),
+            "i32" => decode_as::<$wrap<i32>>($bytes),
+            "i64" => decode_as::<$wrap<i64>>($bytes),
+            "u8" => decode_as::<$wrap<u8>>($bytes),
+            "u16" => decode_as::<$wrap<u16>>($bytes),
+            "u32" => decode_as::<$wrap<u32>>($bytes),
+            "u64" => decode_as::<$wrap<u64>>($bytes),
+            "isize" => decode_as::<$wrap<isize>>($bytes),
+            "usize" => decode_as::<$wrap<usize>>($bytes),
+            "f32" => decode_as::<$wrap<f32>>($bytes),
+            "f64" => decode_as::<$wrap<f64>>($bytes),
+            "bool" => decode_as::<$wrap<bool>>($bytes),
+            "String" => decode_as::<$wrap<String>>($bytes),
+            _ => None,
+        }
+    };
+}
+
+/// Identity wrapper so `decode_scalar!` can decode bare scalars.
+type Plain<T> = T;
+
+fn decode_json(type_name: &str, bytes: &[u8]) -> Option<Value> {
+    if type_name == "()" {
+        return Some(Value::Null);
+    }
+    if let Some(inner) = type_name
+        .strip_prefix("Vec<")
+        .and_then(|t| t.strip_suffix('>'))
+    {
+        return decode_scalar!(inner, bytes, Vec);
+    }
+    if let Some(inner) = type_name
+        .strip_prefix("Option<")
+        .and_then(|t| t.strip_suffix('>'))
+    {
+        return decode_scalar!(inner, bytes, Option);
+    }
+    decode_scalar!(type_name, bytes, Plain)
+}
+
+/// Length and leading bytes of an output that could not be decoded.
+fn byte_summary(bytes: &[u8]) -> Value {
+    let hex: String = bytes
+        .iter()
+        .take(INSPECT_HEX_BYTES)
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    json!({ "length": bytes.len(), "hex": hex })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bytes<T>(value: &T) -> Vec<u8>
+    where
+        T: for<'a> rkyv::Serialize<
+                rkyv::api::high::HighSerializer<
+                    rkyv::util::AlignedVec,
+                    rkyv::ser::allocator::ArenaHandle<'a>,
+                    rkyv::rancor::Error,
+                >,
+            >,
+    {
+        rkyv::to_bytes::<rkyv::rancor::Error>(value)
+            .unwrap()
+            .to_vec()
+    }
+
+    #[test]
+    fn test_inspect_decodes_common_types() {
+        assert_eq!(inspect_output("i32", &bytes(&42i32)), json!(42));
+        assert_eq!(inspect_output("usize", &bytes(&7usize)), json!(7));
+        assert_eq!(
+            inspect_output("Vec < String >", &bytes(&vec!["a".to_string()])),
+            json!(["a"])
+        );
+        assert_eq!(
+            inspect_output("Option<f64>", &bytes(&None::<f64>)),
+            json!(null)
+        );
+        assert_eq!(inspect_output("()", &[]), json!(null));
+    }
+
+    #[test]
+    fn test_inspect_falls_back_to_byte_summary() {
+        assert_eq!(
+            inspect_output("MyStruct", &[0xde, 0xad]),
+            json!({ "length": 2, "hex": "dead" })
+        );
+    }
+}
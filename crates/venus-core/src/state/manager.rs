@@ -254,6 +254,45 @@ impl StateManager {
         Ok(count)
     }
 
+    /// Remove cached outputs for cells that no longer exist.
+    ///
+    /// Outputs are keyed by cell ID, so `live_cells` must hold the IDs of the
+    /// notebook's current cells; their outputs are never touched. Leftover
+    /// temporary files from interrupted writes are removed as well.
+    ///
+    /// Returns the number of bytes reclaimed on disk.
+    pub fn gc(&mut self, live_cells: &[CellId]) -> Result<u64> {
+        let live: HashSet<CellId> = live_cells.iter().copied().collect();
+
+        self.outputs.retain(|id, _| live.contains(id));
+        self.fingerprints.retain(|id, _| live.contains(id));
+        self.dirty.retain(|id| live.contains(id));
+
+        let outputs_dir = self.state_dir.join("outputs");
+        if !outputs_dir.exists() {
+            return Ok(0);
+        }
+
+        let mut reclaimed = 0;
+        for entry in fs::read_dir(&outputs_dir)? {
+            let path = entry?.path();
+            let is_live = path.extension().is_some_and(|e| e == "bin")
+                && path
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .and_then(|s| s.parse::<usize>().ok())
+                    .is_some_and(|id| live.contains(&CellId::new(id)));
+
+            if !is_live && path.is_file() {
+                let size = fs::metadata(&path)?.len();
+                fs::remove_file(&path)?;
+                reclaimed += size;
+            }
+        }
+
+        Ok(reclaimed)
+    }
+
     /// Get the path for a cell's output file.
     fn output_path(&self, cell_id: CellId) -> PathBuf {
         self.state_dir
@@ -425,6 +464,27 @@ mod tests {
         assert_eq!(output, loaded);
     }
 
+    #[test]
+    fn test_gc_removes_only_orphaned_outputs() {
+        let (mut manager, temp) = setup();
+        let live = CellId::new(0);
+        let orphan = CellId::new(7);
+
+        manager.save(live, &TestOutput { value: 1 }).unwrap();
+        manager.save(orphan, &TestOutput { value: 2 }).unwrap();
+        manager.flush().unwrap();
+        let stale_tmp = temp.path().join("outputs").join("3.tmp");
+        fs::write(&stale_tmp, b"partial").unwrap();
+
+        let reclaimed = manager.gc(&[live]).unwrap();
+
+        assert!(reclaimed > b"partial".len() as u64);
+        assert!(manager.has_output(live));
+        assert!(!manager.has_output(orphan));
+        assert!(!stale_tmp.exists());
+        assert_eq!(manager.load::<TestOutput>(live).unwrap().value, 1);
+    }
+
     #[test]
     fn test_invalidate() {
         let (mut manager, _temp) = setup();
@@ -4,11 +4,20 @@
 //! - Cell output serialization (Serde + rkyv fast path)
 //! - Schema evolution detection
 //! - State persistence and restoration
+//! - Output caching keyed by cell inputs
+//! - An index of persisted outputs by cell name
+//! - Structured inspection of outputs
 
+mod cache;
+mod index;
+mod inspect;
 mod manager;
 mod output;
 mod schema;
 
+pub use cache::{CachedOutput, OutputCache, inputs_hash};
+pub use index::{OutputIndex, output_fingerprints};
+pub use inspect::{INSPECT_HEX_BYTES, inspect_output};
 pub use manager::StateManager;
-pub use output::{BoxedOutput, CellOutput, ZeroCopyOutput};
+pub use output::{BoxedOutput, CellOutput, OutputAssembler, ZeroCopyOutput, deserialize_output};
 pub use schema::{SchemaChange, TypeFingerprint};
@@ -108,7 +108,8 @@ pub struct BoxedOutput {
     /// Type name for debugging
     type_name: String,
 
-    /// Human-readable display text (Debug format)
+    /// Human-readable display text (Debug format), optionally followed by a
+    /// NUL and rich HTML.
     display_text: Option<String>,
 }
 
@@ -143,7 +144,8 @@ impl BoxedOutput {
     /// Create a boxed output from raw bytes with display text.
     ///
     /// Used when loading outputs from FFI calls that include
-    /// a human-readable representation.
+    /// a human-readable representation. Cells whose type implements `Render`
+    /// append a NUL and the HTML rendering to the display string.
     pub fn from_raw_bytes_with_display(bytes: Vec<u8>, display: String) -> Self {
         Self {
             bytes,
@@ -166,6 +168,16 @@ impl BoxedOutput {
         }
     }
 
+    /// Record the declared type of an output created from FFI bytes.
+    ///
+    /// FFI outputs carry no type information, so the executor can attach the
+    /// cell's declared return type once the output is known. The type hash is
+    /// left untouched.
+    pub fn with_type_name(mut self, type_name: impl Into<String>) -> Self {
+        self.type_name = type_name.into();
+        self
+    }
+
     /// Get the serialized bytes.
     pub fn bytes(&self) -> &[u8] {
         &self.bytes
@@ -183,7 +195,17 @@ impl BoxedOutput {
 
     /// Get the display text (Debug format) if available.
     pub fn display_text(&self) -> Option<&str> {
-        self.display_text.as_deref()
+        self.display_text
+            .as_deref()
+            .map(|display| display.split_once('\0').map_or(display, |(text, _)| text))
+    }
+
+    /// Get the rich HTML rendering if the cell's type provided one.
+    pub fn display_html(&self) -> Option<&str> {
+        self.display_text
+            .as_deref()
+            .and_then(|display| display.split_once('\0'))
+            .map(|(_, html)| html)
     }
 
     /// Deserialize to a specific type.
@@ -215,6 +237,91 @@ impl BoxedOutput {
     }
 }
 
+/// Rebuilds a [`BoxedOutput`] from the segments a worker sends it in.
+///
+/// Worker output is laid out as `display_len (8 bytes, u64 LE) |
+/// display_bytes | rkyv_data`. The display text leads, so it can be shown
+/// as segments arrive, before the rest of the output is in.
+#[derive(Debug, Default)]
+pub struct OutputAssembler {
+    bytes: Vec<u8>,
+    /// Bytes of display text already returned by `push`.
+    text_sent: usize,
+    /// Whether all of the display text has been returned.
+    text_done: bool,
+}
+
+impl OutputAssembler {
+    /// Create an empty assembler.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append the next segment of the output.
+    ///
+    /// Returns the display text the segment completes, and whether that is
+    /// the end of it, or `None` if there is nothing new to show. Only the
+    /// plain text is returned, not the HTML that may follow a NUL.
+    pub fn push(&mut self, segment: &[u8]) -> Option<(String, bool)> {
+        self.bytes.extend_from_slice(segment);
+        if self.text_done || self.bytes.len() < 8 {
+            return None;
+        }
+
+        let display_len = u64::from_le_bytes(self.bytes[..8].try_into().unwrap()) as usize;
+        let display_end = self.bytes.len().min(8usize.saturating_add(display_len));
+        let pending = &self.bytes[8 + self.text_sent..display_end];
+        let (pending, done) = match pending.iter().position(|&b| b == 0) {
+            Some(nul) => (&pending[..nul], true),
+            None => (pending, display_end - 8 == display_len),
+        };
+
+        let text = if done {
+            String::from_utf8_lossy(pending).into_owned()
+        } else {
+            // Hold back a character split across segments
+            let valid = match std::str::from_utf8(pending) {
+                Ok(text) => text.len(),
+                Err(e) => e.valid_up_to(),
+            };
+            String::from_utf8_lossy(&pending[..valid]).into_owned()
+        };
+        self.text_sent += if done { pending.len() } else { text.len() };
+        self.text_done = done;
+
+        (done || !text.is_empty()).then_some((text, done))
+    }
+
+    /// Parse the assembled output of the cell `cell_name`.
+    pub fn finish(self, cell_name: &str) -> Result<BoxedOutput> {
+        let bytes = self.bytes;
+        if bytes.len() < 8 {
+            return Err(Error::Execution(format!(
+                "Cell {} output too short: {} bytes (need at least 8 for header)",
+                cell_name,
+                bytes.len()
+            )));
+        }
+
+        let display_len = u64::from_le_bytes(bytes[0..8].try_into().unwrap()) as usize;
+        let display_end = 8usize.saturating_add(display_len);
+        if bytes.len() < display_end {
+            return Err(Error::Execution(format!(
+                "Cell {} output too short for display data",
+                cell_name
+            )));
+        }
+
+        let display_text = String::from_utf8_lossy(&bytes[8..display_end]).to_string();
+        let rkyv_data = bytes[display_end..].to_vec();
+
+        Ok(BoxedOutput::from_raw_bytes_with_display(
+            rkyv_data,
+            display_text,
+        ))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -272,6 +379,20 @@ mod tests {
         assert_eq!(output, restored);
     }
 
+    #[test]
+    fn test_display_html_split() {
+        let plain = BoxedOutput::from_raw_bytes_with_display(vec![], "[1, 2]".to_string());
+        assert_eq!(plain.display_text(), Some("[1, 2]"));
+        assert_eq!(plain.display_html(), None);
+
+        let rich = BoxedOutput::from_raw_bytes_with_display(
+            vec![],
+            "[1, 2]\0<ul><li>1</li><li>2</li></ul>".to_string(),
+        );
+        assert_eq!(rich.display_text(), Some("[1, 2]"));
+        assert_eq!(rich.display_html(), Some("<ul><li>1</li><li>2</li></ul>"));
+    }
+
     #[test]
     fn test_boxed_output_type_mismatch() {
         let output = TestOutput {
@@ -305,4 +426,35 @@ mod tests {
         let restored: Vec<i32> = deserialize_output(&bytes).unwrap();
         assert_eq!(vec_val, restored);
     }
+
+    #[test]
+    fn test_output_assembler_streams_display_text() {
+        let display = "h\u{e9}llo\0<b>hi</b>";
+        let mut bytes = (display.len() as u64).to_le_bytes().to_vec();
+        bytes.extend_from_slice(display.as_bytes());
+        bytes.extend_from_slice(&[7, 8, 9]);
+
+        let mut assembler = OutputAssembler::new();
+        let pieces: Vec<_> = bytes
+            .chunks(5)
+            .filter_map(|segment| assembler.push(segment))
+            .collect();
+
+        // The é is split across segments and held back until complete
+        assert_eq!(
+            pieces,
+            vec![("h".to_string(), false), ("\u{e9}llo".to_string(), true)]
+        );
+        let output = assembler.finish("cell").unwrap();
+        assert_eq!(output.display_text(), Some("h\u{e9}llo"));
+        assert_eq!(output.display_html(), Some("<b>hi</b>"));
+        assert_eq!(output.bytes(), &[7, 8, 9]);
+    }
+
+    #[test]
+    fn test_output_assembler_rejects_truncated_output() {
+        let mut assembler = OutputAssembler::new();
+        assert_eq!(assembler.push(&10u64.to_le_bytes()), None);
+        assert!(assembler.finish("cell").is_err());
+    }
 }
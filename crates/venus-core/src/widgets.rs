@@ -84,6 +84,22 @@ impl WidgetDef {
             WidgetDef::Checkbox { id, .. } => id,
         }
     }
+
+    /// Replace the widget's current value.
+    ///
+    /// Returns `false` and leaves the widget unchanged if the value has the
+    /// wrong kind for this widget.
+    pub fn set_value(&mut self, new_value: &WidgetValue) -> bool {
+        match self {
+            WidgetDef::Slider { value, .. } => new_value.as_f64().map(|v| *value = v),
+            WidgetDef::TextInput { value, .. } => {
+                new_value.as_string().map(|v| *value = v.to_string())
+            }
+            WidgetDef::Select { selected, .. } => new_value.as_index().map(|v| *selected = v),
+            WidgetDef::Checkbox { value, .. } => new_value.as_bool().map(|v| *value = v),
+        }
+        .is_some()
+    }
 }
 
 /// Widget value that can be stored in state.
@@ -508,6 +524,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_set_value() {
+        let mut slider = WidgetDef::Slider {
+            id: "speed".to_string(),
+            label: "Speed".to_string(),
+            min: 0.0,
+            max: 100.0,
+            step: 1.0,
+            value: 50.0,
+        };
+        assert!(slider.set_value(&WidgetValue::Number(75.0)));
+        assert!(matches!(slider, WidgetDef::Slider { value, .. } if value == 75.0));
+
+        assert!(!slider.set_value(&WidgetValue::Bool(true)));
+        assert!(matches!(slider, WidgetDef::Slider { value, .. } if value == 75.0));
+
+        let mut checkbox = WidgetDef::Checkbox {
+            id: "on".to_string(),
+            label: "On".to_string(),
+            value: false,
+        };
+        assert!(checkbox.set_value(&WidgetValue::Bool(true)));
+        assert!(matches!(checkbox, WidgetDef::Checkbox { value: true, .. }));
+    }
+
     #[test]
     fn test_slider_with_existing_value() {
         let mut values = HashMap::new();
@@ -3,10 +3,13 @@
 //! This crate provides the `#[venus::cell]` attribute macro that marks functions
 //! as notebook cells. The macro is a passthrough in library mode (for `cargo build`),
 //! while the Venus runtime interprets these attributes for reactive execution.
+//!
+//! It also provides `#[derive(Render)]`, which renders a struct as a table of
+//! its fields.
 
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{ItemFn, parse_macro_input};
+use syn::{Data, DeriveInput, ItemFn, LitStr, parse_macro_input};
 
 /// Marks a function as a notebook cell.
 ///
@@ -41,6 +44,16 @@ use syn::{ItemFn, parse_macro_input};
 /// }
 /// ```
 ///
+/// The attribute also works on associated functions of an inherent `impl`
+/// block, as long as they take no `self`.
+///
+/// # Arguments
+///
+/// - `timeout_ms = N`: kill the cell if it runs longer than `N` milliseconds
+/// - `opt_level = N`: compile the cell at optimization level `N` (0-3)
+/// - `output = "json"`: display hint for the output (`text`, `json`, `csv`,
+///   `html` or `image`)
+///
 /// # Behavior
 ///
 /// In **library mode** (when compiled with `cargo build`), this attribute is a
@@ -52,34 +65,144 @@ use syn::{ItemFn, parse_macro_input};
 /// tracking and re-execution.
 #[proc_macro_attribute]
 pub fn cell(attr: TokenStream, item: TokenStream) -> TokenStream {
+    // Attribute arguments (e.g. `timeout_ms`) are read by the Venus runtime
+    // from the source file; library builds ignore them.
+    let _attr_tokens = proc_macro2::TokenStream::from(attr);
+
+    // Passthrough the function unchanged. The Venus runtime parses the
+    // source file with `syn` to extract cell metadata (dependencies, return
+    // type, doc comments), so no marker item is emitted; one would not be
+    // allowed next to an associated function in an `impl` block anyway.
     let input = parse_macro_input!(item as ItemFn);
+    TokenStream::from(quote!(#input))
+}
 
-    // Parse optional attributes (future use: priority, cache settings, etc.)
-    let _attr_tokens = proc_macro2::TokenStream::from(attr);
+/// Derives `venus::render::Render` for a struct.
+///
+/// Fields are shown in declaration order: as `label: value` lines for text,
+/// and as a two-column table for HTML. Values use the field's `Debug` output.
+/// A `Vec` of the struct renders as a table with one column per field.
+///
+/// # Field Attributes
+///
+/// - `#[render(skip)]`: leave the field out
+/// - `#[render(label = "...")]`: show the field under a different name
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use venus::prelude::*;
+///
+/// #[derive(Debug, Render)]
+/// pub struct Summary {
+///     #[render(label = "Row count")]
+///     rows: usize,
+///     mean: f64,
+///     #[render(skip)]
+///     raw: Vec<f64>,
+/// }
+/// ```
+#[proc_macro_derive(Render, attributes(render))]
+pub fn derive_render(item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    expand_render(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand_render(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "Render can only be derived for structs",
+        ));
+    };
 
-    // For now, passthrough the function unchanged.
-    // The Venus runtime will parse the source file with `syn` to extract
-    // cell metadata (dependencies, return type, doc comments).
-    //
-    // We add a hidden marker attribute that the runtime can detect,
-    // but this doesn't affect compilation.
-    let vis = &input.vis;
-    let sig = &input.sig;
-    let block = &input.block;
-    let attrs = &input.attrs;
+    let mut labels = Vec::new();
+    let mut accessors = Vec::new();
+    for (index, field) in data.fields.iter().enumerate() {
+        let mut skip = false;
+        let mut label = None;
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("render")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("skip") {
+                    skip = true;
+                    Ok(())
+                } else if meta.path.is_ident("label") {
+                    label = Some(meta.value()?.parse::<LitStr>()?.value());
+                    Ok(())
+                } else {
+                    Err(meta.error("unknown render attribute; expected `skip` or `label`"))
+                }
+            })?;
+        }
+        if skip {
+            continue;
+        }
 
-    let expanded = quote! {
-        #(#attrs)*
-        #[doc(hidden)]
-        #[allow(dead_code)]
-        const _: () = {
-            // Marker for Venus runtime detection
-            // This const is optimized away but appears in the AST
+        let member = match &field.ident {
+            Some(ident) => quote!(#ident),
+            None => {
+                let index = syn::Index::from(index);
+                quote!(#index)
+            }
         };
-        #vis #sig #block
+        labels.push(label.unwrap_or_else(|| match &field.ident {
+            Some(ident) => ident.to_string(),
+            None => index.to_string(),
+        }));
+        accessors.push(member);
+    }
+
+    let name = &input.ident;
+    let name_str = name.to_string();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    // A struct with nothing to show renders as its name
+    let text_body = if accessors.is_empty() {
+        quote!(::std::string::String::from(#name_str))
+    } else {
+        quote! {
+            let lines: ::std::vec::Vec<::std::string::String> = ::std::vec![
+                #(::std::format!("{}: {:?}", #labels, self.#accessors)),*
+            ];
+            lines.join("\n")
+        }
     };
 
-    TokenStream::from(expanded)
+    Ok(quote! {
+        impl #impl_generics ::venus::render::Render for #name #ty_generics #where_clause {
+            fn render_text(&self) -> ::std::string::String {
+                #text_body
+            }
+
+            fn render_html(&self) -> ::std::option::Option<::std::string::String> {
+                let mut html = ::std::string::String::from("<table class=\"venus-struct\">");
+                html.push_str(&::std::format!("<caption>{}</caption>", #name_str));
+                #(
+                    html.push_str(&::std::format!(
+                        "<tr><th>{}</th><td>{}</td></tr>",
+                        ::venus::render::html_escape(#labels),
+                        ::venus::render::html_escape(&::std::format!("{:?}", self.#accessors)),
+                    ));
+                )*
+                html.push_str("</table>");
+                ::std::option::Option::Some(html)
+            }
+
+            fn render_header() -> ::std::option::Option<::std::vec::Vec<::std::string::String>> {
+                ::std::option::Option::Some(::std::vec![
+                    #(::std::string::String::from(#labels)),*
+                ])
+            }
+
+            fn render_row(&self) -> ::std::option::Option<::std::vec::Vec<::std::string::String>> {
+                ::std::option::Option::Some(::std::vec![
+                    #(::venus::render::html_escape(&::std::format!("{:?}", self.#accessors))),*
+                ])
+            }
+        }
+    })
 }
 
 #[cfg(test)]
@@ -4,6 +4,8 @@ use std::path::PathBuf;
 
 use venus_core::graph::CellId;
 
+use crate::protocol::CellKind;
+
 /// Server error type.
 #[derive(Debug, thiserror::Error)]
 pub enum ServerError {
@@ -19,6 +21,14 @@ pub enum ServerError {
     #[error("Cell not found: {0:?}")]
     CellNotFound(CellId),
 
+    /// Cell exists but is of a different kind than the operation needs.
+    #[error("Cell {} is {}, not {}", .cell_id.as_usize(), .actual.describe(), .expected.describe())]
+    WrongCellKind {
+        cell_id: CellId,
+        actual: CellKind,
+        expected: CellKind,
+    },
+
     /// Execution already in progress.
     #[error("Execution already in progress")]
     ExecutionInProgress,
@@ -69,6 +79,16 @@ mod tests {
         let err = ServerError::CellNotFound(CellId::new(42));
         assert_eq!(err.to_string(), "Cell not found: CellId(42)");
 
+        let err = ServerError::WrongCellKind {
+            cell_id: CellId::new(3),
+            actual: CellKind::Code,
+            expected: CellKind::Markdown,
+        };
+        assert_eq!(
+            err.to_string(),
+            "Cell 3 is a code cell, not a markdown cell"
+        );
+
         let err = ServerError::ExecutionInProgress;
         assert_eq!(err.to_string(), "Execution already in progress");
 
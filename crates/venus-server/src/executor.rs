@@ -0,0 +1,220 @@
+//! Choice of executor for a notebook session.
+//!
+//! Sessions run cells with [`ProcessExecutor`] by default. For debugging,
+//! [`ExecutorKind::Linear`] runs them in the server process with
+//! [`LinearExecutor`] instead, so a native debugger can be attached and
+//! panics keep their full backtraces. That mode gives up interruption,
+//! timeouts, resource sampling and crash isolation: a cell that loops
+//! forever or aborts takes the server with it.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use venus_core::Result;
+use venus_core::compile::CompiledCell;
+use venus_core::execute::{
+    ExecutionProfile, ExecutorKillHandle, LinearExecutor, ProcessExecutor, ResourceMonitor,
+};
+use venus_core::graph::CellId;
+use venus_core::ipc::WorkerSpawnConfig;
+use venus_core::state::{BoxedOutput, StateManager};
+
+/// Where a session runs its cells.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExecutorKind {
+    /// In isolated worker processes that can be killed at any time.
+    #[default]
+    Process,
+    /// In the server process, without IPC. Cells cannot be interrupted and
+    /// a crashing cell brings down the server.
+    Linear,
+}
+
+impl std::str::FromStr for ExecutorKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "process" => Ok(Self::Process),
+            "linear" => Ok(Self::Linear),
+            other => Err(format!(
+                "unknown executor '{other}', expected 'process' or 'linear'"
+            )),
+        }
+    }
+}
+
+/// The executor of a session, dispatching to the configured kind.
+pub enum SessionExecutor {
+    Process(ProcessExecutor),
+    Linear(LinearExecutor),
+}
+
+impl SessionExecutor {
+    /// Create an executor of the given kind.
+    pub fn new(kind: ExecutorKind, state_dir: impl AsRef<Path>) -> Result<Self> {
+        Ok(match kind {
+            ExecutorKind::Process => Self::Process(ProcessExecutor::new(state_dir)?),
+            ExecutorKind::Linear => Self::Linear(LinearExecutor::new(state_dir)?),
+        })
+    }
+
+    /// The kind of this executor.
+    pub fn kind(&self) -> ExecutorKind {
+        match self {
+            Self::Process(_) => ExecutorKind::Process,
+            Self::Linear(_) => ExecutorKind::Linear,
+        }
+    }
+
+    /// Make a compiled cell available for execution.
+    ///
+    /// The linear executor loads the cell's library here, which can fail.
+    pub fn register_cell(&mut self, compiled: CompiledCell, dep_count: usize) -> Result<()> {
+        match self {
+            Self::Process(executor) => {
+                executor.register_cell(compiled, dep_count);
+                Ok(())
+            }
+            Self::Linear(executor) => executor.load_cell(compiled, dep_count),
+        }
+    }
+
+    /// Forget a previously registered cell.
+    pub fn unregister_cell(&mut self, cell_id: CellId) {
+        match self {
+            Self::Process(executor) => {
+                executor.unregister_cell(cell_id);
+            }
+            Self::Linear(executor) => {
+                executor.unload_cell(cell_id);
+            }
+        }
+    }
+
+    /// Execute a registered cell with the given inputs and widget values.
+    ///
+    /// Returns the cell output and any registered widget definitions as JSON.
+    pub fn execute_cell_with_widgets(
+        &mut self,
+        cell_id: CellId,
+        inputs: &[Arc<BoxedOutput>],
+        widget_values_json: Vec<u8>,
+    ) -> Result<(BoxedOutput, Vec<u8>)> {
+        match self {
+            Self::Process(executor) => {
+                executor.execute_cell_with_widgets(cell_id, inputs, widget_values_json)
+            }
+            Self::Linear(executor) => {
+                executor.execute_cell_with_widgets(cell_id, inputs, widget_values_json)
+            }
+        }
+    }
+
+    /// Execute a registered cell, streaming the display text of a large
+    /// output to `on_text` as it arrives from the worker.
+    ///
+    /// See [`ProcessExecutor::execute_cell_streamed`]. The linear executor
+    /// has no worker, so its outputs are never streamed.
+    pub fn execute_cell_streamed(
+        &mut self,
+        cell_id: CellId,
+        inputs: &[Arc<BoxedOutput>],
+        widget_values_json: Vec<u8>,
+        on_text: &mut dyn FnMut(&str, bool),
+    ) -> Result<(BoxedOutput, Vec<u8>)> {
+        match self {
+            Self::Process(executor) => {
+                executor.execute_cell_streamed(cell_id, inputs, widget_values_json, on_text)
+            }
+            Self::Linear(executor) => {
+                executor.execute_cell_with_widgets(cell_id, inputs, widget_values_json)
+            }
+        }
+    }
+
+    /// Take the phase timings of the last execution.
+    ///
+    /// Always `None` for the linear executor.
+    pub fn take_last_profile(&mut self) -> Option<ExecutionProfile> {
+        match self {
+            Self::Process(executor) => executor.take_last_profile(),
+            Self::Linear(_) => None,
+        }
+    }
+
+    /// Get a handle for killing the executing cell from another thread.
+    ///
+    /// `None` for the linear executor, whose cells cannot be killed.
+    pub fn get_kill_handle(&self) -> Option<ExecutorKillHandle> {
+        match self {
+            Self::Process(executor) => executor.get_kill_handle(),
+            Self::Linear(_) => None,
+        }
+    }
+
+    /// Get a handle for sampling the executing worker's resource usage.
+    ///
+    /// `None` for the linear executor, which has no worker to sample.
+    pub fn resource_monitor(&self) -> Option<ResourceMonitor> {
+        match self {
+            Self::Process(executor) => Some(executor.resource_monitor()),
+            Self::Linear(_) => None,
+        }
+    }
+
+    /// Abort execution and kill any running cell.
+    ///
+    /// A no-op for the linear executor: a running cell always finishes.
+    pub fn abort(&mut self) {
+        if let Self::Process(executor) = self {
+            executor.abort();
+        }
+    }
+
+    /// Get a mutable reference to the state manager.
+    pub fn state_mut(&mut self) -> &mut StateManager {
+        match self {
+            Self::Process(executor) => executor.state_mut(),
+            Self::Linear(executor) => executor.state_mut(),
+        }
+    }
+
+    /// Set the environment variables and working directory of the worker
+    /// processes. Ignored by the linear executor, whose cells share the
+    /// server's environment; the session refuses to change them in that mode.
+    pub fn set_worker_config(&mut self, config: WorkerSpawnConfig) {
+        if let Self::Process(executor) = self {
+            executor.set_worker_config(config);
+        }
+    }
+
+    /// Shut down the executor's workers, if any.
+    pub fn shutdown(&mut self) {
+        if let Self::Process(executor) = self {
+            executor.shutdown();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_executor_kind_from_str() {
+        assert_eq!("process".parse(), Ok(ExecutorKind::Process));
+        assert_eq!("linear".parse(), Ok(ExecutorKind::Linear));
+        assert!("threads".parse::<ExecutorKind>().is_err());
+        assert_eq!(ExecutorKind::default(), ExecutorKind::Process);
+    }
+
+    #[test]
+    fn test_linear_executor_has_no_kill_handle() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let executor = SessionExecutor::new(ExecutorKind::Linear, temp.path()).unwrap();
+        assert_eq!(executor.kind(), ExecutorKind::Linear);
+        assert!(executor.get_kill_handle().is_none());
+        assert!(executor.resource_monitor().is_none());
+    }
+}
@@ -1,6 +1,10 @@
 //! HTML generation for Venus notebook export.
 //!
 //! Generates standalone HTML files with embedded CSS and syntax highlighting.
+//! Used by `venus export` and by the server's `RunAndExport`.
+
+use venus_core::markdown;
+use venus_sync::NotebookMetadata;
 
 /// Cell data for HTML export.
 pub struct CellExport {
@@ -23,7 +27,16 @@ pub struct CellExport {
 }
 
 /// Generate standalone HTML from cell exports.
-pub fn generate_html(title: &str, cells: &[CellExport], dark_theme: bool) -> String {
+///
+/// The metadata title, if set, takes precedence over `title`.
+pub fn generate_html(
+    title: &str,
+    metadata: &NotebookMetadata,
+    cells: &[CellExport],
+    dark_theme: bool,
+) -> String {
+    let title = metadata.title.as_deref().unwrap_or(title);
+    let metadata_html = generate_metadata_html(metadata);
     let theme_css = if dark_theme {
         DARK_THEME_CSS
     } else {
@@ -53,6 +66,7 @@ pub fn generate_html(title: &str, cells: &[CellExport], dark_theme: bool) -> Str
             <h1 class="logo">Venus</h1>
             <span class="notebook-title">{title}</span>
         </header>
+{metadata_html}
         <main class="cells">
 {cells_html}
         </main>
@@ -95,7 +109,12 @@ fn generate_cell_html(cell: &CellExport, index: usize) -> String {
     let description_html = cell
         .description
         .as_ref()
-        .map(|d| format!(r#"<div class="cell-description">{}</div>"#, html_escape(d)))
+        .map(|d| {
+            format!(
+                r#"<div class="cell-description">{}</div>"#,
+                markdown::to_html(d)
+            )
+        })
         .unwrap_or_default();
 
     let timing_html = cell
@@ -152,6 +171,40 @@ fn generate_cell_html(cell: &CellExport, index: usize) -> String {
     )
 }
 
+/// Generate the notebook metadata block shown under the header.
+fn generate_metadata_html(metadata: &NotebookMetadata) -> String {
+    let mut details: Vec<String> = Vec::new();
+    if !metadata.authors.is_empty() {
+        details.push(html_escape(&metadata.authors.join(", ")));
+    }
+    if let Some(created) = &metadata.created {
+        details.push(html_escape(created));
+    }
+    details.extend(
+        metadata
+            .tags
+            .iter()
+            .map(|t| format!(r#"<span class="tag">{}</span>"#, html_escape(t))),
+    );
+
+    let mut html = String::new();
+    if !details.is_empty() {
+        html.push_str(&format!(
+            r#"        <div class="notebook-meta">{}</div>"#,
+            details.join(" · ")
+        ));
+        html.push('\n');
+    }
+    if let Some(description) = &metadata.description {
+        html.push_str(&format!(
+            r#"        <div class="notebook-description">{}</div>"#,
+            markdown::to_html(description)
+        ));
+        html.push('\n');
+    }
+    html
+}
+
 /// Escape HTML special characters.
 pub fn html_escape(s: &str) -> String {
     s.replace('&', "&amp;")
@@ -219,6 +272,23 @@ body {
     font-family: var(--font-mono);
 }
 
+.notebook-meta {
+    margin: -1rem 0 1rem;
+    color: var(--text-secondary);
+    font-size: 0.85rem;
+}
+
+.notebook-meta .tag {
+    padding: 0.1rem 0.4rem;
+    border: 1px solid var(--border-primary);
+    border-radius: 4px;
+}
+
+.notebook-description {
+    margin-bottom: 2rem;
+    color: var(--text-secondary);
+}
+
 .cells { display: flex; flex-direction: column; gap: 1.5rem; }
 
 .cell {
@@ -401,6 +471,23 @@ body {
     font-family: var(--font-mono);
 }
 
+.notebook-meta {
+    margin: -1rem 0 1rem;
+    color: var(--text-secondary);
+    font-size: 0.85rem;
+}
+
+.notebook-meta .tag {
+    padding: 0.1rem 0.4rem;
+    border: 1px solid var(--border-primary);
+    border-radius: 4px;
+}
+
+.notebook-description {
+    margin-bottom: 2rem;
+    color: var(--text-secondary);
+}
+
 .cells { display: flex; flex-direction: column; gap: 1.5rem; }
 
 .cell {
@@ -587,10 +674,28 @@ mod tests {
             execution_time_ms: Some(10),
         }];
 
-        let html = generate_html("Test", &cells, true);
+        let html = generate_html("Test", &NotebookMetadata::default(), &cells, true);
         assert!(html.contains("<!DOCTYPE html>"));
         assert!(html.contains("Test - Venus Notebook"));
         assert!(html.contains("test"));
         assert!(html.contains("42"));
     }
+
+    #[test]
+    fn test_generate_html_with_metadata() {
+        let metadata = NotebookMetadata {
+            title: Some("Sales <Q3>".to_string()),
+            authors: vec!["Ada".to_string()],
+            tags: vec!["sales".to_string()],
+            ..Default::default()
+        };
+
+        let html = generate_html("notebook", &metadata, &[], false);
+        assert!(html.contains("Sales &lt;Q3&gt; - Venus Notebook"));
+        assert!(
+            html.contains(
+                r#"<div class="notebook-meta">Ada · <span class="tag">sales</span></div>"#
+            )
+        );
+    }
 }
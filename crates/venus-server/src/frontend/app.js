@@ -7,6 +7,9 @@
 // Configure Monaco editor loader
 require.config({ paths: { 'vs': 'https://cdn.jsdelivr.net/npm/monaco-editor@0.45.0/min/vs' }});
 
+// WebSocket protocol version spoken by this frontend (must match the server)
+const PROTOCOL_VERSION = 1;
+
 // Global state
 const state = {
     ws: null,
@@ -30,6 +33,12 @@ const state = {
     canRedo: false,
     undoDescription: null,
     redoDescription: null,
+    otherClients: 0,  // Other connections viewing this notebook
+    clientId: null,  // This connection's ID, from the welcome message
+    reactiveCells: new Set(),  // Cells that re-run when a widget changes
+    cellLocks: new Map(),  // Map<cellId, clientId> of locks held by any client
+    streamedOutputs: new Map(),  // Map<cellId, Array<string>> of output chunks received so far
+    breakpoints: new Set(),  // Cell IDs that pause a run after executing
 };
 
 // DOM Elements
@@ -131,6 +140,7 @@ function connect() {
         state.reconnectAttempts = 0;
         updateConnectionStatus('connected');
         console.log('WebSocket connected');
+        send({ type: 'hello', protocol_version: PROTOCOL_VERSION });
     };
 
     state.ws.onmessage = (event) => {
@@ -144,6 +154,7 @@ function connect() {
 
     state.ws.onclose = () => {
         state.connected = false;
+        state.cellLocks.clear();  // Resent by the server on reconnect
         updateConnectionStatus('disconnected');
         console.log('WebSocket disconnected');
         scheduleReconnect();
@@ -180,7 +191,9 @@ function updateConnectionStatus(status) {
     const statusText = elements.connectionStatus.querySelector('.status-text');
     switch (status) {
         case 'connected':
-            statusText.textContent = 'Connected';
+            statusText.textContent = state.otherClients > 0
+                ? `Connected · ${state.otherClients} other${state.otherClients === 1 ? '' : 's'} viewing`
+                : 'Connected';
             break;
         case 'disconnected':
             statusText.textContent = 'Reconnecting...';
@@ -196,12 +209,42 @@ function updateConnectionStatus(status) {
 
 function handleServerMessage(msg) {
     switch (msg.type) {
+        case 'welcome':
+            if (!msg.accepted) {
+                showToast(`Server speaks protocol v${msg.protocol_version}, this page v${PROTOCOL_VERSION}. Please refresh.`, 'error');
+            }
+            state.clientId = msg.client_id;
+            break;
+        case 'resync':
+            send({ type: 'resync' });
+            break;
+        case 'cell_locked':
+            handleCellLocked(msg);
+            break;
+        case 'widget_reactive':
+            handleWidgetReactive(msg);
+            break;
+        case 'presence':
+            state.otherClients = Math.max(0, msg.clients.length - 1);
+            if (state.connected) {
+                updateConnectionStatus('connected');
+            }
+            break;
         case 'notebook_state':
             handleNotebookState(msg);
             break;
         case 'cell_started':
             handleCellStarted(msg);
             break;
+        case 'cell_output_appended':
+            handleCellOutputAppended(msg);
+            break;
+        case 'cell_executing':
+            handleCellExecuting(msg);
+            break;
+        case 'resource_usage':
+            handleResourceUsage(msg);
+            break;
         case 'cell_completed':
             handleCellCompleted(msg);
             break;
@@ -211,9 +254,33 @@ function handleServerMessage(msg) {
         case 'cell_error':
             handleCellError(msg);
             break;
+        case 'cell_panic':
+            handleCellPanic(msg);
+            break;
+        case 'cell_output_stale':
+            handleCellOutputStale(msg);
+            break;
         case 'compile_error':
             handleCompileError(msg);
             break;
+        case 'paused': {
+            const cell = state.cells.get(msg.at);
+            const next = msg.next != null ? state.cells.get(msg.next) : null;
+            const nextText = next ? `, next: ${next.name}` : '';
+            showToast(`Paused after ${cell ? cell.name : 'breakpoint'}${nextText}`, 'info');
+            break;
+        }
+        case 'breakpoints':
+            state.breakpoints = new Set(msg.cell_ids);
+            break;
+        case 'export_ready':
+            handleExportReady(msg);
+            break;
+        case 'compile_output':
+            if (msg.errors.length > 0) {
+                handleCompileError(msg);
+            }
+            break;
         // Graph hidden (plotr in development)
         // case 'graph_updated':
         //     handleGraphUpdated(msg);
@@ -239,6 +306,25 @@ function handleServerMessage(msg) {
         case 'markdown_cell_deleted':
             handleMarkdownCellDeleted(msg);
             break;
+        case 'cell_converted':
+            if (msg.error) {
+                showToast(`Failed to convert cell: ${msg.error}`, 'error');
+            }
+            break;
+        case 'notebook_reordered':
+            if (msg.error) {
+                showToast(`Failed to reorder notebook: ${msg.error}`, 'error');
+            } else if (msg.applied) {
+                showToast(`Moved ${msg.moved} cell(s) into execution order`, 'success');
+            }
+            break;
+        case 'orphan_outputs':
+            if (msg.removed) {
+                showToast(`Removed ${msg.names.length} orphaned output(s)`, 'success');
+            } else if (msg.names.length > 0) {
+                showToast(`Orphaned outputs: ${msg.names.join(', ')}`, 'warning');
+            }
+            break;
         case 'markdown_cell_moved':
             handleMarkdownCellMoved(msg);
             break;
@@ -248,6 +334,11 @@ function handleServerMessage(msg) {
         case 'definition_cell_edited':
             handleDefinitionCellEdited(msg);
             break;
+        case 'unparsed_region_edited':
+            if (msg.error) {
+                showToast(`Failed to save: ${msg.error}`, 'error');
+            }
+            break;
         case 'definition_cell_deleted':
             handleDefinitionCellDeleted(msg);
             break;
@@ -266,18 +357,39 @@ function handleServerMessage(msg) {
         case 'file_changed':
             handleFileChanged(msg);
             break;
+        case 'warning':
+            showToast(msg.message, 'warning');
+            break;
+        case 'dependencies_changed_on_disk':
+            showToast('Cargo dependencies changed on disk - rebuild the universe to apply them', 'warning');
+            break;
         case 'sync_completed':
             handleSyncCompleted(msg);
             break;
         case 'execution_aborted':
             handleExecutionAborted(msg);
             break;
+        case 'compile_aborted':
+            handleCompileAborted(msg);
+            break;
+        case 'lsp_ready':
+            handleLspReady();
+            break;
+        case 'lsp_restarted':
+            handleLspRestarted();
+            break;
         case 'history_selected':
             handleHistorySelected(msg);
             break;
         case 'kernel_restarted':
             handleKernelRestarted(msg);
             break;
+        case 'parse_error':
+            handleParseError(msg);
+            break;
+        case 'universe_reloaded':
+            handleUniverseReloaded(msg);
+            break;
         case 'outputs_cleared':
             handleOutputsCleared(msg);
             break;
@@ -313,6 +425,17 @@ function handleNotebookState(msg) {
     updateCellCount();
     renderVariableExplorer();
 
+    // Show cells that were already compiling or running when we connected
+    const inFlight = msg.in_flight || [];
+    inFlight.forEach(flight => {
+        state.executing = true;
+        state.runningCellId = flight.cell_id;
+        handleCellExecuting(flight);
+    });
+    if (inFlight.length > 0) {
+        updateExecutionUI();
+    }
+
     // Graph hidden (plotr in development)
     // if (state.graphVisible && typeof renderGraph === 'function') {
     //     renderGraph(state.cells, msg.execution_order);
@@ -331,20 +454,65 @@ function handleCellStarted(msg) {
     }
 }
 
+function handleCellExecuting(msg) {
+    const cell = state.cells.get(msg.cell_id);
+    const statusEl = document.querySelector(`#cell-${msg.cell_id} .cell-status`);
+    if (cell && cell.status === 'running' && statusEl) {
+        const seconds = Math.floor(msg.elapsed_ms / 1000);
+        statusEl.innerHTML = `<div class="spinner"></div> Running ${seconds}s`;
+    }
+}
+
+function handleResourceUsage(msg) {
+    const statusEl = document.querySelector(`#cell-${msg.cell_id} .cell-status`);
+    if (statusEl) {
+        const memoryMb = (msg.rss_bytes / (1024 * 1024)).toFixed(1);
+        const cpuSeconds = (msg.cpu_ms / 1000).toFixed(1);
+        statusEl.title = `Memory: ${memoryMb} MB, CPU: ${cpuSeconds} s`;
+    }
+}
+
+function handleCellOutputAppended(msg) {
+    const chunks = state.streamedOutputs.get(msg.cell_id) || [];
+    if (msg.seq === 0) {
+        chunks.length = 0;
+    }
+    chunks.push(msg.chunk);
+    state.streamedOutputs.set(msg.cell_id, chunks);
+
+    // Show the output as it arrives
+    const cell = state.cells.get(msg.cell_id);
+    if (cell) {
+        cell.output = { ...(cell.output || {}), text: chunks.join('') };
+        updateCellOutput(msg.cell_id);
+    }
+}
+
 function handleCellCompleted(msg) {
+    // Large output text was streamed ahead of the completion
+    const streamed = state.streamedOutputs.get(msg.cell_id);
+    if (streamed) {
+        state.streamedOutputs.delete(msg.cell_id);
+        if (msg.output && msg.output.text == null) {
+            msg.output.text = streamed.join('');
+        }
+    }
+
     const cell = state.cells.get(msg.cell_id);
     if (cell) {
         cell.status = 'success';
         cell.output = msg.output;
         cell.dirty = false;
 
-        // Add to execution history
-        addToHistory(msg.cell_id, {
-            output: msg.output,
-            error: null,
-            duration: msg.duration_ms,
-            source: cell.source,
-        });
+        // Add to execution history (a cached result repeats the previous run)
+        if (!msg.from_cache) {
+            addToHistory(msg.cell_id, {
+                output: msg.output,
+                error: null,
+                duration: msg.duration_ms,
+                source: cell.source,
+            });
+        }
 
         // Clear execution state if this was the running cell
         if (state.runningCellId === msg.cell_id) {
@@ -359,7 +527,7 @@ function handleCellCompleted(msg) {
         updateHistoryControls(msg.cell_id);
 
         if (msg.duration_ms !== undefined) {
-            updateCellTiming(msg.cell_id, msg.duration_ms);
+            updateCellTiming(msg.cell_id, msg.duration_ms, msg.from_cache);
         }
     }
 }
@@ -372,6 +540,14 @@ function handleCellDirty(msg) {
     }
 }
 
+function handleCellOutputStale(msg) {
+    const cell = state.cells.get(msg.cell_id);
+    if (cell && cell.output) {
+        cell.output.stale = true;
+        updateCellOutput(msg.cell_id);
+    }
+}
+
 function handleCellError(msg) {
     const cell = state.cells.get(msg.cell_id);
     if (cell) {
@@ -400,6 +576,22 @@ function handleCellError(msg) {
     }
 }
 
+function handleCellPanic(msg) {
+    // Show the backtrace, when captured, below the panic message
+    const error = msg.backtrace ? `${msg.message}\n\nBacktrace:\n${msg.backtrace}` : msg.message;
+    handleCellError({ cell_id: msg.cell_id, error, location: null });
+}
+
+function handleExportReady(msg) {
+    const url = URL.createObjectURL(new Blob([msg.html], { type: 'text/html' }));
+    const link = document.createElement('a');
+    link.href = url;
+    link.download = 'notebook.html';
+    link.click();
+    URL.revokeObjectURL(url);
+    showToast('Report exported', 'info');
+}
+
 function handleCompileError(msg) {
     const cell = state.cells.get(msg.cell_id);
     if (cell) {
@@ -579,6 +771,30 @@ function handleSyncCompleted(msg) {
     showToast(`Exported to ${msg.ipynb_path}`, 'success');
 }
 
+function handleCompileAborted(msg) {
+    if (msg.cell_id !== null) {
+        const cell = state.cells.get(msg.cell_id);
+        if (cell) {
+            cell.status = 'idle';
+            updateCellStatus(msg.cell_id);
+        }
+    }
+    showToast('Compilation aborted', 'info');
+}
+
+function handleLspReady() {
+    if (!lspState.ready) {
+        lspState.ready = true;
+        showToast('Language server ready', 'success');
+    }
+}
+
+function handleLspRestarted() {
+    lspState.ready = false;
+    // The server reopens our documents in the new process
+    showToast('Language server restarted', 'warning');
+}
+
 function handleExecutionAborted(msg) {
     // Reset the interrupted cell status and show interrupted message
     const cellId = msg.cell_id !== undefined ? msg.cell_id : state.runningCellId;
@@ -594,7 +810,8 @@ function handleExecutionAborted(msg) {
             const outputEl = document.getElementById(`output-${cellId}`);
             if (outputEl) {
                 outputEl.style.display = 'block';
-                outputEl.innerHTML = '<div class="output-interrupted">Execution interrupted</div>';
+                const note = msg.reason ? `Execution interrupted: ${escapeHtml(msg.reason)}` : 'Execution interrupted';
+                outputEl.innerHTML = `<div class="output-interrupted">${note}</div>`;
             }
         }
     }
@@ -613,6 +830,19 @@ function handleKernelRestarted(msg) {
     // NotebookState message will follow and update the UI
 }
 
+function handleParseError(msg) {
+    showToast(`Syntax error at line ${msg.line}, column ${msg.column}: ${msg.message}`, 'error');
+}
+
+function handleUniverseReloaded(msg) {
+    if (msg.error) {
+        showToast(`Universe rebuild failed: ${msg.error}`, 'error');
+    } else {
+        showToast('Universe rebuilt', 'success');
+    }
+    // NotebookState message will follow and update the UI
+}
+
 function handleOutputsCleared(msg) {
     if (msg.error) {
         showToast(`Clear outputs failed: ${msg.error}`, 'error');
@@ -673,7 +903,7 @@ function updateExecutionUI() {
         `;
         runAllBtn.classList.remove('btn-danger');
         runAllBtn.classList.add('btn-primary');
-        runAllBtn.onclick = executeAll;
+        runAllBtn.onclick = (e) => executeAll(e.shiftKey);
     }
 }
 
@@ -728,16 +958,39 @@ function createCellElement(cell) {
     // Route to appropriate renderer based on cell type
     if (cell.cell_type === 'markdown') {
         return createMarkdownCellElement(cell);
+    } else if (cell.cell_type === 'unparsed') {
+        return createUnparsedCellElement(cell);
     } else {
         // Both code and definition cells use the same component
         return createCodeCellElement(cell);
     }
 }
 
+function createUnparsedCellElement(cell) {
+    const div = document.createElement('div');
+    div.className = 'cell cell-unparsed';
+    div.id = `cell-${cell.id}`;
+    div.dataset.cellId = cell.id;
+    div.dataset.cellType = 'unparsed';
+
+    div.innerHTML = `
+        <div class="unparsed-cell-header">Syntax error at line ${cell.line}: ${escapeHtml(cell.message)}</div>
+        <textarea class="unparsed-cell-source" spellcheck="false">${escapeHtml(cell.content)}</textarea>
+        <div class="unparsed-cell-actions">
+            <button class="btn btn-sm btn-primary" data-cell-id="${cell.id}" data-action="save-unparsed">Save</button>
+        </div>
+    `;
+    const textarea = div.querySelector('.unparsed-cell-source');
+    textarea.rows = Math.max(cell.content.split('\n').length, 2);
+
+    return div;
+}
+
 function createMarkdownCellElement(cell) {
     const div = document.createElement('div');
     div.className = 'cell cell-markdown';
     div.id = `cell-${cell.id}`;
+    applyCellLock(div, cell.id);
     div.dataset.cellId = cell.id;
     div.dataset.cellType = 'markdown';
 
@@ -803,6 +1056,7 @@ function createCodeCellElement(cell) {
     const dirtyClass = !isDefinition && cell.dirty ? ' dirty' : '';
     div.className = `cell ${isDefinition ? 'cell-definition' : cell.status}${dirtyClass}`;
     div.id = `cell-${cell.id}`;
+    applyCellLock(div, cell.id);
     div.dataset.cellId = cell.id;
     div.dataset.cellType = cell.cell_type || 'code';
 
@@ -836,6 +1090,15 @@ function createCodeCellElement(cell) {
                ${ICONS.play}
            </button>`;
 
+    // Run from here (code cells only)
+    const runFromButton = !isDefinition
+        ? `<button class="btn btn-icon btn-run-from" data-cell-id="${cell.id}" data-action="run-from" title="Run from here">
+               <svg width="16" height="16" viewBox="0 0 24 24" fill="currentColor">
+                   <path d="M4 18l8.5-6L4 6v12zm9-12v12l8.5-6L13 6z"/>
+               </svg>
+           </button>`
+        : '';
+
     // Display name
     const displayName = isDefinition
         ? (cell.definition_type || 'definition').replace('_', ' ').toUpperCase()
@@ -857,6 +1120,7 @@ function createCodeCellElement(cell) {
                 <span class="cell-timing" id="timing-${cell.id}"></span>
                 ${statusHtml}
                 ${actionButton}
+                ${runFromButton}
                 <button class="btn btn-icon btn-insert" data-cell-id="${cell.id}" data-action="insert-cell" title="Insert cell below">
                     <svg width="16" height="16" viewBox="0 0 24 24" fill="currentColor">
                         <path d="M19 13h-6v6h-2v-6H5v-2h6V5h2v6h6v2z"/>
@@ -867,6 +1131,11 @@ function createCodeCellElement(cell) {
                         <path d="M16 1H4c-1.1 0-2 .9-2 2v14h2V3h12V1zm3 4H8c-1.1 0-2 .9-2 2v14c0 1.1.9 2 2 2h11c1.1 0 2-.9 2-2V7c0-1.1-.9-2-2-2zm0 16H8V7h11v14z"/>
                     </svg>
                 </button>
+                <button class="btn btn-icon btn-clear-output" data-cell-id="${cell.id}" data-action="clear-cell-output" title="Clear output">
+                    <svg width="16" height="16" viewBox="0 0 24 24" fill="currentColor">
+                        <path d="M19 6.41L17.59 5 12 10.59 6.41 5 5 6.41 10.59 12 5 17.59 6.41 19 12 13.41 17.59 19 19 17.59 13.41 12z"/>
+                    </svg>
+                </button>
                 <button class="btn btn-icon btn-move" data-cell-id="${cell.id}" data-action="move-cell-up" title="Move cell up">
                     <svg width="16" height="16" viewBox="0 0 24 24" fill="currentColor">
                         <path d="M7.41 15.41L12 10.83l4.59 4.58L18 14l-6-6-6 6z"/>
@@ -1051,8 +1320,8 @@ function updateCellStatus(cellId) {
                     <path d="M8 5v14l11-7z"/>
                 </svg>
             `;
-            runBtn.onclick = () => executeCell(cellId);
-            runBtn.title = 'Run Cell';
+            runBtn.onclick = (e) => executeCell(cellId, e.shiftKey);
+            runBtn.title = 'Run Cell (Shift+click to bypass the cache)';
             runBtn.classList.remove('btn-danger');
         }
     }
@@ -1063,14 +1332,14 @@ function updateCellStatus(cellId) {
     // }
 }
 
-function updateCellTiming(cellId, durationMs) {
+function updateCellTiming(cellId, durationMs, fromCache = false) {
     const timingEl = document.getElementById(`timing-${cellId}`);
     if (timingEl) {
-        if (durationMs < 1000) {
-            timingEl.textContent = `${durationMs}ms`;
-        } else {
-            timingEl.textContent = `${(durationMs / 1000).toFixed(2)}s`;
-        }
+        const duration = durationMs < 1000
+            ? `${durationMs}ms`
+            : `${(durationMs / 1000).toFixed(2)}s`;
+        timingEl.textContent = fromCache ? `cached (${duration})` : duration;
+        timingEl.title = fromCache ? 'Inputs unchanged; output reused from a previous run' : '';
     }
 }
 
@@ -1088,7 +1357,19 @@ function renderWidgets(cellId, widgets) {
     if (!widgets || widgets.length === 0) return '';
 
     const widgetsHtml = widgets.map(widget => renderWidget(cellId, widget)).join('');
-    return `<div class="cell-widgets">${widgetsHtml}</div>`;
+    const reactive = state.reactiveCells.has(cellId);
+    return `
+        <div class="cell-widgets">
+            ${widgetsHtml}
+            <label class="widget-reactive-toggle" title="Re-run this cell and its dependents when a widget changes">
+                <input type="checkbox"
+                    class="widget-reactive-input"
+                    data-cell-id="${cellId}"
+                    ${reactive ? 'checked' : ''}>
+                <span>Re-run on change</span>
+            </label>
+        </div>
+    `;
 }
 
 /**
@@ -1226,9 +1507,15 @@ function updateCellOutput(cellId) {
     } else if (output.image) {
         contentType = 'image';
         content = `<img src="data:image/png;base64,${output.image}" alt="Cell output">`;
+    } else if (output.json && cell.output_format === 'json') {
+        contentType = 'json';
+        content = renderJsonTree(output.json);
     } else if (output.json) {
         contentType = 'text';
         content = JSON.stringify(output.json, null, 2);
+    } else if (output.text && cell.output_format === 'csv') {
+        contentType = 'html';
+        content = renderCsvTable(output.text);
     } else if (output.text) {
         contentType = 'text';
         content = escapeHtml(output.text);
@@ -1238,11 +1525,11 @@ function updateCellOutput(cellId) {
     const widgetsHtml = renderWidgets(cellId, output.widgets);
 
     // Add re-run button for all outputs (useful to re-run without scrolling to top)
-    const rerunBtn = `<button class="output-rerun-btn" onclick="executeCell(${cellId})" title="Re-run cell">${ICONS.play}</button>`;
+    const rerunBtn = `<button class="output-rerun-btn" onclick="executeCell(${cellId}, event.shiftKey)" title="Re-run cell (Shift+click to bypass the cache)">${ICONS.play}</button>`;
 
     outputEl.innerHTML = `
         <div class="cell-output-header">
-            <span>Output</span>
+            <span>Output${output.stale ? ' (stale: last run failed)' : ''}</span>
             <div class="output-header-controls">
                 ${rerunBtn}
                 ${renderHistoryControls(cellId)}
@@ -1257,6 +1544,57 @@ function updateCellOutput(cellId) {
     updateHistoryControls(cellId);
 }
 
+/**
+ * Render JSON as a collapsible tree, for cells with an `output = "json"` hint.
+ */
+function renderJsonTree(value, key = null) {
+    const label = key === null ? '' : `<span class="json-key">${escapeHtml(String(key))}</span>: `;
+
+    if (value === null || typeof value !== 'object') {
+        return `<div class="json-leaf">${label}<span class="json-${value === null ? 'null' : typeof value}">${escapeHtml(JSON.stringify(value))}</span></div>`;
+    }
+
+    const entries = Array.isArray(value) ? value.map((v, i) => [i, v]) : Object.entries(value);
+    const summary = Array.isArray(value) ? `[${entries.length}]` : `{${entries.length}}`;
+    const children = entries.map(([k, v]) => renderJsonTree(v, k)).join('');
+    return `<details class="json-node" open><summary>${label}${summary}</summary>${children}</details>`;
+}
+
+/**
+ * Render comma-separated text as a table, for cells with an `output = "csv"`
+ * hint. The first line is the header. Quoted fields may contain commas.
+ */
+function renderCsvTable(text) {
+    const parseLine = (line) => {
+        const fields = [];
+        let field = '';
+        let quoted = false;
+        for (let i = 0; i < line.length; i++) {
+            const ch = line[i];
+            if (quoted && ch === '"' && line[i + 1] === '"') {
+                field += '"';
+                i++;
+            } else if (ch === '"') {
+                quoted = !quoted;
+            } else if (ch === ',' && !quoted) {
+                fields.push(field);
+                field = '';
+            } else {
+                field += ch;
+            }
+        }
+        fields.push(field);
+        return fields;
+    };
+
+    const [header, ...rows] = text.split(/\r?\n/).filter(line => line.length > 0).map(parseLine);
+    if (!header) return '';
+
+    const cells = (fields, tag) => fields.map(f => `<${tag}>${escapeHtml(f)}</${tag}>`).join('');
+    const body = rows.map(row => `<tr>${cells(row, 'td')}</tr>`).join('');
+    return `<table><thead><tr>${cells(header, 'th')}</tr></thead><tbody>${body}</tbody></table>`;
+}
+
 function updateCellError(cellId) {
     const cell = state.cells.get(cellId);
     if (!cell || !cell.error) return;
@@ -1315,18 +1653,29 @@ function updateCellCount() {
 // Actions
 // =====================================
 
-function executeCell(cellId) {
+// Run buttons re-execute past the output cache when Shift-clicked, so
+// nondeterministic or side-effecting cells can be run again
+function executeCell(cellId, force = false) {
     // Get the editor content and send it to server before executing
     const editor = state.editors.get(cellId);
     if (editor) {
         const source = editor.getValue();
         send({ type: 'cell_edit', cell_id: cellId, source: source });
     }
-    send({ type: 'execute_cell', cell_id: cellId });
+    send({ type: 'execute_cell', cell_id: cellId, force });
 }
 
-function executeAll() {
-    send({ type: 'execute_all' });
+function executeAll(force = false) {
+    send({ type: 'execute_all', force });
+}
+
+function runFrom(cellId) {
+    // Send pending editor content first, as for a single-cell run
+    const editor = state.editors.get(cellId);
+    if (editor) {
+        send({ type: 'cell_edit', cell_id: cellId, source: editor.getValue() });
+    }
+    send({ type: 'run_from', cell_id: cellId });
 }
 
 function interruptExecution() {
@@ -1423,6 +1772,10 @@ function duplicateCell(cellId) {
     send({ type: 'duplicate_cell', cell_id: cellId });
 }
 
+function clearCellOutput(cellId) {
+    send({ type: 'clear_cell_output', cell_id: cellId });
+}
+
 function moveCellUp(cellId) {
     send({ type: 'move_cell', cell_id: cellId, direction: 'up' });
 }
@@ -2055,6 +2408,43 @@ function escapeHtml(text) {
     return div.innerHTML;
 }
 
+function handleCellLocked(msg) {
+    if (msg.client_id === null) {
+        state.cellLocks.delete(msg.cell_id);
+    } else {
+        state.cellLocks.set(msg.cell_id, msg.client_id);
+    }
+
+    const cellEl = document.getElementById(`cell-${msg.cell_id}`);
+    if (cellEl) {
+        applyCellLock(cellEl, msg.cell_id);
+    }
+}
+
+function handleWidgetReactive(msg) {
+    if (msg.enabled) {
+        state.reactiveCells.add(msg.cell_id);
+    } else {
+        state.reactiveCells.delete(msg.cell_id);
+    }
+
+    document
+        .querySelectorAll(`.widget-reactive-input[data-cell-id="${msg.cell_id}"]`)
+        .forEach(input => { input.checked = msg.enabled; });
+}
+
+/** Mark a cell element as locked when another client holds its lock. */
+function applyCellLock(cellEl, cellId) {
+    const holder = state.cellLocks.get(cellId);
+    const lockedByOther = holder !== undefined && holder !== state.clientId;
+    cellEl.classList.toggle('locked', lockedByOther);
+    if (lockedByOther) {
+        cellEl.title = `Being edited by client ${holder}`;
+    } else {
+        cellEl.removeAttribute('title');
+    }
+}
+
 function showToast(message, type = 'info') {
     const toast = document.createElement('div');
     toast.className = `toast ${type}`;
@@ -2098,8 +2488,16 @@ document.addEventListener('click', (e) => {
 
     switch (action) {
         case 'run-cell':
-            executeCell(cellId);
+            executeCell(cellId, e.shiftKey);
+            break;
+        case 'run-from':
+            runFrom(cellId);
+            break;
+        case 'save-unparsed': {
+            const source = document.querySelector(`#cell-${cellId} .unparsed-cell-source`);
+            send({ type: 'edit_unparsed_region', cell_id: cellId, new_content: source.value });
             break;
+        }
         case 'insert-cell':
             insertCellAfter(cellId);
             break;
@@ -2112,6 +2510,9 @@ document.addEventListener('click', (e) => {
         case 'duplicate-cell':
             duplicateCell(cellId);
             break;
+        case 'clear-cell-output':
+            clearCellOutput(cellId);
+            break;
         case 'move-cell-up':
             moveCellUp(cellId);
             break;
@@ -2234,6 +2635,14 @@ document.addEventListener('input', (e) => {
 // Widget event delegation for change events (text, select, checkbox)
 document.addEventListener('change', (e) => {
     const target = e.target;
+    if (target.classList.contains('widget-reactive-input')) {
+        send({
+            type: 'set_widget_reactive',
+            cell_id: parseInt(target.dataset.cellId, 10),
+            enabled: target.checked
+        });
+        return;
+    }
     if (!target.dataset.widgetType) return;
 
     const cellId = parseInt(target.dataset.cellId, 10);
@@ -9,6 +9,7 @@ const lspState = {
     ws: null,
     connected: false,
     initialized: false,
+    ready: false,
     pendingRequests: new Map(),
     requestId: 0,
     capabilities: null,
@@ -31,6 +32,9 @@ function connectLsp() {
     lspState.ws.onopen = () => {
         lspState.connected = true;
         console.log('[LSP] WebSocket connected');
+        if (!lspState.ready && typeof showToast === 'function') {
+            showToast('Language server starting…', 'info');
+        }
         initializeLsp();
     };
 
@@ -46,6 +50,8 @@ function connectLsp() {
     lspState.ws.onclose = () => {
         lspState.connected = false;
         lspState.initialized = false;
+        // A reconnect starts a fresh rust-analyzer that has to index again
+        lspState.ready = false;
         console.log('LSP WebSocket disconnected');
         // Try to reconnect after delay
         setTimeout(connectLsp, 5000);
@@ -227,6 +233,10 @@ async function initializeLsp() {
                         relatedInformation: true,
                     },
                 },
+                experimental: {
+                    // Lets the server report when indexing is done (LspReady)
+                    serverStatusNotification: true,
+                },
             },
             // Set the universe directory as the workspace root
             // This makes rust-analyzer treat it as a standalone workspace
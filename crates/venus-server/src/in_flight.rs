@@ -0,0 +1,186 @@
+//! Cells being compiled or run, readable without the session lock.
+//!
+//! Compiling and running a cell holds the session's write lock until the
+//! cell finishes, so a client connecting meanwhile cannot read the notebook
+//! state from the session. The session records the cells in flight here,
+//! together with the last notebook state it produced and the status changes
+//! since, so that client is sent that state, patched with the new statuses
+//! and the cells' elapsed time, instead of waiting for the cell.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use venus_core::graph::CellId;
+
+use crate::protocol::{CellState, CellStatus, InFlightCell, ServerMessage};
+
+/// Shared registry of the cells being compiled or run.
+///
+/// Clones share the same registry.
+#[derive(Debug, Clone, Default)]
+pub struct InFlightCells {
+    inner: Arc<Mutex<Inner>>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    /// Status of each cell in flight and when it entered that status.
+    cells: HashMap<CellId, (CellStatus, Instant)>,
+    /// Last `NotebookState` produced by the session.
+    state: Option<ServerMessage>,
+    /// Cell statuses set since `state` was recorded.
+    statuses: HashMap<CellId, CellStatus>,
+}
+
+impl InFlightCells {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a cell's new status.
+    ///
+    /// `Compiling` and `Running` put the cell in flight, restarting its
+    /// timer; any other status takes it out.
+    pub fn set_status(&self, cell_id: CellId, status: CellStatus) {
+        let mut inner = self.lock();
+        inner.statuses.insert(cell_id, status);
+        match status {
+            CellStatus::Compiling | CellStatus::Running => {
+                inner.cells.insert(cell_id, (status, Instant::now()));
+            }
+            CellStatus::Idle | CellStatus::Success | CellStatus::Error => {
+                inner.cells.remove(&cell_id);
+            }
+        }
+    }
+
+    /// Take every cell out of flight.
+    pub fn clear(&self) {
+        self.lock().cells.clear();
+    }
+
+    /// Whether no cell is in flight.
+    pub fn is_empty(&self) -> bool {
+        self.lock().cells.is_empty()
+    }
+
+    /// The cells in flight, with the time since they entered their status.
+    pub fn cells(&self) -> Vec<InFlightCell> {
+        let mut cells: Vec<_> = self
+            .lock()
+            .cells
+            .iter()
+            .map(|(&cell_id, &(status, since))| InFlightCell {
+                cell_id,
+                status,
+                elapsed_ms: since.elapsed().as_millis() as u64,
+            })
+            .collect();
+        cells.sort_by_key(|cell| cell.cell_id.as_usize());
+        cells
+    }
+
+    /// Remember the last `NotebookState` produced by the session.
+    pub fn record_state(&self, state: &ServerMessage) {
+        if matches!(state, ServerMessage::NotebookState { .. }) {
+            let mut inner = self.lock();
+            inner.state = Some(state.clone());
+            inner.statuses.clear();
+        }
+    }
+
+    /// The last recorded `NotebookState`, with the cell statuses and the
+    /// elapsed times of the cells in flight brought up to date.
+    pub fn snapshot(&self) -> Option<ServerMessage> {
+        let current = self.cells();
+        let inner = self.lock();
+        let mut state = inner.state.clone()?;
+        if let ServerMessage::NotebookState {
+            cells, in_flight, ..
+        } = &mut state
+        {
+            for cell in cells {
+                if let CellState::Code { id, status, .. } = cell
+                    && let Some(new_status) = inner.statuses.get(id)
+                {
+                    *status = *new_status;
+                }
+            }
+            *in_flight = current;
+        }
+        Some(state)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cells_leave_flight_when_done() {
+        let in_flight = InFlightCells::new();
+        let cell = CellId::new(1);
+
+        in_flight.set_status(cell, CellStatus::Compiling);
+        in_flight.set_status(cell, CellStatus::Running);
+        let cells = in_flight.cells();
+        assert_eq!(cells.len(), 1);
+        assert_eq!(cells[0].status, CellStatus::Running);
+
+        in_flight.set_status(cell, CellStatus::Success);
+        assert!(in_flight.is_empty());
+    }
+
+    #[test]
+    fn test_snapshot_reports_cells_in_flight() {
+        let in_flight = InFlightCells::new();
+        assert!(in_flight.snapshot().is_none());
+
+        in_flight.record_state(&ServerMessage::NotebookState {
+            path: "notebook.rs".to_string(),
+            cells: vec![CellState::Code {
+                id: CellId::new(2),
+                name: "total".to_string(),
+                display_name: "total".to_string(),
+                source: String::new(),
+                description: None,
+                return_type: "i32".to_string(),
+                dependencies: Vec::new(),
+                output_format: None,
+                status: CellStatus::Idle,
+                output: None,
+                dirty: false,
+            }],
+            source_order: Vec::new(),
+            execution_order: Vec::new(),
+            workspace_root: None,
+            cargo_toml_path: None,
+            metadata: Default::default(),
+            in_flight: Vec::new(),
+        });
+        in_flight.set_status(CellId::new(2), CellStatus::Compiling);
+
+        let Some(ServerMessage::NotebookState {
+            cells, in_flight, ..
+        }) = in_flight.snapshot()
+        else {
+            panic!("expected a notebook state");
+        };
+        assert!(matches!(
+            cells[0],
+            CellState::Code {
+                status: CellStatus::Compiling,
+                ..
+            }
+        ));
+        assert_eq!(in_flight.len(), 1);
+        assert_eq!(in_flight[0].cell_id, CellId::new(2));
+        assert_eq!(in_flight[0].status, CellStatus::Compiling);
+    }
+}
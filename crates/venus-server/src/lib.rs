@@ -9,6 +9,7 @@
 //! - **Protocol**: Defines client/server message types
 //! - **Routes**: HTTP and WebSocket handlers
 //! - **Watcher**: File system monitoring for external changes
+//! - **Settings**: Per-notebook configuration persisted across sessions
 //!
 //! # Features
 //!
@@ -17,23 +18,38 @@
 #[cfg(feature = "embedded-frontend")]
 pub mod embedded_frontend;
 pub mod error;
+pub mod executor;
+pub mod export;
+pub mod in_flight;
+pub mod logs;
 pub mod lsp;
+pub mod presence;
 pub mod protocol;
+pub mod reactive;
 pub mod routes;
 pub mod rust_analyzer;
 pub mod session;
+pub mod settings;
 pub mod undo;
+pub mod virtual_file;
 pub mod watcher;
 
 use std::net::SocketAddr;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
+use std::time::Duration;
 
 use tokio::sync::{Mutex as TokioMutex, RwLock};
 
+use session::dependency_block_hash;
+
 pub use error::{ServerError, ServerResult};
+pub use executor::ExecutorKind;
+pub use in_flight::InFlightCells;
+pub use presence::PresenceRegistry;
 pub use protocol::{ClientMessage, ServerMessage};
+pub use reactive::ReactiveWidgets;
 pub use routes::{AppState, create_router};
 pub use session::{NotebookSession, SessionHandle};
 pub use watcher::{FileEvent, FileWatcher};
@@ -41,6 +57,19 @@ pub use watcher::{FileEvent, FileWatcher};
 // Re-export LSP cleanup function
 pub use lsp::kill_all_processes as kill_all_lsp_processes;
 
+/// Default maximum size of a single WebSocket message or HTTP body (16 MiB).
+pub const DEFAULT_MAX_MESSAGE_SIZE: usize = 16 * 1024 * 1024;
+
+/// Default number of server messages buffered per client before a slow
+/// client starts missing messages and is asked to resync.
+pub const DEFAULT_MESSAGE_CHANNEL_CAPACITY: usize = 256;
+
+/// Interval between `ResourceUsage` reports when sampling is enabled.
+pub const DEFAULT_RESOURCE_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Number of consecutive ports tried when `port_fallback` is enabled.
+const PORT_FALLBACK_ATTEMPTS: u16 = 10;
+
 /// Server configuration.
 #[derive(Debug, Clone)]
 pub struct ServerConfig {
@@ -50,6 +79,45 @@ pub struct ServerConfig {
     pub port: u16,
     /// Whether to open browser on start.
     pub open_browser: bool,
+    /// Origins allowed to make cross-origin requests (e.g. `http://localhost:5173`).
+    ///
+    /// When empty, CORS is permissive for loopback binds and same-origin only
+    /// for any other address.
+    pub allowed_origins: Vec<String>,
+    /// Maximum size in bytes of a WebSocket message or HTTP request body.
+    pub max_message_size: usize,
+    /// If the port is already in use, try the next few ports instead of failing.
+    pub port_fallback: bool,
+    /// Report the executing worker's CPU and memory at this interval.
+    ///
+    /// Disabled when `None`, since sampling has some overhead.
+    pub resource_sample_interval: Option<Duration>,
+    /// Number of server messages buffered for each client.
+    ///
+    /// A client that falls further behind than this misses messages and is
+    /// sent `Resync` so it can refetch the full state.
+    pub message_channel_capacity: usize,
+    /// When a cell's rerun fails, keep its last good output available to
+    /// dependents (flagged stale) instead of blocking them.
+    pub use_stale_on_failure: bool,
+    /// Extra environment variables for the processes that run cells
+    /// (e.g. loaded with `--env-file`).
+    pub env: Vec<(String, String)>,
+    /// Working directory for the processes that run cells, relative to the
+    /// notebook's directory. Defaults to the notebook's directory.
+    pub working_dir: Option<PathBuf>,
+    /// Directory to keep build artifacts, caches and state in, instead of
+    /// `.venus` next to the notebook. Falls back to `VENUS_BUILD_DIR`.
+    pub build_dir: Option<PathBuf>,
+    /// Write the combined `notebook.rs` that the in-browser rust-analyzer
+    /// analyzes on every reload. Turning this off saves disk writes on large
+    /// notebooks, at the cost of editor completions and diagnostics.
+    pub lsp_virtual_file: bool,
+    /// How cells are run. `Linear` runs them in the server process so a
+    /// debugger can be attached and panics keep full backtraces, but cells
+    /// can then neither be interrupted nor time out, and a crashing cell
+    /// takes down the server.
+    pub executor: ExecutorKind,
 }
 
 impl Default for ServerConfig {
@@ -58,23 +126,68 @@ impl Default for ServerConfig {
             host: "127.0.0.1".to_string(),
             port: 3000,
             open_browser: false,
+            allowed_origins: Vec::new(),
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            port_fallback: false,
+            resource_sample_interval: None,
+            message_channel_capacity: DEFAULT_MESSAGE_CHANNEL_CAPACITY,
+            use_stale_on_failure: false,
+            env: Vec::new(),
+            working_dir: None,
+            build_dir: None,
+            lsp_virtual_file: true,
+            executor: ExecutorKind::Process,
         }
     }
 }
 
+impl ServerConfig {
+    /// Check whether the server is bound to a loopback address.
+    pub fn is_loopback(&self) -> bool {
+        self.host == "localhost"
+            || self
+                .host
+                .parse::<std::net::IpAddr>()
+                .is_ok_and(|ip| ip.is_loopback())
+    }
+}
+
 /// Start the Venus server for a notebook.
 pub async fn serve(notebook_path: impl AsRef<Path>, config: ServerConfig) -> ServerResult<()> {
+    serve_with(notebook_path, config, |_| {}).await
+}
+
+/// Start the Venus server for a notebook, calling `on_listening` with the
+/// bound address once it accepts connections.
+///
+/// With `port_fallback`, the address can differ from the configured port.
+pub async fn serve_with(
+    notebook_path: impl AsRef<Path>,
+    config: ServerConfig,
+    on_listening: impl FnOnce(SocketAddr),
+) -> ServerResult<()> {
     let path = notebook_path.as_ref();
 
     // Create shared interrupt flag (AtomicBool for lock-free access)
     let interrupted = Arc::new(AtomicBool::new(false));
 
     // Create session with shared interrupt flag
-    let (session, _rx) = NotebookSession::new(path, interrupted.clone())?;
+    let (mut session, _rx) = NotebookSession::with_config(path, interrupted.clone(), &config)?;
+    session.set_resource_sampling(config.resource_sample_interval);
+    session.set_use_stale_on_failure(config.use_stale_on_failure);
+    session.set_env(config.env.clone(), &[])?;
+    if let Some(dir) = &config.working_dir {
+        session.set_working_directory(dir)?;
+    }
 
     // Get the kill handle from the executor - it's an Arc so it will see
     // updates when workers are spawned during execution
     let kill_handle = session.get_kill_handle();
+    let compile_abort = session.compile_abort_handle();
+    let in_flight = session.in_flight_cells();
+    let cell_locks = session.cell_locks();
+    let broadcaster = session.broadcaster();
+    let notebook_path = session.path().to_path_buf();
 
     let session = Arc::new(RwLock::new(session));
 
@@ -83,10 +196,16 @@ pub async fn serve(notebook_path: impl AsRef<Path>, config: ServerConfig) -> Ser
         session: session.clone(),
         kill_handle: Arc::new(TokioMutex::new(kill_handle)),
         interrupted,
+        compile_abort,
+        max_message_size: config.max_message_size,
+        presence: PresenceRegistry::with_cell_locks(cell_locks),
+        reactive: ReactiveWidgets::new(),
+        in_flight,
+        broadcaster,
     });
 
     // Create router
-    let app = create_router(state);
+    let app = create_router(state, &config);
 
     // Create file watcher
     let mut watcher = FileWatcher::new(path)?;
@@ -102,6 +221,18 @@ pub async fn serve(notebook_path: impl AsRef<Path>, config: ServerConfig) -> Ser
                     tracing::debug!(
                         "Notebook file changed externally (ignored, use Restart Kernel to apply)"
                     );
+
+                    // A changed dependency block needs a universe rebuild, which
+                    // the client is told about separately. The file is read and
+                    // hashed before taking the lock, which is only held to compare.
+                    let Ok(source) = tokio::fs::read_to_string(&notebook_path).await else {
+                        continue;
+                    };
+                    let hash = dependency_block_hash(&source);
+                    let mut session = session.write().await;
+                    if session.dependencies_changed_on_disk(hash) {
+                        session.broadcast(ServerMessage::DependenciesChangedOnDisk);
+                    }
                 }
                 FileEvent::Removed(path) => {
                     tracing::warn!("Notebook file removed: {}", path.display());
@@ -115,20 +246,22 @@ pub async fn serve(notebook_path: impl AsRef<Path>, config: ServerConfig) -> Ser
     let addr: SocketAddr = format!("{}:{}", config.host, config.port)
         .parse()
         .map_err(|_| ServerError::Io {
-            path: std::path::PathBuf::new(),
+            path: PathBuf::new(),
             message: format!("Invalid address: {}:{}", config.host, config.port),
         })?;
 
+    // Start server with graceful shutdown
+    let listener = bind_listener(addr, config.port_fallback).await?;
+    let addr = listener.local_addr()?;
+
     tracing::info!("Starting Venus server at http://{}", addr);
+    on_listening(addr);
 
-    // Open browser if requested
+    // Open browser if requested (only once the listener is ready to accept)
     if config.open_browser {
-        tracing::info!("Open http://{} in your browser", addr);
+        open_browser(&format!("http://{}", addr));
     }
 
-    // Start server with graceful shutdown
-    let listener = tokio::net::TcpListener::bind(addr).await?;
-
     // Create shutdown signal channel
     let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
 
@@ -156,6 +289,86 @@ pub async fn serve(notebook_path: impl AsRef<Path>, config: ServerConfig) -> Ser
     Ok(())
 }
 
+/// Bind a TCP listener, optionally falling back to the next free port.
+///
+/// With `port_fallback`, an `AddrInUse` error moves on to the next port (up
+/// to [`PORT_FALLBACK_ATTEMPTS`] ports). Any other error, or a busy port
+/// without fallback, is returned as-is.
+async fn bind_listener(
+    addr: SocketAddr,
+    port_fallback: bool,
+) -> std::io::Result<tokio::net::TcpListener> {
+    let attempts = if port_fallback {
+        PORT_FALLBACK_ATTEMPTS
+    } else {
+        1
+    };
+
+    let mut last_err = None;
+    for offset in 0..attempts {
+        let Some(port) = addr.port().checked_add(offset) else {
+            break;
+        };
+        let candidate = SocketAddr::new(addr.ip(), port);
+
+        match tokio::net::TcpListener::bind(candidate).await {
+            Ok(listener) => {
+                if offset > 0 {
+                    tracing::warn!(
+                        "Port {} is in use, serving at http://{} instead",
+                        addr.port(),
+                        candidate
+                    );
+                }
+                return Ok(listener);
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AddrInUse => last_err = Some(e),
+            Err(e) => return Err(e),
+        }
+    }
+
+    Err(last_err.unwrap_or_else(|| std::io::Error::from(std::io::ErrorKind::AddrInUse)))
+}
+
+/// Open a URL in the user's default browser.
+///
+/// Uses the platform opener (`open` on macOS, `start` on Windows, `xdg-open`
+/// elsewhere). On headless machines (no display server) or when launching
+/// fails, the URL is logged instead so the user can open it manually.
+fn open_browser(url: &str) {
+    #[cfg(target_os = "macos")]
+    let command = std::process::Command::new("open").arg(url).spawn();
+
+    #[cfg(target_os = "windows")]
+    let command = std::process::Command::new("cmd")
+        .args(["/C", "start", "", url])
+        .spawn();
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let command = {
+        if std::env::var_os("DISPLAY").is_none() && std::env::var_os("WAYLAND_DISPLAY").is_none() {
+            tracing::info!("No display available, open {} in your browser", url);
+            return;
+        }
+        std::process::Command::new("xdg-open")
+            .arg(url)
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .spawn()
+    };
+
+    if let Err(e) = command {
+        tracing::warn!("Failed to open browser ({}), open {} manually", e, url);
+    }
+}
+
+/// Lock a mutex shared between connection handlers.
+///
+/// The data stays consistent even if a holder panicked, so poisoning is ignored.
+pub(crate) fn lock<T>(mutex: &std::sync::Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -166,5 +379,43 @@ mod tests {
         assert_eq!(config.host, "127.0.0.1");
         assert_eq!(config.port, 3000);
         assert!(!config.open_browser);
+        assert!(config.allowed_origins.is_empty());
+        assert_eq!(config.max_message_size, DEFAULT_MAX_MESSAGE_SIZE);
+        assert!(!config.port_fallback);
+        assert_eq!(
+            config.message_channel_capacity,
+            DEFAULT_MESSAGE_CHANNEL_CAPACITY
+        );
+        assert!(!config.use_stale_on_failure);
+    }
+
+    #[test]
+    fn test_is_loopback() {
+        let mut config = ServerConfig::default();
+        assert!(config.is_loopback());
+
+        config.host = "localhost".to_string();
+        assert!(config.is_loopback());
+
+        config.host = "::1".to_string();
+        assert!(config.is_loopback());
+
+        config.host = "0.0.0.0".to_string();
+        assert!(!config.is_loopback());
+
+        config.host = "192.168.1.10".to_string();
+        assert!(!config.is_loopback());
+    }
+
+    #[tokio::test]
+    async fn test_bind_listener_port_fallback() {
+        let busy = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let busy_addr = busy.local_addr().unwrap();
+
+        let err = bind_listener(busy_addr, false).await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::AddrInUse);
+
+        let listener = bind_listener(busy_addr, true).await.unwrap();
+        assert_ne!(listener.local_addr().unwrap().port(), busy_addr.port());
     }
 }
@@ -0,0 +1,182 @@
+//! In-memory buffer of recent log records.
+//!
+//! The CLI installs [`layer`] next to its terminal logger, so the last
+//! [`LOG_BUFFER_CAPACITY`] tracing events can be fetched by clients with
+//! `GetLogs` without access to the server's terminal.
+
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+
+use crate::protocol::{LogLevel, LogLine};
+
+/// Maximum number of records kept in the global buffer.
+pub const LOG_BUFFER_CAPACITY: usize = 1000;
+
+/// Global buffer written by [`layer`].
+static LOG_BUFFER: OnceLock<LogBuffer> = OnceLock::new();
+
+/// Get the global log buffer.
+pub fn global() -> &'static LogBuffer {
+    LOG_BUFFER.get_or_init(|| LogBuffer::new(LOG_BUFFER_CAPACITY))
+}
+
+/// Create a tracing layer that records events into the global buffer.
+pub fn layer() -> LogLayer {
+    LogLayer { buffer: global() }
+}
+
+/// Bounded ring buffer of log records; the oldest records are dropped first.
+#[derive(Debug)]
+pub struct LogBuffer {
+    capacity: usize,
+    lines: Mutex<VecDeque<LogLine>>,
+}
+
+impl LogBuffer {
+    /// Create an empty buffer holding at most `capacity` records.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            lines: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    /// Append a record, evicting the oldest one if the buffer is full.
+    pub fn push(&self, line: LogLine) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut lines = self.lines.lock().unwrap_or_else(|e| e.into_inner());
+        if lines.len() == self.capacity {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+
+    /// Records at or above `min_level` (all records if `None`), oldest first.
+    pub fn lines(&self, min_level: Option<LogLevel>) -> Vec<LogLine> {
+        let lines = self.lines.lock().unwrap_or_else(|e| e.into_inner());
+        lines
+            .iter()
+            .filter(|line| min_level.is_none_or(|min| line.level >= min))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Tracing layer that stores events in a [`LogBuffer`].
+#[derive(Debug, Clone, Copy)]
+pub struct LogLayer {
+    buffer: &'static LogBuffer,
+}
+
+impl<S: Subscriber> Layer<S> for LogLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+
+        self.buffer.push(LogLine {
+            timestamp,
+            level: LogLevel::from(*event.metadata().level()),
+            target: event.metadata().target().to_string(),
+            message: visitor.message,
+        });
+    }
+}
+
+/// Formats an event's message followed by its other fields as `key=value`.
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+}
+
+impl MessageVisitor {
+    fn append(&mut self, field: &Field, value: std::fmt::Arguments<'_>) {
+        if field.name() == "message" {
+            // The message comes first, ahead of any fields recorded before it
+            let fields = std::mem::take(&mut self.message);
+            let _ = write!(self.message, "{}{}", value, fields);
+        } else {
+            let _ = write!(self.message, " {}={}", field.name(), value);
+        }
+    }
+}
+
+impl Visit for MessageVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.append(field, format_args!("{}", value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.append(field, format_args!("{:?}", value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(level: LogLevel, message: &str) -> LogLine {
+        LogLine {
+            timestamp: 0,
+            level,
+            target: "venus_server".to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_buffer_is_bounded() {
+        let buffer = LogBuffer::new(2);
+        buffer.push(line(LogLevel::Info, "a"));
+        buffer.push(line(LogLevel::Info, "b"));
+        buffer.push(line(LogLevel::Info, "c"));
+
+        let messages: Vec<_> = buffer.lines(None).into_iter().map(|l| l.message).collect();
+        assert_eq!(messages, vec!["b", "c"]);
+    }
+
+    #[test]
+    fn test_lines_filters_by_level() {
+        let buffer = LogBuffer::new(10);
+        buffer.push(line(LogLevel::Debug, "debug"));
+        buffer.push(line(LogLevel::Warn, "warn"));
+        buffer.push(line(LogLevel::Error, "error"));
+
+        let messages: Vec<_> = buffer
+            .lines(Some(LogLevel::Warn))
+            .into_iter()
+            .map(|l| l.message)
+            .collect();
+        assert_eq!(messages, vec!["warn", "error"]);
+    }
+
+    #[test]
+    fn test_layer_records_message_and_fields() {
+        use tracing_subscriber::prelude::*;
+
+        let buffer: &'static LogBuffer = Box::leak(Box::new(LogBuffer::new(10)));
+        let subscriber = tracing_subscriber::registry().with(LogLayer { buffer });
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::warn!(cell = "load", "Compilation failed");
+        });
+
+        let lines = buffer.lines(None);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].level, LogLevel::Warn);
+        assert_eq!(lines[0].message, "Compilation failed cell=load");
+    }
+}
@@ -5,16 +5,19 @@
 //!
 //! rust-analyzer is automatically downloaded and cached if not available.
 
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
 
 use axum::extract::ws::{Message, WebSocket};
 use futures::{SinkExt, StreamExt};
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
-use tokio::process::Command;
-use tokio::sync::Mutex;
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::sync::{Mutex, broadcast, mpsc};
 
+use crate::protocol::ServerMessage;
 use crate::rust_analyzer;
 
 /// Global registry of all running rust-analyzer processes.
@@ -71,26 +74,86 @@ pub async fn kill_all_processes() {
     tracing::info!("All rust-analyzer processes terminated");
 }
 
+/// How many times rust-analyzer is restarted after crashing before giving up.
+pub const MAX_LSP_RESTARTS: u32 = 3;
+
+/// A rust-analyzer that stayed up this long resets the restart count.
+const LSP_RESTART_WINDOW: Duration = Duration::from_secs(60);
+
+/// Request ID used when replaying `initialize` to a restarted rust-analyzer.
+///
+/// The response is swallowed, since the client already initialized.
+const REPLAY_INITIALIZE_ID: &str = "venus/replay-initialize";
+
 /// Handle an LSP WebSocket connection.
-pub async fn handle_lsp_websocket(socket: WebSocket, notebook_path: PathBuf) {
+///
+/// Messages are exchanged with [`run_lsp_session`] until the socket closes.
+/// A rust-analyzer that exits on its own is restarted, at most
+/// [`MAX_LSP_RESTARTS`] times in a row.
+pub async fn handle_lsp_websocket(
+    socket: WebSocket,
+    notebook_path: PathBuf,
+    broadcaster: broadcast::Sender<ServerMessage>,
+) {
     let (mut ws_sender, mut ws_receiver) = socket.split();
+    let (inbound_tx, inbound_rx) = mpsc::unbounded_channel();
+    let (outbound_tx, mut outbound_rx) = mpsc::unbounded_channel::<String>();
+
+    // Task: Forward WebSocket messages to the session
+    let ws_to_lsp = tokio::spawn(async move {
+        while let Some(msg) = ws_receiver.next().await {
+            match msg {
+                Ok(Message::Text(text)) if inbound_tx.send(text.to_string()).is_err() => break,
+                Ok(Message::Close(_)) => break,
+                Err(_) => break,
+                _ => {}
+            }
+        }
+    });
 
+    // Task: Forward session messages to the WebSocket
+    let lsp_to_ws = tokio::spawn(async move {
+        while let Some(text) = outbound_rx.recv().await {
+            if ws_sender.send(Message::Text(text.into())).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    run_lsp_session(notebook_path, broadcaster, inbound_rx, outbound_tx).await;
+
+    ws_to_lsp.abort();
+    let _ = lsp_to_ws.await;
+}
+
+/// Run rust-analyzer for one client.
+///
+/// JSON-RPC messages from the client arrive on `inbound`, without
+/// Content-Length framing, and rust-analyzer's messages are sent on
+/// `outbound`. The session ends when `inbound` closes.
+///
+/// `LspReady` is broadcast on `broadcaster` once rust-analyzer has finished
+/// indexing. If rust-analyzer exits while the client is connected it is
+/// respawned, replaying the client's `initialize` and reopening its open
+/// documents, and `LspRestarted` is broadcast.
+pub async fn run_lsp_session(
+    notebook_path: PathBuf,
+    broadcaster: broadcast::Sender<ServerMessage>,
+    mut inbound: mpsc::UnboundedReceiver<String>,
+    outbound: mpsc::UnboundedSender<String>,
+) {
     // Ensure rust-analyzer is available (download if needed)
     let ra_path = match rust_analyzer::ensure_available().await {
         Ok(path) => path,
         Err(e) => {
             tracing::error!("Failed to get rust-analyzer: {}", e);
-            let error_msg = serde_json::json!({
-                "jsonrpc": "2.0",
-                "method": "window/showMessage",
-                "params": {
-                    "type": 1,
-                    "message": format!("Failed to get rust-analyzer: {}. Please install it manually.", e)
-                }
-            });
-            let _ = ws_sender
-                .send(Message::Text(error_msg.to_string().into()))
-                .await;
+            show_error(
+                &outbound,
+                format!(
+                    "Failed to get rust-analyzer: {}. Please install it manually.",
+                    e
+                ),
+            );
             return;
         }
     };
@@ -101,11 +164,146 @@ pub async fn handle_lsp_websocket(socket: WebSocket, notebook_path: PathBuf) {
         .parent()
         .expect("Notebook path must have a parent directory");
 
-    tracing::info!("Starting rust-analyzer from: {}", ra_path.display());
-    tracing::info!("Working directory: {}", notebook_dir.display());
+    // Stdin of the current rust-analyzer, `None` while it is being replaced
+    let stdin: Arc<Mutex<Option<ChildStdin>>> = Arc::new(Mutex::new(None));
+    // The client's `initialize` request, replayed after a restart
+    let initialize: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
+    // The client's open documents, reopened after a restart
+    let documents: Arc<Mutex<OpenDocuments>> = Arc::new(Mutex::new(OpenDocuments::default()));
+
+    // Task: Forward client messages to rust-analyzer stdin
+    let mut client_to_lsp = {
+        let stdin = stdin.clone();
+        let initialize = initialize.clone();
+        let documents = documents.clone();
+        tokio::spawn(async move {
+            while let Some(content) = inbound.recv().await {
+                if initialize.lock().await.is_none() && is_initialize_request(&content) {
+                    *initialize.lock().await = Some(content.clone());
+                }
+                documents.lock().await.track(&content);
+
+                // Messages sent while rust-analyzer restarts are dropped
+                let mut stdin = stdin.lock().await;
+                if let Some(writer) = stdin.as_mut()
+                    && write_lsp_message(writer, &content).await.is_err()
+                {
+                    *stdin = None;
+                }
+            }
+        })
+    };
+
+    let mut restarts = 0;
+    loop {
+        tracing::info!("Starting rust-analyzer from: {}", ra_path.display());
+        tracing::info!("Working directory: {}", notebook_dir.display());
+
+        let mut child = match spawn_analyzer(&ra_path, notebook_dir) {
+            Ok(child) => child,
+            Err(e) => {
+                tracing::error!("Failed to start rust-analyzer: {}", e);
+                show_error(&outbound, format!("Failed to start rust-analyzer: {}", e));
+                break;
+            }
+        };
+
+        // Get process ID and register for cleanup
+        let pid = child.id().expect("Failed to get process ID");
+        register_process(pid).await;
+        let started = Instant::now();
+
+        let mut child_stdin = child.stdin.take().expect("Failed to get stdin");
+        let stdout = child.stdout.take().expect("Failed to get stdout");
+        let stderr = child.stderr.take().expect("Failed to get stderr");
+
+        if restarts > 0 {
+            // Bring the new process to where the client left the old one
+            if let Some(request) = initialize.lock().await.as_deref() {
+                let _ = write_lsp_message(&mut child_stdin, &replay_initialize(request)).await;
+                let initialized = serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "method": "initialized",
+                    "params": {}
+                });
+                let _ = write_lsp_message(&mut child_stdin, &initialized.to_string()).await;
+                for notification in documents.lock().await.replay() {
+                    let _ = write_lsp_message(&mut child_stdin, notification).await;
+                }
+            }
+        }
+        *stdin.lock().await = Some(child_stdin);
+
+        if restarts > 0 {
+            let _ = broadcaster.send(ServerMessage::LspRestarted { attempt: restarts });
+        }
+
+        let stdout_task = tokio::spawn(forward_stdout(
+            stdout,
+            outbound.clone(),
+            broadcaster.clone(),
+        ));
+
+        // Task: Log stderr
+        let stderr_task = tokio::spawn(async move {
+            let mut reader = BufReader::new(stderr);
+            let mut line = String::new();
+
+            while reader.read_line(&mut line).await.is_ok() {
+                if line.is_empty() {
+                    break;
+                }
+                tracing::debug!("rust-analyzer stderr: {}", line.trim());
+                line.clear();
+            }
+        });
+
+        let exited = tokio::select! {
+            status = child.wait() => Some(status),
+            _ = &mut client_to_lsp => None,
+        };
+
+        *stdin.lock().await = None;
+        if exited.is_none() {
+            // Client disconnected: kill rust-analyzer
+            let _ = child.kill().await;
+        }
+        let _ = stdout_task.await;
+        stderr_task.abort();
+        let _ = stderr_task.await;
+
+        // Unregister from cleanup list
+        unregister_process(pid).await;
 
+        let Some(status) = exited else {
+            break;
+        };
+
+        tracing::warn!("rust-analyzer exited unexpectedly: {:?}", status);
+        if started.elapsed() >= LSP_RESTART_WINDOW {
+            restarts = 0;
+        }
+        if restarts >= MAX_LSP_RESTARTS {
+            tracing::error!("rust-analyzer crashed {} times, giving up", restarts + 1);
+            show_error(
+                &outbound,
+                "rust-analyzer keeps crashing. Reload the page to try again.".to_string(),
+            );
+            // Keep the connection open so the client does not reconnect in a loop
+            let _ = (&mut client_to_lsp).await;
+            break;
+        }
+        restarts += 1;
+    }
+
+    client_to_lsp.abort();
+    tracing::info!("LSP session ended");
+}
+
+/// Spawn rust-analyzer with piped stdio in `notebook_dir`.
+fn spawn_analyzer(ra_path: &Path, notebook_dir: &Path) -> std::io::Result<Child> {
     // Build command with process group configuration
-    let mut cmd = Command::new(&ra_path);
+    let mut cmd = Command::new(ra_path);
     cmd.current_dir(notebook_dir) // Use notebook directory for workspace access
         .stdin(Stdio::piped())
         .stdout(Stdio::piped())
@@ -142,147 +340,214 @@ pub async fn handle_lsp_websocket(socket: WebSocket, notebook_path: PathBuf) {
         }
     }
 
-    let mut child = match cmd.spawn() {
-        Ok(child) => child,
-        Err(e) => {
-            tracing::error!("Failed to start rust-analyzer: {}", e);
-            let error_msg = serde_json::json!({
-                "jsonrpc": "2.0",
-                "method": "window/showMessage",
-                "params": {
-                    "type": 1,
-                    "message": format!("Failed to start rust-analyzer: {}", e)
-                }
-            });
-            let _ = ws_sender
-                .send(Message::Text(error_msg.to_string().into()))
-                .await;
-            return;
+    cmd.spawn()
+}
+
+/// Forward rust-analyzer stdout to the client until the process exits.
+async fn forward_stdout(
+    stdout: ChildStdout,
+    outbound: mpsc::UnboundedSender<String>,
+    broadcaster: broadcast::Sender<ServerMessage>,
+) {
+    let mut reader = BufReader::new(stdout);
+    let mut header_buf = String::new();
+    let mut ready = false;
+
+    loop {
+        header_buf.clear();
+
+        // Read Content-Length header
+        if reader.read_line(&mut header_buf).await.is_err() {
+            break;
         }
-    };
 
-    // Get process ID and register for cleanup
-    let pid = child.id().expect("Failed to get process ID");
-    register_process(pid).await;
+        if header_buf.is_empty() {
+            break;
+        }
 
-    let stdin = child.stdin.take().expect("Failed to get stdin");
-    let stdout = child.stdout.take().expect("Failed to get stdout");
-    let stderr = child.stderr.take().expect("Failed to get stderr");
+        // Parse Content-Length
+        let content_length: usize = if header_buf.starts_with("Content-Length:") {
+            header_buf
+                .trim_start_matches("Content-Length:")
+                .trim()
+                .parse()
+                .unwrap_or(0)
+        } else {
+            continue;
+        };
+
+        // Skip empty line after header
+        header_buf.clear();
+        if reader.read_line(&mut header_buf).await.is_err() {
+            break;
+        }
 
-    let stdin = Arc::new(Mutex::new(stdin));
-    let stdin_clone = stdin.clone();
+        // Read content
+        let mut content = vec![0u8; content_length];
+        if tokio::io::AsyncReadExt::read_exact(&mut reader, &mut content)
+            .await
+            .is_err()
+        {
+            break;
+        }
 
-    // Task: Forward WebSocket messages to rust-analyzer stdin
-    let ws_to_lsp = tokio::spawn(async move {
-        while let Some(msg) = ws_receiver.next().await {
-            match msg {
-                Ok(Message::Text(text)) => {
-                    // LSP requires Content-Length header
-                    let content = text.as_str();
-                    let header = format!("Content-Length: {}\r\n\r\n", content.len());
-
-                    let mut stdin = stdin_clone.lock().await;
-                    if stdin.write_all(header.as_bytes()).await.is_err() {
-                        break;
-                    }
-                    if stdin.write_all(content.as_bytes()).await.is_err() {
-                        break;
-                    }
-                    if stdin.flush().await.is_err() {
-                        break;
-                    }
+        // Send to client
+        if let Ok(text) = String::from_utf8(content) {
+            if let Ok(msg) = serde_json::from_str::<serde_json::Value>(&text) {
+                if msg["id"].as_str() == Some(REPLAY_INITIALIZE_ID) {
+                    continue;
+                }
+                if !ready && rust_analyzer::is_ready_notification(&msg) {
+                    ready = true;
+                    tracing::info!("rust-analyzer is ready");
+                    let _ = broadcaster.send(ServerMessage::LspReady);
                 }
-                Ok(Message::Close(_)) => break,
-                Err(_) => break,
-                _ => {}
             }
-        }
-    });
-
-    // Wrap sender in Arc<Mutex> for sharing
-    let ws_sender = Arc::new(Mutex::new(ws_sender));
-    let ws_sender_clone = ws_sender.clone();
 
-    // Task: Forward rust-analyzer stdout to WebSocket
-    let lsp_to_ws = tokio::spawn(async move {
-        let mut reader = BufReader::new(stdout);
-        let mut header_buf = String::new();
-
-        loop {
-            header_buf.clear();
-
-            // Read Content-Length header
-            if reader.read_line(&mut header_buf).await.is_err() {
+            if outbound.send(text).is_err() {
                 break;
             }
+        }
+    }
+}
 
-            if header_buf.is_empty() {
-                break;
-            }
+/// Write one message to rust-analyzer with its Content-Length header.
+async fn write_lsp_message(stdin: &mut ChildStdin, content: &str) -> std::io::Result<()> {
+    // LSP requires Content-Length header
+    let header = format!("Content-Length: {}\r\n\r\n", content.len());
+    stdin.write_all(header.as_bytes()).await?;
+    stdin.write_all(content.as_bytes()).await?;
+    stdin.flush().await
+}
 
-            // Parse Content-Length
-            let content_length: usize = if header_buf.starts_with("Content-Length:") {
-                header_buf
-                    .trim_start_matches("Content-Length:")
-                    .trim()
-                    .parse()
-                    .unwrap_or(0)
-            } else {
-                continue;
-            };
-
-            // Skip empty line after header
-            header_buf.clear();
-            if reader.read_line(&mut header_buf).await.is_err() {
-                break;
-            }
+/// Show an error to the client through `window/showMessage`.
+fn show_error(outbound: &mpsc::UnboundedSender<String>, message: String) {
+    let error_msg = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "window/showMessage",
+        "params": {
+            "type": 1,
+            "message": message
+        }
+    });
+    let _ = outbound.send(error_msg.to_string());
+}
 
-            // Read content
-            let mut content = vec![0u8; content_length];
-            if tokio::io::AsyncReadExt::read_exact(&mut reader, &mut content)
-                .await
-                .is_err()
-            {
-                break;
-            }
+/// Documents the client has open, tracked to reopen them in a restarted
+/// rust-analyzer.
+#[derive(Debug, Default)]
+struct OpenDocuments {
+    /// Per document URI, the notifications that bring a new process to the
+    /// client's current text: a `didOpen`, then any incremental `didChange`s.
+    documents: HashMap<String, Vec<String>>,
+}
 
-            // Send to WebSocket
-            if let Ok(text) = String::from_utf8(content) {
-                let mut sender = ws_sender_clone.lock().await;
-                if sender.send(Message::Text(text.into())).await.is_err() {
-                    break;
+impl OpenDocuments {
+    /// Update the open documents from a client message.
+    ///
+    /// A `didChange` replacing the whole text is folded into the `didOpen`,
+    /// so only incremental changes accumulate.
+    fn track(&mut self, content: &str) {
+        let Ok(msg) = serde_json::from_str::<serde_json::Value>(content) else {
+            return;
+        };
+        let Some(uri) = msg["params"]["textDocument"]["uri"].as_str() else {
+            return;
+        };
+        match msg["method"].as_str() {
+            Some("textDocument/didOpen") => {
+                self.documents
+                    .insert(uri.to_string(), vec![content.to_string()]);
+            }
+            Some("textDocument/didChange") => {
+                let Some(notifications) = self.documents.get_mut(uri) else {
+                    return;
+                };
+                let full_text = msg["params"]["contentChanges"]
+                    .as_array()
+                    .and_then(|changes| changes.last())
+                    .filter(|change| change.get("range").is_none())
+                    .and_then(|change| change["text"].as_str());
+                match full_text {
+                    Some(text) => {
+                        let mut open: serde_json::Value =
+                            serde_json::from_str(&notifications[0]).unwrap_or_default();
+                        open["params"]["textDocument"]["text"] = text.into();
+                        open["params"]["textDocument"]["version"] =
+                            msg["params"]["textDocument"]["version"].clone();
+                        *notifications = vec![open.to_string()];
+                    }
+                    None => notifications.push(content.to_string()),
                 }
             }
+            Some("textDocument/didClose") => {
+                self.documents.remove(uri);
+            }
+            _ => {}
         }
-    });
+    }
 
-    // Task: Log stderr
-    let stderr_task = tokio::spawn(async move {
-        let mut reader = BufReader::new(stderr);
-        let mut line = String::new();
+    /// Notifications reopening every document, in the order to send them.
+    fn replay(&self) -> impl Iterator<Item = &String> {
+        self.documents.values().flatten()
+    }
+}
 
-        while reader.read_line(&mut line).await.is_ok() {
-            if line.is_empty() {
-                break;
-            }
-            tracing::debug!("rust-analyzer stderr: {}", line.trim());
-            line.clear();
-        }
-    });
+/// Whether a client message is the `initialize` request.
+fn is_initialize_request(content: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(content)
+        .is_ok_and(|msg| msg["method"].as_str() == Some("initialize"))
+}
 
-    // Wait for BOTH tasks to complete (not just first one)
-    // This prevents orphaned tasks from continuing to run
-    let _ = tokio::join!(ws_to_lsp, lsp_to_ws);
+/// Rewrite the client's `initialize` request for replay to a new process.
+fn replay_initialize(request: &str) -> String {
+    let mut msg: serde_json::Value = serde_json::from_str(request).unwrap_or_default();
+    msg["id"] = REPLAY_INITIALIZE_ID.into();
+    msg.to_string()
+}
 
-    // Clean up stderr task
-    stderr_task.abort();
-    let _ = stderr_task.await;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    // Kill rust-analyzer process
-    let _ = child.kill().await;
+    #[test]
+    fn test_replay_initialize() {
+        let request =
+            r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"rootUri":"file:///nb"}}"#;
+        assert!(is_initialize_request(request));
 
-    // Unregister from cleanup list
-    unregister_process(pid).await;
+        let replay: serde_json::Value = serde_json::from_str(&replay_initialize(request)).unwrap();
+        assert_eq!(replay["id"], REPLAY_INITIALIZE_ID);
+        assert_eq!(replay["method"], "initialize");
+        assert_eq!(replay["params"]["rootUri"], "file:///nb");
 
-    tracing::info!("LSP session ended");
+        assert!(!is_initialize_request(
+            r#"{"jsonrpc":"2.0","method":"initialized","params":{}}"#
+        ));
+    }
+
+    #[test]
+    fn test_open_documents_replay_current_text() {
+        let mut documents = OpenDocuments::default();
+        documents.track(
+            r#"{"jsonrpc":"2.0","method":"textDocument/didOpen","params":{"textDocument":{"uri":"file:///nb.rs","languageId":"rust","version":1,"text":"fn a() {}"}}}"#,
+        );
+        documents.track(
+            r#"{"jsonrpc":"2.0","method":"textDocument/didChange","params":{"textDocument":{"uri":"file:///nb.rs","version":2},"contentChanges":[{"text":"fn b() {}"}]}}"#,
+        );
+
+        let replay: Vec<serde_json::Value> = documents
+            .replay()
+            .map(|msg| serde_json::from_str(msg).unwrap())
+            .collect();
+        assert_eq!(replay.len(), 1);
+        assert_eq!(replay[0]["method"], "textDocument/didOpen");
+        assert_eq!(replay[0]["params"]["textDocument"]["text"], "fn b() {}");
+        assert_eq!(replay[0]["params"]["textDocument"]["version"], 2);
+
+        documents.track(
+            r#"{"jsonrpc":"2.0","method":"textDocument/didClose","params":{"textDocument":{"uri":"file:///nb.rs"}}}"#,
+        );
+        assert_eq!(documents.replay().count(), 0);
+    }
 }
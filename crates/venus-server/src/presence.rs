@@ -0,0 +1,339 @@
+//! Tracking of connected WebSocket clients.
+//!
+//! Each connection is assigned a client ID when it opens. The registry is
+//! shared through [`AppState`](crate::routes::AppState), and the list of
+//! connected clients is broadcast as [`ServerMessage::Presence`] whenever a
+//! client connects or disconnects.
+//!
+//! Clients can also take soft locks on cells. While a cell is locked, edits
+//! to it from other clients are rejected. Locks are released when their
+//! holder disconnects. Cell IDs are reassigned when the notebook is
+//! reloaded, so the session moves the locks to the cells' new IDs through
+//! the shared [`CellLocks`].
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tokio::sync::broadcast;
+
+use venus_core::graph::CellId;
+
+use crate::lock;
+use crate::protocol::{ClientInfo, ServerMessage};
+
+/// Registry of connected clients.
+#[derive(Debug, Default)]
+pub struct PresenceRegistry {
+    /// Next client ID to assign.
+    next_id: AtomicU64,
+    /// Connected clients, in connection order.
+    clients: Mutex<Vec<ClientInfo>>,
+    /// Cell locks, shared with the session.
+    cell_locks: CellLocks,
+}
+
+impl PresenceRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an empty registry using the session's cell locks.
+    pub fn with_cell_locks(cell_locks: CellLocks) -> Self {
+        Self {
+            cell_locks,
+            ..Self::default()
+        }
+    }
+
+    /// Register a new connection and return its identity.
+    pub fn join(&self) -> ClientInfo {
+        let connected_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+
+        let client = ClientInfo {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed) + 1,
+            connected_at,
+        };
+        lock(&self.clients).push(client.clone());
+        client
+    }
+
+    /// Remove a connection and release its cell locks.
+    ///
+    /// Returns the cells that were unlocked, or `None` if the client was not
+    /// registered.
+    pub fn leave(&self, id: u64) -> Option<Vec<CellId>> {
+        let mut clients = lock(&self.clients);
+        let before = clients.len();
+        clients.retain(|c| c.id != id);
+        if clients.len() == before {
+            return None;
+        }
+
+        let mut locks = self.cell_locks.lock();
+        let released: Vec<CellId> = locks
+            .iter()
+            .filter(|(_, holder)| **holder == id)
+            .map(|(cell_id, _)| *cell_id)
+            .collect();
+        for cell_id in &released {
+            locks.remove(cell_id);
+        }
+        Some(released)
+    }
+
+    /// Snapshot of the connected clients.
+    pub fn clients(&self) -> Vec<ClientInfo> {
+        lock(&self.clients).clone()
+    }
+
+    /// Lock a cell for a client.
+    ///
+    /// Re-acquiring a lock the client already holds succeeds. Returns the
+    /// holder's ID if another client holds the lock.
+    pub fn acquire_cell_lock(&self, cell_id: CellId, client_id: u64) -> Result<(), u64> {
+        let mut locks = self.cell_locks.lock();
+        match locks.get(&cell_id) {
+            Some(&holder) if holder != client_id => Err(holder),
+            _ => {
+                locks.insert(cell_id, client_id);
+                Ok(())
+            }
+        }
+    }
+
+    /// Release a cell lock. Returns `false` if the client did not hold it.
+    pub fn release_cell_lock(&self, cell_id: CellId, client_id: u64) -> bool {
+        let mut locks = self.cell_locks.lock();
+        if locks.get(&cell_id) == Some(&client_id) {
+            locks.remove(&cell_id);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Client holding a lock on a cell, if any.
+    pub fn cell_lock_holder(&self, cell_id: CellId) -> Option<u64> {
+        self.cell_locks.lock().get(&cell_id).copied()
+    }
+
+    /// A cell locked by a client other than `client_id`, with its holder.
+    pub fn cell_locked_by_other(&self, client_id: u64) -> Option<(CellId, u64)> {
+        self.cell_locks
+            .lock()
+            .iter()
+            .find(|(_, holder)| **holder != client_id)
+            .map(|(cell_id, holder)| (*cell_id, *holder))
+    }
+
+    /// Messages announcing every currently held lock.
+    pub fn cell_lock_messages(&self) -> Vec<ServerMessage> {
+        self.cell_locks
+            .lock()
+            .iter()
+            .map(|(cell_id, holder)| ServerMessage::CellLocked {
+                cell_id: *cell_id,
+                client_id: Some(*holder),
+            })
+            .collect()
+    }
+
+    /// Build the presence message for the current clients.
+    pub fn message(&self) -> ServerMessage {
+        ServerMessage::Presence {
+            clients: self.clients(),
+        }
+    }
+}
+
+/// Cell locks, mapping cell to holding client.
+///
+/// Clones share the same locks.
+#[derive(Debug, Clone, Default)]
+pub struct CellLocks {
+    inner: Arc<Mutex<HashMap<CellId, u64>>>,
+}
+
+impl CellLocks {
+    /// Create an empty set of locks.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Move locks to the cells' new IDs after a reload.
+    ///
+    /// `ids` maps old IDs to new ones; locks on cells missing from it are
+    /// released. Returns the `CellLocked` messages announcing the changes.
+    pub fn remap(&self, ids: &HashMap<CellId, CellId>) -> Vec<ServerMessage> {
+        let mut locks = self.lock();
+        let old = std::mem::take(&mut *locks);
+        let mut released = Vec::new();
+        let mut moved = Vec::new();
+        for (cell_id, holder) in old {
+            match ids.get(&cell_id) {
+                Some(&new_id) => {
+                    locks.insert(new_id, holder);
+                    if new_id != cell_id {
+                        released.push(cell_id);
+                        moved.push((new_id, holder));
+                    }
+                }
+                None => released.push(cell_id),
+            }
+        }
+        let mut messages: Vec<ServerMessage> = released
+            .into_iter()
+            .filter(|cell_id| !locks.contains_key(cell_id))
+            .map(|cell_id| ServerMessage::CellLocked {
+                cell_id,
+                client_id: None,
+            })
+            .collect();
+        messages.extend(
+            moved
+                .into_iter()
+                .map(|(cell_id, holder)| ServerMessage::CellLocked {
+                    cell_id,
+                    client_id: Some(holder),
+                }),
+        );
+        messages
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<CellId, u64>> {
+        lock(&self.inner)
+    }
+}
+
+/// Removes a client from the registry when its connection ends.
+///
+/// Dropping the guard broadcasts the released locks and the updated presence
+/// list, so clients are cleaned up however the connection handler exits.
+pub struct PresenceGuard<'a> {
+    registry: &'a PresenceRegistry,
+    id: u64,
+    tx: broadcast::Sender<ServerMessage>,
+}
+
+impl<'a> PresenceGuard<'a> {
+    /// Create a guard for a registered client.
+    pub fn new(
+        registry: &'a PresenceRegistry,
+        id: u64,
+        tx: broadcast::Sender<ServerMessage>,
+    ) -> Self {
+        Self { registry, id, tx }
+    }
+}
+
+impl Drop for PresenceGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(released) = self.registry.leave(self.id) {
+            for cell_id in released {
+                let _ = self.tx.send(ServerMessage::CellLocked {
+                    cell_id,
+                    client_id: None,
+                });
+            }
+            let _ = self.tx.send(self.registry.message());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_join_assigns_unique_ids() {
+        let registry = PresenceRegistry::new();
+        let a = registry.join();
+        let b = registry.join();
+
+        assert_ne!(a.id, b.id);
+        assert_eq!(registry.clients(), vec![a, b]);
+    }
+
+    #[test]
+    fn test_guard_leaves_and_broadcasts() {
+        let registry = PresenceRegistry::new();
+        let (tx, mut rx) = broadcast::channel(4);
+        let a = registry.join();
+        let b = registry.join();
+
+        drop(PresenceGuard::new(&registry, a.id, tx));
+
+        assert_eq!(registry.clients(), vec![b.clone()]);
+        match rx.try_recv().unwrap() {
+            ServerMessage::Presence { clients } => assert_eq!(clients, vec![b]),
+            other => panic!("unexpected message: {:?}", other),
+        }
+        assert!(registry.leave(a.id).is_none());
+    }
+
+    #[test]
+    fn test_cell_locks() {
+        let registry = PresenceRegistry::new();
+        let a = registry.join();
+        let b = registry.join();
+        let cell = CellId::new(1);
+
+        assert_eq!(registry.acquire_cell_lock(cell, a.id), Ok(()));
+        assert_eq!(registry.acquire_cell_lock(cell, a.id), Ok(()));
+        assert_eq!(registry.acquire_cell_lock(cell, b.id), Err(a.id));
+
+        // Only the holder can release
+        assert!(!registry.release_cell_lock(cell, b.id));
+        assert!(registry.release_cell_lock(cell, a.id));
+        assert_eq!(registry.cell_lock_holder(cell), None);
+    }
+
+    #[test]
+    fn test_leave_releases_locks() {
+        let registry = PresenceRegistry::new();
+        let (tx, mut rx) = broadcast::channel(4);
+        let a = registry.join();
+        let cell = CellId::new(7);
+        registry.acquire_cell_lock(cell, a.id).unwrap();
+
+        drop(PresenceGuard::new(&registry, a.id, tx));
+
+        assert_eq!(registry.cell_lock_holder(cell), None);
+        assert!(matches!(
+            rx.try_recv().unwrap(),
+            ServerMessage::CellLocked {
+                cell_id,
+                client_id: None,
+            } if cell_id == cell
+        ));
+    }
+
+    #[test]
+    fn test_remap_moves_locks_to_new_ids() {
+        let locks = CellLocks::new();
+        let registry = PresenceRegistry::with_cell_locks(locks.clone());
+        let a = registry.join();
+        registry.acquire_cell_lock(CellId::new(1), a.id).unwrap();
+        registry.acquire_cell_lock(CellId::new(2), a.id).unwrap();
+
+        // Cell 1 became cell 3, cell 2 is gone
+        let ids = HashMap::from([(CellId::new(1), CellId::new(3))]);
+        let messages = locks.remap(&ids);
+
+        assert_eq!(registry.cell_lock_holder(CellId::new(1)), None);
+        assert_eq!(registry.cell_lock_holder(CellId::new(2)), None);
+        assert_eq!(registry.cell_lock_holder(CellId::new(3)), Some(a.id));
+        assert_eq!(messages.len(), 3);
+        assert!(messages.iter().any(|msg| matches!(
+            msg,
+            ServerMessage::CellLocked { cell_id, client_id: Some(id) }
+                if *cell_id == CellId::new(3) && *id == a.id
+        )));
+    }
+}
@@ -2,17 +2,53 @@
 //!
 //! Defines the message types exchanged between client and server.
 
+use std::collections::{BTreeMap, HashMap};
+
 use serde::{Deserialize, Serialize};
-use venus_core::graph::{CellId, DefinitionType};
+use venus_core::graph::{CellId, DefinitionType, OutputFormat};
 use venus_core::widgets::{WidgetDef, WidgetValue};
 
-// Re-export MoveDirection from venus_core for use in protocol messages
-pub use venus_core::graph::MoveDirection;
+// Re-export types used in protocol messages
+pub use venus_core::graph::{CellType, MoveDirection};
+pub use venus_core::lint::{LintRule, LintWarning};
+pub use venus_sync::NotebookMetadata;
+
+/// Version of the WebSocket protocol spoken by this server.
+///
+/// Bumped on breaking changes to message shapes; purely additive messages
+/// do not change it.
+pub const PROTOCOL_VERSION: u32 = 1;
 
 /// Messages sent from client to server.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ClientMessage {
+    /// Handshake sent by the client right after connecting.
+    Hello {
+        /// Protocol version the client speaks.
+        protocol_version: u32,
+    },
+
+    /// Liveness probe; answered with [`ServerMessage::Pong`].
+    Ping {
+        /// Opaque value echoed back in the reply.
+        nonce: u64,
+    },
+
+    /// Send an LSP message to rust-analyzer over this connection instead
+    /// of the `/lsp` socket.
+    ///
+    /// The first such message starts a rust-analyzer for the connection;
+    /// its messages come back as [`ServerMessage::Lsp`].
+    Lsp {
+        /// JSON-RPC message, without Content-Length framing.
+        payload: serde_json::Value,
+    },
+
+    /// Request everything needed to rebuild the client's view: notebook
+    /// state, undo/redo state, history positions and cell locks.
+    Resync,
+
     /// Request current notebook state.
     GetState,
 
@@ -24,14 +60,99 @@ pub enum ClientMessage {
         source: String,
     },
 
+    /// Apply edits to several code cells in one step.
+    ///
+    /// The source file is rewritten and reloaded once, and the whole batch
+    /// is undone as a single operation.
+    BatchEdit {
+        /// Cells to edit and their new sources.
+        edits: Vec<CellSourceEdit>,
+    },
+
     /// Execute a specific cell.
     ExecuteCell {
         /// Cell to execute.
         cell_id: CellId,
+        /// Re-execute even if a cached output matches the cell's inputs.
+        #[serde(default)]
+        force: bool,
+    },
+
+    /// Execute a cell once with some widget values replaced.
+    ///
+    /// The overrides apply to this execution only; stored widget values are
+    /// not changed.
+    ExecuteCellWith {
+        /// Cell to execute.
+        cell_id: CellId,
+        /// Widget values by widget ID, taking precedence over stored values.
+        widget_overrides: HashMap<String, WidgetValue>,
+    },
+
+    /// Run a cell once per value of one widget; answered with
+    /// [`ServerMessage::SweepResults`].
+    ///
+    /// Like [`ClientMessage::ExecuteCellWith`], stored widget values and the
+    /// cell's output are left unchanged.
+    SweepCell {
+        /// Cell to run.
+        cell_id: CellId,
+        /// Widget whose value is swept.
+        widget_id: String,
+        /// Values to run the cell with, in order.
+        values: Vec<WidgetValue>,
     },
 
     /// Execute all cells.
-    ExecuteAll,
+    ExecuteAll {
+        /// Re-execute every cell, bypassing the output cache.
+        #[serde(default)]
+        force: bool,
+    },
+
+    /// Execute all cells, then export the notebook as standalone HTML.
+    RunAndExport {
+        /// Use the dark theme.
+        #[serde(default)]
+        dark: bool,
+        /// Re-execute every cell, bypassing the output cache.
+        #[serde(default)]
+        force: bool,
+    },
+
+    /// Execute a cell and every cell after it in execution order.
+    RunFrom {
+        /// First cell to execute.
+        cell_id: CellId,
+        /// Re-execute the cells, bypassing the output cache.
+        #[serde(default)]
+        force: bool,
+    },
+
+    /// Set or clear a breakpoint. Runs pause after executing a breakpointed cell.
+    SetBreakpoint {
+        /// Cell to set the breakpoint on.
+        cell_id: CellId,
+        /// Whether the breakpoint is set.
+        enabled: bool,
+    },
+
+    /// Resume a run paused at a breakpoint.
+    Continue,
+
+    /// Execute the next cell of the paused run (or the first cell of a new
+    /// run over the whole notebook) and pause again.
+    StepExecution,
+
+    /// Run a code snippet as an ephemeral cell without saving it to the notebook.
+    ExecuteSelection {
+        /// Rust expression or block to evaluate.
+        source: String,
+        /// IDs of cells whose outputs are available to the snippet, each as
+        /// a reference named after the cell.
+        #[serde(default)]
+        deps: Vec<CellId>,
+    },
 
     /// Execute cells that need re-execution.
     ExecuteDirty,
@@ -39,12 +160,85 @@ pub enum ClientMessage {
     /// Interrupt running execution.
     Interrupt,
 
+    /// Kill the compiler process of an in-progress cell compile or
+    /// universe build.
+    AbortCompile,
+
     /// Sync notebook to .ipynb format.
     Sync,
 
+    /// Execute a cell (bypassing the output cache) and report a breakdown
+    /// of where the time went.
+    ProfileCell {
+        /// Cell to profile.
+        cell_id: CellId,
+    },
+
     /// Request dependency graph.
     GetGraph,
 
+    /// Request summary statistics for the notebook.
+    GetNotebookStats,
+
+    /// Request server version and capability information.
+    GetServerInfo,
+
+    /// Request a cell's doc comment rendered as HTML.
+    GetCellDoc {
+        /// Cell whose documentation to render.
+        cell_id: CellId,
+    },
+
+    /// Ask what kind of cell an ID refers to.
+    GetCellType {
+        /// Cell to look up.
+        cell_id: CellId,
+    },
+
+    /// Request a code cell's function signature.
+    GetCellSignature {
+        /// Cell to look up.
+        cell_id: CellId,
+    },
+
+    /// Request the notebook's definition cells (imports, types, helpers).
+    GetDefinitions,
+
+    /// Request the run plan: code cells in execution order with their levels.
+    GetExecutionOrder,
+
+    /// Export cells with their dependencies as a standalone notebook.
+    ExportSubgraph {
+        /// Cells to export; their upstream cells are included.
+        cell_ids: Vec<CellId>,
+    },
+
+    /// Export every cell's current output as data.
+    ExportData,
+
+    /// Remove cached outputs and build artifacts of deleted or renamed cells.
+    GarbageCollect,
+
+    /// Find the code cells that use a definition.
+    GetReferences {
+        /// Name of the definition (struct, enum, function, ...).
+        symbol: String,
+    },
+
+    /// Run the notebook linter.
+    Lint {
+        /// Rules to skip.
+        #[serde(default)]
+        disabled: Vec<LintRule>,
+    },
+
+    /// Request recent server log records.
+    GetLogs {
+        /// Minimum severity to include (all records if omitted).
+        #[serde(default)]
+        level: Option<LogLevel>,
+    },
+
     /// Update a widget value.
     WidgetUpdate {
         /// Cell containing the widget.
@@ -55,6 +249,17 @@ pub enum ClientMessage {
         value: WidgetValue,
     },
 
+    /// Fetch the widget definitions and values of every cell.
+    GetWidgetState,
+
+    /// Turn re-running a cell on widget changes on or off.
+    SetWidgetReactive {
+        /// Cell whose widgets to make reactive.
+        cell_id: CellId,
+        /// Whether widget updates re-run the cell and its dependents.
+        enabled: bool,
+    },
+
     /// Select a history entry to use as the current output.
     SelectHistory {
         /// Cell to select history for.
@@ -63,12 +268,40 @@ pub enum ClientMessage {
         index: usize,
     },
 
+    /// List a cell's output history.
+    GetHistory {
+        /// Cell to list history for.
+        cell_id: CellId,
+    },
+
+    /// Ask where the notebook's build artifacts live.
+    GetBuildPaths,
+
+    /// Decode a cell's output into a JSON tree.
+    InspectOutput {
+        /// Cell whose output to inspect.
+        cell_id: CellId,
+    },
+
+    /// Fetch the diagnostics from a cell's last compilation.
+    GetCompileOutput {
+        /// Cell to fetch diagnostics for.
+        cell_id: CellId,
+    },
+
     /// Insert a new cell.
     InsertCell {
         /// Cell ID to insert after. None = insert at end.
         after_cell_id: Option<CellId>,
     },
 
+    /// Insert a new cell at a position in the source order.
+    InsertCellAt {
+        /// Index in `source_order` to insert at; the cell currently there
+        /// moves down. Equal to the number of cells = insert at end.
+        index: usize,
+    },
+
     /// Delete a cell.
     DeleteCell {
         /// Cell to delete.
@@ -89,6 +322,34 @@ pub enum ClientMessage {
         direction: MoveDirection,
     },
 
+    /// Move any cell (code, markdown or definition) to a position in the
+    /// source order.
+    MoveCellToIndex {
+        /// Cell to move.
+        cell_id: CellId,
+        /// Index in `source_order` the cell should end up at.
+        index: usize,
+    },
+
+    /// Rewrite the notebook file so cells appear in execution order:
+    /// definitions first, then code cells in topological order, with
+    /// markdown cells kept next to the cell after them.
+    ///
+    /// Without `confirm` nothing is changed; the server replies with a
+    /// `Warning` describing the rewrite and a `NotebookReordered` with
+    /// `applied: false`.
+    ReorderToExecutionOrder {
+        /// Apply the rewrite.
+        #[serde(default)]
+        confirm: bool,
+    },
+
+    /// List outputs and history kept for cells that no longer exist.
+    FindOrphanOutputs,
+
+    /// Remove outputs and history kept for cells that no longer exist.
+    CleanOrphanOutputs,
+
     /// Undo the last cell management operation.
     Undo,
 
@@ -101,6 +362,53 @@ pub enum ClientMessage {
     /// Clear all cell outputs without restarting the kernel.
     ClearOutputs,
 
+    /// Clear a single cell's output.
+    ClearCellOutput {
+        /// Cell whose output to clear.
+        cell_id: CellId,
+        /// Also drop the cell's output history.
+        #[serde(default)]
+        clear_history: bool,
+    },
+
+    /// Re-read the notebook file from disk, keeping outputs of unchanged cells.
+    ReloadFromDisk,
+
+    /// Set or remove environment variables for cell execution.
+    ///
+    /// Takes effect for the next cell run without restarting the kernel.
+    SetEnv {
+        /// Variables to set.
+        #[serde(default)]
+        vars: BTreeMap<String, String>,
+        /// Variables to remove.
+        #[serde(default)]
+        unset: Vec<String>,
+    },
+
+    /// Change the working directory of the processes that run cells.
+    SetWorkingDirectory {
+        /// Directory path, relative to the notebook's directory or absolute.
+        path: String,
+    },
+
+    /// Rebuild the universe (dependencies and definitions), keeping outputs.
+    ReloadUniverse,
+
+    /// Switch the cell compiler backend.
+    ///
+    /// `release: true` compiles with optimized LLVM, `false` with Cranelift.
+    SetBackend {
+        /// Use the optimized LLVM backend.
+        release: bool,
+    },
+
+    /// Set the optimization level (0-3) cells are compiled at.
+    SetOptLevel {
+        /// Optimization level passed to rustc as `-Copt-level`.
+        level: u8,
+    },
+
     /// Rename a cell's display name.
     RenameCell {
         /// Cell to rename.
@@ -139,6 +447,23 @@ pub enum ClientMessage {
         direction: MoveDirection,
     },
 
+    /// Convert a cell between code and markdown.
+    ///
+    /// Code becomes a `//` comment block; markdown becomes a cell that
+    /// returns its text as a `String`.
+    ConvertCell {
+        /// Cell to convert.
+        cell_id: CellId,
+        /// Target cell type (`code` or `markdown`).
+        to: CellType,
+    },
+
+    /// Set the notebook metadata, replacing all fields.
+    SetMetadata {
+        /// New notebook metadata.
+        metadata: NotebookMetadata,
+    },
+
     /// Insert a new definition cell.
     InsertDefinitionCell {
         /// Definition content (source code).
@@ -157,10 +482,21 @@ pub enum ClientMessage {
         new_content: String,
     },
 
+    /// Replace the text of an item that failed to parse.
+    EditUnparsedRegion {
+        /// Unparsed region to edit.
+        cell_id: CellId,
+        /// New source text for the item.
+        new_content: String,
+    },
+
     /// Delete a definition cell.
     DeleteDefinitionCell {
         /// Cell to delete.
         cell_id: CellId,
+        /// Delete even if code cells use its definitions.
+        #[serde(default)]
+        force: bool,
     },
 
     /// Move a definition cell up or down.
@@ -170,12 +506,114 @@ pub enum ClientMessage {
         /// Direction to move.
         direction: MoveDirection,
     },
+
+    /// Lock a cell against edits from other clients.
+    AcquireCellLock {
+        /// Cell to lock.
+        cell_id: CellId,
+    },
+
+    /// Release a lock held by this client.
+    ReleaseCellLock {
+        /// Cell to unlock.
+        cell_id: CellId,
+    },
+}
+
+impl ClientMessage {
+    /// Cells whose source this message modifies.
+    ///
+    /// These messages are rejected while another client holds a lock on
+    /// any of the cells.
+    pub fn edited_cells(&self) -> Vec<CellId> {
+        match self {
+            Self::CellEdit { cell_id, .. }
+            | Self::DeleteCell { cell_id }
+            | Self::MoveCell { cell_id, .. }
+            | Self::MoveCellToIndex { cell_id, .. }
+            | Self::RenameCell { cell_id, .. }
+            | Self::DuplicateCell { cell_id }
+            | Self::EditMarkdownCell { cell_id, .. }
+            | Self::DeleteMarkdownCell { cell_id }
+            | Self::MoveMarkdownCell { cell_id, .. }
+            | Self::ConvertCell { cell_id, .. }
+            | Self::EditDefinitionCell { cell_id, .. }
+            | Self::EditUnparsedRegion { cell_id, .. }
+            | Self::DeleteDefinitionCell { cell_id, .. }
+            | Self::MoveDefinitionCell { cell_id, .. } => vec![*cell_id],
+            Self::BatchEdit { edits } => edits.iter().map(|e| e.cell_id).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Whether this message may modify the source of any cell.
+    ///
+    /// These messages are rejected while another client holds any lock.
+    pub fn edits_all_cells(&self) -> bool {
+        matches!(
+            self,
+            Self::Undo | Self::Redo | Self::ReorderToExecutionOrder { confirm: true }
+        )
+    }
 }
 
 /// Messages sent from server to client.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ServerMessage {
+    /// Handshake response to [`ClientMessage::Hello`].
+    Welcome {
+        /// Protocol version the server speaks.
+        protocol_version: u32,
+        /// Whether the client's protocol version is supported.
+        /// If false, the server closes the connection.
+        accepted: bool,
+        /// ID assigned to this connection (see [`ServerMessage::Presence`]).
+        #[serde(default)]
+        client_id: u64,
+    },
+
+    /// Reply to [`ClientMessage::Ping`].
+    Pong {
+        /// Nonce from the ping.
+        nonce: u64,
+        /// Server time in milliseconds since the Unix epoch.
+        server_time: u64,
+    },
+
+    /// A message from the rust-analyzer started by [`ClientMessage::Lsp`].
+    ///
+    /// Sent only to the connection that tunnels LSP.
+    Lsp {
+        /// JSON-RPC message from rust-analyzer.
+        payload: serde_json::Value,
+    },
+
+    /// The client fell behind and missed server messages.
+    ///
+    /// The client should refetch the notebook state rather than trust its
+    /// current view.
+    Resync {
+        /// Number of messages that were dropped.
+        missed: u64,
+    },
+
+    /// A cell was locked or unlocked.
+    CellLocked {
+        /// Cell whose lock changed.
+        cell_id: CellId,
+        /// Client holding the lock, or `None` if the cell was unlocked.
+        client_id: Option<u64>,
+    },
+
+    /// Clients currently connected to the server.
+    ///
+    /// Broadcast whenever a client connects or disconnects.
+    Presence {
+        /// Connected clients, in connection order.
+        clients: Vec<ClientInfo>,
+    },
+
     /// Full notebook state (sent on connection or refresh).
     NotebookState {
         /// Path to the notebook file.
@@ -190,6 +628,13 @@ pub enum ServerMessage {
         workspace_root: Option<String>,
         /// Path to the Cargo.toml file for LSP configuration.
         cargo_toml_path: Option<String>,
+        /// Notebook title, authors, description, creation date and tags.
+        #[serde(default)]
+        metadata: NotebookMetadata,
+        /// Cells compiling or running right now, so a client that connects
+        /// mid-run shows them as busy.
+        #[serde(default)]
+        in_flight: Vec<InFlightCell>,
     },
 
     /// Cell execution started.
@@ -198,6 +643,29 @@ pub enum ServerMessage {
         cell_id: CellId,
     },
 
+    /// Periodic report that a cell is still executing.
+    ///
+    /// Sent every second after `CellStarted` until the cell completes, fails
+    /// or is aborted.
+    CellExecuting {
+        /// Cell being executed.
+        cell_id: CellId,
+        /// Time since execution started, in milliseconds.
+        elapsed_ms: u64,
+    },
+
+    /// Periodic resource usage of the worker executing a cell.
+    ///
+    /// Only sent when resource sampling is enabled on the server.
+    ResourceUsage {
+        /// Cell being executed.
+        cell_id: CellId,
+        /// Resident memory of the worker process, in bytes.
+        rss_bytes: u64,
+        /// CPU time used by the worker process so far, in milliseconds.
+        cpu_ms: u64,
+    },
+
     /// Cell execution completed successfully.
     CellCompleted {
         /// Cell that completed.
@@ -206,6 +674,50 @@ pub enum ServerMessage {
         duration_ms: u64,
         /// Cell output (serialized).
         output: Option<CellOutput>,
+        /// Whether the output was reused from the output cache rather than
+        /// computed by this run.
+        #[serde(default)]
+        from_cache: bool,
+    },
+
+    /// Segment of a large cell output's text.
+    ///
+    /// Sent before `CellCompleted`, whose output then carries no text; the
+    /// client concatenates the chunks in `seq` order. Outputs over 64 KiB
+    /// come from the worker in segments, and each chunk is sent as soon as
+    /// its segment arrives, so a large output shows before all of it is in.
+    CellOutputAppended {
+        /// Cell producing the output.
+        cell_id: CellId,
+        /// Text segment.
+        chunk: String,
+        /// Position of this segment, starting at 0.
+        seq: u32,
+        /// Whether this is the last segment.
+        #[serde(rename = "final")]
+        is_final: bool,
+    },
+
+    /// Phase timings of a `ProfileCell` run, in milliseconds.
+    CellProfile {
+        /// Cell that was profiled.
+        cell_id: CellId,
+        /// Compiling the cell (near zero when the build cache was used).
+        compile_ms: f64,
+        /// Worker round trips, excluding the time the cell ran.
+        ipc_ms: f64,
+        /// Running the cell in the worker, including serializing its output.
+        exec_ms: f64,
+        /// Copying inputs and decoding the output in the server.
+        serialize_ms: f64,
+    },
+
+    /// Result of an `ExecuteSelection` request.
+    SelectionResult {
+        /// Output of the snippet (its `Debug` rendering).
+        output: Option<CellOutput>,
+        /// Error message if compilation or execution failed.
+        error: Option<String>,
     },
 
     /// Cell marked as dirty (needs re-execution because upstream changed).
@@ -224,6 +736,28 @@ pub enum ServerMessage {
         location: Option<SourceLocation>,
     },
 
+    /// Cell execution failed because the cell panicked.
+    ///
+    /// Sent instead of `CellError`.
+    CellPanic {
+        /// Cell that panicked.
+        cell_id: CellId,
+        /// Panic message, with its source location if known.
+        message: String,
+        /// Backtrace of the panic, if the server was started with
+        /// `RUST_BACKTRACE` set. Very long backtraces are truncated.
+        backtrace: Option<String>,
+    },
+
+    /// A cell failed but its last good output stays available to dependents.
+    ///
+    /// Only sent when the server runs with `use_stale_on_failure`; the
+    /// cell's output in the notebook state is flagged `stale`.
+    CellOutputStale {
+        /// Cell whose output is stale.
+        cell_id: CellId,
+    },
+
     /// Compilation error (before execution).
     CompileError {
         /// Cell with compilation error.
@@ -232,6 +766,42 @@ pub enum ServerMessage {
         errors: Vec<CompileErrorInfo>,
     },
 
+    /// Locations of the notebook's build artifacts, in response to
+    /// `GetBuildPaths`.
+    BuildPaths {
+        /// Directory of compiled cells and the universe crate.
+        build_dir: String,
+        /// Directory of compilation cache metadata.
+        cache_dir: String,
+        /// Directory of persisted cell outputs.
+        state_dir: String,
+        /// Compiled universe library, once it has been built.
+        universe_path: Option<String>,
+        /// Generated `notebook.rs` holding all cells, for rust-analyzer.
+        virtual_notebook: String,
+    },
+
+    /// Decoded output of a cell, in response to `InspectOutput`.
+    OutputInspection {
+        /// Inspected cell.
+        cell_id: CellId,
+        /// Declared return type of the cell.
+        type_name: String,
+        /// Decoded value, or `{ "length", "hex" }` if it could not be decoded.
+        decoded_json: serde_json::Value,
+    },
+
+    /// Diagnostics from a cell's last compilation, in response to
+    /// `GetCompileOutput`.
+    CompileOutput {
+        /// Cell the diagnostics belong to.
+        cell_id: CellId,
+        /// Compiler errors (empty if the last compilation succeeded).
+        errors: Vec<CompileErrorInfo>,
+        /// Compiler warnings.
+        warnings: Vec<CompileErrorInfo>,
+    },
+
     /// Dependency graph updated.
     GraphUpdated {
         /// New dependency edges.
@@ -240,6 +810,16 @@ pub enum ServerMessage {
         levels: Vec<Vec<CellId>>,
     },
 
+    /// The notebook file has a syntax error; the previous cells are kept.
+    ParseError {
+        /// Error message from the Rust parser.
+        message: String,
+        /// Line of the error (1-based).
+        line: usize,
+        /// Column of the error (1-based).
+        column: usize,
+    },
+
     /// Notebook file changed externally.
     FileChanged {
         /// Cells that were modified.
@@ -250,6 +830,135 @@ pub enum ServerMessage {
         removed_cells: Vec<CellId>,
     },
 
+    /// The cargo dependency block in the notebook file changed on disk.
+    ///
+    /// Applying it needs a universe rebuild (`ReloadUniverse`), not just a
+    /// reload of the cells.
+    DependenciesChangedOnDisk,
+
+    /// Something in the notebook is accepted but probably not doing what the
+    /// user expects (e.g. a `fn main` that is never called).
+    Warning {
+        /// Human-readable warning.
+        message: String,
+    },
+
+    /// Summary statistics for the notebook.
+    NotebookStats {
+        /// Number of code cells.
+        code_cells: usize,
+        /// Number of markdown cells.
+        markdown_cells: usize,
+        /// Number of definition cells.
+        definition_cells: usize,
+        /// Total number of dependencies across all code cells.
+        total_deps: usize,
+        /// Number of code cells that completed successfully.
+        executed: usize,
+        /// Number of code cells that failed.
+        failed: usize,
+        /// Sum of the execution times of the current outputs, in milliseconds.
+        total_exec_time_ms: u64,
+    },
+
+    /// Self-contained notebook source produced by `ExportSubgraph`.
+    SubgraphSource {
+        /// Notebook source code.
+        source: String,
+    },
+
+    /// Cell outputs produced by `ExportData`.
+    DataExport {
+        /// Time of the export in milliseconds since the Unix epoch.
+        exported_at: u64,
+        /// Cells with an output, in notebook order.
+        cells: Vec<ExportedOutput>,
+    },
+
+    /// Standalone HTML report produced by `RunAndExport`.
+    ExportReady {
+        /// Complete HTML document.
+        html: String,
+    },
+
+    /// Result of `GarbageCollect`.
+    GarbageCollected {
+        /// Disk space freed, in bytes.
+        bytes_reclaimed: u64,
+    },
+
+    /// Result of `GetReferences`.
+    References {
+        /// Code cells whose source mentions the symbol, in notebook order.
+        cells: Vec<CellId>,
+    },
+
+    /// Lint findings for the notebook.
+    LintReport {
+        /// Warnings, grouped by rule in source order.
+        warnings: Vec<LintWarning>,
+    },
+
+    /// Recent server log records, oldest first.
+    Logs {
+        /// Log records.
+        lines: Vec<LogLine>,
+    },
+
+    /// Definition cells, in source order.
+    Definitions {
+        /// Definition blocks.
+        blocks: Vec<DefinitionBlock>,
+    },
+
+    /// Run plan requested with `GetExecutionOrder`.
+    ExecutionOrder {
+        /// Code cells in execution order, grouped by level.
+        steps: Vec<ExecutionStep>,
+    },
+
+    /// Rendered documentation for a cell.
+    CellDoc {
+        /// Cell the documentation belongs to.
+        cell_id: CellId,
+        /// Doc comment rendered from markdown, without the display-name heading.
+        html: String,
+    },
+
+    /// Kind of a cell, in response to `GetCellType`.
+    CellType {
+        /// Cell that was looked up.
+        cell_id: CellId,
+        /// Kind of the cell.
+        kind: CellKind,
+    },
+
+    /// Function signature of a code cell, in response to `GetCellSignature`.
+    CellSignature {
+        /// Cell that was looked up.
+        cell_id: CellId,
+        /// Cell function name.
+        name: String,
+        /// Parameters, one per dependency, in declaration order.
+        params: Vec<CellParam>,
+        /// Return type.
+        return_type: String,
+        /// Whether the function is declared `async`.
+        is_async: bool,
+    },
+
+    /// Server version and capability information.
+    ServerInfo {
+        /// Venus version of the server.
+        venus_version: String,
+        /// WebSocket protocol version (see [`PROTOCOL_VERSION`]).
+        protocol_version: u32,
+        /// Version of the rustc toolchain used for cell compilation.
+        toolchain_version: String,
+        /// Optional capabilities enabled on this server (e.g. "cranelift").
+        features: Vec<String>,
+    },
+
     /// Sync completed.
     SyncCompleted {
         /// Path to generated .ipynb file.
@@ -260,6 +969,51 @@ pub enum ServerMessage {
     ExecutionAborted {
         /// The cell that was interrupted (if known).
         cell_id: Option<CellId>,
+        /// Why execution stopped, if not a user request (e.g. a timeout).
+        #[serde(default)]
+        reason: Option<String>,
+    },
+
+    /// Results of a [`ClientMessage::SweepCell`], sent to the requesting client.
+    SweepResults {
+        /// Cell that was swept.
+        cell_id: CellId,
+        /// One entry per run, in the order of the requested values.
+        results: Vec<SweepRun>,
+        /// Whether an interrupt stopped the sweep before all values ran.
+        interrupted: bool,
+    },
+
+    /// A compilation was killed by `AbortCompile`.
+    CompileAborted {
+        /// Cell whose compilation was aborted, or `None` for a universe build.
+        cell_id: Option<CellId>,
+    },
+
+    /// rust-analyzer behind `/lsp` finished indexing and can serve requests.
+    LspReady,
+
+    /// rust-analyzer crashed and was restarted behind the open `/lsp` socket.
+    ///
+    /// Clients must re-send `textDocument/didOpen` for their documents.
+    LspRestarted {
+        /// Restart number since rust-analyzer last ran stably.
+        attempt: u32,
+    },
+
+    /// A run paused at a breakpoint or after a step; `Continue` resumes it.
+    Paused {
+        /// The cell that just executed.
+        at: CellId,
+        /// The cell the run executes next.
+        #[serde(default)]
+        next: Option<CellId>,
+    },
+
+    /// Cells that currently have a breakpoint.
+    Breakpoints {
+        /// Breakpointed cells.
+        cell_ids: Vec<CellId>,
     },
 
     /// Generic error message.
@@ -316,6 +1070,28 @@ pub enum ServerMessage {
         dirty_cells: Vec<CellId>,
     },
 
+    /// Output history of a cell, in response to `GetHistory`.
+    CellHistory {
+        /// Cell the history belongs to.
+        cell_id: CellId,
+        /// Entries from oldest to newest.
+        entries: Vec<HistoryEntryInfo>,
+    },
+
+    /// Widget definitions and values, in response to `GetWidgetState`.
+    WidgetState {
+        /// Cells with widgets, in notebook order.
+        per_cell: Vec<CellWidgetState>,
+    },
+
+    /// Reactive widget mode of a cell changed.
+    WidgetReactive {
+        /// Cell whose mode changed.
+        cell_id: CellId,
+        /// Whether widget updates re-run the cell and its dependents.
+        enabled: bool,
+    },
+
     /// Undo operation result.
     UndoResult {
         /// Whether the undo succeeded.
@@ -354,12 +1130,52 @@ pub enum ServerMessage {
         error: Option<String>,
     },
 
+    /// Progress of a universe rebuild started by `ReloadUniverse`.
+    UniverseBuildProgress {
+        /// Crate cargo started compiling.
+        crate_name: String,
+        /// Number of crates compiled so far, including this one.
+        current: usize,
+        /// Number of crates to compile, if known.
+        total: Option<usize>,
+    },
+
+    /// Universe rebuild completed.
+    UniverseReloaded {
+        /// Cells marked dirty because the rebuilt universe may differ.
+        dirty_cells: Vec<CellId>,
+        /// Error message if the build failed.
+        error: Option<String>,
+    },
+
     /// All outputs cleared.
     OutputsCleared {
         /// Error message if clear failed.
         error: Option<String>,
     },
 
+    /// Notebook reloaded from disk.
+    NotebookReloaded {
+        /// Error message if reload failed.
+        error: Option<String>,
+    },
+
+    /// Compiler backend changed.
+    BackendChanged {
+        /// Whether the optimized LLVM backend is now active.
+        release: bool,
+        /// Error message if the switch failed.
+        error: Option<String>,
+    },
+
+    /// Optimization level changed.
+    OptLevelChanged {
+        /// Optimization level now in use.
+        level: u8,
+        /// Error message if the change failed.
+        error: Option<String>,
+    },
+
     /// Cell rename result.
     CellRenamed {
         /// ID of the renamed cell.
@@ -421,6 +1237,14 @@ pub enum ServerMessage {
         dirty_cells: Vec<CellId>,
     },
 
+    /// Result of editing an unparsed region.
+    UnparsedRegionEdited {
+        /// ID of the edited region.
+        cell_id: CellId,
+        /// Error message if the edit failed.
+        error: Option<String>,
+    },
+
     /// Definition cell deletion result.
     DefinitionCellDeleted {
         /// ID of the deleted definition cell.
@@ -436,6 +1260,55 @@ pub enum ServerMessage {
         /// Error message if move failed.
         error: Option<String>,
     },
+
+    /// Environment variables changed, in response to `SetEnv`.
+    ///
+    /// Only names are sent, since values may be secrets.
+    EnvSet {
+        /// Names of all extra variables now set.
+        names: Vec<String>,
+    },
+
+    /// Working directory changed, in response to `SetWorkingDirectory`.
+    WorkingDirectorySet {
+        /// Absolute path of the new working directory.
+        path: String,
+    },
+
+    /// Metadata update result.
+    MetadataSet {
+        /// Error message if the update failed.
+        error: Option<String>,
+    },
+
+    /// Outputs and history kept for cells that no longer exist.
+    OrphanOutputs {
+        /// Stale cell IDs (`cell_<id>`) and cell names with leftover entries.
+        names: Vec<String>,
+        /// Whether the entries were removed (reply to `CleanOrphanOutputs`).
+        #[serde(default)]
+        removed: bool,
+    },
+
+    /// Result of reordering the notebook to execution order.
+    NotebookReordered {
+        /// Number of cells whose position changes.
+        moved: usize,
+        /// Whether the file was rewritten.
+        applied: bool,
+        /// Error message if the reorder failed.
+        error: Option<String>,
+    },
+
+    /// Cell conversion result.
+    CellConverted {
+        /// ID of the cell before conversion.
+        cell_id: CellId,
+        /// Type the cell was converted to.
+        to: CellType,
+        /// Error message if conversion failed.
+        error: Option<String>,
+    },
 }
 
 /// State of a single cell (code, markdown, or definition).
@@ -459,6 +1332,9 @@ pub enum CellState {
         return_type: String,
         /// Dependencies (parameter names).
         dependencies: Vec<String>,
+        /// Output format hint from `#[venus::cell(output = "...")]`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        output_format: Option<OutputFormat>,
         /// Current execution status.
         status: CellStatus,
         /// Last output if available.
@@ -484,6 +1360,17 @@ pub enum CellState {
         /// Attached doc comment.
         doc_comment: Option<String>,
     },
+    /// Top-level item that failed to parse; the rest of the notebook stays usable.
+    Unparsed {
+        /// Unique cell identifier.
+        id: CellId,
+        /// Source text of the item.
+        content: String,
+        /// Parser error.
+        message: String,
+        /// First line of the item in the notebook file (1-based).
+        line: usize,
+    },
 }
 
 impl CellState {
@@ -492,7 +1379,8 @@ impl CellState {
         match self {
             CellState::Code { id, .. }
             | CellState::Markdown { id, .. }
-            | CellState::Definition { id, .. } => *id,
+            | CellState::Definition { id, .. }
+            | CellState::Unparsed { id, .. } => *id,
         }
     }
 
@@ -500,7 +1388,9 @@ impl CellState {
     pub fn name(&self) -> Option<&str> {
         match self {
             CellState::Code { name, .. } => Some(name),
-            CellState::Markdown { .. } | CellState::Definition { .. } => None,
+            CellState::Markdown { .. }
+            | CellState::Definition { .. }
+            | CellState::Unparsed { .. } => None,
         }
     }
 
@@ -508,7 +1398,9 @@ impl CellState {
     pub fn is_dirty(&self) -> bool {
         match self {
             CellState::Code { dirty, .. } => *dirty,
-            CellState::Markdown { .. } | CellState::Definition { .. } => false,
+            CellState::Markdown { .. }
+            | CellState::Definition { .. }
+            | CellState::Unparsed { .. } => false,
         }
     }
 
@@ -523,7 +1415,9 @@ impl CellState {
     pub fn status(&self) -> Option<CellStatus> {
         match self {
             CellState::Code { status, .. } => Some(*status),
-            CellState::Markdown { .. } | CellState::Definition { .. } => None,
+            CellState::Markdown { .. }
+            | CellState::Definition { .. }
+            | CellState::Unparsed { .. } => None,
         }
     }
 
@@ -534,6 +1428,26 @@ impl CellState {
         }
     }
 
+    /// Get output (only for code cells).
+    pub fn output(&self) -> Option<&CellOutput> {
+        match self {
+            CellState::Code { output, .. } => output.as_ref(),
+            CellState::Markdown { .. }
+            | CellState::Definition { .. }
+            | CellState::Unparsed { .. } => None,
+        }
+    }
+
+    /// Get mutable output (only for code cells).
+    pub fn output_mut(&mut self) -> Option<&mut CellOutput> {
+        match self {
+            CellState::Code { output, .. } => output.as_mut(),
+            CellState::Markdown { .. }
+            | CellState::Definition { .. }
+            | CellState::Unparsed { .. } => None,
+        }
+    }
+
     /// Set output (only for code cells).
     pub fn set_output(&mut self, new_output: Option<CellOutput>) {
         if let CellState::Code { output, .. } = self {
@@ -580,6 +1494,34 @@ pub struct CellOutput {
     /// Interactive widgets defined by this cell.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub widgets: Vec<WidgetDef>,
+    /// Output of an earlier successful run, kept for dependents after the
+    /// cell's latest run failed.
+    #[serde(default)]
+    pub stale: bool,
+}
+
+/// A cell that is compiling or running.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InFlightCell {
+    /// Cell in flight.
+    pub cell_id: CellId,
+    /// `Compiling` or `Running`.
+    pub status: CellStatus,
+    /// Time since the cell entered this status, in milliseconds.
+    pub elapsed_ms: u64,
+}
+
+/// One run of a parameter sweep.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SweepRun {
+    /// Widget value the cell ran with.
+    pub value: WidgetValue,
+    /// Output of the run, if it succeeded.
+    pub output: Option<CellOutput>,
+    /// Execution time in milliseconds.
+    pub duration_ms: u64,
+    /// Error message, if the run failed.
+    pub error: Option<String>,
 }
 
 /// Source location for error reporting.
@@ -619,6 +1561,212 @@ pub struct DependencyEdge {
     pub param_name: String,
 }
 
+/// A connected WebSocket client.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClientInfo {
+    /// Client ID, unique for the lifetime of the server.
+    pub id: u64,
+    /// Connection time in milliseconds since the Unix epoch.
+    pub connected_at: u64,
+}
+
+/// Kind of a cell, matching the variants of [`CellState`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CellKind {
+    /// Executable `#[venus::cell]` function.
+    Code,
+    /// Doc comment block.
+    Markdown,
+    /// Imports, types and helper functions.
+    Definition,
+    /// Source that could not be parsed into a cell.
+    Unparsed,
+}
+
+impl CellKind {
+    /// Description with an article, for messages ("a code cell").
+    pub fn describe(self) -> &'static str {
+        match self {
+            CellKind::Code => "a code cell",
+            CellKind::Markdown => "a markdown cell",
+            CellKind::Definition => "a definition cell",
+            CellKind::Unparsed => "an unparsed region",
+        }
+    }
+}
+
+/// Severity of a log record, ordered from least to most severe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    /// Very verbose diagnostics.
+    Trace,
+    /// Debugging information.
+    Debug,
+    /// Normal operation.
+    Info,
+    /// Recoverable problems.
+    Warn,
+    /// Failures.
+    Error,
+}
+
+impl From<tracing::Level> for LogLevel {
+    fn from(level: tracing::Level) -> Self {
+        match level {
+            tracing::Level::TRACE => LogLevel::Trace,
+            tracing::Level::DEBUG => LogLevel::Debug,
+            tracing::Level::INFO => LogLevel::Info,
+            tracing::Level::WARN => LogLevel::Warn,
+            tracing::Level::ERROR => LogLevel::Error,
+        }
+    }
+}
+
+/// One cell's new source within [`ClientMessage::BatchEdit`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CellSourceEdit {
+    /// Cell identifier.
+    pub cell_id: CellId,
+    /// New source code.
+    pub source: String,
+}
+
+/// Widgets of one cell, as reported by `GetWidgetState`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CellWidgetState {
+    /// Cell the widgets belong to.
+    pub cell_id: CellId,
+    /// Widgets defined by the cell's last run.
+    pub defs: Vec<WidgetDef>,
+    /// Current values by widget ID, including values set since the last run.
+    pub values: HashMap<String, WidgetValue>,
+}
+
+/// One cell's output in a `DataExport`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedOutput {
+    /// Cell the output belongs to.
+    pub cell_id: CellId,
+    /// Cell name.
+    pub name: String,
+    /// Declared return type of the cell.
+    pub type_name: String,
+    /// Size of the serialized output in bytes.
+    pub size: usize,
+    /// Serialized output (rkyv), base64 encoded. `None` if truncated.
+    pub bytes: Option<String>,
+    /// Output decoded to JSON, as by `InspectOutput`. `None` if truncated.
+    pub json: Option<serde_json::Value>,
+    /// Whether the data was left out to keep the export under the size limit.
+    pub truncated: bool,
+}
+
+/// Summary of one output history entry, as reported by `GetHistory`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntryInfo {
+    /// History index (0 = oldest), as used by `SelectHistory`.
+    pub index: usize,
+    /// Time the execution completed, in milliseconds since the Unix epoch.
+    pub timestamp: u64,
+    /// Short preview of the output.
+    pub preview: String,
+    /// Execution time in milliseconds.
+    pub duration_ms: u64,
+}
+
+/// A server log record.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogLine {
+    /// Time of the record in milliseconds since the Unix epoch.
+    pub timestamp: u64,
+    /// Severity.
+    pub level: LogLevel,
+    /// Module that emitted the record.
+    pub target: String,
+    /// Message followed by any structured fields as `key=value`.
+    pub message: String,
+}
+
+/// A definition cell as reported by `GetDefinitions`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DefinitionBlock {
+    /// Definition cell ID.
+    pub id: CellId,
+    /// Kind of definition.
+    pub definition_type: DefinitionType,
+    /// Source code of the definition.
+    pub content: String,
+    /// Attached doc comment, if any.
+    pub doc: Option<String>,
+}
+
+/// A code cell's place in the run plan.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionStep {
+    /// Cell ID.
+    pub cell_id: CellId,
+    /// Cell function name.
+    pub name: String,
+    /// Parallel level; cells in the same level don't depend on each other.
+    pub level: usize,
+}
+
+/// A parameter of a cell function.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CellParam {
+    /// Parameter name (the name of the cell it depends on).
+    pub name: String,
+    /// Parameter type, without the reference.
+    #[serde(rename = "type")]
+    pub param_type: String,
+    /// Whether the parameter is a reference (`&T` or `&mut T`).
+    pub is_ref: bool,
+    /// Whether the parameter is a mutable reference (`&mut T`).
+    pub is_mut: bool,
+}
+
+/// Output text longer than this many bytes that was not streamed from a
+/// worker, such as a cached output, is sent as
+/// [`ServerMessage::CellOutputAppended`] chunks of at most this size.
+pub const STREAMED_OUTPUT_THRESHOLD: usize = 64 * 1024;
+
+/// Split output text into `CellOutputAppended` messages.
+///
+/// Chunks are at most `chunk_size` bytes, split on character boundaries
+/// (a single character wider than `chunk_size` gets a chunk of its own).
+pub fn output_chunks(cell_id: CellId, text: &str, chunk_size: usize) -> Vec<ServerMessage> {
+    let mut chunks = Vec::new();
+    let mut rest = text;
+
+    while !rest.is_empty() {
+        let mut end = chunk_size.min(rest.len());
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        if end == 0 {
+            end = rest.chars().next().map_or(rest.len(), char::len_utf8);
+        }
+
+        let (chunk, tail) = rest.split_at(end);
+        chunks.push(chunk);
+        rest = tail;
+    }
+
+    let count = chunks.len();
+    chunks
+        .into_iter()
+        .enumerate()
+        .map(|(i, chunk)| ServerMessage::CellOutputAppended {
+            cell_id,
+            chunk: chunk.to_string(),
+            seq: i as u32,
+            is_final: i + 1 == count,
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -627,19 +1775,34 @@ mod tests {
     fn test_client_message_serialization() {
         let msg = ClientMessage::ExecuteCell {
             cell_id: CellId::new(1),
+            force: false,
         };
         let json = serde_json::to_string(&msg).unwrap();
         assert!(json.contains("execute_cell"));
 
         let parsed: ClientMessage = serde_json::from_str(&json).unwrap();
         match parsed {
-            ClientMessage::ExecuteCell { cell_id } => {
+            ClientMessage::ExecuteCell { cell_id, force } => {
                 assert_eq!(cell_id, CellId::new(1));
+                assert!(!force);
             }
             _ => panic!("Wrong message type"),
         }
     }
 
+    #[test]
+    fn test_force_defaults_to_false() {
+        let parsed: ClientMessage = serde_json::from_str(r#"{"type":"execute_all"}"#).unwrap();
+        assert!(matches!(parsed, ClientMessage::ExecuteAll { force: false }));
+
+        let parsed: ClientMessage =
+            serde_json::from_str(r#"{"type":"execute_cell","cell_id":3}"#).unwrap();
+        assert!(matches!(
+            parsed,
+            ClientMessage::ExecuteCell { force: false, .. }
+        ));
+    }
+
     #[test]
     fn test_server_message_serialization() {
         let msg = ServerMessage::CellStarted {
@@ -649,8 +1812,49 @@ mod tests {
         assert!(json.contains("cell_started"));
     }
 
+    #[test]
+    fn test_cell_completed_from_cache_defaults_to_false() {
+        let json = r#"{"type":"cell_completed","cell_id":1,"duration_ms":5,"output":null}"#;
+        let parsed: ServerMessage = serde_json::from_str(json).unwrap();
+        assert!(matches!(
+            parsed,
+            ServerMessage::CellCompleted {
+                from_cache: false,
+                ..
+            }
+        ));
+    }
+
     #[test]
     fn test_cell_status_default() {
         assert_eq!(CellStatus::default(), CellStatus::Idle);
     }
+
+    #[test]
+    fn test_output_chunks_split_on_char_boundaries() {
+        let text = "ab\u{e9}cd";
+        let chunks = output_chunks(CellId::new(1), text, 3);
+
+        let mut assembled = String::new();
+        for (i, msg) in chunks.iter().enumerate() {
+            match msg {
+                ServerMessage::CellOutputAppended {
+                    chunk,
+                    seq,
+                    is_final,
+                    ..
+                } => {
+                    assert!(chunk.len() <= 3);
+                    assert_eq!(*seq, i as u32);
+                    assert_eq!(*is_final, i + 1 == chunks.len());
+                    assembled.push_str(chunk);
+                }
+                other => panic!("unexpected message: {:?}", other),
+            }
+        }
+        assert_eq!(assembled, text);
+
+        let json = serde_json::to_string(&chunks[0]).unwrap();
+        assert!(json.contains(r#""final":false"#));
+    }
 }
@@ -0,0 +1,104 @@
+//! Reactive widgets: re-running a cell when one of its widgets changes.
+//!
+//! Widget updates only store the new value by default. Cells switched to
+//! reactive mode with `SetWidgetReactive` are re-run, together with their
+//! dependents, after each update. A burst of updates, such as a slider
+//! drag, results in a single run once the updates pause for
+//! [`REACTIVE_WIDGET_DEBOUNCE`].
+//!
+//! Cells are kept by name, like breakpoints, so the setting survives reloads
+//! that reassign cell IDs.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::lock;
+
+/// How long widget updates must pause before a reactive cell is re-run.
+pub const REACTIVE_WIDGET_DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// Cells in reactive widget mode and their pending runs.
+#[derive(Debug, Default)]
+pub struct ReactiveWidgets {
+    /// Names of cells that re-run when a widget changes.
+    enabled: Mutex<HashSet<String>>,
+    /// Number of widget updates seen per cell, identifying the latest one.
+    updates: Mutex<HashMap<String, u64>>,
+}
+
+impl ReactiveWidgets {
+    /// Create a registry with no reactive cells.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Turn reactive mode on or off for a cell.
+    ///
+    /// Returns whether the setting changed.
+    pub fn set_enabled(&self, name: &str, enabled: bool) -> bool {
+        let mut cells = lock(&self.enabled);
+        if enabled {
+            cells.insert(name.to_string())
+        } else {
+            lock(&self.updates).remove(name);
+            cells.remove(name)
+        }
+    }
+
+    /// Whether a cell re-runs when its widgets change.
+    pub fn is_enabled(&self, name: &str) -> bool {
+        lock(&self.enabled).contains(name)
+    }
+
+    /// Names of cells in reactive mode.
+    pub fn cells(&self) -> HashSet<String> {
+        lock(&self.enabled).clone()
+    }
+
+    /// Record a widget update of a reactive cell.
+    ///
+    /// Returns a ticket to pass to [`is_latest`](Self::is_latest) once the
+    /// debounce delay has passed, or `None` if the cell is not reactive.
+    pub fn record_update(&self, name: &str) -> Option<u64> {
+        if !self.is_enabled(name) {
+            return None;
+        }
+        let mut updates = lock(&self.updates);
+        let count = updates.entry(name.to_string()).or_default();
+        *count += 1;
+        Some(*count)
+    }
+
+    /// Whether no update of the cell was recorded after `ticket`.
+    ///
+    /// Only the run for the latest update goes ahead; earlier ones are
+    /// superseded.
+    pub fn is_latest(&self, name: &str, ticket: u64) -> bool {
+        self.is_enabled(name) && lock(&self.updates).get(name) == Some(&ticket)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_latest_update_runs() {
+        let reactive = ReactiveWidgets::new();
+        let cell = "threshold";
+        assert_eq!(reactive.record_update(cell), None);
+
+        assert!(reactive.set_enabled(cell, true));
+        assert!(!reactive.set_enabled(cell, true));
+        let first = reactive.record_update(cell).unwrap();
+        let second = reactive.record_update(cell).unwrap();
+
+        assert!(!reactive.is_latest(cell, first));
+        assert!(reactive.is_latest(cell, second));
+
+        // Turning reactive mode off cancels the pending run
+        assert!(reactive.set_enabled(cell, false));
+        assert!(!reactive.is_latest(cell, second));
+    }
+}
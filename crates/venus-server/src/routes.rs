@@ -2,12 +2,13 @@
 
 use std::sync::Arc;
 use std::sync::atomic::Ordering;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use axum::{
     Router,
     extract::{
-        State, WebSocketUpgrade,
-        ws::{Message, WebSocket},
+        DefaultBodyLimit, State, WebSocketUpgrade,
+        ws::{CloseFrame, Message, WebSocket, close_code},
     },
     response::{IntoResponse, Json},
     routing::get,
@@ -16,16 +17,23 @@ use axum::{
 #[cfg(feature = "embedded-frontend")]
 use axum::extract::Path as AxumPath;
 
+use axum::http::HeaderValue;
 #[cfg(not(feature = "embedded-frontend"))]
 use axum::response::Html;
 use futures::{SinkExt, StreamExt};
-use tokio::sync::{Mutex as TokioMutex, RwLock};
-use tower_http::cors::CorsLayer;
+use tokio::sync::{Mutex as TokioMutex, RwLock, broadcast, mpsc};
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+use venus_core::compile::CompileAbortHandle;
 use venus_core::execute::ExecutorKillHandle;
 use venus_core::graph::CellId;
 
+use crate::ServerConfig;
+use crate::error::ServerError;
+use crate::in_flight::InFlightCells;
 use crate::lsp;
-use crate::protocol::{CellState, ClientMessage, ServerMessage};
+use crate::presence::{PresenceGuard, PresenceRegistry};
+use crate::protocol::{CellState, ClientMessage, PROTOCOL_VERSION, ServerMessage};
+use crate::reactive::{REACTIVE_WIDGET_DEBOUNCE, ReactiveWidgets};
 use crate::session::{InterruptFlag, NotebookSession};
 
 #[cfg(feature = "embedded-frontend")]
@@ -42,10 +50,24 @@ pub struct AppState {
     /// Flag to track if execution was interrupted by user.
     /// Uses AtomicBool so it can be checked without locks.
     pub interrupted: InterruptFlag,
+    /// Abort handle of the session's compiler, usable without the session lock.
+    pub compile_abort: CompileAbortHandle,
+    /// Maximum size in bytes of a single WebSocket message.
+    pub max_message_size: usize,
+    /// Connected clients.
+    pub presence: PresenceRegistry,
+    /// Cells that re-run when their widgets change.
+    pub reactive: ReactiveWidgets,
+    /// Cells being compiled or run, with the notebook state to send clients
+    /// that connect meanwhile.
+    pub in_flight: InFlightCells,
+    /// Sender of the session's broadcast channel, so clients can subscribe
+    /// while the session is locked.
+    pub broadcaster: broadcast::Sender<ServerMessage>,
 }
 
 /// Create the router with all routes.
-pub fn create_router(state: Arc<AppState>) -> Router {
+pub fn create_router(state: Arc<AppState>, config: &ServerConfig) -> Router {
     let router = Router::new()
         .route("/health", get(health_handler))
         .route("/ws", get(ws_handler))
@@ -62,7 +84,46 @@ pub fn create_router(state: Arc<AppState>) -> Router {
     #[cfg(not(feature = "embedded-frontend"))]
     let router = router.route("/", get(index_handler));
 
-    router.layer(CorsLayer::permissive()).with_state(state)
+    router
+        .layer(DefaultBodyLimit::max(config.max_message_size))
+        .layer(cors_layer(config))
+        .with_state(state)
+}
+
+/// Build the CORS layer for the server configuration.
+///
+/// Explicit `allowed_origins` always win. Without them, loopback binds stay
+/// permissive for local development, while network binds only allow
+/// same-origin requests.
+fn cors_layer(config: &ServerConfig) -> CorsLayer {
+    if !config.allowed_origins.is_empty() {
+        let origins: Vec<HeaderValue> = config
+            .allowed_origins
+            .iter()
+            .filter_map(|origin| match HeaderValue::from_str(origin) {
+                Ok(value) => Some(value),
+                Err(_) => {
+                    tracing::warn!("Ignoring invalid allowed origin: {}", origin);
+                    None
+                }
+            })
+            .collect();
+
+        return CorsLayer::new()
+            .allow_origin(AllowOrigin::list(origins))
+            .allow_methods(Any)
+            .allow_headers(Any);
+    }
+
+    if config.is_loopback() {
+        CorsLayer::permissive()
+    } else {
+        tracing::warn!(
+            "Server bound to {} without allowed origins; cross-origin requests are rejected",
+            config.host
+        );
+        CorsLayer::new()
+    }
 }
 
 /// Index page handler (fallback when embedded-frontend is disabled).
@@ -143,8 +204,14 @@ async fn graph_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse
 }
 
 /// WebSocket upgrade handler.
+///
+/// Frames and messages larger than `max_message_size` are rejected by the
+/// WebSocket layer before they are buffered or deserialized.
 async fn ws_handler(ws: WebSocketUpgrade, State(state): State<Arc<AppState>>) -> impl IntoResponse {
-    ws.on_upgrade(|socket| handle_websocket(socket, state))
+    let max_size = state.max_message_size;
+    ws.max_message_size(max_size)
+        .max_frame_size(max_size)
+        .on_upgrade(|socket| handle_websocket(socket, state))
 }
 
 /// LSP WebSocket upgrade handler.
@@ -152,11 +219,11 @@ async fn lsp_handler(
     ws: WebSocketUpgrade,
     State(state): State<Arc<AppState>>,
 ) -> impl IntoResponse {
-    let notebook_path = {
+    let (notebook_path, broadcaster) = {
         let session = state.session.read().await;
-        session.path().to_path_buf()
+        (session.path().to_path_buf(), session.broadcaster())
     };
-    ws.on_upgrade(move |socket| lsp::handle_lsp_websocket(socket, notebook_path))
+    ws.on_upgrade(move |socket| lsp::handle_lsp_websocket(socket, notebook_path, broadcaster))
 }
 
 /// Handle WebSocket connection.
@@ -164,16 +231,17 @@ async fn handle_websocket(socket: WebSocket, state: Arc<AppState>) {
     let (mut sender, mut receiver) = socket.split();
 
     // Subscribe to server messages
-    let mut rx = {
-        let session = state.session.read().await;
-        session.subscribe()
-    };
+    let (mut rx, tx) = (state.broadcaster.subscribe(), state.broadcaster.clone());
 
-    // Send initial state
-    {
-        let session = state.session.read().await;
-        let initial_state = session.get_state();
-        if let Ok(json) = serde_json::to_string(&initial_state) {
+    // Register the connection; the guard removes it however this handler exits
+    let client = state.presence.join();
+    let _presence_guard = PresenceGuard::new(&state.presence, client.id, tx.clone());
+    let _ = tx.send(state.presence.message());
+
+    // Send the current view: state with outputs and widget values, undo
+    // state, history positions and any cell locks held by other clients
+    for msg in current_view(&state).await {
+        if let Ok(json) = serde_json::to_string(&msg) {
             let _ = sender.send(Message::Text(json.into())).await;
         }
     }
@@ -181,9 +249,10 @@ async fn handle_websocket(socket: WebSocket, state: Arc<AppState>) {
     // Spawn task to forward server messages to client
     let sender = Arc::new(tokio::sync::Mutex::new(sender));
     let sender_clone = sender.clone();
+    let client_id = client.id;
 
     let forward_task = tokio::spawn(async move {
-        while let Ok(msg) = rx.recv().await {
+        while let Some(msg) = next_forwarded_message(&mut rx, client_id).await {
             if let Ok(json) = serde_json::to_string(&msg) {
                 let mut sender = sender_clone.lock().await;
                 if sender.send(Message::Text(json.into())).await.is_err() {
@@ -193,6 +262,12 @@ async fn handle_websocket(socket: WebSocket, state: Arc<AppState>) {
         }
     });
 
+    // Whether the client has sent `Hello` (or we already warned that it didn't)
+    let mut handshake_seen = false;
+
+    // LSP tunneled through this connection, started by the first `Lsp` message
+    let mut lsp_tunnel: Option<mpsc::UnboundedSender<String>> = None;
+
     // Handle incoming client messages
     while let Some(result) = receiver.next().await {
         tracing::debug!("Received WebSocket message");
@@ -200,9 +275,29 @@ async fn handle_websocket(socket: WebSocket, state: Arc<AppState>) {
             Ok(Message::Text(text)) => {
                 tracing::debug!("Parsing message: {}", &text[..text.len().min(100)]);
                 match serde_json::from_str::<ClientMessage>(&text) {
+                    Ok(ClientMessage::Hello { protocol_version }) => {
+                        handshake_seen = true;
+                        if !handle_hello(protocol_version, client.id, &sender).await {
+                            break;
+                        }
+                    }
+                    Ok(ClientMessage::Lsp { payload }) => {
+                        let tunnel = match &lsp_tunnel {
+                            Some(tunnel) => tunnel,
+                            None => lsp_tunnel.insert(start_lsp_tunnel(&state, &sender).await),
+                        };
+                        let _ = tunnel.send(payload.to_string());
+                    }
                     Ok(msg) => {
+                        if !handshake_seen {
+                            handshake_seen = true;
+                            tracing::warn!(
+                                "Client did not send a hello handshake; connecting without \
+                                 protocol negotiation is deprecated"
+                            );
+                        }
                         tracing::debug!("Dispatching message: {:?}", std::mem::discriminant(&msg));
-                        handle_client_message(msg, &state, &sender).await;
+                        handle_client_message(msg, &state, client.id, &sender).await;
                     }
                     Err(e) => {
                         tracing::warn!("Failed to parse client message: {} (input: {})", e, text);
@@ -218,7 +313,19 @@ async fn handle_websocket(socket: WebSocket, state: Arc<AppState>) {
             }
             Ok(Message::Close(_)) => break,
             Err(e) => {
+                // Oversized messages surface here; let the client know why
+                // the connection is being dropped.
                 tracing::warn!("WebSocket error: {}", e);
+                send_message(
+                    &sender,
+                    &ServerMessage::Error {
+                        message: format!(
+                            "WebSocket error (messages are limited to {} bytes): {}",
+                            state.max_message_size, e
+                        ),
+                    },
+                )
+                .await;
                 break;
             }
             _ => {}
@@ -228,6 +335,86 @@ async fn handle_websocket(socket: WebSocket, state: Arc<AppState>) {
     // Ensure forward task terminates cleanly
     forward_task.abort();
     let _ = forward_task.await;
+
+    // Closing the tunnel's inbound channel stops its rust-analyzer
+    drop(lsp_tunnel);
+}
+
+/// Start rust-analyzer for LSP tunneled through a `/ws` connection.
+///
+/// Returns the channel for the client's LSP messages. rust-analyzer's
+/// messages are sent to `sender` as [`ServerMessage::Lsp`].
+async fn start_lsp_tunnel(
+    state: &Arc<AppState>,
+    sender: &Arc<tokio::sync::Mutex<futures::stream::SplitSink<WebSocket, Message>>>,
+) -> mpsc::UnboundedSender<String> {
+    let (notebook_path, broadcaster) = {
+        let session = state.session.read().await;
+        (session.path().to_path_buf(), session.broadcaster())
+    };
+    let (inbound_tx, inbound_rx) = mpsc::unbounded_channel();
+    let (outbound_tx, mut outbound_rx) = mpsc::unbounded_channel::<String>();
+
+    tokio::spawn(lsp::run_lsp_session(
+        notebook_path,
+        broadcaster,
+        inbound_rx,
+        outbound_tx,
+    ));
+
+    let sender = sender.clone();
+    tokio::spawn(async move {
+        while let Some(text) = outbound_rx.recv().await {
+            let Ok(payload) = serde_json::from_str(&text) else {
+                continue;
+            };
+            send_message(&sender, &ServerMessage::Lsp { payload }).await;
+        }
+    });
+
+    inbound_tx
+}
+
+/// Answer a client `Hello`.
+///
+/// Returns `false` if the client's protocol version is incompatible, in which
+/// case the connection has been closed with an explanatory reason.
+async fn handle_hello(
+    client_version: u32,
+    client_id: u64,
+    sender: &Arc<tokio::sync::Mutex<futures::stream::SplitSink<WebSocket, Message>>>,
+) -> bool {
+    let accepted = client_version == PROTOCOL_VERSION;
+    send_message(
+        sender,
+        &ServerMessage::Welcome {
+            protocol_version: PROTOCOL_VERSION,
+            accepted,
+            client_id,
+        },
+    )
+    .await;
+
+    if !accepted {
+        tracing::warn!(
+            "Rejecting client with protocol version {} (server speaks {})",
+            client_version,
+            PROTOCOL_VERSION
+        );
+        let reason = format!(
+            "Unsupported protocol version {} (server speaks {})",
+            client_version, PROTOCOL_VERSION
+        );
+        let mut sender = sender.lock().await;
+        let _ = sender
+            .send(Message::Close(Some(CloseFrame {
+                code: close_code::PROTOCOL,
+                reason: reason.into(),
+            })))
+            .await;
+    }
+
+    accepted
 }
 
 /// Send a server message through the WebSocket.
@@ -284,20 +471,125 @@ async fn handle_cell_operation<T, F, R>(
     };
 }
 
-/// Handle a client message.
+/// Messages that bring a client's view up to date: notebook state (with
+/// outputs), undo/redo state, history positions and cell locks.
+///
+/// While a cell is compiling or running the session stays locked, so the
+/// last recorded state, listing the cells in flight, is sent instead.
+async fn current_view(state: &AppState) -> Vec<ServerMessage> {
+    let snapshot = match state.session.try_read() {
+        Err(_) if !state.in_flight.is_empty() => state.in_flight.snapshot(),
+        _ => None,
+    };
+    let mut messages = match snapshot {
+        Some(snapshot) => vec![snapshot],
+        None => {
+            let session = state.session.read().await;
+            let mut messages = vec![session.get_state(), session.get_undo_redo_state()];
+            messages.extend(session.history_positions());
+            messages.extend(session.warnings());
+            messages.push(session.breakpoints_message());
+            messages
+        }
+    };
+    messages.extend(state.presence.cell_lock_messages());
+    // Reactive cells are kept by name; announce them under their current IDs
+    let reactive = state.reactive.cells();
+    let reactive_ids: Vec<CellId> = match messages.first() {
+        Some(ServerMessage::NotebookState { cells, .. }) => cells
+            .iter()
+            .filter_map(|cell| match cell {
+                CellState::Code { id, name, .. } if reactive.contains(name) => Some(*id),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    messages.extend(
+        reactive_ids
+            .into_iter()
+            .map(|cell_id| ServerMessage::WidgetReactive {
+                cell_id,
+                enabled: true,
+            }),
+    );
+    messages
+}
+
+/// Receive the next message to forward to a client.
+///
+/// A receiver that lagged behind yields `Resync` in place of the dropped
+/// messages and keeps receiving; `None` means the channel is closed.
+async fn next_forwarded_message(
+    rx: &mut broadcast::Receiver<ServerMessage>,
+    client_id: u64,
+) -> Option<ServerMessage> {
+    match rx.recv().await {
+        Ok(msg) => Some(msg),
+        Err(broadcast::error::RecvError::Lagged(missed)) => {
+            tracing::warn!("Client {} lagged, missed {} messages", client_id, missed);
+            Some(ServerMessage::Resync { missed })
+        }
+        Err(broadcast::error::RecvError::Closed) => None,
+    }
+}
+
+/// Handle a client message from the connection identified by `client_id`.
 async fn handle_client_message(
     msg: ClientMessage,
     state: &Arc<AppState>,
+    client_id: u64,
     sender: &Arc<tokio::sync::Mutex<futures::stream::SplitSink<WebSocket, Message>>>,
 ) {
+    // Reject edits to cells locked by another client
+    let locked = if msg.edits_all_cells() {
+        state.presence.cell_locked_by_other(client_id)
+    } else {
+        msg.edited_cells().into_iter().find_map(|id| {
+            state
+                .presence
+                .cell_lock_holder(id)
+                .filter(|holder| *holder != client_id)
+                .map(|holder| (id, holder))
+        })
+    };
+    if let Some((cell_id, holder)) = locked {
+        send_message(
+            sender,
+            &ServerMessage::Error {
+                message: format!("Cell {} is locked by client {}", cell_id.as_usize(), holder),
+            },
+        )
+        .await;
+        return;
+    }
+
     match msg {
+        ClientMessage::Hello { .. } | ClientMessage::Lsp { .. } => {
+            // Handshake and LSP tunnel are handled per-connection in handle_websocket
+        }
+
+        ClientMessage::Ping { nonce } => {
+            let server_time = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64;
+            send_message(sender, &ServerMessage::Pong { nonce, server_time }).await;
+        }
+
         ClientMessage::GetState => {
             let session = state.session.read().await;
             let state_msg = session.get_state();
             send_message(sender, &state_msg).await;
         }
 
-        ClientMessage::ExecuteCell { cell_id } => {
+        ClientMessage::Resync => {
+            for msg in current_view(state).await {
+                send_message(sender, &msg).await;
+            }
+        }
+
+        ClientMessage::ExecuteCell { cell_id, force } => {
             // Spawn execution in a separate task so interrupt can be processed
             let state_clone = state.clone();
 
@@ -308,7 +600,7 @@ async fn handle_client_message(
                     let rt = tokio::runtime::Handle::current();
                     rt.block_on(async {
                         let mut session = state_for_blocking.session.write().await;
-                        session.execute_cell(cell_id).await
+                        session.execute_cell(cell_id, force).await
                     })
                 })
                 .await;
@@ -325,7 +617,38 @@ async fn handle_client_message(
             });
         }
 
-        ClientMessage::ExecuteAll => {
+        ClientMessage::ExecuteCellWith {
+            cell_id,
+            widget_overrides,
+        } => {
+            // Same as ExecuteCell: run in a separate task so interrupt can be processed
+            let state_clone = state.clone();
+
+            tokio::spawn(async move {
+                let exec_result = tokio::task::spawn_blocking(move || {
+                    let rt = tokio::runtime::Handle::current();
+                    rt.block_on(async {
+                        let mut session = state_clone.session.write().await;
+                        session
+                            .execute_cell_with(cell_id, false, widget_overrides)
+                            .await
+                    })
+                })
+                .await;
+
+                match exec_result {
+                    Ok(Ok(())) => {}
+                    Ok(Err(e)) => {
+                        tracing::debug!("Execution error: {}", e);
+                    }
+                    Err(e) => {
+                        tracing::error!("Task join error: {}", e);
+                    }
+                }
+            });
+        }
+
+        ClientMessage::ExecuteAll { force } => {
             // Spawn execution in a separate task so interrupt can be processed
             let state_clone = state.clone();
 
@@ -336,7 +659,7 @@ async fn handle_client_message(
                     let rt = tokio::runtime::Handle::current();
                     rt.block_on(async {
                         let mut session = state_for_blocking.session.write().await;
-                        session.execute_all().await
+                        session.execute_all(force).await
                     })
                 })
                 .await;
@@ -353,6 +676,226 @@ async fn handle_client_message(
             });
         }
 
+        ClientMessage::SetBreakpoint { cell_id, enabled } => {
+            let mut session = state.session.write().await;
+            match session.set_breakpoint(cell_id, enabled) {
+                Ok(()) => {
+                    let msg = session.breakpoints_message();
+                    session.broadcast(msg);
+                }
+                Err(e) => {
+                    send_message(
+                        sender,
+                        &ServerMessage::Error {
+                            message: e.to_string(),
+                        },
+                    )
+                    .await;
+                }
+            }
+        }
+
+        ClientMessage::Continue | ClientMessage::StepExecution => {
+            // Spawn so that an interrupt can still be processed during the run
+            let state_clone = state.clone();
+            let sender = sender.clone();
+            let step = matches!(msg, ClientMessage::StepExecution);
+
+            tokio::spawn(async move {
+                let exec_result = tokio::task::spawn_blocking(move || {
+                    let rt = tokio::runtime::Handle::current();
+                    rt.block_on(async {
+                        let mut session = state_clone.session.write().await;
+                        if step {
+                            session.step_execution().await
+                        } else {
+                            session.continue_execution().await
+                        }
+                    })
+                })
+                .await;
+
+                match exec_result {
+                    Ok(Ok(())) => {}
+                    Ok(Err(ServerError::InvalidOperation(message))) => {
+                        send_message(&sender, &ServerMessage::Error { message }).await;
+                    }
+                    Ok(Err(e)) => {
+                        tracing::debug!("Execution error: {}", e);
+                    }
+                    Err(e) => {
+                        tracing::error!("Task join error: {}", e);
+                    }
+                }
+            });
+        }
+
+        ClientMessage::SweepCell {
+            cell_id,
+            widget_id,
+            values,
+        } => {
+            // Spawn so that an interrupt can still be processed during the sweep
+            let state_clone = state.clone();
+            let sender = sender.clone();
+
+            tokio::spawn(async move {
+                let sweep_result = tokio::task::spawn_blocking(move || {
+                    let rt = tokio::runtime::Handle::current();
+                    rt.block_on(async {
+                        let mut session = state_clone.session.write().await;
+                        session.sweep_cell(cell_id, &widget_id, values)
+                    })
+                })
+                .await;
+
+                let msg = match sweep_result {
+                    Ok(Ok((results, interrupted))) => ServerMessage::SweepResults {
+                        cell_id,
+                        results,
+                        interrupted,
+                    },
+                    Ok(Err(e)) => ServerMessage::Error {
+                        message: format!("Sweep failed: {}", e),
+                    },
+                    Err(e) => {
+                        tracing::error!("Task join error: {}", e);
+                        return;
+                    }
+                };
+                send_message(&sender, &msg).await;
+            });
+        }
+
+        ClientMessage::RunAndExport { dark, force } => {
+            // Spawn so that an interrupt can still be processed during the run
+            let state_clone = state.clone();
+            let sender = sender.clone();
+
+            tokio::spawn(async move {
+                let export_result = tokio::task::spawn_blocking(move || {
+                    let rt = tokio::runtime::Handle::current();
+                    rt.block_on(async {
+                        let mut session = state_clone.session.write().await;
+                        session.run_and_export(dark, force).await
+                    })
+                })
+                .await;
+
+                let msg = match export_result {
+                    Ok(Ok(html)) => ServerMessage::ExportReady { html },
+                    Ok(Err(ServerError::ExecutionAborted)) => ServerMessage::Error {
+                        message: "Export cancelled: execution was interrupted".to_string(),
+                    },
+                    Ok(Err(e)) => ServerMessage::Error {
+                        message: format!("Export failed: {}", e),
+                    },
+                    Err(e) => {
+                        tracing::error!("Task join error: {}", e);
+                        return;
+                    }
+                };
+                send_message(&sender, &msg).await;
+            });
+        }
+
+        ClientMessage::RunFrom { cell_id, force } => {
+            // Spawn execution in a separate task so interrupt can be processed
+            let state_clone = state.clone();
+
+            tokio::spawn(async move {
+                // Use spawn_blocking because execute_from does synchronous IPC
+                let state_for_blocking = state_clone.clone();
+                let exec_result = tokio::task::spawn_blocking(move || {
+                    let rt = tokio::runtime::Handle::current();
+                    rt.block_on(async {
+                        let mut session = state_for_blocking.session.write().await;
+                        session.execute_from(cell_id, force).await
+                    })
+                })
+                .await;
+
+                match exec_result {
+                    Ok(Ok(())) => {}
+                    Ok(Err(e)) => {
+                        tracing::debug!("Execution error: {}", e);
+                    }
+                    Err(e) => {
+                        tracing::error!("Task join error: {}", e);
+                    }
+                }
+            });
+        }
+
+        ClientMessage::ExecuteSelection { source, deps } => {
+            // Spawn execution in a separate task so interrupt can be processed
+            let state_clone = state.clone();
+            let sender = sender.clone();
+
+            tokio::spawn(async move {
+                // Use spawn_blocking because compilation and IPC are synchronous
+                let exec_result = tokio::task::spawn_blocking(move || {
+                    let rt = tokio::runtime::Handle::current();
+                    rt.block_on(async {
+                        let mut session = state_clone.session.write().await;
+                        session.execute_selection(source, deps).await
+                    })
+                })
+                .await;
+
+                let msg = match exec_result {
+                    Ok(Ok(output)) => ServerMessage::SelectionResult {
+                        output: Some(output),
+                        error: None,
+                    },
+                    Ok(Err(e)) => ServerMessage::SelectionResult {
+                        output: None,
+                        error: Some(e.to_string()),
+                    },
+                    Err(e) => {
+                        tracing::error!("Task join error: {}", e);
+                        ServerMessage::SelectionResult {
+                            output: None,
+                            error: Some(e.to_string()),
+                        }
+                    }
+                };
+                send_message(&sender, &msg).await;
+            });
+        }
+
+        ClientMessage::ProfileCell { cell_id } => {
+            // Spawn execution in a separate task so interrupt can be processed
+            let state_clone = state.clone();
+            let sender = sender.clone();
+
+            tokio::spawn(async move {
+                // Use spawn_blocking because compilation and IPC are synchronous
+                let exec_result = tokio::task::spawn_blocking(move || {
+                    let rt = tokio::runtime::Handle::current();
+                    rt.block_on(async {
+                        let mut session = state_clone.session.write().await;
+                        session.profile_cell(cell_id).await
+                    })
+                })
+                .await;
+
+                let msg = match exec_result {
+                    Ok(Ok(profile)) => profile,
+                    Ok(Err(e)) => ServerMessage::Error {
+                        message: e.to_string(),
+                    },
+                    Err(e) => {
+                        tracing::error!("Task join error: {}", e);
+                        ServerMessage::Error {
+                            message: e.to_string(),
+                        }
+                    }
+                };
+                send_message(&sender, &msg).await;
+            });
+        }
+
         ClientMessage::ExecuteDirty => {
             // Spawn execution in a separate task so interrupt can be processed
             let state_clone = state.clone();
@@ -370,7 +913,7 @@ async fn handle_client_message(
                         let rt = tokio::runtime::Handle::current();
                         rt.block_on(async {
                             let mut session = state_for_blocking.session.write().await;
-                            session.execute_cell(cell_id).await
+                            session.execute_cell(cell_id, false).await
                         })
                     })
                     .await;
@@ -418,6 +961,20 @@ async fn handle_client_message(
             }
         }
 
+        ClientMessage::AbortCompile => {
+            // Like the kill handle, the abort handle works without the
+            // session lock held by the compiling task
+            if !state.compile_abort.abort() {
+                send_message(
+                    sender,
+                    &ServerMessage::Error {
+                        message: "No compilation in progress to abort".to_string(),
+                    },
+                )
+                .await;
+            }
+        }
+
         ClientMessage::Sync => {
             let session = state.session.read().await;
             let rs_path = session.path();
@@ -452,15 +1009,260 @@ async fn handle_client_message(
             send_message(sender, &state_msg).await;
         }
 
+        ClientMessage::GetNotebookStats => {
+            let session = state.session.read().await;
+            let stats_msg = session.get_notebook_stats();
+            send_message(sender, &stats_msg).await;
+        }
+
+        ClientMessage::GetServerInfo => {
+            let session = state.session.read().await;
+            let info_msg = session.get_server_info();
+            send_message(sender, &info_msg).await;
+        }
+
+        ClientMessage::GetCellDoc { cell_id } => {
+            let session = state.session.read().await;
+            let msg = match session.get_cell_doc(cell_id) {
+                Ok(html) => ServerMessage::CellDoc { cell_id, html },
+                Err(e) => ServerMessage::Error {
+                    message: e.to_string(),
+                },
+            };
+            send_message(sender, &msg).await;
+        }
+
+        ClientMessage::GetCellType { cell_id } => {
+            let session = state.session.read().await;
+            let msg = match session.cell_kind(cell_id) {
+                Some(kind) => ServerMessage::CellType { cell_id, kind },
+                None => ServerMessage::Error {
+                    message: ServerError::CellNotFound(cell_id).to_string(),
+                },
+            };
+            send_message(sender, &msg).await;
+        }
+
+        ClientMessage::GetCellSignature { cell_id } => {
+            let session = state.session.read().await;
+            let msg =
+                session
+                    .get_cell_signature(cell_id)
+                    .unwrap_or_else(|e| ServerMessage::Error {
+                        message: e.to_string(),
+                    });
+            send_message(sender, &msg).await;
+        }
+
+        ClientMessage::GetDefinitions => {
+            let session = state.session.read().await;
+            let definitions_msg = session.get_definitions();
+            send_message(sender, &definitions_msg).await;
+        }
+
+        ClientMessage::GetExecutionOrder => {
+            let session = state.session.read().await;
+            let msg = match session.execution_steps() {
+                Ok(steps) => ServerMessage::ExecutionOrder { steps },
+                Err(e) => ServerMessage::Error {
+                    message: e.to_string(),
+                },
+            };
+            send_message(sender, &msg).await;
+        }
+
+        ClientMessage::ExportSubgraph { cell_ids } => {
+            let session = state.session.read().await;
+            let msg = match session.export_subgraph(&cell_ids) {
+                Ok(source) => ServerMessage::SubgraphSource { source },
+                Err(e) => ServerMessage::Error {
+                    message: e.to_string(),
+                },
+            };
+            send_message(sender, &msg).await;
+        }
+
+        ClientMessage::ExportData => {
+            let session = state.session.read().await;
+            let cells = session.export_data(state.max_message_size);
+            let exported_at = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64;
+            send_message(sender, &ServerMessage::DataExport { exported_at, cells }).await;
+        }
+
+        ClientMessage::GetReferences { symbol } => {
+            let session = state.session.read().await;
+            let msg = ServerMessage::References {
+                cells: session.cell_references(&symbol),
+            };
+            send_message(sender, &msg).await;
+        }
+
+        ClientMessage::GarbageCollect => {
+            let mut session = state.session.write().await;
+            let msg = match session.garbage_collect() {
+                Ok(bytes_reclaimed) => ServerMessage::GarbageCollected { bytes_reclaimed },
+                Err(e) => ServerMessage::Error {
+                    message: e.to_string(),
+                },
+            };
+            send_message(sender, &msg).await;
+        }
+
+        ClientMessage::Lint { disabled } => {
+            let session = state.session.read().await;
+            let report_msg = session.lint(disabled);
+            send_message(sender, &report_msg).await;
+        }
+
+        ClientMessage::GetLogs { level } => {
+            let logs_msg = ServerMessage::Logs {
+                lines: crate::logs::global().lines(level),
+            };
+            send_message(sender, &logs_msg).await;
+        }
+
         ClientMessage::WidgetUpdate {
             cell_id,
             widget_id,
             value,
         } => {
             // Store the new widget value - does NOT trigger re-execution
+            // unless the cell is reactive
+            let name = {
+                let mut session = state.session.write().await;
+                session.update_widget_value(cell_id, widget_id, value);
+                session.cell_name(cell_id).ok()
+            };
+
+            if let Some((name, ticket)) =
+                name.and_then(|name| state.reactive.record_update(&name).map(|t| (name, t)))
+            {
+                let state_clone = state.clone();
+                tokio::spawn(async move {
+                    tokio::time::sleep(REACTIVE_WIDGET_DEBOUNCE).await;
+                    if !state_clone.reactive.is_latest(&name, ticket) {
+                        return;
+                    }
+
+                    let exec_result = tokio::task::spawn_blocking(move || {
+                        let rt = tokio::runtime::Handle::current();
+                        rt.block_on(async {
+                            let mut session = state_clone.session.write().await;
+                            // A newer update may have arrived while waiting for the lock
+                            if !state_clone.reactive.is_latest(&name, ticket) {
+                                return Ok(());
+                            }
+                            // The notebook may have been reloaded meanwhile
+                            let Some(cell_id) = session.cell_id_by_name(&name) else {
+                                return Ok(());
+                            };
+                            session.execute_with_dependents(cell_id).await
+                        })
+                    })
+                    .await;
+
+                    match exec_result {
+                        Ok(Ok(())) => {}
+                        Ok(Err(e)) => {
+                            tracing::debug!("Reactive execution error: {}", e);
+                        }
+                        Err(e) => {
+                            tracing::error!("Task join error: {}", e);
+                        }
+                    }
+                });
+            }
+        }
+
+        ClientMessage::SetWidgetReactive { cell_id, enabled } => {
+            let session = state.session.read().await;
+            match session.cell_name(cell_id) {
+                Ok(name) => {
+                    if state.reactive.set_enabled(&name, enabled) {
+                        session.broadcast(ServerMessage::WidgetReactive { cell_id, enabled });
+                    }
+                }
+                Err(e) => {
+                    send_message(
+                        sender,
+                        &ServerMessage::Error {
+                            message: e.to_string(),
+                        },
+                    )
+                    .await;
+                }
+            }
+        }
+
+        ClientMessage::GetWidgetState => {
+            let session = state.session.read().await;
+            let per_cell = session.widget_state();
+            send_message(sender, &ServerMessage::WidgetState { per_cell }).await;
+        }
+
+        ClientMessage::GetHistory { cell_id } => {
+            let session = state.session.read().await;
+            let entries = session.get_history(cell_id);
+            send_message(sender, &ServerMessage::CellHistory { cell_id, entries }).await;
+        }
+
+        ClientMessage::FindOrphanOutputs => {
+            let session = state.session.read().await;
+            let msg = ServerMessage::OrphanOutputs {
+                names: session.find_orphan_outputs(),
+                removed: false,
+            };
+            send_message(sender, &msg).await;
+        }
+
+        ClientMessage::CleanOrphanOutputs => {
             let mut session = state.session.write().await;
-            session.update_widget_value(cell_id, widget_id, value);
-            // No response needed - value is stored silently
+            let names = session.clean_orphan_outputs();
+            if !names.is_empty() {
+                tracing::info!("Removed {} orphaned outputs", names.len());
+            }
+            let msg = ServerMessage::OrphanOutputs {
+                names,
+                removed: true,
+            };
+            send_message(sender, &msg).await;
+        }
+
+        ClientMessage::GetBuildPaths => {
+            let session = state.session.read().await;
+            send_message(sender, &session.build_paths()).await;
+        }
+
+        ClientMessage::InspectOutput { cell_id } => {
+            let session = state.session.read().await;
+            let msg = match session.inspect_output(cell_id) {
+                Ok((type_name, decoded_json)) => ServerMessage::OutputInspection {
+                    cell_id,
+                    type_name,
+                    decoded_json,
+                },
+                Err(e) => ServerMessage::Error {
+                    message: e.to_string(),
+                },
+            };
+            send_message(sender, &msg).await;
+        }
+
+        ClientMessage::GetCompileOutput { cell_id } => {
+            let session = state.session.read().await;
+            let (errors, warnings) = session.get_compile_output(cell_id);
+            send_message(
+                sender,
+                &ServerMessage::CompileOutput {
+                    cell_id,
+                    errors,
+                    warnings,
+                },
+            )
+            .await;
         }
 
         ClientMessage::SelectHistory { cell_id, index } => {
@@ -531,6 +1333,45 @@ async fn handle_client_message(
             }
         }
 
+        ClientMessage::InsertCellAt { index } => {
+            let mut session = state.session.write().await;
+
+            match session.insert_cell_at(index) {
+                Ok(new_name) => {
+                    let new_cell_id = session
+                        .cell_states()
+                        .iter()
+                        .find(|(_, s)| s.name().unwrap_or("") == new_name)
+                        .map(|(id, _)| *id)
+                        .unwrap_or(CellId::new(0));
+
+                    send_message(
+                        sender,
+                        &ServerMessage::CellInserted {
+                            cell_id: new_cell_id,
+                            error: None,
+                        },
+                    )
+                    .await;
+
+                    let state_msg = session.get_state();
+                    session.broadcast(state_msg);
+                    let undo_state = session.get_undo_redo_state();
+                    session.broadcast(undo_state);
+                }
+                Err(e) => {
+                    send_message(
+                        sender,
+                        &ServerMessage::CellInserted {
+                            cell_id: CellId::new(0),
+                            error: Some(e.to_string()),
+                        },
+                    )
+                    .await;
+                }
+            }
+        }
+
         ClientMessage::DeleteCell { cell_id } => {
             let mut session = state.session.write().await;
 
@@ -743,6 +1584,165 @@ async fn handle_client_message(
             // OutputsCleared message already broadcast by clear_outputs()
         }
 
+        ClientMessage::BatchEdit { edits } => {
+            let mut session = state.session.write().await;
+            let edits = edits.into_iter().map(|e| (e.cell_id, e.source)).collect();
+            match session.batch_edit(edits) {
+                Ok(()) => {
+                    let state_msg = session.get_state();
+                    session.broadcast(state_msg);
+                    let undo_state = session.get_undo_redo_state();
+                    session.broadcast(undo_state);
+                }
+                Err(e) => {
+                    send_message(
+                        sender,
+                        &ServerMessage::Error {
+                            message: e.to_string(),
+                        },
+                    )
+                    .await;
+                }
+            }
+        }
+
+        ClientMessage::ClearCellOutput {
+            cell_id,
+            clear_history,
+        } => {
+            let mut session = state.session.write().await;
+            match session.clear_cell_output(cell_id, clear_history) {
+                Ok(_) => {
+                    let state_msg = session.get_state();
+                    session.broadcast(state_msg);
+                }
+                Err(e) => {
+                    send_message(
+                        sender,
+                        &ServerMessage::Error {
+                            message: e.to_string(),
+                        },
+                    )
+                    .await;
+                }
+            }
+        }
+
+        ClientMessage::SetBackend { release } => {
+            let mut session = state.session.write().await;
+            let result = session.set_backend(release);
+
+            // Report the backend actually in use, even if the switch failed
+            let msg = ServerMessage::BackendChanged {
+                release: session.is_release(),
+                error: result.as_ref().err().map(|e| e.to_string()),
+            };
+            send_message(sender, &msg).await;
+
+            if result.is_ok() {
+                let state_msg = session.get_state();
+                session.broadcast(state_msg);
+            }
+        }
+
+        ClientMessage::SetOptLevel { level } => {
+            let mut session = state.session.write().await;
+            let result = session.set_opt_level(level);
+
+            let msg = ServerMessage::OptLevelChanged {
+                level: session.opt_level(),
+                error: result.as_ref().err().map(|e| e.to_string()),
+            };
+            send_message(sender, &msg).await;
+
+            if result.is_ok() {
+                let state_msg = session.get_state();
+                session.broadcast(state_msg);
+            }
+        }
+
+        ClientMessage::ReloadFromDisk => {
+            let mut session = state.session.write().await;
+
+            handle_cell_operation(
+                &mut session,
+                |s| s.reload_from_disk(),
+                |result| ServerMessage::NotebookReloaded {
+                    error: result.err(),
+                },
+                sender,
+            )
+            .await;
+        }
+
+        ClientMessage::SetEnv { vars, unset } => {
+            let mut session = state.session.write().await;
+            match session.set_env(vars, &unset) {
+                Ok(names) => session.broadcast(ServerMessage::EnvSet { names }),
+                Err(e) => {
+                    send_message(
+                        sender,
+                        &ServerMessage::Error {
+                            message: e.to_string(),
+                        },
+                    )
+                    .await;
+                }
+            }
+        }
+
+        ClientMessage::SetWorkingDirectory { path } => {
+            let mut session = state.session.write().await;
+            match session.set_working_directory(&path) {
+                Ok(dir) => session.broadcast(ServerMessage::WorkingDirectorySet {
+                    path: dir.display().to_string(),
+                }),
+                Err(e) => {
+                    send_message(
+                        sender,
+                        &ServerMessage::Error {
+                            message: e.to_string(),
+                        },
+                    )
+                    .await;
+                }
+            }
+        }
+
+        ClientMessage::ReloadUniverse => {
+            // Spawn the build so other messages are processed meanwhile
+            let state_clone = state.clone();
+
+            tokio::spawn(async move {
+                // Use spawn_blocking because the cargo build is synchronous
+                let state_for_blocking = state_clone.clone();
+                let result = tokio::task::spawn_blocking(move || {
+                    let rt = tokio::runtime::Handle::current();
+                    rt.block_on(async {
+                        let mut session = state_for_blocking.session.write().await;
+                        let msg = match session.reload_universe() {
+                            Ok(dirty_cells) => ServerMessage::UniverseReloaded {
+                                dirty_cells,
+                                error: None,
+                            },
+                            Err(e) => ServerMessage::UniverseReloaded {
+                                dirty_cells: vec![],
+                                error: Some(e.to_string()),
+                            },
+                        };
+                        session.broadcast(msg);
+                        let state_msg = session.get_state();
+                        session.broadcast(state_msg);
+                    })
+                })
+                .await;
+
+                if let Err(e) = result {
+                    tracing::error!("Task join error: {}", e);
+                }
+            });
+        }
+
         ClientMessage::RenameCell {
             cell_id,
             new_display_name,
@@ -840,6 +1840,36 @@ async fn handle_client_message(
             .await;
         }
 
+        ClientMessage::ConvertCell { cell_id, to } => {
+            let mut session = state.session.write().await;
+
+            handle_cell_operation(
+                &mut session,
+                |s| s.convert_cell(cell_id, to),
+                |result| ServerMessage::CellConverted {
+                    cell_id,
+                    to,
+                    error: result.err(),
+                },
+                sender,
+            )
+            .await;
+        }
+
+        ClientMessage::SetMetadata { metadata } => {
+            let mut session = state.session.write().await;
+
+            handle_cell_operation(
+                &mut session,
+                |s| s.set_metadata(metadata),
+                |result| ServerMessage::MetadataSet {
+                    error: result.err(),
+                },
+                sender,
+            )
+            .await;
+        }
+
         ClientMessage::DeleteMarkdownCell { cell_id } => {
             let mut session = state.session.write().await;
 
@@ -855,6 +1885,75 @@ async fn handle_client_message(
             .await;
         }
 
+        ClientMessage::MoveCellToIndex { cell_id, index } => {
+            let mut session = state.session.write().await;
+
+            handle_cell_operation(
+                &mut session,
+                |s| s.move_cell_to_index(cell_id, index),
+                |result| ServerMessage::CellMoved {
+                    cell_id,
+                    error: result.err(),
+                },
+                sender,
+            )
+            .await;
+        }
+
+        ClientMessage::ReorderToExecutionOrder { confirm: false } => {
+            let mut session = state.session.write().await;
+
+            match session.reorder_to_execution_order(false) {
+                Ok(moved) => {
+                    if moved > 0 {
+                        let message = format!(
+                            "Reordering rewrites the notebook file and moves {} cell(s). \
+                             Confirm to apply; it can be undone.",
+                            moved
+                        );
+                        send_message(sender, &ServerMessage::Warning { message }).await;
+                    }
+                    let msg = ServerMessage::NotebookReordered {
+                        moved,
+                        applied: false,
+                        error: None,
+                    };
+                    send_message(sender, &msg).await;
+                }
+                Err(e) => {
+                    let msg = ServerMessage::NotebookReordered {
+                        moved: 0,
+                        applied: false,
+                        error: Some(e.to_string()),
+                    };
+                    send_message(sender, &msg).await;
+                }
+            }
+        }
+
+        ClientMessage::ReorderToExecutionOrder { confirm: true } => {
+            let mut session = state.session.write().await;
+
+            handle_cell_operation(
+                &mut session,
+                |s| s.reorder_to_execution_order(true),
+                |result| match result {
+                    Ok(moved) => ServerMessage::NotebookReordered {
+                        moved,
+                        applied: moved > 0,
+                        error: None,
+                    },
+                    Err(error) => ServerMessage::NotebookReordered {
+                        moved: 0,
+                        applied: false,
+                        error: Some(error),
+                    },
+                },
+                sender,
+            )
+            .await;
+        }
+
         ClientMessage::MoveMarkdownCell { cell_id, direction } => {
             let mut session = state.session.write().await;
 
@@ -921,12 +2020,30 @@ async fn handle_client_message(
             .await;
         }
 
-        ClientMessage::DeleteDefinitionCell { cell_id } => {
+        ClientMessage::EditUnparsedRegion {
+            cell_id,
+            new_content,
+        } => {
+            let mut session = state.session.write().await;
+
+            handle_cell_operation(
+                &mut session,
+                |s| s.edit_unparsed_region(cell_id, new_content),
+                |result| ServerMessage::UnparsedRegionEdited {
+                    cell_id,
+                    error: result.err(),
+                },
+                sender,
+            )
+            .await;
+        }
+
+        ClientMessage::DeleteDefinitionCell { cell_id, force } => {
             let mut session = state.session.write().await;
 
             handle_cell_operation(
                 &mut session,
-                |s| s.delete_definition_cell(cell_id),
+                |s| s.delete_definition_cell(cell_id, force),
                 |result| ServerMessage::DefinitionCellDeleted {
                     cell_id,
                     error: result.err(),
@@ -950,11 +2067,144 @@ async fn handle_client_message(
             )
             .await;
         }
+
+        ClientMessage::AcquireCellLock { cell_id } => {
+            match state.presence.acquire_cell_lock(cell_id, client_id) {
+                Ok(()) => {
+                    let session = state.session.read().await;
+                    let _ = session.broadcaster().send(ServerMessage::CellLocked {
+                        cell_id,
+                        client_id: Some(client_id),
+                    });
+                }
+                Err(holder) => {
+                    send_message(
+                        sender,
+                        &ServerMessage::Error {
+                            message: format!(
+                                "Cell {} is locked by client {}",
+                                cell_id.as_usize(),
+                                holder
+                            ),
+                        },
+                    )
+                    .await;
+                }
+            }
+        }
+
+        ClientMessage::ReleaseCellLock { cell_id } => {
+            if state.presence.release_cell_lock(cell_id, client_id) {
+                let session = state.session.read().await;
+                let _ = session.broadcaster().send(ServerMessage::CellLocked {
+                    cell_id,
+                    client_id: None,
+                });
+            }
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::{Request, header};
+    use tower::ServiceExt;
+
+    /// Send a GET /health with the given Origin through a CORS-wrapped router
+    /// and return the `access-control-allow-origin` header, if any.
+    async fn allowed_origin(config: &ServerConfig, origin: &str) -> Option<String> {
+        let app = Router::new()
+            .route("/health", get(health_handler))
+            .layer(cors_layer(config));
+        let response = app
+            .oneshot(
+                Request::get("/health")
+                    .header(header::ORIGIN, origin)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        response
+            .headers()
+            .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+            .map(|v| v.to_str().unwrap().to_string())
+    }
+
+    #[tokio::test]
+    async fn test_lagged_receiver_resyncs_and_keeps_receiving() {
+        let (tx, mut rx) = broadcast::channel(2);
+        for nonce in 0..5 {
+            tx.send(ServerMessage::Pong {
+                nonce,
+                server_time: 0,
+            })
+            .unwrap();
+        }
+
+        assert!(matches!(
+            next_forwarded_message(&mut rx, 1).await,
+            Some(ServerMessage::Resync { missed: 3 })
+        ));
+        for expected in 3..5 {
+            assert!(matches!(
+                next_forwarded_message(&mut rx, 1).await,
+                Some(ServerMessage::Pong { nonce, .. }) if nonce == expected
+            ));
+        }
+
+        tx.send(ServerMessage::Pong {
+            nonce: 5,
+            server_time: 0,
+        })
+        .unwrap();
+        assert!(matches!(
+            next_forwarded_message(&mut rx, 1).await,
+            Some(ServerMessage::Pong { nonce: 5, .. })
+        ));
+
+        drop(tx);
+        assert!(next_forwarded_message(&mut rx, 1).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cors_permissive_on_loopback() {
+        let config = ServerConfig::default();
+        assert_eq!(
+            allowed_origin(&config, "http://example.com")
+                .await
+                .as_deref(),
+            Some("*")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cors_rejects_cross_origin_on_network_bind() {
+        let config = ServerConfig {
+            host: "0.0.0.0".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(allowed_origin(&config, "http://example.com").await, None);
+    }
+
+    #[tokio::test]
+    async fn test_cors_allowed_origins() {
+        let config = ServerConfig {
+            host: "0.0.0.0".to_string(),
+            allowed_origins: vec!["http://trusted.example".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            allowed_origin(&config, "http://trusted.example")
+                .await
+                .as_deref(),
+            Some("http://trusted.example")
+        );
+        assert_eq!(allowed_origin(&config, "http://evil.example").await, None);
+    }
+
     #[test]
     fn test_health_json() {
         let health = serde_json::json!({
@@ -149,6 +149,23 @@ pub async fn download() -> Result<PathBuf, String> {
     Ok(binary_path)
 }
 
+/// Whether an LSP message from rust-analyzer signals that it is ready.
+///
+/// rust-analyzer reports readiness through `experimental/serverStatus` with
+/// `quiescent: true` when the client opted into status notifications, and
+/// otherwise ends the `rustAnalyzer/Indexing` progress.
+pub fn is_ready_notification(message: &serde_json::Value) -> bool {
+    let params = &message["params"];
+    match message["method"].as_str() {
+        Some("experimental/serverStatus") => params["quiescent"].as_bool() == Some(true),
+        Some("$/progress") => {
+            params["token"].as_str() == Some("rustAnalyzer/Indexing")
+                && params["value"]["kind"].as_str() == Some("end")
+        }
+        _ => false,
+    }
+}
+
 /// Get the target triple for the current platform.
 fn get_target_triple() -> &'static str {
     #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
@@ -198,4 +215,35 @@ mod tests {
         let triple = get_target_triple();
         assert!(!triple.is_empty());
     }
+
+    #[test]
+    fn test_is_ready_notification() {
+        let quiescent = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "experimental/serverStatus",
+            "params": { "health": "ok", "quiescent": true }
+        });
+        let busy = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "experimental/serverStatus",
+            "params": { "health": "ok", "quiescent": false }
+        });
+        let indexing_done = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "$/progress",
+            "params": { "token": "rustAnalyzer/Indexing", "value": { "kind": "end" } }
+        });
+        let indexing = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "$/progress",
+            "params": { "token": "rustAnalyzer/Indexing", "value": { "kind": "report" } }
+        });
+        let response = serde_json::json!({ "jsonrpc": "2.0", "id": 1, "result": null });
+
+        assert!(is_ready_notification(&quiescent));
+        assert!(is_ready_notification(&indexing_done));
+        assert!(!is_ready_notification(&busy));
+        assert!(!is_ready_notification(&indexing));
+        assert!(!is_ready_notification(&response));
+    }
 }
@@ -3,7 +3,7 @@
 //! Manages the state of an active notebook session including
 //! compilation, execution, and output caching.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -12,20 +12,38 @@ use std::time::{Duration, Instant};
 
 use tokio::sync::{RwLock, broadcast};
 use venus_core::compile::{
-    CellCompiler, CompilationResult, CompilerConfig, ToolchainManager, UniverseBuilder,
+    BuildEvent, CellCompiler, CompilationResult, CompileAbortHandle, CompilerConfig,
+    DependencyParser, ToolchainManager, UniverseBuilder,
 };
-use venus_core::execute::{ExecutorKillHandle, ProcessExecutor};
+use venus_core::execute::ExecutorKillHandle;
 use venus_core::graph::{
-    CellId, CellInfo, CellParser, CellType, DefinitionCell, GraphEngine, MarkdownCell,
-    MoveDirection, SourceEditor,
+    CellId, CellInfo, CellParser, CellType, DefinitionCell, DefinitionChange, Dependency,
+    GraphEngine, MarkdownCell, MoveDirection, OutputFormat, SourceEditor, SourceSpan,
+    UnparsedRegion, affected_symbols, classify_definition_change, defined_symbols,
+    extract_subgraph, references_any, unqualified_name,
 };
+use venus_core::ipc::WorkerSpawnConfig;
+use venus_core::lint::{self, LintConfig, LintRule};
+use venus_core::markdown;
 use venus_core::paths::NotebookDirs;
 use venus_core::widgets::{WidgetDef, WidgetValue};
+use venus_sync::{NotebookMetadata, RsParser, parse_metadata_block, write_metadata_block};
 
+use crate::ServerConfig;
 use crate::error::{ServerError, ServerResult};
-use crate::protocol::{CellOutput, CellState, CellStatus, ServerMessage};
+use crate::executor::{ExecutorKind, SessionExecutor};
+use crate::export::{CellExport, generate_html};
+use crate::in_flight::InFlightCells;
+use crate::presence::CellLocks;
+use crate::protocol::{
+    CellKind, CellOutput, CellParam, CellState, CellStatus, CellWidgetState, CompileErrorInfo,
+    DefinitionBlock, ExecutionStep, ExportedOutput, HistoryEntryInfo, PROTOCOL_VERSION,
+    STREAMED_OUTPUT_THRESHOLD, ServerMessage, SweepRun, output_chunks,
+};
+use crate::settings::{NotebookSettings, SETTINGS_FILE};
 use crate::undo::{UndoManager, UndoableOperation};
-use venus_core::state::BoxedOutput;
+use crate::virtual_file::VirtualFileWriter;
+use venus_core::state::{BoxedOutput, OutputCache};
 
 /// Find workspace root by walking up from notebook path to find Cargo.toml.
 /// Returns (workspace_root, cargo_toml_path).
@@ -46,11 +64,6 @@ fn find_workspace_root(notebook_path: &Path) -> (Option<PathBuf>, Option<PathBuf
 /// Shared interrupt flag that can be checked without locks.
 pub type InterruptFlag = Arc<AtomicBool>;
 
-/// Capacity for the broadcast channel.
-/// 256 messages should be sufficient for normal notebook operation.
-/// If clients fall behind, older messages will be dropped.
-const MESSAGE_CHANNEL_CAPACITY: usize = 256;
-
 /// A notebook session.
 pub struct NotebookSession {
     /// Path to the notebook file.
@@ -59,6 +72,15 @@ pub struct NotebookSession {
     /// Path to workspace Cargo.toml (if found).
     workspace_cargo_toml: Option<PathBuf>,
 
+    /// Directories holding the notebook's build artifacts, caches and state.
+    dirs: NotebookDirs,
+
+    /// Whether to write the virtual `notebook.rs` that rust-analyzer reads.
+    lsp_virtual_file: bool,
+
+    /// Debounced writer of the virtual `notebook.rs`.
+    virtual_file: VirtualFileWriter,
+
     /// Parsed code cells.
     cells: Vec<CellInfo>,
 
@@ -68,6 +90,9 @@ pub struct NotebookSession {
     /// Parsed definition cells (imports, types, helpers).
     definition_cells: Vec<DefinitionCell>,
 
+    /// Top-level items that failed to parse.
+    unparsed_regions: Vec<UnparsedRegion>,
+
     /// Dependency graph.
     graph: GraphEngine,
 
@@ -86,29 +111,74 @@ pub struct NotebookSession {
     /// Dependencies hash for cache invalidation.
     deps_hash: u64,
 
+    /// Hash of the cargo dependency block the universe was last built from,
+    /// and of the block last reported by `dependencies_changed_on_disk`.
+    dependency_block_hash: u64,
+    reported_dependency_block_hash: u64,
+
+    /// Line of a top-level `fn main`, which Venus never runs.
+    main_function_line: Option<usize>,
+
     /// Broadcast channel for server messages.
     tx: broadcast::Sender<ServerMessage>,
 
     /// Whether an execution is in progress.
     executing: bool,
 
+    /// Cells being compiled or run, shared with connection handlers that
+    /// cannot take the session lock meanwhile.
+    in_flight: InFlightCells,
+
+    /// Client locks on cells, moved to the cells' new IDs on reload.
+    cell_locks: CellLocks,
+
+    /// Names of cells that pause a run after they execute.
+    breakpoints: HashSet<String>,
+
+    /// Remaining `(cell, force)` steps of a run paused at a breakpoint.
+    paused_run: Option<VecDeque<(CellId, bool)>>,
+
+    /// Whether a cell was interrupted since the flag was last cleared.
+    /// Lets `run_and_export` tell a complete run from a cancelled one.
+    run_interrupted: bool,
+
     /// Cached cell outputs for dependency passing.
     /// Maps cell ID to its serialized output.
     cell_outputs: HashMap<CellId, Arc<BoxedOutput>>,
 
-    /// Process-based executor for isolated cell execution.
-    /// Uses worker processes that can be killed for true interruption.
-    executor: ProcessExecutor,
+    /// Executor for cells. Process-based by default, using worker processes
+    /// that can be killed for true interruption.
+    executor: SessionExecutor,
 
     /// Optional execution timeout for execute_all.
     /// After this duration, the executor kills the current worker.
+    /// Also the default per-cell timeout for cells without `timeout_ms`.
     execution_timeout: Option<Duration>,
 
+    /// How often to report the executing worker's CPU and memory usage.
+    /// `None` disables sampling.
+    resource_sample_interval: Option<Duration>,
+
+    /// Whether a failed cell keeps its last good output for dependents.
+    use_stale_on_failure: bool,
+
+    /// Extra environment variables for the worker processes.
+    env: BTreeMap<String, String>,
+
+    /// Working directory of the worker processes.
+    working_dir: PathBuf,
+
+    /// Time taken by the last successful cell compilation (for `ProfileCell`).
+    last_compile_time: Option<Duration>,
+
     /// Shared flag indicating if current execution was interrupted by user.
     /// When true, errors should be reported as "interrupted" not as failures.
     /// This is shared with AppState so interrupt handler can set it.
     interrupted: InterruptFlag,
 
+    /// Handle for killing the running rustc or cargo process.
+    compile_abort: CompileAbortHandle,
+
     /// Widget values per cell.
     /// Maps cell ID -> widget ID -> current value.
     widget_values: HashMap<CellId, HashMap<String, WidgetValue>>,
@@ -124,17 +194,112 @@ pub struct NotebookSession {
     /// Current history index per cell.
     cell_history_index: HashMap<CellId, usize>,
 
+    /// Diagnostics from the last real compilation of each cell (errors, warnings).
+    /// Kept so clients can re-show them without recompiling.
+    compile_output: HashMap<CellId, (Vec<CompileErrorInfo>, Vec<CompileErrorInfo>)>,
+
     /// Undo/redo manager for cell operations.
     undo_manager: UndoManager,
 
     /// Pending edits from the editor (not yet saved to disk).
     /// These are saved to disk when the cell is executed.
     pending_edits: HashMap<CellId, String>,
+
+    /// Outputs keyed by the inputs that produced them.
+    /// Lets cells whose source and inputs are unchanged skip execution.
+    output_cache: OutputCache,
+
+    /// Notebook metadata parsed from the module doc.
+    metadata: NotebookMetadata,
+
+    /// Settings persisted to `.venus/settings.toml`.
+    settings: NotebookSettings,
+
+    /// Path of the settings file.
+    settings_path: PathBuf,
+}
+
+/// A notebook file parsed into cells, ready to replace the session's cells.
+struct ParsedNotebook {
+    source: String,
+    metadata: NotebookMetadata,
+    cells: Vec<CellInfo>,
+    markdown_cells: Vec<MarkdownCell>,
+    definition_cells: Vec<DefinitionCell>,
+    unparsed_regions: Vec<UnparsedRegion>,
+    graph: GraphEngine,
+}
+
+impl ParsedNotebook {
+    /// Parse notebook source, resolve its dependency graph and assign cell IDs.
+    ///
+    /// Top-level items that don't parse are skipped and kept as unparsed
+    /// regions, as long as the rest of the file parses.
+    fn parse(source: String, path: &Path) -> ServerResult<Self> {
+        let metadata = RsParser::new().parse_metadata(&source);
+
+        // Parse cells (code, markdown, and definitions)
+        let parse_result = CellParser::new().parse_str_partial(&source, path)?;
+        let mut cells = parse_result.code_cells;
+        let mut markdown_cells = parse_result.markdown_cells;
+        let mut definition_cells = parse_result.definition_cells;
+        let mut unparsed_regions = parse_result.unparsed_regions;
+
+        // Build graph and update code cells with real IDs (parser returns placeholder IDs)
+        let mut graph = GraphEngine::new();
+        for cell in &mut cells {
+            let real_id = graph.add_cell(cell.clone());
+            cell.id = real_id;
+        }
+        graph.resolve_dependencies()?;
+
+        // Assign unique IDs to markdown cells (they don't participate in the dependency graph)
+        let mut next_id = if let Some(max_code_id) = cells.iter().map(|c| c.id.as_usize()).max() {
+            max_code_id + 1
+        } else {
+            0
+        };
+        for md_cell in &mut markdown_cells {
+            md_cell.id = CellId::new(next_id);
+            next_id += 1;
+        }
+
+        // Assign unique IDs to definition cells
+        for def_cell in &mut definition_cells {
+            def_cell.id = CellId::new(next_id);
+            next_id += 1;
+        }
+
+        // Assign unique IDs to unparsed regions
+        for region in &mut unparsed_regions {
+            region.id = CellId::new(next_id);
+            next_id += 1;
+        }
+
+        Ok(Self {
+            source,
+            metadata,
+            cells,
+            markdown_cells,
+            definition_cells,
+            unparsed_regions,
+            graph,
+        })
+    }
 }
 
+/// Function name of the ephemeral cell compiled by `execute_selection`.
+const SELECTION_CELL_NAME: &str = "__venus_selection";
+
 /// Maximum number of history entries per cell.
 const MAX_HISTORY_PER_CELL: usize = 10;
 
+/// Maximum number of characters in a history entry preview.
+const HISTORY_PREVIEW_CHARS: usize = 80;
+
+/// Interval between `CellExecuting` reports while a cell runs.
+const EXECUTING_REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
 /// A single history entry for a cell's execution.
 #[derive(Clone)]
 pub struct OutputHistoryEntry {
@@ -144,6 +309,8 @@ pub struct OutputHistoryEntry {
     pub display: CellOutput,
     /// Timestamp when this execution completed.
     pub timestamp: u64,
+    /// Execution time in milliseconds.
+    pub duration_ms: u64,
 }
 
 /// Thread-safe session handle.
@@ -161,6 +328,25 @@ impl NotebookSession {
         path: impl AsRef<Path>,
         interrupted: InterruptFlag,
     ) -> ServerResult<(Self, broadcast::Receiver<ServerMessage>)> {
+        Self::with_config(path, interrupted, &ServerConfig::default())
+    }
+
+    /// Create a new notebook session with the startup options of `config`.
+    ///
+    /// The broadcast channel buffers `message_channel_capacity` messages per
+    /// subscriber; subscribers that fall further behind miss the oldest
+    /// messages. Build artifacts, caches and state go under `build_dir` if
+    /// given (see [`NotebookDirs::with_build_root`]), the virtual
+    /// `notebook.rs` is only written if `lsp_virtual_file` is set, and cells
+    /// run with the `executor` kind.
+    pub fn with_config(
+        path: impl AsRef<Path>,
+        interrupted: InterruptFlag,
+        config: &ServerConfig,
+    ) -> ServerResult<(Self, broadcast::Receiver<ServerMessage>)> {
+        let capacity = config.message_channel_capacity;
+        let lsp_virtual_file = config.lsp_virtual_file;
+        let executor_kind = config.executor;
         let path = path.as_ref().canonicalize().map_err(|e| ServerError::Io {
             path: path.as_ref().to_path_buf(),
             message: e.to_string(),
@@ -170,42 +356,81 @@ impl NotebookSession {
         let (_workspace_root, workspace_cargo_toml) = find_workspace_root(&path);
 
         // Set up directories using shared abstraction
-        let dirs = NotebookDirs::from_notebook_path(&path)?;
+        let notebook_dir = path.parent().unwrap_or(Path::new("."));
+        let dirs = NotebookDirs::with_build_root(notebook_dir, config.build_dir.as_deref())?;
 
         let toolchain = ToolchainManager::new()?;
-        let config = CompilerConfig::for_notebook(&dirs);
 
-        let (tx, rx) = broadcast::channel(MESSAGE_CHANNEL_CAPACITY);
+        // Restore settings from the last time the notebook was open
+        let settings_path = dirs.venus_dir.join(SETTINGS_FILE);
+        let settings = NotebookSettings::load(&settings_path);
+        let mut config = if settings.release {
+            CompilerConfig::for_notebook_release(&dirs)
+        } else {
+            CompilerConfig::for_notebook(&dirs)
+        };
+        if let Some(level) = settings.opt_level {
+            config.opt_level = level;
+        }
+
+        let (tx, rx) = broadcast::channel(capacity);
+
+        // Create the executor (process-based with a warm worker pool by default)
+        let executor = SessionExecutor::new(executor_kind, &dirs.state_dir)?;
 
-        // Create process executor with warm worker pool
-        let executor = ProcessExecutor::new(&dirs.state_dir)?;
+        // Cells run in the notebook's directory unless told otherwise
+        let working_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
 
         let mut session = Self {
             path,
             workspace_cargo_toml,
+            dirs,
+            lsp_virtual_file,
+            virtual_file: VirtualFileWriter::new(virtual_notebook_path(&config)),
             cells: Vec::new(),
             markdown_cells: Vec::new(),
             definition_cells: Vec::new(),
+            unparsed_regions: Vec::new(),
             graph: GraphEngine::new(),
             cell_states: HashMap::new(),
             toolchain,
             config,
             universe_path: None,
             deps_hash: 0,
+            dependency_block_hash: 0,
+            reported_dependency_block_hash: 0,
+            main_function_line: None,
             tx,
             executing: false,
+            in_flight: InFlightCells::new(),
+            cell_locks: CellLocks::new(),
+            breakpoints: HashSet::new(),
+            paused_run: None,
+            run_interrupted: false,
             cell_outputs: HashMap::new(),
             executor,
-            execution_timeout: None,
+            execution_timeout: settings.timeout(),
+            resource_sample_interval: None,
+            use_stale_on_failure: false,
+            env: BTreeMap::new(),
+            working_dir,
+            last_compile_time: None,
             interrupted,
+            compile_abort: CompileAbortHandle::new(),
             widget_values: HashMap::new(),
             widget_defs: HashMap::new(),
             cell_output_history: HashMap::new(),
+            compile_output: HashMap::new(),
             cell_history_index: HashMap::new(),
             undo_manager: UndoManager::new(),
             pending_edits: HashMap::new(),
+            output_cache: OutputCache::new(),
+            metadata: NotebookMetadata::default(),
+            settings,
+            settings_path,
         };
 
+        session.executor.set_worker_config(session.worker_config());
         session.reload()?;
 
         Ok((session, rx))
@@ -221,11 +446,56 @@ impl NotebookSession {
         self.tx.subscribe()
     }
 
+    /// Get a handle for broadcasting server messages without the session lock.
+    pub fn broadcaster(&self) -> broadcast::Sender<ServerMessage> {
+        self.tx.clone()
+    }
+
     /// Get a cell by ID.
     fn get_cell(&self, cell_id: CellId) -> Option<&CellInfo> {
         self.cells.iter().find(|c| c.id == cell_id)
     }
 
+    /// Name of the code cell with the given ID.
+    pub fn cell_name(&self, cell_id: CellId) -> ServerResult<String> {
+        self.get_cell(cell_id)
+            .map(|c| c.name.clone())
+            .ok_or_else(|| self.cell_kind_error(cell_id, CellKind::Code))
+    }
+
+    /// ID of the code cell with the given name, if there is one.
+    pub fn cell_id_by_name(&self, name: &str) -> Option<CellId> {
+        self.cells.iter().find(|c| c.name == name).map(|c| c.id)
+    }
+
+    /// Kind of the cell with the given ID, if there is one.
+    pub fn cell_kind(&self, cell_id: CellId) -> Option<CellKind> {
+        if self.cells.iter().any(|c| c.id == cell_id) {
+            Some(CellKind::Code)
+        } else if self.markdown_cells.iter().any(|m| m.id == cell_id) {
+            Some(CellKind::Markdown)
+        } else if self.definition_cells.iter().any(|d| d.id == cell_id) {
+            Some(CellKind::Definition)
+        } else if self.unparsed_regions.iter().any(|r| r.id == cell_id) {
+            Some(CellKind::Unparsed)
+        } else {
+            None
+        }
+    }
+
+    /// Error for an operation on `cell_id` that needs a cell of the
+    /// `expected` kind but did not find one.
+    fn cell_kind_error(&self, cell_id: CellId, expected: CellKind) -> ServerError {
+        match self.cell_kind(cell_id) {
+            Some(actual) if actual != expected => ServerError::WrongCellKind {
+                cell_id,
+                actual,
+                expected,
+            },
+            _ => ServerError::CellNotFound(cell_id),
+        }
+    }
+
     /// Set the status of a code cell.
     fn set_cell_status(&mut self, cell_id: CellId, status: CellStatus) {
         if let Some(CellState::Code {
@@ -235,6 +505,8 @@ impl NotebookSession {
         {
             *cell_status = status;
         }
+        // Clients connecting mid-run get the last state patched with this
+        self.in_flight.set_status(cell_id, status);
     }
 
     /// Broadcast a server message, ignoring send failures.
@@ -243,58 +515,138 @@ impl NotebookSession {
     }
 
     /// Reload the notebook from disk.
+    ///
+    /// The new contents are only applied once they parse and their dependency
+    /// graph resolves. On a syntax error the previous cells stay in place and
+    /// a `ParseError` is broadcast, so the UI stays usable while the file is
+    /// fixed.
     pub fn reload(&mut self) -> ServerResult<()> {
-        let source = std::fs::read_to_string(&self.path)?;
-
-        // Parse cells (code, markdown, and definitions)
-        let mut parser = CellParser::new();
-        let parse_result = parser.parse_file(&self.path)?;
-        self.cells = parse_result.code_cells;
-        self.markdown_cells = parse_result.markdown_cells;
-        self.definition_cells = parse_result.definition_cells;
-
-        // Build graph and update code cells with real IDs (parser returns placeholder IDs)
-        self.graph = GraphEngine::new();
-        for cell in &mut self.cells {
-            let real_id = self.graph.add_cell(cell.clone());
-            cell.id = real_id;
-        }
-        self.graph.resolve_dependencies()?;
-
-        // Assign unique IDs to markdown cells (they don't participate in the dependency graph)
-        let mut next_id =
-            if let Some(max_code_id) = self.cells.iter().map(|c| c.id.as_usize()).max() {
-                max_code_id + 1
-            } else {
-                0
-            };
-        for md_cell in &mut self.markdown_cells {
-            md_cell.id = CellId::new(next_id);
-            next_id += 1;
-        }
+        let parsed = self.parse_notebook()?;
+        self.apply_parsed(parsed)
+    }
 
-        // Assign unique IDs to definition cells
-        for def_cell in &mut self.definition_cells {
-            def_cell.id = CellId::new(next_id);
-            next_id += 1;
+    /// Read and parse the notebook file, broadcasting `ParseError` if it
+    /// is not valid Rust.
+    fn parse_notebook(&self) -> ServerResult<ParsedNotebook> {
+        let source = std::fs::read_to_string(&self.path)?;
+        if let Err(error) = CellParser::check_syntax(&source) {
+            self.broadcast(ServerMessage::ParseError {
+                message: error.message,
+                line: error.line,
+                column: error.column,
+            });
         }
+        ParsedNotebook::parse(source, &self.path)
+    }
 
-        // Write virtual notebook.rs file for LSP analysis BEFORE building universe
-        // This ensures the file exists when universe is compiled (lib.rs includes `pub mod notebook;`)
-        if let Err(e) = self.write_virtual_notebook_file() {
-            tracing::warn!("Failed to write virtual notebook file: {}", e);
-        }
+    /// Replace the session's cells with a parsed notebook and rebuild the universe.
+    ///
+    /// The universe is built first, so if that fails the session is unchanged.
+    fn apply_parsed(&mut self, parsed: ParsedNotebook) -> ServerResult<()> {
+        let universe = self.build_universe(&parsed)?;
+        self.install_parsed(parsed, universe);
+        Ok(())
+    }
 
+    /// Build the universe for a parsed notebook, returning its path and
+    /// dependency hash.
+    ///
+    /// Nothing in the session changes, so a failed or aborted build leaves
+    /// the current notebook in place.
+    fn build_universe(&self, parsed: &ParsedNotebook) -> ServerResult<(PathBuf, u64)> {
         // Build universe (always needed for bincode/serde runtime)
         let mut universe_builder = UniverseBuilder::new(
             self.config.clone(),
             self.toolchain.clone(),
             self.workspace_cargo_toml.clone(),
+        )
+        .with_abort_handle(self.compile_abort.clone())
+        .with_notebook_module(self.lsp_virtual_file);
+        universe_builder.parse_dependencies(&parsed.source, &parsed.definition_cells)?;
+
+        let result = {
+            let _hold = self.virtual_file.hold();
+            universe_builder.build()
+        };
+        let universe_path = self.check_universe_build(result)?;
+        Ok((universe_path, universe_builder.deps_hash()))
+    }
+
+    /// Replace the session's cells with a parsed notebook whose universe
+    /// has been built.
+    fn install_parsed(&mut self, parsed: ParsedNotebook, universe: (PathBuf, u64)) {
+        let ParsedNotebook {
+            source,
+            metadata,
+            cells,
+            markdown_cells,
+            definition_cells,
+            unparsed_regions,
+            graph,
+        } = parsed;
+
+        // Cell IDs are reassigned, so move locks to the cells' new IDs
+        let mut ids = HashMap::new();
+        pair_cell_ids(
+            self.cells.iter().map(|c| (c.id, c.name.as_str())),
+            cells.iter().map(|c| (c.id, c.name.as_str())),
+            &mut ids,
         );
-        universe_builder.parse_dependencies(&source, &self.definition_cells)?;
+        pair_cell_ids(
+            self.markdown_cells
+                .iter()
+                .map(|c| (c.id, c.content.as_str())),
+            markdown_cells.iter().map(|c| (c.id, c.content.as_str())),
+            &mut ids,
+        );
+        pair_cell_ids(
+            self.definition_cells
+                .iter()
+                .map(|c| (c.id, c.content.as_str())),
+            definition_cells.iter().map(|c| (c.id, c.content.as_str())),
+            &mut ids,
+        );
+        pair_cell_ids(
+            self.unparsed_regions
+                .iter()
+                .map(|c| (c.id, c.content.as_str())),
+            unparsed_regions.iter().map(|c| (c.id, c.content.as_str())),
+            &mut ids,
+        );
+        for msg in self.cell_locks.remap(&ids) {
+            let _ = self.tx.send(msg);
+        }
 
-        self.universe_path = Some(universe_builder.build()?);
-        self.deps_hash = universe_builder.deps_hash();
+        self.metadata = metadata;
+        self.cells = cells;
+        self.markdown_cells = markdown_cells;
+        self.definition_cells = definition_cells;
+        self.unparsed_regions = unparsed_regions;
+        self.graph = graph;
+        // Cell IDs are reassigned, so a paused run can no longer be resumed
+        self.paused_run = None;
+
+        let (universe_path, deps_hash) = universe;
+        self.universe_path = Some(universe_path);
+        self.deps_hash = deps_hash;
+        self.dependency_block_hash = dependency_block_hash(&source);
+        self.reported_dependency_block_hash = self.dependency_block_hash;
+
+        // Only announce `fn main` when it first appears, not on every reload
+        let main_function_line = CellParser::main_function_line(&self.definition_cells);
+        let main_appeared = main_function_line.is_some() && self.main_function_line.is_none();
+        self.main_function_line = main_function_line;
+        if main_appeared {
+            for warning in self.warnings() {
+                let _ = self.tx.send(warning);
+            }
+        }
+
+        // Queue the virtual notebook.rs file for LSP analysis; it is written
+        // once edits settle.
+        if self.lsp_virtual_file {
+            self.write_virtual_notebook_file();
+        }
 
         // Update cell states
         self.update_cell_states();
@@ -303,6 +655,218 @@ impl NotebookSession {
         // when the notebook file changes (e.g., editor auto-save). Broadcasting on every file
         // change causes the UI to refresh continuously. Instead, each cell operation (insert,
         // edit, delete, etc.) explicitly broadcasts state after calling reload().
+    }
+
+    /// Pass a universe build result through, announcing `CompileAborted`
+    /// if the build was aborted.
+    fn check_universe_build(&self, result: venus_core::Result<PathBuf>) -> ServerResult<PathBuf> {
+        if matches!(result, Err(venus_core::Error::CompilationAborted)) {
+            self.compile_abort.take_aborted();
+            self.broadcast(ServerMessage::CompileAborted { cell_id: None });
+        }
+        Ok(result?)
+    }
+
+    /// Standing warnings about the notebook, for clients that connect later.
+    pub fn warnings(&self) -> Vec<ServerMessage> {
+        self.main_function_line
+            .map(|line| ServerMessage::Warning {
+                message: main_function_warning(line),
+            })
+            .into_iter()
+            .collect()
+    }
+
+    /// Check whether the cargo dependency block now on disk, with hash
+    /// `on_disk_hash`, differs from the one the universe was built from.
+    ///
+    /// Meant for the file watcher, which does not reload on external changes
+    /// and hashes the file without holding the session lock. Returns `true`
+    /// once per new on-disk block, so repeated saves of the same change are
+    /// reported only once.
+    pub fn dependencies_changed_on_disk(&mut self, on_disk_hash: u64) -> bool {
+        if on_disk_hash == self.reported_dependency_block_hash {
+            return false;
+        }
+        self.reported_dependency_block_hash = on_disk_hash;
+        on_disk_hash != self.dependency_block_hash
+    }
+
+    /// Rebuild the universe from the current dependency block and definitions.
+    ///
+    /// The cached build is discarded, so cargo runs even if nothing in the
+    /// notebook changed (e.g. after a manual `cargo update`). Progress is
+    /// broadcast as `UniverseBuildProgress`. Outputs are kept, but cells with
+    /// output are marked dirty since the dependencies' ABI may have changed.
+    /// Returns the cells marked dirty.
+    pub fn reload_universe(&mut self) -> ServerResult<Vec<CellId>> {
+        if self.executing {
+            return Err(ServerError::ExecutionInProgress);
+        }
+
+        let source = std::fs::read_to_string(&self.path)?;
+        let mut universe_builder = UniverseBuilder::new(
+            self.config.clone(),
+            self.toolchain.clone(),
+            self.workspace_cargo_toml.clone(),
+        )
+        .with_abort_handle(self.compile_abort.clone())
+        .with_notebook_module(self.lsp_virtual_file);
+        universe_builder.parse_dependencies(&source, &self.definition_cells)?;
+        universe_builder.invalidate_cache()?;
+
+        let tx = self.tx.clone();
+        let hold = self.virtual_file.hold();
+        let result = universe_builder.build_with_progress(|event| {
+            if let BuildEvent::Compiling {
+                name,
+                current,
+                total,
+            } = event
+            {
+                let _ = tx.send(ServerMessage::UniverseBuildProgress {
+                    crate_name: name.clone(),
+                    current: *current,
+                    total: *total,
+                });
+            }
+        });
+        drop(hold);
+        self.universe_path = Some(self.check_universe_build(result)?);
+        self.deps_hash = universe_builder.deps_hash();
+        self.dependency_block_hash = dependency_block_hash(&source);
+        self.reported_dependency_block_hash = self.dependency_block_hash;
+
+        let dirty_cells: Vec<CellId> = self
+            .cells
+            .iter()
+            .filter(|c| self.cell_outputs.contains_key(&c.id))
+            .map(|c| c.id)
+            .collect();
+        for &cell_id in &dirty_cells {
+            if let Some(state) = self.cell_states.get_mut(&cell_id) {
+                state.set_dirty(true);
+            }
+        }
+
+        Ok(dirty_cells)
+    }
+
+    /// Re-read the notebook from disk, keeping outputs of unchanged cells.
+    ///
+    /// Unlike [`restart_kernel`](Self::restart_kernel), execution state is
+    /// preserved: cells whose source is identical to the previous version
+    /// keep their output, history and widgets (re-keyed to their new IDs).
+    /// Cells whose source changed lose their output, and their dependents
+    /// are marked dirty. If the file does not parse or the universe fails
+    /// to build, the session is left as it was.
+    pub fn reload_from_disk(&mut self) -> ServerResult<()> {
+        struct PreservedCell {
+            source: String,
+            output: Option<Arc<BoxedOutput>>,
+            state: Option<CellState>,
+            history: Option<Vec<OutputHistoryEntry>>,
+            history_index: Option<usize>,
+            compile_output: Option<(Vec<CompileErrorInfo>, Vec<CompileErrorInfo>)>,
+            widget_values: Option<HashMap<String, WidgetValue>>,
+            widget_defs: Option<Vec<WidgetDef>>,
+            pending_edit: Option<String>,
+        }
+
+        // Parse and build first, so a broken file or a failed universe build
+        // leaves the current state untouched
+        let parsed = self.parse_notebook()?;
+        let universe = self.build_universe(&parsed)?;
+
+        // Snapshot execution state by name BEFORE reload (IDs will change)
+        let mut preserved: HashMap<String, PreservedCell> = HashMap::new();
+        for cell in &self.cells {
+            preserved.insert(
+                cell.name.clone(),
+                PreservedCell {
+                    source: cell.source_code.clone(),
+                    output: self.cell_outputs.remove(&cell.id),
+                    state: self.cell_states.get(&cell.id).cloned(),
+                    history: self.cell_output_history.remove(&cell.id),
+                    history_index: self.cell_history_index.remove(&cell.id),
+                    compile_output: self.compile_output.remove(&cell.id),
+                    widget_values: self.widget_values.remove(&cell.id),
+                    widget_defs: self.widget_defs.remove(&cell.id),
+                    pending_edit: self.pending_edits.remove(&cell.id),
+                },
+            );
+        }
+
+        self.cell_outputs.clear();
+        self.cell_output_history.clear();
+        self.cell_history_index.clear();
+        self.compile_output.clear();
+        self.widget_values.clear();
+        self.widget_defs.clear();
+        self.pending_edits.clear();
+
+        self.install_parsed(parsed, universe);
+
+        let mut changed = Vec::new();
+        let cells: Vec<(CellId, String, String)> = self
+            .cells
+            .iter()
+            .map(|c| (c.id, c.name.clone(), c.source_code.clone()))
+            .collect();
+
+        for (cell_id, name, source) in cells {
+            let Some(old) = preserved.remove(&name) else {
+                continue;
+            };
+
+            if let Some(edit) = old.pending_edit {
+                self.pending_edits.insert(cell_id, edit);
+            }
+
+            if old.source != source {
+                if old.output.is_some() {
+                    changed.push(cell_id);
+                }
+                if let Some(state) = self.cell_states.get_mut(&cell_id) {
+                    state.set_status(CellStatus::Idle);
+                    state.clear_output();
+                    state.set_dirty(false);
+                }
+                continue;
+            }
+
+            if let Some(output) = old.output {
+                self.cell_outputs.insert(cell_id, output);
+            }
+            if let Some(history) = old.history {
+                self.cell_output_history.insert(cell_id, history);
+            }
+            if let Some(index) = old.history_index {
+                self.cell_history_index.insert(cell_id, index);
+            }
+            if let Some(diagnostics) = old.compile_output {
+                self.compile_output.insert(cell_id, diagnostics);
+            }
+            if let Some(values) = old.widget_values {
+                self.widget_values.insert(cell_id, values);
+            }
+            if let Some(defs) = old.widget_defs {
+                self.widget_defs.insert(cell_id, defs);
+            }
+            if let (Some(old_state), Some(state)) = (old.state, self.cell_states.get_mut(&cell_id))
+            {
+                state.set_status(old_state.status().unwrap_or_default());
+                state.set_dirty(old_state.is_dirty());
+                if let CellState::Code { output, .. } = old_state {
+                    state.set_output(output);
+                }
+            }
+        }
+
+        // Outputs downstream of a changed cell are now stale
+        for cell_id in changed {
+            self.mark_dependents_dirty_and_get(cell_id);
+        }
 
         Ok(())
     }
@@ -342,6 +906,44 @@ impl NotebookSession {
         })
     }
 
+    /// Render a cell's documentation as HTML.
+    ///
+    /// The display-name heading is stripped first, matching the description
+    /// shown in cell state. Cells without documentation render as empty.
+    pub fn get_cell_doc(&self, cell_id: CellId) -> ServerResult<String> {
+        let cell = self
+            .get_cell(cell_id)
+            .ok_or_else(|| self.cell_kind_error(cell_id, CellKind::Code))?;
+
+        Ok(Self::strip_display_name_from_description(&cell.doc_comment)
+            .map(|doc| markdown::to_html(&doc))
+            .unwrap_or_default())
+    }
+
+    /// Get a code cell's function signature, as extracted by the parser.
+    pub fn get_cell_signature(&self, cell_id: CellId) -> ServerResult<ServerMessage> {
+        let cell = self
+            .get_cell(cell_id)
+            .ok_or_else(|| self.cell_kind_error(cell_id, CellKind::Code))?;
+
+        Ok(ServerMessage::CellSignature {
+            cell_id,
+            name: cell.name.clone(),
+            params: cell
+                .dependencies
+                .iter()
+                .map(|dep| CellParam {
+                    name: dep.param_name.clone(),
+                    param_type: dep.param_type.clone(),
+                    is_ref: dep.is_ref,
+                    is_mut: dep.is_mut,
+                })
+                .collect(),
+            return_type: cell.return_type.clone(),
+            is_async: cell.is_async,
+        })
+    }
+
     /// Update cell states from parsed cells.
     fn update_cell_states(&mut self) {
         let mut new_states = HashMap::new();
@@ -376,6 +978,7 @@ impl NotebookSession {
                     .iter()
                     .map(|d| d.param_name.clone())
                     .collect(),
+                output_format: cell.output_format,
                 status,
                 output,
                 dirty,
@@ -403,6 +1006,17 @@ impl NotebookSession {
             new_states.insert(def_cell.id, state);
         }
 
+        // Add items that failed to parse
+        for region in &self.unparsed_regions {
+            let state = CellState::Unparsed {
+                id: region.id,
+                content: region.content.clone(),
+                message: region.message.clone(),
+                line: region.span.start_line,
+            };
+            new_states.insert(region.id, state);
+        }
+
         self.cell_states = new_states;
     }
 
@@ -430,14 +1044,24 @@ impl NotebookSession {
         all_cells
     }
 
-    fn write_virtual_notebook_file(&self) -> std::io::Result<()> {
-        use std::fs;
-
-        let dirs = NotebookDirs::from_notebook_path(&self.path)
-            .map_err(|e| std::io::Error::other(e.to_string()))?;
-        let universe_src = dirs.build_dir.join("universe").join("src");
-        fs::create_dir_all(&universe_src)?;
+    /// Every cell the UI shows, including unparsed regions, with its start
+    /// line, in the order they appear in the .rs file.
+    fn source_order_with_lines(&self) -> Vec<(CellId, usize)> {
+        let mut all_cells: Vec<(CellId, usize)> = self
+            .collect_cells_in_source_order()
+            .into_iter()
+            .map(|(id, line, _)| (id, line))
+            .chain(
+                self.unparsed_regions
+                    .iter()
+                    .map(|r| (r.id, r.span.start_line)),
+            )
+            .collect();
+        all_cells.sort_by_key(|(_, line)| *line);
+        all_cells
+    }
 
+    fn write_virtual_notebook_file(&self) {
         let mut lines = Vec::new();
         let all_cells = self.collect_cells_in_source_order();
 
@@ -460,19 +1084,32 @@ impl NotebookSession {
             }
         }
 
-        let content = lines.join("\n");
-        fs::write(universe_src.join("notebook.rs"), content)?;
+        self.virtual_file.update(lines.join("\n"));
+    }
 
-        Ok(())
+    /// Where the notebook's build artifacts, caches and state live.
+    pub fn build_paths(&self) -> ServerMessage {
+        let display = |path: &Path| path.display().to_string();
+        ServerMessage::BuildPaths {
+            build_dir: display(&self.dirs.build_dir),
+            cache_dir: display(&self.dirs.cache_dir),
+            state_dir: display(&self.dirs.state_dir),
+            universe_path: self.universe_path.as_deref().map(display),
+            virtual_notebook: display(&virtual_notebook_path(&self.config)),
+        }
     }
 
     /// Get the full notebook state.
-    /// Returns a snapshot of the current notebook state for UI rendering.
+    /// Returns a snapshot of the current notebook state for UI rendering,
+    /// and keeps it for clients that connect while a cell is in flight.
     /// Note: The virtual notebook.rs file for LSP is written during reload(), not here.
     pub fn get_state(&self) -> ServerMessage {
         // Source order: all cells (code + markdown + definition) in the order they appear in the .rs file
-        let all_cells = self.collect_cells_in_source_order();
-        let source_order: Vec<CellId> = all_cells.into_iter().map(|(id, _, _)| id).collect();
+        let source_order: Vec<CellId> = self
+            .source_order_with_lines()
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
 
         // Execution order: topologically sorted for dependency resolution (code cells only)
         let execution_order = match self.graph.topological_order() {
@@ -486,14 +1123,30 @@ impl NotebookSession {
         // Find workspace root by walking up from notebook path to find Cargo.toml
         let (workspace_root, cargo_toml_path) = find_workspace_root(&self.path);
 
-        ServerMessage::NotebookState {
+        let state = ServerMessage::NotebookState {
             path: self.path.display().to_string(),
             cells: self.cell_states.values().cloned().collect(),
             source_order,
             execution_order,
             workspace_root: workspace_root.map(|p| p.display().to_string()),
             cargo_toml_path: cargo_toml_path.map(|p| p.display().to_string()),
-        }
+            metadata: self.metadata.clone(),
+            in_flight: self.in_flight.cells(),
+        };
+        self.in_flight.record_state(&state);
+        state
+    }
+
+    /// Get the registry of cells being compiled or run.
+    ///
+    /// Like the kill handle, it can be used without the session lock.
+    pub fn in_flight_cells(&self) -> InFlightCells {
+        self.in_flight.clone()
+    }
+
+    /// Get the client locks on cells, shared with the presence registry.
+    pub fn cell_locks(&self) -> CellLocks {
+        self.cell_locks.clone()
     }
 
     /// Store a pending edit from the editor (not yet saved to disk).
@@ -507,12 +1160,30 @@ impl NotebookSession {
     ///
     /// Uses process isolation - the cell runs in a worker process that can
     /// be killed immediately for interruption.
-    pub async fn execute_cell(&mut self, cell_id: CellId) -> ServerResult<()> {
-        // Get cell name before potential reload (IDs change after reload!)
-        let cell_name = self
-            .get_cell(cell_id)
-            .map(|c| c.name.clone())
-            .ok_or(ServerError::CellNotFound(cell_id))?;
+    ///
+    /// If the cell's source and inputs (dependency outputs and widget values)
+    /// match a previous run, the cached output is reused without compiling or
+    /// executing. Pass `force` to always re-execute.
+    pub async fn execute_cell(&mut self, cell_id: CellId, force: bool) -> ServerResult<()> {
+        self.execute_cell_with(cell_id, force, HashMap::new()).await
+    }
+
+    /// Execute a cell with some widget values replaced for this run only.
+    ///
+    /// `widget_overrides` take precedence over the stored widget values,
+    /// which are left unchanged. Otherwise this is
+    /// [`execute_cell`](Self::execute_cell).
+    pub async fn execute_cell_with(
+        &mut self,
+        cell_id: CellId,
+        force: bool,
+        widget_overrides: HashMap<String, WidgetValue>,
+    ) -> ServerResult<()> {
+        // Get cell name before potential reload (IDs change after reload!)
+        let cell_name = self
+            .get_cell(cell_id)
+            .map(|c| c.name.clone())
+            .ok_or_else(|| self.cell_kind_error(cell_id, CellKind::Code))?;
 
         // Save pending edit to disk before executing
         if let Some(new_source) = self.pending_edits.remove(&cell_id) {
@@ -537,46 +1208,99 @@ impl NotebookSession {
         // Reset interrupted flag at the start of each execution
         self.interrupted.store(false, Ordering::SeqCst);
 
-        // Check if all dependencies have outputs available
-        let missing_deps: Vec<&str> = cell
-            .dependencies
-            .iter()
-            .filter(|dep| {
-                let producer = self.cells.iter().find(|c| c.name == dep.param_name);
-                match producer {
-                    Some(c) => !self.cell_outputs.contains_key(&c.id),
-                    None => true,
+        // Check if all dependencies have outputs available, telling apart
+        // dependencies that never ran from ones whose last run failed
+        let mut failed_deps = Vec::new();
+        let mut missing_deps = Vec::new();
+        for dep in &cell.dependencies {
+            let producer = self
+                .cells
+                .iter()
+                .find(|c| c.output_name() == dep.param_name);
+            match producer {
+                Some(c) if self.cell_outputs.contains_key(&c.id) => {}
+                Some(c)
+                    if self.cell_states.get(&c.id).and_then(|s| s.status())
+                        == Some(CellStatus::Error) =>
+                {
+                    failed_deps.push(dep.param_name.as_str())
                 }
-            })
-            .map(|d| d.param_name.as_str())
-            .collect();
+                _ => missing_deps.push(dep.param_name.as_str()),
+            }
+        }
 
-        if !missing_deps.is_empty() {
+        if !failed_deps.is_empty() || !missing_deps.is_empty() {
             self.set_cell_status(cell_id, CellStatus::Error);
             self.broadcast(ServerMessage::CellError {
                 cell_id,
-                error: format!(
-                    "Missing dependencies: {}. Run dependent cells first.",
-                    missing_deps.join(", ")
-                ),
+                error: unavailable_dependencies_message(&cell.name, &failed_deps, &missing_deps),
                 location: None,
             });
             self.executing = false;
             return Ok(());
         }
 
+        // Gather dependency outputs in the order the cell expects them
+        let inputs: Vec<Arc<BoxedOutput>> = cell
+            .dependencies
+            .iter()
+            .filter_map(|dep| {
+                self.cells
+                    .iter()
+                    .find(|c| c.output_name() == dep.param_name)
+                    .and_then(|c| self.cell_outputs.get(&c.id).cloned())
+            })
+            .collect();
+
+        // Get ALL widget values from all cells (widgets can be in any cell).
+        // Sorted so the serialized form is stable for the inputs hash.
+        let mut widget_values: BTreeMap<String, WidgetValue> =
+            self.get_all_widget_values().into_iter().collect();
+        widget_values.extend(widget_overrides);
+        let widget_values_json = if widget_values.is_empty() {
+            Vec::new()
+        } else {
+            serde_json::to_vec(&widget_values).unwrap_or_default()
+        };
+
+        let inputs_hash = venus_core::state::inputs_hash(
+            Self::cell_source_hash(&cell, self.deps_hash),
+            &inputs,
+            &widget_values_json,
+        );
+
+        if !force && let Some(cached) = self.output_cache.get(&cell.name, inputs_hash).cloned() {
+            self.complete_from_cache(cell_id, cached.output, cached.execution_time_ms);
+            self.executing = false;
+            return Ok(());
+        }
+
         // Compile
         self.set_cell_status(cell_id, CellStatus::Compiling);
 
-        let mut compiler = CellCompiler::new(self.config.clone(), self.toolchain.clone());
+        let mut compiler = CellCompiler::new(self.config.clone(), self.toolchain.clone())
+            .with_abort_handle(self.compile_abort.clone());
         if let Some(ref up) = self.universe_path {
             compiler = compiler.with_universe(up.clone());
         }
 
+        let compile_start = Instant::now();
         let result = compiler.compile(&cell, self.deps_hash);
+        if self.compile_abort.take_aborted() {
+            self.run_interrupted = true;
+            self.set_cell_status(cell_id, CellStatus::Idle);
+            self.broadcast(ServerMessage::CompileAborted {
+                cell_id: Some(cell_id),
+            });
+            self.executing = false;
+            return Ok(());
+        }
+        self.record_compile_output(cell_id, &result);
 
         match result {
             CompilationResult::Success(compiled) | CompilationResult::Cached(compiled) => {
+                self.last_compile_time = Some(compile_start.elapsed());
+
                 // Execute
                 self.set_cell_status(cell_id, CellStatus::Running);
                 self.broadcast(ServerMessage::CellStarted { cell_id });
@@ -584,34 +1308,98 @@ impl NotebookSession {
                 let start = Instant::now();
 
                 // Register the compiled cell with the executor
-                self.executor
+                let registered = self
+                    .executor
                     .register_cell(compiled, cell.dependencies.len());
 
-                // Gather dependency outputs in the order the cell expects them
-                let inputs: Vec<Arc<BoxedOutput>> = cell
-                    .dependencies
-                    .iter()
-                    .filter_map(|dep| {
-                        self.cells
-                            .iter()
-                            .find(|c| c.name == dep.param_name)
-                            .and_then(|c| self.cell_outputs.get(&c.id).cloned())
-                    })
-                    .collect();
+                // Kill the worker if the cell outlives its timeout. The
+                // per-cell `timeout_ms` overrides the session default.
+                let timeout = cell
+                    .timeout_ms
+                    .map(Duration::from_millis)
+                    .or(self.execution_timeout);
+                let timed_out = Arc::new(AtomicBool::new(false));
+                let watchdog =
+                    timeout
+                        .zip(self.executor.get_kill_handle())
+                        .map(|(timeout, kill_handle)| {
+                            let timed_out = timed_out.clone();
+                            let (done_tx, done_rx) = std::sync::mpsc::channel::<()>();
+                            std::thread::spawn(move || {
+                                if done_rx.recv_timeout(timeout)
+                                    == Err(std::sync::mpsc::RecvTimeoutError::Timeout)
+                                {
+                                    timed_out.store(true, Ordering::SeqCst);
+                                    kill_handle.kill();
+                                }
+                            });
+                            done_tx
+                        });
 
-                // Get ALL widget values from all cells (widgets can be in any cell)
-                let widget_values = self.get_all_widget_values();
-                let widget_values_json = if widget_values.is_empty() {
-                    Vec::new()
-                } else {
-                    serde_json::to_vec(&widget_values).unwrap_or_default()
+                // Report the worker's CPU and memory until the cell finishes
+                let sampler = self.resource_sample_interval.and_then(|interval| {
+                    let monitor = self.executor.resource_monitor()?;
+                    let tx = self.tx.clone();
+                    let (done_tx, done_rx) = std::sync::mpsc::channel::<()>();
+                    std::thread::spawn(move || {
+                        while done_rx.recv_timeout(interval)
+                            == Err(std::sync::mpsc::RecvTimeoutError::Timeout)
+                        {
+                            if let Some(sample) = monitor.sample() {
+                                let _ = tx.send(ServerMessage::ResourceUsage {
+                                    cell_id,
+                                    rss_bytes: sample.rss_bytes,
+                                    cpu_ms: sample.cpu_ms,
+                                });
+                            }
+                        }
+                    });
+                    Some(done_tx)
+                });
+
+                // Report the elapsed time so clients can show a live timer
+                let heartbeat = {
+                    let tx = self.tx.clone();
+                    let (done_tx, done_rx) = std::sync::mpsc::channel::<()>();
+                    std::thread::spawn(move || {
+                        while done_rx.recv_timeout(EXECUTING_REPORT_INTERVAL)
+                            == Err(std::sync::mpsc::RecvTimeoutError::Timeout)
+                        {
+                            let _ = tx.send(ServerMessage::CellExecuting {
+                                cell_id,
+                                elapsed_ms: start.elapsed().as_millis() as u64,
+                            });
+                        }
+                    });
+                    done_tx
                 };
 
-                // Execute the cell in an isolated worker process with widget values
-                let exec_result =
-                    self.executor
-                        .execute_cell_with_widgets(cell_id, &inputs, widget_values_json);
+                // Execute the cell with widget values, passing on the text
+                // of a large output as it arrives from the worker
+                let mut streamed_chunks = 0;
+                let exec_result = registered.and_then(|()| {
+                    let tx = self.tx.clone();
+                    self.executor.execute_cell_streamed(
+                        cell_id,
+                        &inputs,
+                        widget_values_json,
+                        &mut |chunk, is_final| {
+                            let _ = tx.send(ServerMessage::CellOutputAppended {
+                                cell_id,
+                                chunk: chunk.to_string(),
+                                seq: streamed_chunks,
+                                is_final,
+                            });
+                            streamed_chunks += 1;
+                        },
+                    )
+                });
 
+                // Dropping the senders stops the watchdog, the sampler and
+                // the elapsed time reports
+                drop(watchdog);
+                drop(sampler);
+                drop(heartbeat);
                 let duration = start.elapsed();
 
                 match exec_result {
@@ -627,6 +1415,12 @@ impl NotebookSession {
                         // Store output for dependent cells
                         let output_arc = Arc::new(output);
                         self.cell_outputs.insert(cell_id, output_arc.clone());
+                        self.output_cache.insert(
+                            cell.name.clone(),
+                            inputs_hash,
+                            output_arc.clone(),
+                            duration.as_millis() as u64,
+                        );
 
                         // Also store in executor state for consistency
                         self.executor
@@ -643,20 +1437,26 @@ impl NotebookSession {
 
                         let cell_output = CellOutput {
                             text: output_arc.display_text().map(|s| s.to_string()),
-                            html: None,
+                            html: output_arc.display_html().map(|s| s.to_string()),
                             image: None,
-                            json: None,
+                            json: self.output_json(cell_id, &output_arc),
                             widgets,
+                            stale: false,
                         };
 
                         // Add to history
-                        self.add_to_history(cell_id, output_arc.clone(), cell_output.clone());
+                        self.add_to_history(
+                            cell_id,
+                            output_arc.clone(),
+                            cell_output.clone(),
+                            duration.as_millis() as u64,
+                        );
 
                         if let Some(state) = self.cell_states.get_mut(&cell_id) {
-                            state.set_status(CellStatus::Success);
                             state.set_output(Some(cell_output.clone()));
                             state.set_dirty(false);
                         }
+                        self.set_cell_status(cell_id, CellStatus::Success);
 
                         // Mark dependents dirty if output changed
                         if output_changed {
@@ -666,10 +1466,26 @@ impl NotebookSession {
                             }
                         }
 
-                        self.broadcast(ServerMessage::CellCompleted {
+                        // Streamed text is not repeated in the completion
+                        let mut completed_output = cell_output;
+                        if streamed_chunks > 0 {
+                            completed_output.text = None;
+                        }
+                        self.broadcast_completed(
                             cell_id,
-                            duration_ms: duration.as_millis() as u64,
-                            output: Some(cell_output),
+                            duration.as_millis() as u64,
+                            completed_output,
+                            false,
+                        );
+                    }
+                    Err(_) if timed_out.load(Ordering::SeqCst) => {
+                        self.set_cell_status(cell_id, CellStatus::Idle);
+                        self.broadcast(ServerMessage::ExecutionAborted {
+                            cell_id: Some(cell_id),
+                            reason: Some(format!(
+                                "cell timed out after {} ms",
+                                timeout.unwrap_or_default().as_millis()
+                            )),
                         });
                     }
                     Err(e) => {
@@ -677,17 +1493,29 @@ impl NotebookSession {
                         let was_interrupted = self.interrupted.swap(false, Ordering::SeqCst);
                         if matches!(e, venus_core::Error::Aborted) || was_interrupted {
                             // Send friendly "interrupted" message instead of error
+                            self.run_interrupted = true;
                             self.set_cell_status(cell_id, CellStatus::Idle);
                             self.broadcast(ServerMessage::ExecutionAborted {
                                 cell_id: Some(cell_id),
+                                reason: None,
                             });
                         } else {
                             self.set_cell_status(cell_id, CellStatus::Error);
-                            self.broadcast(ServerMessage::CellError {
-                                cell_id,
-                                error: e.to_string(),
-                                location: None,
+                            self.broadcast(match e {
+                                venus_core::Error::CellPanic { message, backtrace } => {
+                                    ServerMessage::CellPanic {
+                                        cell_id,
+                                        message,
+                                        backtrace,
+                                    }
+                                }
+                                e => ServerMessage::CellError {
+                                    cell_id,
+                                    error: e.to_string(),
+                                    location: None,
+                                },
                             });
+                            self.handle_failed_output(cell_id);
                         }
                     }
                 }
@@ -695,25 +1523,13 @@ impl NotebookSession {
             CompilationResult::Failed { errors, .. } => {
                 self.set_cell_status(cell_id, CellStatus::Error);
 
-                let compile_errors = errors
-                    .iter()
-                    .map(|e| crate::protocol::CompileErrorInfo {
-                        message: e.message.clone(),
-                        code: e.code.clone(),
-                        location: e.spans.first().map(|s| crate::protocol::SourceLocation {
-                            line: s.location.line as u32,
-                            column: s.location.column as u32,
-                            end_line: s.end_location.as_ref().map(|l| l.line as u32),
-                            end_column: s.end_location.as_ref().map(|l| l.column as u32),
-                        }),
-                        rendered: e.rendered.clone(),
-                    })
-                    .collect();
+                let compile_errors = errors.iter().map(compile_error_info).collect();
 
                 self.broadcast(ServerMessage::CompileError {
                     cell_id,
                     errors: compile_errors,
                 });
+                self.handle_failed_output(cell_id);
             }
         }
 
@@ -721,26 +1537,763 @@ impl NotebookSession {
         Ok(())
     }
 
+    /// Remember the diagnostics of a compilation for `get_compile_output`.
+    ///
+    /// Cache hits carry no diagnostics, so they leave the last ones in place.
+    fn record_compile_output(&mut self, cell_id: CellId, result: &CompilationResult) {
+        let diagnostics = match result {
+            CompilationResult::Success(compiled) => (
+                Vec::new(),
+                compiled.warnings.iter().map(compile_error_info).collect(),
+            ),
+            CompilationResult::Failed { errors, .. } => {
+                let (warnings, errors): (Vec<_>, Vec<_>) = errors
+                    .iter()
+                    .partition(|e| e.level == venus_core::compile::ErrorLevel::Warning);
+                (
+                    errors.into_iter().map(compile_error_info).collect(),
+                    warnings.into_iter().map(compile_error_info).collect(),
+                )
+            }
+            CompilationResult::Cached(_) => return,
+        };
+        self.compile_output.insert(cell_id, diagnostics);
+    }
+
+    /// Collect every cell's current output with its decoded form.
+    ///
+    /// Outputs are added in notebook order while their encoded size fits in
+    /// `max_bytes`; the data of the remaining ones is left out and they are
+    /// marked as truncated.
+    pub fn export_data(&self, max_bytes: usize) -> Vec<ExportedOutput> {
+        use base64::Engine;
+
+        let mut remaining = max_bytes;
+        let mut exported = Vec::new();
+        for cell in &self.cells {
+            let Some(output) = self.cell_outputs.get(&cell.id) else {
+                continue;
+            };
+            let type_name: String = cell.return_type.split_whitespace().collect();
+            let bytes = base64::engine::general_purpose::STANDARD.encode(output.bytes());
+            let json = venus_core::state::inspect_output(&type_name, output.bytes());
+            let encoded_size = bytes.len() + json.to_string().len();
+
+            let fits = encoded_size <= remaining;
+            if fits {
+                remaining -= encoded_size;
+            }
+            exported.push(ExportedOutput {
+                cell_id: cell.id,
+                name: cell.name.clone(),
+                type_name,
+                size: output.bytes().len(),
+                bytes: fits.then_some(bytes),
+                json: fits.then_some(json),
+                truncated: !fits,
+            });
+        }
+        exported
+    }
+
+    /// Decode a cell's current output to JSON for inspection.
+    ///
+    /// Returns the cell's declared return type and the decoded value, or a
+    /// byte summary for types that cannot be decoded structurally.
+    pub fn inspect_output(&self, cell_id: CellId) -> ServerResult<(String, serde_json::Value)> {
+        let cell = self
+            .cells
+            .iter()
+            .find(|c| c.id == cell_id)
+            .ok_or_else(|| self.cell_kind_error(cell_id, CellKind::Code))?;
+        let output = self.cell_outputs.get(&cell_id).ok_or_else(|| {
+            ServerError::InvalidOperation(format!("Cell '{}' has no output", cell.name))
+        })?;
+
+        let type_name: String = cell.return_type.split_whitespace().collect();
+        let decoded = venus_core::state::inspect_output(&type_name, output.bytes());
+        Ok((type_name, decoded))
+    }
+
+    /// Errors and warnings from the last compilation of a cell.
+    ///
+    /// Both are empty if the cell has not been compiled in this session.
+    pub fn get_compile_output(
+        &self,
+        cell_id: CellId,
+    ) -> (Vec<CompileErrorInfo>, Vec<CompileErrorInfo>) {
+        self.compile_output
+            .get(&cell_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Deal with the previous output of a cell whose run just failed.
+    ///
+    /// With `use_stale_on_failure` the output stays available to dependents,
+    /// flagged stale; otherwise it is dropped so dependents are blocked until
+    /// the cell succeeds again.
+    fn handle_failed_output(&mut self, cell_id: CellId) {
+        if !self.cell_outputs.contains_key(&cell_id) {
+            return;
+        }
+
+        if self.use_stale_on_failure {
+            if let Some(output) = self
+                .cell_states
+                .get_mut(&cell_id)
+                .and_then(|s| s.output_mut())
+            {
+                output.stale = true;
+            }
+            self.broadcast(ServerMessage::CellOutputStale { cell_id });
+        } else {
+            self.cell_outputs.remove(&cell_id);
+            self.executor.state_mut().invalidate(cell_id);
+        }
+    }
+
+    /// Hash of everything that determines a cell's compiled code.
+    ///
+    /// The output format hint is not part of `source_code` but changes how
+    /// the output is rendered, so it is hashed separately.
+    fn cell_source_hash(cell: &CellInfo, deps_hash: u64) -> u64 {
+        let mut hasher = rustc_hash::FxHasher::default();
+        cell.source_code.hash(&mut hasher);
+        cell.output_format.hash(&mut hasher);
+        deps_hash.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Complete a cell execution with an output from the output cache.
+    ///
+    /// Mirrors the success path of `execute_cell`, except that no history
+    /// entry is added since the output is identical to a previous run.
+    fn complete_from_cache(&mut self, cell_id: CellId, output: Arc<BoxedOutput>, duration_ms: u64) {
+        let output_changed = self
+            .cell_outputs
+            .get(&cell_id)
+            .is_none_or(|old| Self::output_hash(old) != Self::output_hash(&output));
+
+        self.cell_outputs.insert(cell_id, output.clone());
+        self.executor
+            .state_mut()
+            .store_output(cell_id, (*output).clone());
+
+        let cell_output = CellOutput {
+            text: output.display_text().map(|s| s.to_string()),
+            html: output.display_html().map(|s| s.to_string()),
+            image: None,
+            json: self.output_json(cell_id, &output),
+            widgets: self.get_widget_defs(cell_id),
+            stale: false,
+        };
+
+        if let Some(state) = self.cell_states.get_mut(&cell_id) {
+            state.set_status(CellStatus::Success);
+            state.set_output(Some(cell_output.clone()));
+            state.set_dirty(false);
+        }
+
+        if output_changed {
+            let dirty_cells = self.mark_dependents_dirty_and_get(cell_id);
+            for dirty_id in dirty_cells {
+                self.broadcast(ServerMessage::CellDirty { cell_id: dirty_id });
+            }
+        }
+
+        self.broadcast_completed(cell_id, duration_ms, cell_output, true);
+    }
+
+    /// Broadcast a successful completion.
+    ///
+    /// Large output text that was not already streamed from the worker (a
+    /// cached output, or one from the linear executor) is sent as
+    /// `CellOutputAppended` chunks first, so the completion message itself
+    /// stays small.
+    fn broadcast_completed(
+        &self,
+        cell_id: CellId,
+        duration_ms: u64,
+        mut output: CellOutput,
+        from_cache: bool,
+    ) {
+        if let Some(text) = output
+            .text
+            .as_deref()
+            .filter(|text| text.len() > STREAMED_OUTPUT_THRESHOLD)
+        {
+            for chunk in output_chunks(cell_id, text, STREAMED_OUTPUT_THRESHOLD) {
+                self.broadcast(chunk);
+            }
+            output.text = None;
+        }
+
+        self.broadcast(ServerMessage::CellCompleted {
+            cell_id,
+            duration_ms,
+            output: Some(output),
+            from_cache,
+        });
+    }
+
+    /// Execute a cell, bypassing the output cache, and report where the
+    /// time went.
+    ///
+    /// The cell's usual `CellStarted`/`CellCompleted` messages are broadcast
+    /// as for `execute_cell`.
+    pub async fn profile_cell(&mut self, cell_id: CellId) -> ServerResult<ServerMessage> {
+        // Discard timings from earlier runs
+        self.last_compile_time = None;
+        self.executor.take_last_profile();
+
+        self.execute_cell(cell_id, true).await?;
+
+        let (Some(compile), Some(profile)) = (
+            self.last_compile_time.take(),
+            self.executor.take_last_profile(),
+        ) else {
+            return Err(ServerError::InvalidOperation(
+                "Cell did not run to completion; no profile available".to_string(),
+            ));
+        };
+
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+        Ok(ServerMessage::CellProfile {
+            cell_id,
+            compile_ms: ms(compile),
+            ipc_ms: ms(profile.ipc),
+            exec_ms: ms(profile.exec),
+            serialize_ms: ms(profile.serialize),
+        })
+    }
+
+    /// Compile and run a code snippet without touching the notebook file.
+    ///
+    /// The snippet is wrapped in an ephemeral cell that borrows the outputs of
+    /// `deps` (by their output names, so `load` for `Cells::load`) and returns the `Debug` rendering of the
+    /// snippet's value. Its build artifacts are removed afterwards, and no
+    /// state, output or history is recorded.
+    pub async fn execute_selection(
+        &mut self,
+        source: String,
+        deps: Vec<CellId>,
+    ) -> ServerResult<CellOutput> {
+        if self.executing {
+            return Err(ServerError::ExecutionInProgress);
+        }
+
+        let mut dependencies = Vec::new();
+        let mut inputs = Vec::new();
+        let mut missing = Vec::new();
+        for dep_id in deps {
+            let dep = self
+                .get_cell(dep_id)
+                .ok_or(ServerError::CellNotFound(dep_id))?;
+            match self.cell_outputs.get(&dep_id) {
+                Some(output) => inputs.push(output.clone()),
+                None => missing.push(dep.name.clone()),
+            }
+            dependencies.push(Dependency {
+                param_name: dep.output_name().to_string(),
+                param_type: dep.return_type.clone(),
+                is_ref: true,
+                is_mut: false,
+            });
+        }
+
+        if !missing.is_empty() {
+            return Err(ServerError::InvalidOperation(format!(
+                "Missing dependencies: {}. Run dependent cells first.",
+                missing.join(", ")
+            )));
+        }
+
+        let params: Vec<String> = dependencies
+            .iter()
+            .map(|d| format!("{}: &{}", d.param_name, d.param_type))
+            .collect();
+        let source_code = format!(
+            "pub fn {}({}) -> String {{\n    let __value = {{\n{}\n    }};\n    format!(\"{{:?}}\", __value)\n}}",
+            SELECTION_CELL_NAME,
+            params.join(", "),
+            source
+        );
+
+        // Reserved ID that never collides with a parsed cell
+        let cell_id = CellId::new(usize::MAX);
+        let cell = CellInfo {
+            id: cell_id,
+            name: SELECTION_CELL_NAME.to_string(),
+            display_name: SELECTION_CELL_NAME.to_string(),
+            dependencies,
+            return_type: "String".to_string(),
+            doc_comment: None,
+            source_code,
+            span: SourceSpan {
+                start_line: 0,
+                start_col: 0,
+                end_line: 0,
+                end_col: 0,
+            },
+            source_file: self.path.clone(),
+            timeout_ms: None,
+            opt_level: None,
+            output_format: None,
+            is_async: false,
+        };
+
+        let mut compiler = CellCompiler::new(self.config.clone(), self.toolchain.clone());
+        if let Some(ref up) = self.universe_path {
+            compiler = compiler.with_universe(up.clone());
+        }
+
+        self.executing = true;
+        self.interrupted.store(false, Ordering::SeqCst);
+
+        let result = match compiler.compile(&cell, self.deps_hash) {
+            CompilationResult::Success(compiled) | CompilationResult::Cached(compiled) => {
+                let registered = self.executor.register_cell(compiled, inputs.len());
+
+                let widget_values: BTreeMap<String, WidgetValue> =
+                    self.get_all_widget_values().into_iter().collect();
+                let widget_values_json = if widget_values.is_empty() {
+                    Vec::new()
+                } else {
+                    serde_json::to_vec(&widget_values).unwrap_or_default()
+                };
+
+                let exec_result = registered.and_then(|()| {
+                    self.executor
+                        .execute_cell_with_widgets(cell_id, &inputs, widget_values_json)
+                });
+                self.executor.unregister_cell(cell_id);
+
+                match exec_result {
+                    Ok((output, _widgets_json)) => {
+                        // The ephemeral cell returns the rendered value as a String
+                        let text = venus_core::state::deserialize_output::<String>(output.bytes())
+                            .ok()
+                            .or_else(|| output.display_text().map(|s| s.to_string()));
+                        Ok(CellOutput {
+                            text,
+                            html: None,
+                            image: None,
+                            json: None,
+                            widgets: Vec::new(),
+                            stale: false,
+                        })
+                    }
+                    Err(e) => {
+                        if self.interrupted.swap(false, Ordering::SeqCst) {
+                            Err(ServerError::ExecutionAborted)
+                        } else {
+                            Err(e.into())
+                        }
+                    }
+                }
+            }
+            CompilationResult::Failed { errors, .. } => {
+                let message = errors
+                    .iter()
+                    .map(|e| e.rendered.clone().unwrap_or_else(|| e.message.clone()))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                Err(ServerError::Core(venus_core::Error::Compilation {
+                    cell_id: None,
+                    message,
+                }))
+            }
+        };
+
+        compiler.remove_artifacts(SELECTION_CELL_NAME);
+        self.executing = false;
+
+        result
+    }
+
+    /// Execute all cells, then render the notebook as standalone HTML.
+    ///
+    /// With `force`, cached outputs are ignored and every cell re-runs. Fails
+    /// with `ExecutionAborted` if any cell is interrupted, so a
+    /// cancelled run never produces a partial report. Breakpoints are ignored.
+    pub async fn run_and_export(&mut self, dark_theme: bool, force: bool) -> ServerResult<String> {
+        self.run_interrupted = false;
+        let order = self.graph.topological_order()?;
+        let steps = order.into_iter().map(|id| (id, force)).collect();
+        self.execute_sequence(steps, false).await?;
+        if std::mem::take(&mut self.run_interrupted) {
+            return Err(ServerError::ExecutionAborted);
+        }
+        self.export_html(dark_theme)
+    }
+
+    /// Render the current outputs as standalone HTML, in execution order.
+    ///
+    /// Cells that failed show their compile errors, if any.
+    pub fn export_html(&self, dark_theme: bool) -> ServerResult<String> {
+        let cells: Vec<CellExport> = self
+            .graph
+            .topological_order()?
+            .into_iter()
+            .filter_map(|cell_id| {
+                let cell = self.cells.iter().find(|c| c.id == cell_id)?;
+                let state = self.cell_states.get(&cell_id);
+                let error =
+                    (state.and_then(|s| s.status()) == Some(CellStatus::Error)).then(|| {
+                        let (errors, _) = self.get_compile_output(cell_id);
+                        if errors.is_empty() {
+                            "Execution failed".to_string()
+                        } else {
+                            errors
+                                .into_iter()
+                                .map(|e| e.rendered.unwrap_or(e.message))
+                                .collect::<Vec<_>>()
+                                .join("\n")
+                        }
+                    });
+                let execution_time_ms = self.cell_output_history.get(&cell_id).and_then(|h| {
+                    h.get(self.get_history_index(cell_id))
+                        .map(|entry| entry.duration_ms)
+                });
+
+                Some(CellExport {
+                    name: cell.name.clone(),
+                    description: cell.doc_comment.clone(),
+                    source: cell.source_code.clone(),
+                    return_type: cell.return_type.clone(),
+                    dependencies: cell
+                        .dependencies
+                        .iter()
+                        .map(|d| d.param_name.clone())
+                        .collect(),
+                    output: state
+                        .and_then(|s| s.output())
+                        .and_then(|output| output.text.clone()),
+                    error,
+                    execution_time_ms,
+                })
+            })
+            .collect();
+
+        let name = self
+            .path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        Ok(generate_html(&name, &self.metadata, &cells, dark_theme))
+    }
+
+    /// Run a cell once per value of one widget.
+    ///
+    /// The cell is compiled once and executed with `widget_id` set to each
+    /// of `values` in turn, on top of the stored widget values. Outputs are
+    /// returned rather than stored, so the cell's output, history and widget
+    /// values are unchanged. An interrupt stops the sweep; the runs completed
+    /// so far are returned along with `true`.
+    pub fn sweep_cell(
+        &mut self,
+        cell_id: CellId,
+        widget_id: &str,
+        values: Vec<WidgetValue>,
+    ) -> ServerResult<(Vec<SweepRun>, bool)> {
+        if self.executing {
+            return Err(ServerError::ExecutionInProgress);
+        }
+        let cell = self
+            .get_cell(cell_id)
+            .cloned()
+            .ok_or_else(|| self.cell_kind_error(cell_id, CellKind::Code))?;
+
+        let mut inputs: Vec<Arc<BoxedOutput>> = Vec::with_capacity(cell.dependencies.len());
+        for dep in &cell.dependencies {
+            let output = self
+                .cells
+                .iter()
+                .find(|c| c.output_name() == dep.param_name)
+                .and_then(|c| self.cell_outputs.get(&c.id).cloned())
+                .ok_or_else(|| {
+                    ServerError::InvalidOperation(unavailable_dependencies_message(
+                        &cell.name,
+                        &[],
+                        &[dep.param_name.as_str()],
+                    ))
+                })?;
+            inputs.push(output);
+        }
+
+        let mut compiler = CellCompiler::new(self.config.clone(), self.toolchain.clone())
+            .with_abort_handle(self.compile_abort.clone());
+        if let Some(ref up) = self.universe_path {
+            compiler = compiler.with_universe(up.clone());
+        }
+        let result = compiler.compile(&cell, self.deps_hash);
+        if self.compile_abort.take_aborted() {
+            self.broadcast(ServerMessage::CompileAborted {
+                cell_id: Some(cell_id),
+            });
+            return Ok((Vec::new(), true));
+        }
+        let compiled = match result {
+            CompilationResult::Success(compiled) | CompilationResult::Cached(compiled) => compiled,
+            CompilationResult::Failed { .. } => {
+                return Err(ServerError::InvalidOperation(format!(
+                    "`{}` failed to compile; run it to see the errors",
+                    cell.name
+                )));
+            }
+        };
+
+        self.executor
+            .register_cell(compiled, cell.dependencies.len())?;
+
+        self.executing = true;
+        self.interrupted.store(false, Ordering::SeqCst);
+        let previous_status = self.cell_states.get(&cell_id).and_then(|s| s.status());
+        self.set_cell_status(cell_id, CellStatus::Running);
+
+        let stored: BTreeMap<String, WidgetValue> =
+            self.get_all_widget_values().into_iter().collect();
+        let mut runs = Vec::with_capacity(values.len());
+        let mut interrupted = false;
+        for value in values {
+            if self.interrupted.swap(false, Ordering::SeqCst) {
+                interrupted = true;
+                break;
+            }
+
+            let mut widget_values = stored.clone();
+            widget_values.insert(widget_id.to_string(), value.clone());
+            let widget_values_json = serde_json::to_vec(&widget_values).unwrap_or_default();
+
+            let start = Instant::now();
+            let result =
+                self.executor
+                    .execute_cell_with_widgets(cell_id, &inputs, widget_values_json);
+            let duration_ms = start.elapsed().as_millis() as u64;
+
+            match result {
+                Ok((output, _)) => runs.push(SweepRun {
+                    value,
+                    output: Some(CellOutput {
+                        text: output.display_text().map(|s| s.to_string()),
+                        html: output.display_html().map(|s| s.to_string()),
+                        image: None,
+                        json: self.output_json(cell_id, &output),
+                        widgets: Vec::new(),
+                        stale: false,
+                    }),
+                    duration_ms,
+                    error: None,
+                }),
+                Err(e) => {
+                    if matches!(e, venus_core::Error::Aborted)
+                        || self.interrupted.swap(false, Ordering::SeqCst)
+                    {
+                        interrupted = true;
+                        break;
+                    }
+                    runs.push(SweepRun {
+                        value,
+                        output: None,
+                        duration_ms,
+                        error: Some(e.to_string()),
+                    });
+                }
+            }
+        }
+
+        self.set_cell_status(cell_id, previous_status.unwrap_or(CellStatus::Idle));
+        self.executing = false;
+        Ok((runs, interrupted))
+    }
+
     /// Execute all cells in order.
     ///
     /// If `execution_timeout` is set, kills the worker process after that duration.
     /// Unlike cooperative cancellation, this immediately terminates the cell.
-    pub async fn execute_all(&mut self) -> ServerResult<()> {
+    ///
+    /// Cells with unchanged inputs reuse their cached output unless `force` is set.
+    pub async fn execute_all(&mut self, force: bool) -> ServerResult<()> {
         let order = self.graph.topological_order()?;
+        let steps = order.into_iter().map(|id| (id, force)).collect();
+        self.execute_sequence(steps, true).await
+    }
+
+    /// Execute a cell and its transitive dependents in topological order.
+    ///
+    /// Cells whose inputs are unchanged reuse their cached output.
+    pub async fn execute_with_dependents(&mut self, cell_id: CellId) -> ServerResult<()> {
+        if self.get_cell(cell_id).is_none() {
+            return Err(self.cell_kind_error(cell_id, CellKind::Code));
+        }
+        let steps = self
+            .graph
+            .invalidated_cells(cell_id)
+            .into_iter()
+            .map(|id| (id, false))
+            .collect();
+        self.execute_sequence(steps, false).await
+    }
+
+    /// Execute a cell and every cell after it in topological order.
+    ///
+    /// Cells before it are not re-run; their existing outputs are used.
+    /// Upstream cells that the run depends on but that have no output yet
+    /// are executed first, reusing cached outputs where inputs are unchanged.
+    pub async fn execute_from(&mut self, cell_id: CellId, force: bool) -> ServerResult<()> {
+        let order = self.graph.topological_order()?;
+        let position = order
+            .iter()
+            .position(|id| *id == cell_id)
+            .ok_or(ServerError::CellNotFound(cell_id))?;
+        let (upstream, run) = order.split_at(position);
+
+        // Collect transitive dependencies of the run that lie upstream
+        let mut needed = HashSet::new();
+        let mut stack: Vec<CellId> = run.to_vec();
+        while let Some(id) = stack.pop() {
+            for dep in self.graph.dependencies(id) {
+                if needed.insert(dep) {
+                    stack.push(dep);
+                }
+            }
+        }
+
+        let mut cells: Vec<CellId> = upstream
+            .iter()
+            .filter(|id| needed.contains(*id) && !self.cell_outputs.contains_key(*id))
+            .copied()
+            .collect();
+        let upstream_count = cells.len();
+        cells.extend_from_slice(run);
+
+        // Missing upstream outputs are filled from the cache, never forced
+        let steps = cells
+            .into_iter()
+            .enumerate()
+            .map(|(i, id)| (id, force && i >= upstream_count))
+            .collect();
+        self.execute_sequence(steps, true).await
+    }
+
+    /// Execute `(cell, force)` steps one after another, stopping at the first
+    /// failure.
+    ///
+    /// With `pause_at_breakpoints`, the run pauses after a breakpointed cell:
+    /// the remaining steps are kept for `continue_execution` and `Paused` is
+    /// broadcast. Starting a run discards any paused one.
+    async fn execute_sequence(
+        &mut self,
+        steps: Vec<(CellId, bool)>,
+        pause_at_breakpoints: bool,
+    ) -> ServerResult<()> {
+        self.paused_run = None;
         let start = Instant::now();
-        let timeout = self.execution_timeout;
-
-        for cell_id in order {
-            // Check timeout before each cell
-            if timeout.is_some_and(|max_duration| start.elapsed() > max_duration) {
-                self.executor.abort();
-                self.broadcast(ServerMessage::ExecutionAborted {
-                    cell_id: Some(cell_id),
-                });
-                return Err(ServerError::ExecutionTimeout);
+        let mut steps = VecDeque::from(steps);
+        while let Some((cell_id, force)) = steps.pop_front() {
+            self.check_sequence_timeout(start, cell_id)?;
+            self.execute_cell(cell_id, force).await?;
+
+            if pause_at_breakpoints && !steps.is_empty() && self.has_breakpoint(cell_id) {
+                self.pause_run(cell_id, steps);
+                return Ok(());
             }
+        }
+        Ok(())
+    }
+
+    /// Keep the remaining steps of a run and announce the pause.
+    fn pause_run(&mut self, at: CellId, steps: VecDeque<(CellId, bool)>) {
+        let next = steps.front().map(|&(id, _)| id);
+        self.paused_run = Some(steps);
+        self.broadcast(ServerMessage::Paused { at, next });
+    }
+
+    /// Execute the next cell of the paused run, then pause again.
+    ///
+    /// Without a paused run, starts a new one over the whole notebook in
+    /// topological order. No pause is announced after the last cell.
+    pub async fn step_execution(&mut self) -> ServerResult<()> {
+        let mut steps = match self.paused_run.take() {
+            Some(steps) => steps,
+            None => self
+                .graph
+                .topological_order()?
+                .into_iter()
+                .map(|id| (id, false))
+                .collect(),
+        };
+        let Some((cell_id, force)) = steps.pop_front() else {
+            return Ok(());
+        };
 
-            self.execute_cell(cell_id).await?;
+        self.execute_cell(cell_id, force).await?;
+        if !steps.is_empty() {
+            self.pause_run(cell_id, steps);
+        }
+        Ok(())
+    }
+
+    /// Resume a run paused at a breakpoint.
+    pub async fn continue_execution(&mut self) -> ServerResult<()> {
+        let steps = self.paused_run.take().ok_or_else(|| {
+            ServerError::InvalidOperation("No paused run to continue".to_string())
+        })?;
+        self.execute_sequence(steps.into(), true).await
+    }
+
+    /// Whether a run is paused at a breakpoint.
+    pub fn is_paused(&self) -> bool {
+        self.paused_run.is_some()
+    }
+
+    /// Set or clear a breakpoint on a cell.
+    ///
+    /// Breakpoints are kept by cell name, so they survive reloads.
+    pub fn set_breakpoint(&mut self, cell_id: CellId, enabled: bool) -> ServerResult<()> {
+        let name = self.cell_name(cell_id)?;
+        if enabled {
+            self.breakpoints.insert(name);
+        } else {
+            self.breakpoints.remove(&name);
+        }
+        Ok(())
+    }
+
+    /// Whether a cell has a breakpoint.
+    fn has_breakpoint(&self, cell_id: CellId) -> bool {
+        self.cells
+            .iter()
+            .find(|c| c.id == cell_id)
+            .is_some_and(|c| self.breakpoints.contains(&c.name))
+    }
+
+    /// Cells that currently have a breakpoint, as a `Breakpoints` message.
+    pub fn breakpoints_message(&self) -> ServerMessage {
+        ServerMessage::Breakpoints {
+            cell_ids: self
+                .cells
+                .iter()
+                .filter(|c| self.breakpoints.contains(&c.name))
+                .map(|c| c.id)
+                .collect(),
+        }
+    }
+
+    /// Abort a multi-cell run once it exceeds `execution_timeout`.
+    fn check_sequence_timeout(&mut self, start: Instant, next_cell: CellId) -> ServerResult<()> {
+        if self
+            .execution_timeout
+            .is_some_and(|max_duration| start.elapsed() > max_duration)
+        {
+            self.executor.abort();
+            self.broadcast(ServerMessage::ExecutionAborted {
+                cell_id: Some(next_cell),
+                reason: Some("execution timed out".to_string()),
+            });
+            return Err(ServerError::ExecutionTimeout);
         }
         Ok(())
     }
@@ -782,7 +2335,10 @@ impl NotebookSession {
         if self.executing {
             // Kill the worker process - this is immediate
             self.executor.abort();
-            self.broadcast(ServerMessage::ExecutionAborted { cell_id: None });
+            self.broadcast(ServerMessage::ExecutionAborted {
+                cell_id: None,
+                reason: None,
+            });
             self.executing = false;
             true
         } else {
@@ -796,6 +2352,8 @@ impl NotebookSession {
     /// providing immediate interruption of even long-running cells.
     pub fn set_execution_timeout(&mut self, timeout: Option<Duration>) {
         self.execution_timeout = timeout;
+        self.settings.timeout_ms = timeout.map(|t| t.as_millis() as u64);
+        self.save_settings();
     }
 
     /// Get the current execution timeout.
@@ -803,6 +2361,111 @@ impl NotebookSession {
         self.execution_timeout
     }
 
+    /// Enable periodic `ResourceUsage` reports while a cell executes.
+    ///
+    /// Sampling reads the worker's CPU time and memory every `interval`;
+    /// `None` turns it off.
+    pub fn set_resource_sampling(&mut self, interval: Option<Duration>) {
+        self.resource_sample_interval = interval;
+    }
+
+    /// Choose what happens to a cell's previous output when it fails.
+    ///
+    /// When enabled the output stays available to dependents and is flagged
+    /// stale; otherwise it is dropped so dependents cannot run.
+    pub fn set_use_stale_on_failure(&mut self, enabled: bool) {
+        self.use_stale_on_failure = enabled;
+    }
+
+    /// Set or remove environment variables for cell execution.
+    ///
+    /// `vars` are set (replacing existing values) and `unset` names are
+    /// removed. Workers pick them up on their next execution. Compilation is
+    /// unaffected, but cached outputs are dropped so the next run of each
+    /// cell sees the new environment. Returns the names now set.
+    ///
+    /// Fails with the linear executor, whose cells share the server's
+    /// environment, unless there is nothing to change.
+    pub fn set_env(
+        &mut self,
+        vars: impl IntoIterator<Item = (String, String)>,
+        unset: &[String],
+    ) -> ServerResult<Vec<String>> {
+        let vars: Vec<_> = vars.into_iter().collect();
+        if !vars.is_empty() || !unset.is_empty() {
+            self.check_worker_config_supported("environment variables")?;
+        }
+        for name in unset {
+            self.env.remove(name);
+        }
+        self.env.extend(vars);
+        self.executor.set_worker_config(self.worker_config());
+        // Cached outputs were produced under the old environment
+        self.output_cache.clear();
+        Ok(self.env.keys().cloned().collect())
+    }
+
+    /// Set the working directory of the processes that run cells.
+    ///
+    /// Relative paths are resolved against the notebook's directory. The
+    /// directory must exist. Idle workers are restarted so every cell runs
+    /// in it, and cached outputs are dropped since relative paths may now
+    /// resolve to other files. Returns the resolved directory.
+    ///
+    /// Fails with the linear executor, whose cells run in the server's
+    /// working directory.
+    pub fn set_working_directory(&mut self, path: impl AsRef<Path>) -> ServerResult<PathBuf> {
+        self.check_worker_config_supported("the working directory")?;
+        let path = path.as_ref();
+        let resolved = match self.path.parent() {
+            Some(notebook_dir) => notebook_dir.join(path),
+            None => path.to_path_buf(),
+        };
+        let dir = resolved
+            .canonicalize()
+            .ok()
+            .filter(|dir| dir.is_dir())
+            .ok_or_else(|| {
+                ServerError::InvalidOperation(format!("Not a directory: {}", resolved.display()))
+            })?;
+
+        self.working_dir = dir.clone();
+        self.executor.set_worker_config(self.worker_config());
+        // Cached outputs may have read files relative to the old directory
+        self.output_cache.clear();
+        Ok(dir)
+    }
+
+    /// Current working directory of the processes that run cells.
+    pub fn working_directory(&self) -> &Path {
+        &self.working_dir
+    }
+
+    /// Fail if the executor runs cells in the server process, where
+    /// `setting` cannot be changed per notebook.
+    fn check_worker_config_supported(&self, setting: &str) -> ServerResult<()> {
+        if self.executor.kind() == ExecutorKind::Linear {
+            return Err(ServerError::InvalidOperation(format!(
+                "The linear executor runs cells in the server process; \
+                 use the process executor to set {}",
+                setting
+            )));
+        }
+        Ok(())
+    }
+
+    /// Environment and working directory for worker processes.
+    fn worker_config(&self) -> WorkerSpawnConfig {
+        WorkerSpawnConfig {
+            env: self
+                .env
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect(),
+            current_dir: Some(self.working_dir.clone()),
+        }
+    }
+
     /// Set the interrupted flag.
     ///
     /// When true, execution errors will be reported as "interrupted"
@@ -819,6 +2482,13 @@ impl NotebookSession {
         self.executor.get_kill_handle()
     }
 
+    /// Get a handle for aborting the current compilation.
+    ///
+    /// Like the kill handle, it can be used without the session lock.
+    pub fn compile_abort_handle(&self) -> CompileAbortHandle {
+        self.compile_abort.clone()
+    }
+
     /// Restart the kernel: kill WorkerPool, spin up new one, clear memory state, preserve source.
     ///
     /// This clears all execution state including:
@@ -840,11 +2510,9 @@ impl NotebookSession {
         // Shutdown old executor and worker pool
         self.executor.shutdown();
 
-        // Reconstruct state directory path
-        let dirs = NotebookDirs::from_notebook_path(&self.path)?;
-
-        // Create new ProcessExecutor with warm worker pool
-        self.executor = ProcessExecutor::new(&dirs.state_dir)?;
+        // Create a new executor of the same kind
+        self.executor = SessionExecutor::new(self.executor.kind(), &self.dirs.state_dir)?;
+        self.executor.set_worker_config(self.worker_config());
 
         // Clear all execution state
         self.cell_outputs.clear();
@@ -852,8 +2520,10 @@ impl NotebookSession {
         self.widget_defs.clear();
         self.cell_output_history.clear();
         self.cell_history_index.clear();
+        self.output_cache.clear();
 
         // Reset all cell states to Idle and clear outputs
+        self.in_flight.clear();
         for state in self.cell_states.values_mut() {
             state.set_status(CellStatus::Idle);
             state.clear_output();
@@ -890,16 +2560,113 @@ impl NotebookSession {
         self.cell_outputs.clear();
         let _ = self.executor.state_mut().clear();
 
-        // Clear output history
-        self.cell_output_history.clear();
-        self.cell_history_index.clear();
+        // Clear output history
+        self.cell_output_history.clear();
+        self.cell_history_index.clear();
+        self.output_cache.clear();
+
+        // Broadcast outputs cleared message
+        self.broadcast(ServerMessage::OutputsCleared { error: None });
+
+        // Send updated state to all clients
+        let state_msg = self.get_state();
+        self.broadcast(state_msg);
+    }
+
+    /// Clear one cell's output without touching the rest of the notebook.
+    ///
+    /// Drops the cell's display output and cached value, and marks cells
+    /// that depend on it dirty since their input is gone. History is kept
+    /// unless `clear_history` is set. Returns the cells marked dirty.
+    pub fn clear_cell_output(
+        &mut self,
+        cell_id: CellId,
+        clear_history: bool,
+    ) -> ServerResult<Vec<CellId>> {
+        let name = self
+            .cells
+            .iter()
+            .find(|c| c.id == cell_id)
+            .map(|c| c.name.clone())
+            .ok_or_else(|| self.cell_kind_error(cell_id, CellKind::Code))?;
+
+        let dirty_cells = self.mark_dependents_dirty_and_get(cell_id);
+
+        if let Some(state) = self.cell_states.get_mut(&cell_id) {
+            state.clear_output();
+            state.set_dirty(false);
+            state.set_status(CellStatus::Idle);
+        }
+        self.cell_outputs.remove(&cell_id);
+        self.executor.state_mut().invalidate(cell_id);
+        self.output_cache.remove(&name);
+
+        if clear_history {
+            self.cell_output_history.remove(&cell_id);
+            self.cell_history_index.remove(&cell_id);
+        }
+
+        Ok(dirty_cells)
+    }
+
+    /// Outputs and history kept for cells that no longer exist.
+    ///
+    /// Entries keyed by cell ID are listed by that ID (`cell_<id>`), cached
+    /// outputs by cell name. Renaming or deleting cells can leave these
+    /// behind, since outputs are carried across reloads by name.
+    pub fn find_orphan_outputs(&self) -> Vec<String> {
+        let ids: HashSet<CellId> = self.cells.iter().map(|c| c.id).collect();
+        let names: HashSet<&str> = self.cells.iter().map(|c| c.name.as_str()).collect();
+
+        let mut orphans: Vec<CellId> = self
+            .cell_outputs
+            .keys()
+            .chain(self.cell_output_history.keys())
+            .chain(self.cell_history_index.keys())
+            .filter(|id| !ids.contains(id))
+            .copied()
+            .collect();
+        orphans.sort_by_key(|id| id.as_usize());
+        orphans.dedup();
+
+        let mut cached: Vec<&str> = self
+            .output_cache
+            .cell_names()
+            .filter(|name| !names.contains(name))
+            .collect();
+        cached.sort_unstable();
+
+        orphans
+            .iter()
+            .map(ToString::to_string)
+            .chain(cached.into_iter().map(str::to_string))
+            .collect()
+    }
 
-        // Broadcast outputs cleared message
-        self.broadcast(ServerMessage::OutputsCleared { error: None });
+    /// Drop the outputs and history listed by
+    /// [`find_orphan_outputs`](Self::find_orphan_outputs).
+    ///
+    /// Returns what was removed.
+    pub fn clean_orphan_outputs(&mut self) -> Vec<String> {
+        let removed = self.find_orphan_outputs();
+
+        let ids: HashSet<CellId> = self.cells.iter().map(|c| c.id).collect();
+        self.cell_outputs.retain(|id, _| ids.contains(id));
+        self.cell_output_history.retain(|id, _| ids.contains(id));
+        self.cell_history_index.retain(|id, _| ids.contains(id));
+
+        let names: HashSet<&str> = self.cells.iter().map(|c| c.name.as_str()).collect();
+        let stale: Vec<String> = self
+            .output_cache
+            .cell_names()
+            .filter(|name| !names.contains(name))
+            .map(str::to_string)
+            .collect();
+        for name in stale {
+            self.output_cache.remove(&name);
+        }
 
-        // Send updated state to all clients
-        let state_msg = self.get_state();
-        self.broadcast(state_msg);
+        removed
     }
 
     /// Get IDs of all dirty cells in topological order.
@@ -924,8 +2691,25 @@ impl NotebookSession {
     /// Update a widget value for a cell.
     ///
     /// This stores the new value but does NOT trigger re-execution.
-    /// The user must explicitly run the cell to see the effect.
+    /// The user must explicitly run the cell to see the effect, unless the
+    /// cell is in reactive widget mode (see [`crate::reactive`]).
+    ///
+    /// The stored widget definitions and the cell's displayed output are
+    /// updated too, so clients that connect later see the current value.
     pub fn update_widget_value(&mut self, cell_id: CellId, widget_id: String, value: WidgetValue) {
+        let defs = self.widget_defs.get_mut(&cell_id).into_iter().flatten();
+        let shown = self
+            .cell_states
+            .get_mut(&cell_id)
+            .and_then(|s| s.output_mut())
+            .into_iter()
+            .flat_map(|o| o.widgets.iter_mut());
+        for widget in defs.chain(shown) {
+            if widget.id() == widget_id {
+                widget.set_value(&value);
+            }
+        }
+
         self.widget_values
             .entry(cell_id)
             .or_default()
@@ -952,13 +2736,45 @@ impl NotebookSession {
         all_values
     }
 
+    /// Get the widget definitions and values of every cell that has any.
+    pub fn widget_state(&self) -> Vec<CellWidgetState> {
+        self.cells
+            .iter()
+            .filter(|c| {
+                self.widget_defs.contains_key(&c.id) || self.widget_values.contains_key(&c.id)
+            })
+            .map(|c| CellWidgetState {
+                cell_id: c.id,
+                defs: self.get_widget_defs(c.id),
+                values: self.get_widget_values(c.id),
+            })
+            .collect()
+    }
+
     /// Get widget definitions for a cell.
     pub fn get_widget_defs(&self, cell_id: CellId) -> Vec<WidgetDef> {
         self.widget_defs.get(&cell_id).cloned().unwrap_or_default()
     }
 
     /// Store widget definitions from cell execution.
+    ///
+    /// Widget values are shared across the notebook by ID, so an ID already
+    /// used by another cell is reported with a `Warning`.
     fn store_widget_defs(&mut self, cell_id: CellId, widgets: Vec<WidgetDef>) {
+        let others = self
+            .cells
+            .iter()
+            .filter(|c| c.id != cell_id)
+            .filter_map(|c| Some((c.name.as_str(), self.widget_defs.get(&c.id)?.as_slice())));
+        let name = self
+            .get_cell(cell_id)
+            .map(|c| c.name.as_str())
+            .unwrap_or("?");
+        for message in duplicate_widget_warnings(name, &widgets, others) {
+            tracing::warn!("{}", message);
+            self.broadcast(ServerMessage::Warning { message });
+        }
+
         if widgets.is_empty() {
             self.widget_defs.remove(&cell_id);
         } else {
@@ -972,6 +2788,7 @@ impl NotebookSession {
         cell_id: CellId,
         serialized: Arc<BoxedOutput>,
         display: CellOutput,
+        duration_ms: u64,
     ) {
         use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -984,6 +2801,7 @@ impl NotebookSession {
             serialized,
             display,
             timestamp,
+            duration_ms,
         };
 
         let history = self.cell_output_history.entry(cell_id).or_default();
@@ -1052,6 +2870,18 @@ impl NotebookSession {
         dirty_cells
     }
 
+    /// Structured form of an output for cells with a JSON output hint.
+    ///
+    /// The cell wrapper renders such outputs as JSON text; outputs that are
+    /// not valid JSON are left to the text view.
+    fn output_json(&self, cell_id: CellId, output: &BoxedOutput) -> Option<serde_json::Value> {
+        let cell = self.get_cell(cell_id)?;
+        if cell.output_format != Some(OutputFormat::Json) {
+            return None;
+        }
+        serde_json::from_str(output.display_text()?).ok()
+    }
+
     /// Compute a hash of output bytes for change detection.
     fn output_hash(output: &BoxedOutput) -> u64 {
         let mut hasher = rustc_hash::FxHasher::default();
@@ -1059,6 +2889,94 @@ impl NotebookSession {
         hasher.finish()
     }
 
+    /// Set the optimization level cells are compiled at.
+    ///
+    /// The level is part of each cell's build cache key, so cells recompile
+    /// on their next run; cells with an `opt_level` attribute keep their own
+    /// level. Cached outputs are dropped so those runs actually happen.
+    pub fn set_opt_level(&mut self, level: u8) -> ServerResult<()> {
+        if self.executing {
+            return Err(ServerError::ExecutionInProgress);
+        }
+        if level > 3 {
+            return Err(ServerError::InvalidOperation(format!(
+                "Optimization level must be between 0 and 3, got {}",
+                level
+            )));
+        }
+        if self.config.opt_level == level {
+            return Ok(());
+        }
+
+        self.config.opt_level = level;
+        self.output_cache.clear();
+        self.settings.opt_level = Some(level);
+        self.save_settings();
+
+        tracing::info!("Switched to opt-level={}", level);
+        Ok(())
+    }
+
+    /// Get the optimization level cells are compiled at.
+    pub fn opt_level(&self) -> u8 {
+        self.config.opt_level
+    }
+
+    /// Switch between the fast Cranelift backend and optimized LLVM builds.
+    ///
+    /// Cached cell builds and cached outputs are dropped so the next run
+    /// recompiles and re-executes every cell with the new backend. Existing
+    /// outputs stay visible until then.
+    pub fn set_backend(&mut self, release: bool) -> ServerResult<()> {
+        if self.executing {
+            return Err(ServerError::ExecutionInProgress);
+        }
+        if self.is_release() == release {
+            return Ok(());
+        }
+
+        let config = if release {
+            CompilerConfig::for_notebook_release(&self.dirs)
+        } else {
+            CompilerConfig::for_notebook(&self.dirs)
+        };
+
+        CellCompiler::new(self.config.clone(), self.toolchain.clone()).clear_cache();
+        self.config = config;
+        self.output_cache.clear();
+
+        // The new config starts at the backend's default optimization level
+        self.settings.release = release;
+        self.settings.opt_level = None;
+        self.save_settings();
+
+        tracing::info!(
+            "Switched to {} backend",
+            if release {
+                "LLVM (release)"
+            } else {
+                "Cranelift"
+            }
+        );
+        Ok(())
+    }
+
+    /// Persist the current settings, logging (but otherwise ignoring) failures.
+    fn save_settings(&self) {
+        if let Err(e) = self.settings.save(&self.settings_path) {
+            tracing::warn!(
+                "Failed to save settings to {}: {}",
+                self.settings_path.display(),
+                e
+            );
+        }
+    }
+
+    /// Whether cells are compiled with the optimized LLVM backend.
+    pub fn is_release(&self) -> bool {
+        !self.config.use_cranelift
+    }
+
     /// Get history count for a cell.
     pub fn get_history_count(&self, cell_id: CellId) -> usize {
         self.cell_output_history
@@ -1067,16 +2985,223 @@ impl NotebookSession {
             .unwrap_or(0)
     }
 
+    /// Summaries of a cell's output history, oldest first.
+    pub fn get_history(&self, cell_id: CellId) -> Vec<HistoryEntryInfo> {
+        self.cell_output_history
+            .get(&cell_id)
+            .map(|history| {
+                history
+                    .iter()
+                    .enumerate()
+                    .map(|(index, entry)| HistoryEntryInfo {
+                        index,
+                        timestamp: entry.timestamp,
+                        preview: history_preview(&entry.display),
+                        duration_ms: entry.duration_ms,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     /// Get current history index for a cell.
     pub fn get_history_index(&self, cell_id: CellId) -> usize {
         self.cell_history_index.get(&cell_id).copied().unwrap_or(0)
     }
 
+    /// Current history position of every cell that has history, as
+    /// `HistorySelected` messages.
+    ///
+    /// `dirty_cells` is left empty since dirty flags are already part of the
+    /// notebook state.
+    pub fn history_positions(&self) -> Vec<ServerMessage> {
+        self.cell_output_history
+            .iter()
+            .map(|(&cell_id, history)| ServerMessage::HistorySelected {
+                cell_id,
+                index: self.get_history_index(cell_id),
+                count: history.len(),
+                output: self
+                    .cell_states
+                    .get(&cell_id)
+                    .and_then(|s| s.output())
+                    .cloned(),
+                dirty_cells: Vec::new(),
+            })
+            .collect()
+    }
+
     /// Get reference to cell states.
     pub fn cell_states(&self) -> &HashMap<CellId, CellState> {
         &self.cell_states
     }
 
+    /// Describe the server version and enabled capabilities.
+    pub fn get_server_info(&self) -> ServerMessage {
+        let mut features = Vec::new();
+        if self.toolchain.has_cranelift() {
+            features.push("cranelift".to_string());
+        }
+        if cfg!(feature = "embedded-frontend") {
+            features.push("embedded-frontend".to_string());
+        }
+
+        ServerMessage::ServerInfo {
+            venus_version: env!("CARGO_PKG_VERSION").to_string(),
+            protocol_version: PROTOCOL_VERSION,
+            toolchain_version: self.toolchain.version().to_string(),
+            features,
+        }
+    }
+
+    /// Compute summary statistics for the notebook.
+    ///
+    /// Execution time is summed over the currently selected history entry
+    /// of each cell, so it reflects the outputs that are on screen.
+    pub fn get_notebook_stats(&self) -> ServerMessage {
+        let mut executed = 0;
+        let mut failed = 0;
+        for state in self.cell_states.values() {
+            match state.status() {
+                Some(CellStatus::Success) => executed += 1,
+                Some(CellStatus::Error) => failed += 1,
+                _ => {}
+            }
+        }
+
+        let total_exec_time_ms = self
+            .cell_output_history
+            .iter()
+            .filter_map(|(cell_id, history)| history.get(self.get_history_index(*cell_id)))
+            .map(|entry| entry.duration_ms)
+            .sum();
+
+        ServerMessage::NotebookStats {
+            code_cells: self.cells.len(),
+            markdown_cells: self.markdown_cells.len(),
+            definition_cells: self.definition_cells.len(),
+            total_deps: self.cells.iter().map(|c| c.dependencies.len()).sum(),
+            executed,
+            failed,
+            total_exec_time_ms,
+        }
+    }
+
+    /// Run the notebook linter, skipping the `disabled` rules.
+    pub fn lint(&self, disabled: Vec<LintRule>) -> ServerMessage {
+        ServerMessage::LintReport {
+            warnings: lint::lint(&self.graph, &LintConfig::without(disabled)),
+        }
+    }
+
+    /// The run plan: code cells in execution order, with the parallel level
+    /// each one runs in (level 0 has no upstream cells).
+    pub fn execution_steps(&self) -> ServerResult<Vec<ExecutionStep>> {
+        let order = self.graph.topological_order()?;
+        let levels: HashMap<CellId, usize> = self
+            .graph
+            .topological_levels(&order)
+            .into_iter()
+            .enumerate()
+            .flat_map(|(level, cells)| cells.into_iter().map(move |id| (id, level)))
+            .collect();
+
+        let mut steps: Vec<ExecutionStep> = order
+            .iter()
+            .filter_map(|&cell_id| {
+                Some(ExecutionStep {
+                    cell_id,
+                    name: self.get_cell(cell_id)?.name.clone(),
+                    level: *levels.get(&cell_id)?,
+                })
+            })
+            .collect();
+        // Stable, so cells within a level stay in execution order
+        steps.sort_by_key(|step| step.level);
+
+        Ok(steps)
+    }
+
+    /// Export the given cells with everything they need as a standalone notebook.
+    ///
+    /// Upstream cells, referenced definitions and the dependency block are
+    /// included; see [`venus_core::graph::extract_subgraph`].
+    pub fn export_subgraph(&self, cell_ids: &[CellId]) -> ServerResult<String> {
+        let names = cell_ids
+            .iter()
+            .map(|&id| {
+                self.get_cell(id)
+                    .map(|cell| cell.name.clone())
+                    .ok_or(ServerError::CellNotFound(id))
+            })
+            .collect::<ServerResult<Vec<_>>>()?;
+
+        let source = std::fs::read_to_string(&self.path)?;
+        Ok(extract_subgraph(&source, &names)?)
+    }
+
+    /// Code cells whose source mentions `symbol`, in notebook order.
+    ///
+    /// This is an identifier scan, so a local variable that shadows the
+    /// symbol also counts as a use.
+    pub fn cell_references(&self, symbol: &str) -> Vec<CellId> {
+        let symbols = HashSet::from([symbol.to_string()]);
+        self.cells
+            .iter()
+            .filter(|c| references_any(&c.source_code, &symbols))
+            .map(|c| c.id)
+            .collect()
+    }
+
+    /// Remove cached outputs and build artifacts of cells that no longer exist.
+    ///
+    /// Covers persisted execution state, compiled cell dylibs and the
+    /// `.ipynb` output cache. Artifacts of current cells are kept.
+    /// Returns the number of bytes reclaimed.
+    pub fn garbage_collect(&mut self) -> ServerResult<u64> {
+        if self.executing {
+            return Err(ServerError::ExecutionInProgress);
+        }
+
+        let ids: Vec<CellId> = self.cells.iter().map(|c| c.id).collect();
+        let names: Vec<String> = self.cells.iter().map(|c| c.name.clone()).collect();
+
+        let mut reclaimed = self.executor.state_mut().gc(&ids)?;
+        reclaimed +=
+            CellCompiler::new(self.config.clone(), self.toolchain.clone()).prune_artifacts(&names);
+
+        let outputs_dir = self.dirs.venus_dir.join("outputs");
+        if outputs_dir.exists() {
+            reclaimed += venus_sync::OutputCache::new(&outputs_dir)
+                .and_then(|mut cache| cache.gc(&names))
+                .map_err(|e| ServerError::Io {
+                    path: outputs_dir.clone(),
+                    message: e.to_string(),
+                })?;
+        }
+
+        tracing::info!("Garbage collection reclaimed {} bytes", reclaimed);
+        Ok(reclaimed)
+    }
+
+    /// Get the definition cells in source order.
+    pub fn get_definitions(&self) -> ServerMessage {
+        let mut definitions: Vec<&DefinitionCell> = self.definition_cells.iter().collect();
+        definitions.sort_by_key(|d| d.span.start_line);
+
+        ServerMessage::Definitions {
+            blocks: definitions
+                .into_iter()
+                .map(|d| DefinitionBlock {
+                    id: d.id,
+                    definition_type: d.definition_type,
+                    content: d.content.clone(),
+                    doc: d.doc_comment.clone(),
+                })
+                .collect(),
+        }
+    }
+
     /// Insert a new cell after the specified cell.
     ///
     /// Modifies the source file and triggers a reload.
@@ -1108,6 +3233,36 @@ impl NotebookSession {
         Ok(new_name)
     }
 
+    /// Insert a new cell at a position in the source order.
+    ///
+    /// The cell is placed before the cell currently at `index` in
+    /// `source_order`, or at the end of the file if `index` equals its length.
+    /// Returns the name of the newly created cell.
+    pub fn insert_cell_at(&mut self, index: usize) -> ServerResult<String> {
+        let order = self.source_order_with_lines();
+        if index > order.len() {
+            return Err(ServerError::InvalidOperation(format!(
+                "Index {} is out of range (notebook has {} cells)",
+                index,
+                order.len()
+            )));
+        }
+        let before_line = order.get(index).map(|(_, line)| *line);
+
+        let mut editor = SourceEditor::load(&self.path)?;
+        let new_name = editor.insert_cell_before_line(before_line)?;
+        editor.save()?;
+
+        self.undo_manager.record(UndoableOperation::InsertCellAt {
+            cell_name: new_name.clone(),
+            before_line,
+        });
+
+        self.reload()?;
+
+        Ok(new_name)
+    }
+
     /// Delete a cell from the notebook.
     ///
     /// Modifies the .rs source file and reloads the notebook.
@@ -1118,14 +3273,18 @@ impl NotebookSession {
             .iter()
             .find(|c| c.id == cell_id)
             .map(|c| c.name.clone())
-            .ok_or(ServerError::CellNotFound(cell_id))?;
+            .ok_or_else(|| self.cell_kind_error(cell_id, CellKind::Code))?;
 
         // Check if any other cells depend on this cell
         let dependents: Vec<String> = self
             .cells
             .iter()
             .filter(|c| c.id != cell_id) // Don't check self
-            .filter(|c| c.dependencies.iter().any(|dep| dep.param_name == cell_name))
+            .filter(|c| {
+                c.dependencies
+                    .iter()
+                    .any(|dep| dep.param_name == unqualified_name(&cell_name))
+            })
             .map(|c| c.name.clone())
             .collect();
 
@@ -1171,7 +3330,7 @@ impl NotebookSession {
             .iter()
             .find(|c| c.id == cell_id)
             .map(|c| c.name.clone())
-            .ok_or(ServerError::CellNotFound(cell_id))?;
+            .ok_or_else(|| self.cell_kind_error(cell_id, CellKind::Code))?;
 
         // Load and edit the source file
         let mut editor = SourceEditor::load(&self.path)?;
@@ -1200,7 +3359,7 @@ impl NotebookSession {
             .iter()
             .find(|c| c.id == cell_id)
             .map(|c| c.name.clone())
-            .ok_or(ServerError::CellNotFound(cell_id))?;
+            .ok_or_else(|| self.cell_kind_error(cell_id, CellKind::Code))?;
 
         // Load and edit the source file
         let mut editor = SourceEditor::load(&self.path)?;
@@ -1228,7 +3387,7 @@ impl NotebookSession {
             .cells
             .iter()
             .find(|c| c.id == cell_id)
-            .ok_or(ServerError::CellNotFound(cell_id))?;
+            .ok_or_else(|| self.cell_kind_error(cell_id, CellKind::Code))?;
 
         let cell_name = cell.name.clone();
         let old_source = cell.source_code.clone();
@@ -1286,6 +3445,68 @@ impl NotebookSession {
         Ok(())
     }
 
+    /// Edit several code cells at once.
+    ///
+    /// All edits are applied to the source file in one pass, saved once and
+    /// reloaded once, and recorded as a single undoable operation.
+    pub fn batch_edit(&mut self, edits: Vec<(CellId, String)>) -> ServerResult<()> {
+        let mut named = Vec::with_capacity(edits.len());
+        for (cell_id, new_source) in edits {
+            let cell = self
+                .cells
+                .iter()
+                .find(|c| c.id == cell_id)
+                .ok_or_else(|| self.cell_kind_error(cell_id, CellKind::Code))?;
+            named.push((cell_id, cell.name.clone(), new_source));
+        }
+        if named.is_empty() {
+            return Ok(());
+        }
+
+        let mut editor = SourceEditor::load(&self.path)?;
+        let apply: Vec<(String, String)> = named
+            .iter()
+            .map(|(_, name, new)| (name.clone(), new.clone()))
+            .collect();
+        let blocks = editor.edit_cells(&apply)?;
+        editor.save()?;
+
+        tracing::info!("Batch edited {} cells", named.len());
+
+        // The edits are on disk now, so the editor's copies are no longer pending
+        for (cell_id, _, _) in &named {
+            self.pending_edits.remove(cell_id);
+        }
+        let edited: HashSet<String> = named.into_iter().map(|(_, name, _)| name).collect();
+        self.undo_manager
+            .record(UndoableOperation::BatchEdit { blocks });
+
+        // Save outputs by name BEFORE reload (IDs will change)
+        let outputs_by_name: HashMap<String, Arc<BoxedOutput>> = self
+            .cells
+            .iter()
+            .filter_map(|c| {
+                self.cell_outputs
+                    .get(&c.id)
+                    .map(|o| (c.name.clone(), o.clone()))
+            })
+            .collect();
+
+        self.reload()?;
+
+        // Restore outputs with NEW IDs (except for the edited cells)
+        self.cell_outputs.clear();
+        for cell in &self.cells {
+            if !edited.contains(&cell.name)
+                && let Some(output) = outputs_by_name.get(&cell.name)
+            {
+                self.cell_outputs.insert(cell.id, output.clone());
+            }
+        }
+
+        Ok(())
+    }
+
     /// Rename a cell's display name.
     ///
     /// Updates the cell's doc comment with the new display name and reloads the notebook.
@@ -1296,7 +3517,7 @@ impl NotebookSession {
             .iter()
             .find(|c| c.id == cell_id)
             .map(|c| (c.name.clone(), c.display_name.clone()))
-            .ok_or(ServerError::CellNotFound(cell_id))?;
+            .ok_or_else(|| self.cell_kind_error(cell_id, CellKind::Code))?;
 
         // Load and edit the source file
         let mut editor = SourceEditor::load(&self.path)?;
@@ -1374,7 +3595,7 @@ impl NotebookSession {
             .markdown_cells
             .iter()
             .find(|m| m.id == cell_id)
-            .ok_or(ServerError::CellNotFound(cell_id))?;
+            .ok_or_else(|| self.cell_kind_error(cell_id, CellKind::Markdown))?;
 
         let start_line = md_cell.span.start_line;
         let end_line = md_cell.span.end_line;
@@ -1411,23 +3632,125 @@ impl NotebookSession {
             .markdown_cells
             .iter()
             .find(|m| m.id == cell_id)
-            .ok_or(ServerError::CellNotFound(cell_id))?;
+            .ok_or_else(|| self.cell_kind_error(cell_id, CellKind::Markdown))?;
+
+        let start_line = md_cell.span.start_line;
+        let end_line = md_cell.span.end_line;
+        let content = md_cell.content.clone();
+
+        // Load and edit the source file
+        let mut editor = SourceEditor::load(&self.path)?;
+        editor.delete_markdown_cell(start_line, end_line)?;
+        editor.save()?;
+
+        // Record for undo
+        self.undo_manager
+            .record(UndoableOperation::DeleteMarkdownCell {
+                start_line,
+                content,
+            });
+
+        // Reload to update in-memory state
+        self.reload()?;
+
+        Ok(())
+    }
+
+    /// Convert a cell between code and markdown.
+    ///
+    /// Code cells are commented out into a `//` block; markdown cells become
+    /// a cell returning their text. Refuses conversions that would leave the
+    /// notebook invalid, such as commenting out a cell other cells depend on.
+    pub fn convert_cell(&mut self, cell_id: CellId, to: CellType) -> ServerResult<()> {
+        let code_cell = self.cells.iter().find(|c| c.id == cell_id);
+        let md_cell = self.markdown_cells.iter().find(|m| m.id == cell_id);
+
+        let mut editor = SourceEditor::load(&self.path)?;
+
+        let conversion = match (code_cell, md_cell, to) {
+            (Some(cell), _, CellType::Markdown) => {
+                let dependents: Vec<&str> = self
+                    .cells
+                    .iter()
+                    .filter(|c| {
+                        c.dependencies
+                            .iter()
+                            .any(|d| d.param_name == cell.output_name())
+                    })
+                    .map(|c| c.name.as_str())
+                    .collect();
+                if !dependents.is_empty() {
+                    return Err(ServerError::InvalidOperation(format!(
+                        "Cannot convert cell '{}' because it is used by: {}",
+                        cell.name,
+                        dependents.join(", ")
+                    )));
+                }
+                editor.convert_cell_to_markdown(&cell.name)?
+            }
+            (_, Some(md), CellType::Code) => {
+                if md.is_module_doc {
+                    return Err(ServerError::InvalidOperation(
+                        "Module documentation cannot be converted to code".to_string(),
+                    ));
+                }
+                editor
+                    .convert_markdown_to_cell(md.span.start_line, md.span.end_line)?
+                    .0
+            }
+            (Some(_), _, CellType::Code) | (_, Some(_), CellType::Markdown) => {
+                let kind = if to == CellType::Code {
+                    "code"
+                } else {
+                    "markdown"
+                };
+                return Err(ServerError::InvalidOperation(format!(
+                    "Cell is already a {} cell",
+                    kind
+                )));
+            }
+            (Some(_), _, CellType::Definition) | (_, Some(_), CellType::Definition) => {
+                return Err(ServerError::InvalidOperation(
+                    "Cells can only be converted between code and markdown".to_string(),
+                ));
+            }
+            (None, None, _) => return Err(self.cell_kind_error(cell_id, CellKind::Code)),
+        };
+
+        editor.save()?;
+
+        // Record for undo
+        self.undo_manager.record(UndoableOperation::ConvertCell {
+            start_line: conversion.start_line,
+            old_end_line: conversion.old_end_line,
+            new_end_line: conversion.new_end_line,
+            old_text: conversion.old_text,
+            new_text: conversion.new_text,
+            to,
+        });
 
-        let start_line = md_cell.span.start_line;
-        let end_line = md_cell.span.end_line;
-        let content = md_cell.content.clone();
+        // Reload to update in-memory state
+        self.reload()?;
 
-        // Load and edit the source file
+        Ok(())
+    }
+
+    /// Set the notebook metadata.
+    ///
+    /// Writes the `metadata` block in the module doc, replacing any existing
+    /// block, and reloads the notebook.
+    pub fn set_metadata(&mut self, metadata: NotebookMetadata) -> ServerResult<()> {
         let mut editor = SourceEditor::load(&self.path)?;
-        editor.delete_markdown_cell(start_line, end_line)?;
+        let old = parse_metadata_block(editor.content());
+        let updated = write_metadata_block(editor.content(), Some(&metadata));
+        editor.replace_content(updated)?;
         editor.save()?;
 
         // Record for undo
-        self.undo_manager
-            .record(UndoableOperation::DeleteMarkdownCell {
-                start_line,
-                content,
-            });
+        self.undo_manager.record(UndoableOperation::SetMetadata {
+            old: old.map(Box::new),
+            new: Box::new(metadata),
+        });
 
         // Reload to update in-memory state
         self.reload()?;
@@ -1448,14 +3771,14 @@ impl NotebookSession {
             .markdown_cells
             .iter()
             .find(|m| m.id == cell_id)
-            .ok_or(ServerError::CellNotFound(cell_id))?;
+            .ok_or_else(|| self.cell_kind_error(cell_id, CellKind::Markdown))?;
 
         let start_line = md_cell.span.start_line;
         let end_line = md_cell.span.end_line;
 
         // Load and edit the source file
         let mut editor = SourceEditor::load(&self.path)?;
-        editor.move_markdown_cell(start_line, end_line, direction)?;
+        let moved = editor.move_markdown_cell(start_line, end_line, direction)?;
         editor.save()?;
 
         // Record for undo
@@ -1464,6 +3787,7 @@ impl NotebookSession {
                 start_line,
                 end_line,
                 direction,
+                moved,
             });
 
         // Reload to update in-memory state
@@ -1472,6 +3796,163 @@ impl NotebookSession {
         Ok(())
     }
 
+    /// Move any cell to a position in the source order.
+    ///
+    /// The cell ends up at `index` in `source_order`: before the cell
+    /// currently there, counting without the moved cell, or at the end of
+    /// the file if `index` is the last position.
+    pub fn move_cell_to_index(&mut self, cell_id: CellId, index: usize) -> ServerResult<()> {
+        let mut editor = SourceEditor::load(&self.path)?;
+
+        let (start_line, end_line) = if let Some(cell) = self.cells.iter().find(|c| c.id == cell_id)
+        {
+            editor.cell_line_range(&cell.name)?
+        } else if let Some(md) = self.markdown_cells.iter().find(|m| m.id == cell_id) {
+            (md.span.start_line, md.span.end_line)
+        } else if let Some(def) = self.definition_cells.iter().find(|d| d.id == cell_id) {
+            (def.span.start_line, def.span.end_line)
+        } else {
+            return Err(ServerError::CellNotFound(cell_id));
+        };
+
+        let others: Vec<usize> = self
+            .source_order_with_lines()
+            .into_iter()
+            .filter(|(id, _)| *id != cell_id)
+            .map(|(_, line)| line)
+            .collect();
+        if index > others.len() {
+            return Err(ServerError::InvalidOperation(format!(
+                "Index {} is out of range (notebook has {} cells)",
+                index,
+                others.len() + 1
+            )));
+        }
+        let before_line = others.get(index).copied();
+
+        let moved = editor.move_block(start_line, end_line, before_line)?;
+        editor.save()?;
+
+        self.undo_manager
+            .record(UndoableOperation::MoveCellToIndex {
+                start_line,
+                end_line,
+                before_line,
+                moved,
+            });
+
+        self.reload()?;
+
+        Ok(())
+    }
+
+    /// Rewrite the notebook so its cells appear in execution order.
+    ///
+    /// Definition cells come first, in their current order, followed by the
+    /// code cells in topological order. Markdown cells move with the cell
+    /// after them, or with the last cell if none follows; the module doc
+    /// stays at the top. Returns how many cells change position. The file is
+    /// only rewritten if `apply` is set, as one undoable operation.
+    pub fn reorder_to_execution_order(&mut self, apply: bool) -> ServerResult<usize> {
+        if !self.unparsed_regions.is_empty() {
+            return Err(ServerError::InvalidOperation(
+                "Fix the items that failed to parse before reordering the notebook".to_string(),
+            ));
+        }
+
+        let mut editor = SourceEditor::load(&self.path)?;
+
+        // Group each markdown cell with the cell after it
+        struct Group {
+            anchor: Option<(CellId, CellType)>,
+            start_line: usize,
+            end_line: usize,
+            members: Vec<CellId>,
+        }
+        let mut groups: Vec<Group> = Vec::new();
+        let mut pending: Option<Group> = None;
+        for (id, _, cell_type) in self.collect_cells_in_source_order() {
+            let (start_line, end_line) = match cell_type {
+                CellType::Code => {
+                    let cell = self.cells.iter().find(|c| c.id == id);
+                    editor.cell_line_range(&cell.ok_or(ServerError::CellNotFound(id))?.name)?
+                }
+                CellType::Markdown => {
+                    let md = self.markdown_cells.iter().find(|m| m.id == id);
+                    let md = md.ok_or(ServerError::CellNotFound(id))?;
+                    if md.is_module_doc {
+                        continue;
+                    }
+                    (md.span.start_line, md.span.end_line)
+                }
+                CellType::Definition => {
+                    let def = self.definition_cells.iter().find(|d| d.id == id);
+                    let def = def.ok_or(ServerError::CellNotFound(id))?;
+                    (def.span.start_line, def.span.end_line)
+                }
+            };
+            let group = pending.get_or_insert(Group {
+                anchor: None,
+                start_line,
+                end_line,
+                members: Vec::new(),
+            });
+            group.end_line = end_line;
+            group.members.push(id);
+            if cell_type != CellType::Markdown {
+                group.anchor = Some((id, cell_type));
+                groups.extend(pending.take());
+            }
+        }
+        if let Some(trailing) = pending {
+            match groups.last_mut() {
+                Some(last) => {
+                    last.end_line = trailing.end_line;
+                    last.members.extend(trailing.members);
+                }
+                None => groups.push(trailing),
+            }
+        }
+
+        // Definitions first, then code cells in execution order
+        let order = self.graph.topological_order()?;
+        let rank = |group: &Group| match group.anchor {
+            Some((_, CellType::Definition)) | None => (0, 0),
+            Some((id, _)) => (
+                1,
+                order.iter().position(|&c| c == id).unwrap_or(order.len()),
+            ),
+        };
+        let old_members: Vec<CellId> = groups.iter().flat_map(|g| g.members.clone()).collect();
+        groups.sort_by_key(|group| rank(group));
+        let moved = groups
+            .iter()
+            .flat_map(|g| &g.members)
+            .zip(&old_members)
+            .filter(|(new, old)| new != old)
+            .count();
+
+        if !apply || moved == 0 {
+            return Ok(moved);
+        }
+
+        let old_content = editor.content().to_string();
+        let blocks: Vec<(usize, usize)> =
+            groups.iter().map(|g| (g.start_line, g.end_line)).collect();
+        editor.reorder_blocks(&blocks)?;
+        editor.save()?;
+
+        self.undo_manager
+            .record(UndoableOperation::ReorderToExecutionOrder {
+                old_content,
+                new_content: editor.content().to_string(),
+            });
+
+        self.reload()?;
+
+        Ok(moved)
+    }
+
     /// Infer the definition type from content for validation.
     ///
     /// Provides early error detection when users specify an incorrect definition type.
@@ -1607,10 +4088,48 @@ impl NotebookSession {
         Ok(new_cell_id)
     }
 
+    /// Replace the text of an item that failed to parse.
+    ///
+    /// The text is written as-is. If it parses now, the item becomes a cell
+    /// again on reload; otherwise it stays an unparsed region with the new
+    /// error.
+    pub fn edit_unparsed_region(
+        &mut self,
+        cell_id: CellId,
+        new_content: String,
+    ) -> ServerResult<()> {
+        let region = self
+            .unparsed_regions
+            .iter()
+            .find(|r| r.id == cell_id)
+            .ok_or_else(|| self.cell_kind_error(cell_id, CellKind::Unparsed))?;
+        let start_line = region.span.start_line;
+        let old_end_line = region.span.end_line;
+        let old_text = region.content.clone();
+
+        let mut editor = SourceEditor::load(&self.path)?;
+        editor.edit_raw_code(start_line, old_end_line, &new_content)?;
+        editor.save()?;
+
+        let new_end_line = start_line + new_content.lines().count().max(1) - 1;
+        self.undo_manager
+            .record(UndoableOperation::EditUnparsedRegion {
+                start_line,
+                old_end_line,
+                new_end_line,
+                old_text,
+                new_text: new_content,
+            });
+
+        self.reload()
+    }
+
     /// Edit a definition cell's content.
     ///
     /// Modifies the .rs source file and reloads the notebook.
-    /// Returns a list of cells that are now dirty due to the definition change.
+    /// Returns a list of cells that are now dirty due to the definition change:
+    /// none for comment-only edits, the cells using the changed items when the
+    /// same items are still defined, and every cell with output otherwise.
     pub fn edit_definition_cell(
         &mut self,
         cell_id: CellId,
@@ -1621,11 +4140,12 @@ impl NotebookSession {
             .definition_cells
             .iter()
             .find(|d| d.id == cell_id)
-            .ok_or(ServerError::CellNotFound(cell_id))?;
+            .ok_or_else(|| self.cell_kind_error(cell_id, CellKind::Definition))?;
 
         let start_line = def_cell.span.start_line;
         let end_line = def_cell.span.end_line;
         let old_content = def_cell.content.clone();
+        let change = classify_definition_change(&old_content, &new_content);
 
         // Load and edit the source file
         let mut editor = SourceEditor::load(&self.path)?;
@@ -1646,11 +4166,29 @@ impl NotebookSession {
         // Reload to update in-memory state (rebuilds universe with new definitions)
         self.reload()?;
 
-        // Mark ALL executable cells as dirty (only if they have output - pristine cells stay pristine)
+        // Mark the cells the edit can affect as dirty (only if they have output - pristine
+        // cells stay pristine). When the edit can't be analyzed, that's all of them.
+        let affected = match change {
+            DefinitionChange::Unchanged => return Ok(Vec::new()),
+            DefinitionChange::Changed(symbols) => {
+                let definitions: Vec<String> = self
+                    .definition_cells
+                    .iter()
+                    .map(|d| d.content.clone())
+                    .collect();
+                Some(affected_symbols(&definitions, symbols))
+            }
+            DefinitionChange::Unknown => None,
+        };
         let dirty_cells: Vec<CellId> = self
             .cells
             .iter()
             .filter(|c| self.cell_outputs.contains_key(&c.id)) // Only cells with output
+            .filter(|c| {
+                affected
+                    .as_ref()
+                    .is_none_or(|symbols| references_any(&c.source_code, symbols))
+            })
             .map(|c| c.id)
             .collect();
         for &cell_id in &dirty_cells {
@@ -1665,13 +4203,24 @@ impl NotebookSession {
     /// Delete a definition cell.
     ///
     /// Modifies the .rs source file and reloads the notebook.
-    pub fn delete_definition_cell(&mut self, cell_id: CellId) -> ServerResult<()> {
+    /// Unless `force` is set, refuses to delete definitions that code cells use.
+    pub fn delete_definition_cell(&mut self, cell_id: CellId, force: bool) -> ServerResult<()> {
         // Find the definition cell
         let def_cell = self
             .definition_cells
             .iter()
             .find(|d| d.id == cell_id)
-            .ok_or(ServerError::CellNotFound(cell_id))?;
+            .ok_or_else(|| self.cell_kind_error(cell_id, CellKind::Definition))?;
+
+        if !force {
+            let others: Vec<&str> = self
+                .definition_cells
+                .iter()
+                .filter(|d| d.id != cell_id)
+                .map(|d| d.content.as_str())
+                .collect();
+            check_definition_unused(&def_cell.content, &others, &self.cells)?;
+        }
 
         let start_line = def_cell.span.start_line;
         let end_line = def_cell.span.end_line;
@@ -1711,14 +4260,14 @@ impl NotebookSession {
             .definition_cells
             .iter()
             .find(|d| d.id == cell_id)
-            .ok_or(ServerError::CellNotFound(cell_id))?;
+            .ok_or_else(|| self.cell_kind_error(cell_id, CellKind::Definition))?;
 
         let start_line = def_cell.span.start_line;
         let end_line = def_cell.span.end_line;
 
         // Load and edit the source file
         let mut editor = SourceEditor::load(&self.path)?;
-        editor.move_markdown_cell(start_line, end_line, direction)?;
+        let moved = editor.move_markdown_cell(start_line, end_line, direction)?;
         editor.save()?;
 
         // Record for undo
@@ -1727,6 +4276,7 @@ impl NotebookSession {
                 start_line,
                 end_line,
                 direction,
+                moved,
             });
 
         // Reload to update in-memory state
@@ -1750,7 +4300,8 @@ impl NotebookSession {
         let mut editor = SourceEditor::load(&self.path)?;
 
         match &operation {
-            UndoableOperation::InsertCell { cell_name, .. } => {
+            UndoableOperation::InsertCell { cell_name, .. }
+            | UndoableOperation::InsertCellAt { cell_name, .. } => {
                 // Undo insert = delete
                 editor.delete_cell(cell_name)?;
             }
@@ -1794,6 +4345,12 @@ impl NotebookSession {
                 // Undo edit = restore old source
                 editor.edit_raw_code(*start_line, *end_line, old_source)?;
             }
+            UndoableOperation::BatchEdit { blocks } => {
+                // Undo batch edit = restore each block, top-down
+                for block in blocks {
+                    editor.edit_raw_code(block.start_line, block.new_end_line, &block.old_text)?;
+                }
+            }
             UndoableOperation::InsertMarkdownCell {
                 start_line,
                 end_line,
@@ -1824,17 +4381,13 @@ impl NotebookSession {
                 };
                 editor.insert_markdown_cell(content, after_line)?;
             }
-            UndoableOperation::MoveMarkdownCell {
-                start_line,
-                end_line,
-                direction,
-            } => {
-                // Undo move markdown = move in opposite direction
-                let reverse_direction = match direction {
-                    MoveDirection::Up => MoveDirection::Down,
-                    MoveDirection::Down => MoveDirection::Up,
-                };
-                editor.move_markdown_cell(*start_line, *end_line, reverse_direction)?;
+            UndoableOperation::MoveMarkdownCell { moved, .. } => {
+                // Undo move markdown = move it back to where it was
+                editor.move_block(
+                    moved.new_start_line,
+                    moved.new_end_line,
+                    moved.undo_before_line,
+                )?;
             }
             UndoableOperation::InsertDefinitionCell {
                 start_line,
@@ -1866,17 +4419,48 @@ impl NotebookSession {
                 };
                 editor.insert_markdown_cell(content, after_line)?;
             }
-            UndoableOperation::MoveDefinitionCell {
+            UndoableOperation::MoveDefinitionCell { moved, .. } => {
+                // Undo move definition = move it back to where it was
+                editor.move_block(
+                    moved.new_start_line,
+                    moved.new_end_line,
+                    moved.undo_before_line,
+                )?;
+            }
+            UndoableOperation::MoveCellToIndex { moved, .. } => {
+                // Undo move = move it back
+                editor.move_block(
+                    moved.new_start_line,
+                    moved.new_end_line,
+                    moved.undo_before_line,
+                )?;
+            }
+            UndoableOperation::ConvertCell {
                 start_line,
-                end_line,
-                direction,
+                new_end_line,
+                old_text,
+                ..
             } => {
-                // Undo move definition = move in opposite direction
-                let reverse_direction = match direction {
-                    MoveDirection::Up => MoveDirection::Down,
-                    MoveDirection::Down => MoveDirection::Up,
-                };
-                editor.move_markdown_cell(*start_line, *end_line, reverse_direction)?;
+                // Undo convert = restore the original text
+                editor.edit_raw_code(*start_line, *new_end_line, old_text)?;
+            }
+            UndoableOperation::EditUnparsedRegion {
+                start_line,
+                new_end_line,
+                old_text,
+                ..
+            } => {
+                // Undo edit = restore the original text
+                editor.edit_raw_code(*start_line, *new_end_line, old_text)?;
+            }
+            UndoableOperation::SetMetadata { old, .. } => {
+                // Undo set = write back the previous block (or remove it)
+                let restored = write_metadata_block(editor.content(), old.as_deref());
+                editor.replace_content(restored)?;
+            }
+            UndoableOperation::ReorderToExecutionOrder { old_content, .. } => {
+                // Undo reorder = restore the previous source
+                editor.replace_content(old_content.clone())?;
             }
         }
 
@@ -1912,6 +4496,9 @@ impl NotebookSession {
                 // Re-insert at the original position
                 let _ = editor.insert_cell(after_cell_name.as_deref())?;
             }
+            UndoableOperation::InsertCellAt { before_line, .. } => {
+                let _ = editor.insert_cell_before_line(*before_line)?;
+            }
             UndoableOperation::DeleteCell { cell_name, .. } => {
                 // Redo delete = delete again
                 editor.delete_cell(cell_name)?;
@@ -1946,6 +4533,12 @@ impl NotebookSession {
                 // Redo edit = apply new source again
                 editor.edit_raw_code(*start_line, *end_line, new_source)?;
             }
+            UndoableOperation::BatchEdit { blocks } => {
+                // Redo batch edit = reapply each block, bottom-up
+                for block in blocks.iter().rev() {
+                    editor.edit_raw_code(block.start_line, block.old_end_line, &block.new_text)?;
+                }
+            }
             UndoableOperation::InsertMarkdownCell {
                 start_line,
                 content,
@@ -1983,6 +4576,7 @@ impl NotebookSession {
                 start_line,
                 end_line,
                 direction,
+                ..
             } => {
                 // Redo move markdown = move in same direction
                 editor.move_markdown_cell(*start_line, *end_line, *direction)?;
@@ -2023,10 +4617,47 @@ impl NotebookSession {
                 start_line,
                 end_line,
                 direction,
+                ..
             } => {
                 // Redo move definition = move in same direction
                 editor.move_markdown_cell(*start_line, *end_line, *direction)?;
             }
+            UndoableOperation::MoveCellToIndex {
+                start_line,
+                end_line,
+                before_line,
+                ..
+            } => {
+                // Redo move = move it again
+                editor.move_block(*start_line, *end_line, *before_line)?;
+            }
+            UndoableOperation::ConvertCell {
+                start_line,
+                old_end_line,
+                new_text,
+                ..
+            } => {
+                // Redo convert = apply the converted text again
+                editor.edit_raw_code(*start_line, *old_end_line, new_text)?;
+            }
+            UndoableOperation::EditUnparsedRegion {
+                start_line,
+                old_end_line,
+                new_text,
+                ..
+            } => {
+                // Redo edit = apply the new text again
+                editor.edit_raw_code(*start_line, *old_end_line, new_text)?;
+            }
+            UndoableOperation::SetMetadata { new, .. } => {
+                // Redo set = write the metadata again
+                let updated = write_metadata_block(editor.content(), Some(new.as_ref()));
+                editor.replace_content(updated)?;
+            }
+            UndoableOperation::ReorderToExecutionOrder { new_content, .. } => {
+                // Redo reorder = write the reordered source again
+                editor.replace_content(new_content.clone())?;
+            }
         }
 
         editor.save()?;
@@ -2058,6 +4689,186 @@ impl NotebookSession {
     }
 }
 
+/// Pair the IDs of cells of one kind before and after a reload.
+///
+/// Cells are matched by `key` (a code cell's name, otherwise its content);
+/// the rest are paired in source order, so an edited or renamed cell keeps
+/// its identity unless cells were also added or removed.
+fn pair_cell_ids<'a>(
+    old: impl Iterator<Item = (CellId, &'a str)>,
+    new: impl Iterator<Item = (CellId, &'a str)>,
+    ids: &mut HashMap<CellId, CellId>,
+) {
+    let old: Vec<_> = old.collect();
+    let mut new: Vec<_> = new.map(Some).collect();
+    let mut unmatched = Vec::new();
+    for (old_id, key) in old {
+        let found = new
+            .iter_mut()
+            .find(|cell| cell.is_some_and(|(_, k)| k == key))
+            .and_then(Option::take);
+        match found {
+            Some((new_id, _)) => {
+                ids.insert(old_id, new_id);
+            }
+            None => unmatched.push(old_id),
+        }
+    }
+    let remaining = new.into_iter().flatten().map(|(id, _)| id);
+    if unmatched.len() == remaining.clone().count() {
+        ids.extend(unmatched.into_iter().zip(remaining));
+    }
+}
+
+/// Path of the virtual `notebook.rs` written for LSP analysis.
+fn virtual_notebook_path(config: &CompilerConfig) -> PathBuf {
+    config.universe_build_dir().join("src").join("notebook.rs")
+}
+
+/// Convert a compiler diagnostic to its protocol form.
+fn compile_error_info(e: &venus_core::compile::CompileError) -> CompileErrorInfo {
+    CompileErrorInfo {
+        message: e.message.clone(),
+        code: e.code.clone(),
+        location: e.spans.first().map(|s| crate::protocol::SourceLocation {
+            line: s.location.line as u32,
+            column: s.location.column as u32,
+            end_line: s.end_location.as_ref().map(|l| l.line as u32),
+            end_column: s.end_location.as_ref().map(|l| l.column as u32),
+        }),
+        rendered: e.rendered.clone(),
+    }
+}
+
+/// Warning shown when the notebook defines a `fn main`.
+fn main_function_warning(line: usize) -> String {
+    format!(
+        "`fn main` at line {} is never run: Venus only executes `#[venus::cell]` functions",
+        line
+    )
+}
+
+/// Warnings for widgets of cell `name` whose IDs other cells already use.
+fn duplicate_widget_warnings<'a>(
+    name: &str,
+    widgets: &[WidgetDef],
+    others: impl Iterator<Item = (&'a str, &'a [WidgetDef])>,
+) -> Vec<String> {
+    let mut messages = Vec::new();
+    for (other, defs) in others {
+        for widget in widgets {
+            if defs.iter().any(|d| d.id() == widget.id()) {
+                messages.push(format!(
+                    "duplicate widget id '{}' in cells {} and {}: they share one value",
+                    widget.id(),
+                    other,
+                    name
+                ));
+            }
+        }
+    }
+    messages
+}
+
+/// Hash of the external dependencies declared in a notebook's cargo block.
+pub(crate) fn dependency_block_hash(source: &str) -> u64 {
+    let mut parser = DependencyParser::new();
+    parser.parse(source);
+    parser.calculate_hash()
+}
+
+/// Explain why a cell cannot run because some dependency outputs are
+/// unavailable, naming failed dependencies before ones that never ran.
+fn unavailable_dependencies_message(cell_name: &str, failed: &[&str], missing: &[&str]) -> String {
+    let mut message = String::new();
+    match failed {
+        [] => {}
+        [dep] => message.push_str(&format!(
+            "Cannot run `{}`: dependency `{}` failed — fix and rerun `{}` first.",
+            cell_name, dep, dep
+        )),
+        deps => message.push_str(&format!(
+            "Cannot run `{}`: dependencies {} failed — fix and rerun them first.",
+            cell_name,
+            deps.iter()
+                .map(|d| format!("`{}`", d))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+    if !missing.is_empty() {
+        if !message.is_empty() {
+            message.push(' ');
+        }
+        message.push_str(&format!(
+            "Missing dependencies: {}. Run dependent cells first.",
+            missing.join(", ")
+        ));
+    }
+    message
+}
+
+/// One-line preview of an output for history listings.
+///
+/// Uses the first non-empty line of the text representation, shortened to
+/// [`HISTORY_PREVIEW_CHARS`], or names the kind of output when there is no text.
+fn history_preview(output: &CellOutput) -> String {
+    if let Some(line) = output
+        .text
+        .as_deref()
+        .and_then(|text| text.lines().map(str::trim).find(|l| !l.is_empty()))
+    {
+        if line.chars().count() > HISTORY_PREVIEW_CHARS {
+            let short: String = line.chars().take(HISTORY_PREVIEW_CHARS - 1).collect();
+            return format!("{}…", short);
+        }
+        return line.to_string();
+    }
+
+    if output.image.is_some() {
+        "[image]".to_string()
+    } else if output.html.is_some() {
+        "[html]".to_string()
+    } else if output.json.is_some() {
+        "[json]".to_string()
+    } else {
+        String::new()
+    }
+}
+
+/// Fail if code cells use a symbol that only `content` defines.
+///
+/// Content that cannot be parsed is not checked.
+fn check_definition_unused(
+    content: &str,
+    other_definitions: &[&str],
+    cells: &[CellInfo],
+) -> ServerResult<()> {
+    let Some(mut symbols) = defined_symbols(content) else {
+        return Ok(());
+    };
+    for other in other_definitions {
+        for symbol in defined_symbols(other).unwrap_or_default() {
+            symbols.remove(&symbol);
+        }
+    }
+
+    let dependents: Vec<&str> = cells
+        .iter()
+        .filter(|c| references_any(&c.source_code, &symbols))
+        .map(|c| c.name.as_str())
+        .collect();
+
+    if dependents.is_empty() {
+        Ok(())
+    } else {
+        Err(ServerError::InvalidOperation(format!(
+            "Cannot delete definition cell because it is used by: {}",
+            dependents.join(", ")
+        )))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2068,4 +4879,230 @@ mod tests {
         let (tx, _rx) = broadcast::channel::<ServerMessage>(16);
         drop(tx);
     }
+
+    #[test]
+    fn test_cell_source_hash_covers_output_format() {
+        let path = Path::new("notebook.rs");
+        let cell = |attr: &str| {
+            let source = format!("{}\npub fn a() -> i32 {{\n    1\n}}\n", attr);
+            ParsedNotebook::parse(source, path).unwrap().cells.remove(0)
+        };
+        let plain = cell("#[venus::cell]");
+        let json = cell("#[venus::cell(output = \"json\")]");
+
+        assert_eq!(plain.source_code, json.source_code);
+        assert_ne!(
+            NotebookSession::cell_source_hash(&plain, 0),
+            NotebookSession::cell_source_hash(&json, 0)
+        );
+    }
+
+    #[test]
+    fn test_dependency_block_hash_ignores_code_changes() {
+        let notebook = |deps: &str, body: &str| {
+            format!(
+                "//! ```cargo\n//! [dependencies]\n{}//! ```\n\n#[venus::cell]\npub fn a() -> i32 {{\n    {}\n}}\n",
+                deps, body
+            )
+        };
+        let serde = "//! serde = \"1.0\"\n";
+        let base = dependency_block_hash(&notebook(serde, "1"));
+
+        assert_eq!(dependency_block_hash(&notebook(serde, "2")), base);
+        assert_ne!(
+            dependency_block_hash(&notebook(&format!("{}//! rand = \"0.8\"\n", serde), "1")),
+            base
+        );
+    }
+
+    #[test]
+    fn test_duplicate_widget_warnings() {
+        let slider = |id: &str| WidgetDef::Slider {
+            id: id.to_string(),
+            label: id.to_string(),
+            min: 0.0,
+            max: 1.0,
+            step: 0.1,
+            value: 0.5,
+        };
+        let first = [slider("x"), slider("y")];
+        let unrelated = [slider("z")];
+        let others = [("first", &first[..]), ("unrelated", &unrelated[..])];
+
+        assert_eq!(
+            duplicate_widget_warnings("second", &[slider("x")], others.into_iter()),
+            vec!["duplicate widget id 'x' in cells first and second: they share one value"]
+        );
+        assert!(duplicate_widget_warnings("second", &[slider("w")], others.into_iter()).is_empty());
+    }
+
+    #[test]
+    fn test_unavailable_dependencies_message() {
+        assert_eq!(
+            unavailable_dependencies_message("x", &["y"], &[]),
+            "Cannot run `x`: dependency `y` failed — fix and rerun `y` first."
+        );
+        assert_eq!(
+            unavailable_dependencies_message("x", &["y", "z"], &["w"]),
+            "Cannot run `x`: dependencies `y`, `z` failed — fix and rerun them first. \
+             Missing dependencies: w. Run dependent cells first."
+        );
+        assert_eq!(
+            unavailable_dependencies_message("x", &[], &["w"]),
+            "Missing dependencies: w. Run dependent cells first."
+        );
+    }
+
+    #[test]
+    fn test_history_preview() {
+        let text = |t: &str| CellOutput {
+            text: Some(t.to_string()),
+            html: None,
+            image: None,
+            json: None,
+            widgets: Vec::new(),
+            stale: false,
+        };
+
+        assert_eq!(
+            history_preview(&text("\n  first line  \nsecond")),
+            "first line"
+        );
+
+        let long = history_preview(&text(&"é".repeat(200)));
+        assert_eq!(long.chars().count(), HISTORY_PREVIEW_CHARS);
+        assert!(long.ends_with('…'));
+
+        let image = CellOutput {
+            text: None,
+            image: Some("iVBOR".to_string()),
+            ..text("")
+        };
+        assert_eq!(history_preview(&image), "[image]");
+    }
+
+    #[test]
+    fn test_deleting_used_definition_is_refused() {
+        let source = r#"pub struct Config {
+    pub n: i32,
+}
+
+pub struct Unused;
+
+#[venus::cell]
+pub fn config() -> Config {
+    Config { n: 1 }
+}
+
+#[venus::cell]
+pub fn total() -> i32 {
+    2
+}
+"#;
+        let parsed = CellParser::new()
+            .parse_str(source, Path::new("notebook.rs"))
+            .unwrap();
+        let cells = &parsed.code_cells;
+
+        let err = check_definition_unused("pub struct Config {\n    pub n: i32,\n}", &[], cells)
+            .unwrap_err();
+        assert!(matches!(&err, ServerError::InvalidOperation(msg) if msg.ends_with(": config")));
+
+        assert!(check_definition_unused("pub struct Unused;", &[], cells).is_ok());
+
+        // Still defined elsewhere
+        assert!(
+            check_definition_unused("pub struct Config;", &["pub struct Config;"], cells).is_ok()
+        );
+    }
+
+    #[test]
+    fn test_broken_notebook_is_not_parsed() {
+        let path = Path::new("notebook.rs");
+        let good = "#[venus::cell]\npub fn a() -> i32 {\n    1\n}\n";
+        let parsed = ParsedNotebook::parse(good.to_string(), path).unwrap();
+        assert_eq!(parsed.cells.len(), 1);
+
+        // reload() applies nothing when parsing fails, so the previous cells stay
+        let broken = "#[venus::cell]\npub fn a() -> i32 {\n    1 +\n}\n";
+        assert!(ParsedNotebook::parse(broken.to_string(), path).is_err());
+        assert_eq!(CellParser::check_syntax(broken).unwrap_err().line, 4);
+
+        let dangling = "#[venus::cell]\npub fn b(a: &i32) -> i32 {\n    *a\n}\n";
+        assert!(ParsedNotebook::parse(dangling.to_string(), path).is_err());
+
+        // With a good cell alongside, only the broken one is skipped
+        let partial = format!("{}\n{}", good, broken.replace("fn a", "fn c"));
+        let parsed = ParsedNotebook::parse(partial, path).unwrap();
+        assert_eq!(parsed.cells.len(), 1);
+        assert_eq!(parsed.unparsed_regions.len(), 1);
+        assert_eq!(parsed.unparsed_regions[0].span.start_line, 6);
+    }
+
+    #[tokio::test]
+    #[ignore = "Builds a notebook universe"]
+    async fn test_selection_depends_on_impl_block_cell() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("notebook.rs");
+        std::fs::write(
+            &path,
+            "pub struct Cells;\n\nimpl Cells {\n    #[venus::cell]\n    pub fn load() -> i32 {\n        41\n    }\n}\n",
+        )
+        .unwrap();
+
+        let (mut session, _rx) = NotebookSession::new(&path, InterruptFlag::default()).unwrap();
+        let load = session.cell_id_by_name("Cells::load").unwrap();
+        session.execute_cell(load, false).await.unwrap();
+
+        let output = session
+            .execute_selection("*load + 1".to_string(), vec![load])
+            .await
+            .unwrap();
+        assert_eq!(output.text.as_deref(), Some("42"));
+    }
+
+    #[tokio::test]
+    #[ignore = "Builds a notebook universe"]
+    async fn test_large_output_streams_from_worker() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("notebook.rs");
+        std::fs::write(
+            &path,
+            "#[venus::cell]\npub fn big() -> String {\n    \"x\".repeat(200_000)\n}\n",
+        )
+        .unwrap();
+
+        let (mut session, mut rx) = NotebookSession::new(&path, InterruptFlag::default()).unwrap();
+        let big = session.cell_id_by_name("big").unwrap();
+        session.execute_cell(big, false).await.unwrap();
+
+        let mut streamed = String::new();
+        let mut seqs = Vec::new();
+        let completed = loop {
+            match rx.try_recv().unwrap() {
+                ServerMessage::CellOutputAppended {
+                    chunk,
+                    seq,
+                    is_final,
+                    ..
+                } => {
+                    streamed.push_str(&chunk);
+                    seqs.push((seq, is_final));
+                }
+                ServerMessage::CellCompleted { output, .. } => break output.unwrap(),
+                _ => {}
+            }
+        };
+
+        assert!(seqs.len() > 1);
+        assert!(
+            seqs.iter().enumerate().all(|(i, &(seq, is_final))| {
+                seq == i as u32 && is_final == (i + 1 == seqs.len())
+            })
+        );
+        assert_eq!(completed.text, None);
+        let output = &session.cell_outputs[&big];
+        assert_eq!(output.display_text(), Some(streamed.as_str()));
+        assert_eq!(streamed.len(), 200_002);
+    }
 }
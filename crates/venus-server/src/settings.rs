@@ -0,0 +1,177 @@
+//! Persistent per-notebook settings.
+//!
+//! Stored in `.venus/settings.toml` next to the notebook, so reopening it
+//! restores the backend, optimization level and execution timeout. The file
+//! is a flat list of `key = value` pairs:
+//!
+//! ```toml
+//! release = false
+//! opt_level = 1
+//! timeout_ms = 30000
+//! ```
+//!
+//! Missing keys keep their defaults. Unknown keys and invalid values are
+//! ignored with a warning, so a stale or hand-edited file never prevents a
+//! notebook from opening.
+
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+/// Name of the settings file inside the `.venus` directory.
+pub const SETTINGS_FILE: &str = "settings.toml";
+
+/// Settings restored when a notebook is opened.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NotebookSettings {
+    /// Compile cells with the optimized LLVM backend instead of Cranelift.
+    pub release: bool,
+    /// Optimization level for cells, if not the backend's default.
+    pub opt_level: Option<u8>,
+    /// Kill cells that run longer than this, in milliseconds.
+    pub timeout_ms: Option<u64>,
+}
+
+impl NotebookSettings {
+    /// Load settings from a file, using defaults if it does not exist.
+    ///
+    /// Problems with the file are logged and otherwise ignored.
+    pub fn load(path: &Path) -> Self {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Self::default(),
+            Err(e) => {
+                tracing::warn!("Failed to read {}: {}", path.display(), e);
+                return Self::default();
+            }
+        };
+
+        let (settings, warnings) = Self::parse(&content);
+        for warning in warnings {
+            tracing::warn!("{}: {}", path.display(), warning);
+        }
+        settings
+    }
+
+    /// Parse settings, returning them with a warning for each ignored line.
+    pub fn parse(content: &str) -> (Self, Vec<String>) {
+        let mut settings = Self::default();
+        let mut warnings = Vec::new();
+
+        for (index, line) in content.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                warnings.push(format!("line {}: expected `key = value`", index + 1));
+                continue;
+            };
+            let (key, value) = (key.trim(), value.trim());
+
+            let valid = match key {
+                "release" => value.parse().map(|v| settings.release = v).is_ok(),
+                "opt_level" => match value.parse::<u8>() {
+                    Ok(level) if level <= 3 => {
+                        settings.opt_level = Some(level);
+                        true
+                    }
+                    _ => false,
+                },
+                "timeout_ms" => value.parse().map(|v| settings.timeout_ms = Some(v)).is_ok(),
+                _ => {
+                    warnings.push(format!("line {}: unknown setting `{}`", index + 1, key));
+                    continue;
+                }
+            };
+            if !valid {
+                warnings.push(format!(
+                    "line {}: invalid value `{}` for `{}`",
+                    index + 1,
+                    value,
+                    key
+                ));
+            }
+        }
+
+        (settings, warnings)
+    }
+
+    /// Render settings in the file format; unset values are left out.
+    pub fn to_toml(&self) -> String {
+        let mut out = format!("release = {}\n", self.release);
+        if let Some(level) = self.opt_level {
+            out.push_str(&format!("opt_level = {}\n", level));
+        }
+        if let Some(timeout) = self.timeout_ms {
+            out.push_str(&format!("timeout_ms = {}\n", timeout));
+        }
+        out
+    }
+
+    /// Write settings to a file, creating its directory if needed.
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.to_toml())
+    }
+
+    /// The execution timeout, if set.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout_ms.map(Duration::from_millis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_settings() {
+        let (settings, warnings) = NotebookSettings::parse(
+            "# comment\nrelease = true\nopt_level = 2  # fast\n\ntimeout_ms=500\n",
+        );
+        assert!(warnings.is_empty());
+        assert_eq!(
+            settings,
+            NotebookSettings {
+                release: true,
+                opt_level: Some(2),
+                timeout_ms: Some(500),
+            }
+        );
+    }
+
+    #[test]
+    fn test_unknown_and_invalid_settings_warn() {
+        let (settings, warnings) =
+            NotebookSettings::parse("colour = \"red\"\nopt_level = 9\nrelease = true\nnonsense\n");
+        assert_eq!(
+            settings,
+            NotebookSettings {
+                release: true,
+                ..Default::default()
+            }
+        );
+        assert_eq!(warnings.len(), 3);
+        assert!(warnings[0].contains("unknown setting `colour`"));
+        assert!(warnings[1].contains("invalid value `9`"));
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join(".venus").join(SETTINGS_FILE);
+        assert_eq!(NotebookSettings::load(&path), NotebookSettings::default());
+
+        let settings = NotebookSettings {
+            release: false,
+            opt_level: Some(1),
+            timeout_ms: None,
+        };
+        settings.save(&path).unwrap();
+        assert_eq!(NotebookSettings::load(&path), settings);
+    }
+}
@@ -2,7 +2,10 @@
 //!
 //! Tracks operations on the notebook and allows undoing/redoing them.
 
-use venus_core::graph::{CellId, DefinitionType, MoveDirection};
+use venus_core::graph::{
+    BlockMove, CellConversion, CellId, CellType, DefinitionType, MoveDirection,
+};
+use venus_sync::NotebookMetadata;
 
 /// Maximum number of undo operations to track.
 const MAX_UNDO_HISTORY: usize = 50;
@@ -19,6 +22,15 @@ pub enum UndoableOperation {
         after_cell_name: Option<String>,
     },
 
+    /// A cell was inserted at a source position. Undo = delete this cell.
+    InsertCellAt {
+        /// Name of the inserted cell.
+        cell_name: String,
+        /// Line the cell was inserted before (for redo).
+        /// None if inserted at the end of the file.
+        before_line: Option<usize>,
+    },
+
     /// A cell was deleted. Undo = restore it.
     DeleteCell {
         /// Name of the deleted cell.
@@ -70,6 +82,13 @@ pub enum UndoableOperation {
         new_source: String,
     },
 
+    /// Several code cells were edited together. Undo = restore all old sources.
+    BatchEdit {
+        /// Replaced blocks, top-down with line numbers from before the edit.
+        /// Undo restores them in order, redo reapplies them in reverse.
+        blocks: Vec<CellConversion>,
+    },
+
     /// A markdown cell was inserted. Undo = delete it.
     InsertMarkdownCell {
         /// Start line of the inserted markdown cell.
@@ -102,7 +121,7 @@ pub enum UndoableOperation {
         content: String,
     },
 
-    /// A markdown cell was moved. Undo = move in opposite direction.
+    /// A markdown cell was moved. Undo = move it back.
     MoveMarkdownCell {
         /// Start line of the moved cell.
         start_line: usize,
@@ -110,6 +129,8 @@ pub enum UndoableOperation {
         end_line: usize,
         /// Direction it was moved.
         direction: MoveDirection,
+        /// Where the cell ended up (for undo).
+        moved: BlockMove,
     },
 
     /// A definition cell was inserted. Undo = delete it.
@@ -150,7 +171,7 @@ pub enum UndoableOperation {
         definition_type: DefinitionType,
     },
 
-    /// A definition cell was moved. Undo = move in opposite direction.
+    /// A definition cell was moved. Undo = move it back.
     MoveDefinitionCell {
         /// Start line of the moved cell.
         start_line: usize,
@@ -158,6 +179,66 @@ pub enum UndoableOperation {
         end_line: usize,
         /// Direction it was moved.
         direction: MoveDirection,
+        /// Where the cell ended up (for undo).
+        moved: BlockMove,
+    },
+
+    /// A cell was moved to a position in the source order. Undo = move it back.
+    MoveCellToIndex {
+        /// Start line of the cell before the move.
+        start_line: usize,
+        /// End line of the cell before the move.
+        end_line: usize,
+        /// Line the cell was moved before (for redo). None = end of file.
+        before_line: Option<usize>,
+        /// Where the cell ended up (for undo).
+        moved: BlockMove,
+    },
+
+    /// A cell was converted between code and markdown. Undo = restore old text.
+    ConvertCell {
+        /// First line of the converted block.
+        start_line: usize,
+        /// Last line of the block before conversion (for redo).
+        old_end_line: usize,
+        /// Last line of the block after conversion (for undo).
+        new_end_line: usize,
+        /// Source text before conversion.
+        old_text: String,
+        /// Source text after conversion.
+        new_text: String,
+        /// Type the cell was converted to.
+        to: CellType,
+    },
+
+    /// An item that failed to parse was edited. Undo = restore old text.
+    EditUnparsedRegion {
+        /// First line of the item.
+        start_line: usize,
+        /// Last line of the item before the edit (for redo).
+        old_end_line: usize,
+        /// Last line of the item after the edit (for undo).
+        new_end_line: usize,
+        /// Text before the edit.
+        old_text: String,
+        /// Text after the edit.
+        new_text: String,
+    },
+
+    /// Notebook metadata was set. Undo = restore the previous metadata block.
+    SetMetadata {
+        /// Previous metadata block (None if the notebook had none).
+        old: Option<Box<NotebookMetadata>>,
+        /// Metadata that was written.
+        new: Box<NotebookMetadata>,
+    },
+
+    /// Cells were reordered to execution order. Undo = restore the old file.
+    ReorderToExecutionOrder {
+        /// Source before reordering.
+        old_content: String,
+        /// Source after reordering.
+        new_content: String,
     },
 }
 
@@ -165,7 +246,7 @@ impl UndoableOperation {
     /// Get a human-readable description of this operation.
     pub fn description(&self) -> String {
         match self {
-            Self::InsertCell { cell_name, .. } => {
+            Self::InsertCell { cell_name, .. } | Self::InsertCellAt { cell_name, .. } => {
                 format!("Insert cell '{}'", cell_name)
             }
             Self::DeleteCell { cell_name, .. } => {
@@ -194,6 +275,7 @@ impl UndoableOperation {
             Self::EditCell { start_line, .. } => {
                 format!("Edit cell at line {}", start_line)
             }
+            Self::BatchEdit { blocks } => format!("Edit {} cells", blocks.len()),
             Self::InsertMarkdownCell { start_line, .. } => {
                 format!("Insert markdown cell at line {}", start_line)
             }
@@ -234,13 +316,24 @@ impl UndoableOperation {
                 };
                 format!("Move definition cell at line {} {}", start_line, dir_str)
             }
+            Self::MoveCellToIndex { start_line, .. } => {
+                format!("Move cell at line {}", start_line)
+            }
+            Self::ConvertCell { start_line, to, .. } => {
+                format!("Convert cell at line {} to {}", start_line, type_str(*to))
+            }
+            Self::EditUnparsedRegion { start_line, .. } => {
+                format!("Edit unparsed code at line {}", start_line)
+            }
+            Self::SetMetadata { .. } => "Set notebook metadata".to_string(),
+            Self::ReorderToExecutionOrder { .. } => "Reorder cells to execution order".to_string(),
         }
     }
 
     /// Get the reverse operation (what undo would do).
     pub fn undo_description(&self) -> String {
         match self {
-            Self::InsertCell { cell_name, .. } => {
+            Self::InsertCell { cell_name, .. } | Self::InsertCellAt { cell_name, .. } => {
                 format!("Delete cell '{}'", cell_name)
             }
             Self::DeleteCell { cell_name, .. } => {
@@ -269,6 +362,7 @@ impl UndoableOperation {
             Self::EditCell { start_line, .. } => {
                 format!("Restore cell at line {}", start_line)
             }
+            Self::BatchEdit { blocks } => format!("Restore {} cells", blocks.len()),
             Self::InsertMarkdownCell { start_line, .. } => {
                 format!("Delete markdown cell at line {}", start_line)
             }
@@ -309,10 +403,38 @@ impl UndoableOperation {
                 };
                 format!("Move definition cell at line {} {}", start_line, dir_str)
             }
+            Self::MoveCellToIndex { start_line, .. } => {
+                format!("Move cell back to line {}", start_line)
+            }
+            Self::ConvertCell { start_line, to, .. } => {
+                let from = match to {
+                    CellType::Markdown => CellType::Code,
+                    _ => CellType::Markdown,
+                };
+                format!(
+                    "Convert cell at line {} back to {}",
+                    start_line,
+                    type_str(from)
+                )
+            }
+            Self::EditUnparsedRegion { start_line, .. } => {
+                format!("Restore unparsed code at line {}", start_line)
+            }
+            Self::SetMetadata { .. } => "Restore notebook metadata".to_string(),
+            Self::ReorderToExecutionOrder { .. } => "Restore previous cell order".to_string(),
         }
     }
 }
 
+/// Lowercase name of a cell type for descriptions.
+fn type_str(cell_type: CellType) -> &'static str {
+    match cell_type {
+        CellType::Code => "code",
+        CellType::Markdown => "markdown",
+        CellType::Definition => "definition",
+    }
+}
+
 /// Manages undo/redo stacks for cell operations.
 #[derive(Debug, Default)]
 pub struct UndoManager {
@@ -467,4 +589,19 @@ mod tests {
         assert_eq!(op.description(), "Move 'bar' up");
         assert_eq!(op.undo_description(), "Move 'bar' down");
     }
+
+    #[test]
+    fn test_convert_descriptions() {
+        let op = UndoableOperation::ConvertCell {
+            start_line: 3,
+            old_end_line: 7,
+            new_end_line: 7,
+            old_text: String::new(),
+            new_text: String::new(),
+            to: CellType::Markdown,
+        };
+
+        assert_eq!(op.description(), "Convert cell at line 3 to markdown");
+        assert_eq!(op.undo_description(), "Convert cell at line 3 back to code");
+    }
 }
@@ -0,0 +1,161 @@
+//! Debounced writing of the virtual `notebook.rs` analyzed by rust-analyzer.
+//!
+//! The session regenerates the file on every reload. Writes happen on a
+//! background thread: updates arriving in quick succession are coalesced,
+//! and content identical to what is already on disk is not written again,
+//! so rust-analyzer does not re-index and file watchers see no churn.
+
+use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// How long to wait for further updates before writing.
+pub const VIRTUAL_FILE_DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Background writer for the virtual notebook file.
+pub struct VirtualFileWriter {
+    /// Sends new content to the writer thread.
+    tx: Option<mpsc::Sender<String>>,
+    /// Held while the file must not change, e.g. during a universe build.
+    hold: Arc<Mutex<()>>,
+    /// Number of times the file was actually written.
+    writes: Arc<AtomicUsize>,
+    /// The writer thread.
+    thread: Option<JoinHandle<()>>,
+}
+
+impl VirtualFileWriter {
+    /// Start a writer for the file at `path`.
+    pub fn new(path: PathBuf) -> Self {
+        let (tx, rx) = mpsc::channel::<String>();
+        let hold = Arc::new(Mutex::new(()));
+        let writes = Arc::new(AtomicUsize::new(0));
+
+        let thread = {
+            let hold = hold.clone();
+            let writes = writes.clone();
+            std::thread::spawn(move || {
+                while let Ok(mut content) = rx.recv() {
+                    // Keep only the latest of a burst of updates
+                    while let Ok(newer) = rx.recv_timeout(VIRTUAL_FILE_DEBOUNCE) {
+                        content = newer;
+                    }
+
+                    let _hold = hold.lock();
+                    match write_if_changed(&path, &content) {
+                        Ok(true) => {
+                            writes.fetch_add(1, Ordering::SeqCst);
+                        }
+                        Ok(false) => {}
+                        Err(e) => tracing::warn!("Failed to write virtual notebook file: {}", e),
+                    }
+                }
+            })
+        };
+
+        Self {
+            tx: Some(tx),
+            hold,
+            writes,
+            thread: Some(thread),
+        }
+    }
+
+    /// Queue new content for the file.
+    pub fn update(&self, content: String) {
+        if let Some(tx) = &self.tx {
+            let _ = tx.send(content);
+        }
+    }
+
+    /// Keep pending content off disk while the guard is alive.
+    ///
+    /// The universe crate compiles the file as a module, so it must not be
+    /// replaced by cell sources in the middle of a universe build.
+    pub fn hold(&self) -> MutexGuard<'_, ()> {
+        self.hold.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Number of times the file was written.
+    pub fn writes(&self) -> usize {
+        self.writes.load(Ordering::SeqCst)
+    }
+
+    /// Write any pending content and stop the writer thread.
+    pub fn shutdown(&mut self) {
+        self.tx = None;
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for VirtualFileWriter {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// Write `content` to `path` unless the file already holds it.
+///
+/// Returns whether the file was written.
+fn write_if_changed(path: &Path, content: &str) -> std::io::Result<bool> {
+    let hash = |bytes: &[u8]| {
+        let mut hasher = DefaultHasher::new();
+        bytes.hash(&mut hasher);
+        hasher.finish()
+    };
+
+    // Compared with the file on disk rather than the last write, since a
+    // universe build replaces the file with a stub
+    if fs::read(path).is_ok_and(|existing| hash(&existing) == hash(content.as_bytes())) {
+        return Ok(false);
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, content)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identical_updates_write_once() {
+        let temp = tempfile::TempDir::new().expect("Failed to create temp dir");
+        let path = temp.path().join("src").join("notebook.rs");
+        let mut writer = VirtualFileWriter::new(path.clone());
+
+        // Two reloads far enough apart not to be coalesced
+        writer.update("pub fn a() {}".to_string());
+        std::thread::sleep(VIRTUAL_FILE_DEBOUNCE * 3);
+        writer.update("pub fn a() {}".to_string());
+        writer.shutdown();
+
+        assert_eq!(writer.writes(), 1);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "pub fn a() {}");
+    }
+
+    #[test]
+    fn test_burst_of_updates_is_coalesced() {
+        let temp = tempfile::TempDir::new().expect("Failed to create temp dir");
+        let path = temp.path().join("notebook.rs");
+        let mut writer = VirtualFileWriter::new(path.clone());
+
+        for i in 0..5 {
+            writer.update(format!("// version {i}"));
+        }
+        writer.shutdown();
+
+        assert_eq!(writer.writes(), 1);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "// version 4");
+    }
+}
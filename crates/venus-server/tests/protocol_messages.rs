@@ -2,38 +2,144 @@
 //!
 //! Tests all client and server message types for correct JSON serialization.
 
-use venus_core::graph::CellId;
+use venus_core::graph::{CellId, CellType, DefinitionType};
 use venus_server::protocol::*;
 
 #[test]
 fn test_all_client_messages_serialize() {
     // Test all ClientMessage variants
     let messages = vec![
+        ClientMessage::Hello {
+            protocol_version: PROTOCOL_VERSION,
+        },
+        ClientMessage::Ping { nonce: 42 },
+        ClientMessage::Lsp {
+            payload: serde_json::json!({ "jsonrpc": "2.0", "id": 1, "method": "shutdown" }),
+        },
+        ClientMessage::Resync,
         ClientMessage::GetState,
         ClientMessage::CellEdit {
             cell_id: CellId::new(1),
             source: "// test code".to_string(),
         },
+        ClientMessage::BatchEdit {
+            edits: vec![
+                CellSourceEdit {
+                    cell_id: CellId::new(1),
+                    source: "// first".to_string(),
+                },
+                CellSourceEdit {
+                    cell_id: CellId::new(2),
+                    source: "// second".to_string(),
+                },
+            ],
+        },
         ClientMessage::ExecuteCell {
             cell_id: CellId::new(1),
+            force: false,
+        },
+        ClientMessage::ExecuteCellWith {
+            cell_id: CellId::new(1),
+            widget_overrides: [(
+                "threshold".to_string(),
+                venus_core::widgets::WidgetValue::Number(0.9),
+            )]
+            .into(),
+        },
+        ClientMessage::SweepCell {
+            cell_id: CellId::new(1),
+            widget_id: "threshold".to_string(),
+            values: vec![
+                venus_core::widgets::WidgetValue::Number(0.1),
+                venus_core::widgets::WidgetValue::Number(0.5),
+            ],
+        },
+        ClientMessage::ExecuteAll { force: true },
+        ClientMessage::RunAndExport {
+            dark: true,
+            force: false,
+        },
+        ClientMessage::ExecuteSelection {
+            source: "config.len()".to_string(),
+            deps: vec![CellId::new(1)],
+        },
+        ClientMessage::RunFrom {
+            cell_id: CellId::new(2),
+            force: false,
         },
-        ClientMessage::ExecuteAll,
         ClientMessage::ExecuteDirty,
+        ClientMessage::SetBreakpoint {
+            cell_id: CellId::new(2),
+            enabled: true,
+        },
+        ClientMessage::Continue,
+        ClientMessage::StepExecution,
         ClientMessage::Interrupt,
+        ClientMessage::AbortCompile,
         ClientMessage::Sync,
         ClientMessage::GetGraph,
+        ClientMessage::ProfileCell {
+            cell_id: CellId::new(1),
+        },
+        ClientMessage::GetNotebookStats,
+        ClientMessage::GetServerInfo,
+        ClientMessage::GetCellDoc {
+            cell_id: CellId::new(1),
+        },
+        ClientMessage::GetCellType {
+            cell_id: CellId::new(1),
+        },
+        ClientMessage::GetCellSignature {
+            cell_id: CellId::new(1),
+        },
+        ClientMessage::EditUnparsedRegion {
+            cell_id: CellId::new(4),
+            new_content: "pub fn fixed() {}".to_string(),
+        },
+        ClientMessage::GetDefinitions,
+        ClientMessage::GetExecutionOrder,
+        ClientMessage::ExportSubgraph {
+            cell_ids: vec![CellId::new(1), CellId::new(2)],
+        },
+        ClientMessage::ExportData,
+        ClientMessage::GarbageCollect,
+        ClientMessage::GetReferences {
+            symbol: "Config".to_string(),
+        },
+        ClientMessage::Lint {
+            disabled: vec![LintRule::DuplicateBody],
+        },
+        ClientMessage::GetLogs {
+            level: Some(LogLevel::Warn),
+        },
         ClientMessage::WidgetUpdate {
             cell_id: CellId::new(1),
             widget_id: "slider1".to_string(),
             value: venus_core::widgets::WidgetValue::Number(42.0),
         },
+        ClientMessage::GetWidgetState,
+        ClientMessage::SetWidgetReactive {
+            cell_id: CellId::new(1),
+            enabled: true,
+        },
+        ClientMessage::GetHistory {
+            cell_id: CellId::new(1),
+        },
         ClientMessage::SelectHistory {
             cell_id: CellId::new(1),
             index: 0,
         },
+        ClientMessage::GetCompileOutput {
+            cell_id: CellId::new(1),
+        },
+        ClientMessage::GetBuildPaths,
+        ClientMessage::InspectOutput {
+            cell_id: CellId::new(1),
+        },
         ClientMessage::InsertCell {
             after_cell_id: Some(CellId::new(1)),
         },
+        ClientMessage::InsertCellAt { index: 0 },
         ClientMessage::DeleteCell {
             cell_id: CellId::new(1),
         },
@@ -44,10 +150,32 @@ fn test_all_client_messages_serialize() {
             cell_id: CellId::new(1),
             direction: MoveDirection::Up,
         },
+        ClientMessage::MoveCellToIndex {
+            cell_id: CellId::new(1),
+            index: 0,
+        },
+        ClientMessage::ReorderToExecutionOrder { confirm: false },
+        ClientMessage::FindOrphanOutputs,
+        ClientMessage::CleanOrphanOutputs,
         ClientMessage::Undo,
         ClientMessage::Redo,
         ClientMessage::RestartKernel,
         ClientMessage::ClearOutputs,
+        ClientMessage::ClearCellOutput {
+            cell_id: CellId::new(1),
+            clear_history: false,
+        },
+        ClientMessage::ReloadFromDisk,
+        ClientMessage::SetEnv {
+            vars: [("API_KEY".to_string(), "secret".to_string())].into(),
+            unset: vec!["OLD".to_string()],
+        },
+        ClientMessage::SetWorkingDirectory {
+            path: "data".to_string(),
+        },
+        ClientMessage::ReloadUniverse,
+        ClientMessage::SetBackend { release: true },
+        ClientMessage::SetOptLevel { level: 2 },
         ClientMessage::RenameCell {
             cell_id: CellId::new(1),
             new_display_name: "New Name".to_string(),
@@ -67,6 +195,23 @@ fn test_all_client_messages_serialize() {
             cell_id: CellId::new(1),
             direction: MoveDirection::Down,
         },
+        ClientMessage::ConvertCell {
+            cell_id: CellId::new(1),
+            to: CellType::Markdown,
+        },
+        ClientMessage::SetMetadata {
+            metadata: NotebookMetadata {
+                title: Some("Sales".to_string()),
+                authors: vec!["Ada".to_string()],
+                ..Default::default()
+            },
+        },
+        ClientMessage::AcquireCellLock {
+            cell_id: CellId::new(1),
+        },
+        ClientMessage::ReleaseCellLock {
+            cell_id: CellId::new(1),
+        },
     ];
 
     // Serialize and deserialize each message
@@ -76,24 +221,70 @@ fn test_all_client_messages_serialize() {
 
         // Check that the type field matches
         let msg_type = match &msg {
+            ClientMessage::Hello { .. } => "hello",
             ClientMessage::GetState => "get_state",
+            ClientMessage::Ping { .. } => "ping",
+            ClientMessage::Lsp { .. } => "lsp",
+            ClientMessage::Resync => "resync",
             ClientMessage::CellEdit { .. } => "cell_edit",
+            ClientMessage::BatchEdit { .. } => "batch_edit",
             ClientMessage::ExecuteCell { .. } => "execute_cell",
-            ClientMessage::ExecuteAll => "execute_all",
+            ClientMessage::ExecuteCellWith { .. } => "execute_cell_with",
+            ClientMessage::SweepCell { .. } => "sweep_cell",
+            ClientMessage::ExecuteAll { .. } => "execute_all",
+            ClientMessage::RunAndExport { .. } => "run_and_export",
+            ClientMessage::ExecuteSelection { .. } => "execute_selection",
+            ClientMessage::RunFrom { .. } => "run_from",
             ClientMessage::ExecuteDirty => "execute_dirty",
             ClientMessage::Interrupt => "interrupt",
+            ClientMessage::AbortCompile => "abort_compile",
+            ClientMessage::SetBreakpoint { .. } => "set_breakpoint",
+            ClientMessage::Continue => "continue",
+            ClientMessage::StepExecution => "step_execution",
             ClientMessage::Sync => "sync",
+            ClientMessage::ProfileCell { .. } => "profile_cell",
             ClientMessage::GetGraph => "get_graph",
+            ClientMessage::GetNotebookStats => "get_notebook_stats",
+            ClientMessage::GetServerInfo => "get_server_info",
+            ClientMessage::GetCellDoc { .. } => "get_cell_doc",
+            ClientMessage::GetCellType { .. } => "get_cell_type",
+            ClientMessage::GetCellSignature { .. } => "get_cell_signature",
+            ClientMessage::GetDefinitions => "get_definitions",
+            ClientMessage::GetExecutionOrder => "get_execution_order",
+            ClientMessage::ExportSubgraph { .. } => "export_subgraph",
+            ClientMessage::ExportData => "export_data",
+            ClientMessage::GarbageCollect => "garbage_collect",
+            ClientMessage::GetReferences { .. } => "get_references",
+            ClientMessage::Lint { .. } => "lint",
+            ClientMessage::GetLogs { .. } => "get_logs",
             ClientMessage::WidgetUpdate { .. } => "widget_update",
+            ClientMessage::GetWidgetState => "get_widget_state",
+            ClientMessage::SetWidgetReactive { .. } => "set_widget_reactive",
+            ClientMessage::GetHistory { .. } => "get_history",
+            ClientMessage::GetCompileOutput { .. } => "get_compile_output",
+            ClientMessage::GetBuildPaths => "get_build_paths",
+            ClientMessage::InspectOutput { .. } => "inspect_output",
             ClientMessage::SelectHistory { .. } => "select_history",
             ClientMessage::InsertCell { .. } => "insert_cell",
+            ClientMessage::InsertCellAt { .. } => "insert_cell_at",
             ClientMessage::DeleteCell { .. } => "delete_cell",
             ClientMessage::DuplicateCell { .. } => "duplicate_cell",
             ClientMessage::MoveCell { .. } => "move_cell",
+            ClientMessage::MoveCellToIndex { .. } => "move_cell_to_index",
+            ClientMessage::ReorderToExecutionOrder { .. } => "reorder_to_execution_order",
+            ClientMessage::FindOrphanOutputs => "find_orphan_outputs",
+            ClientMessage::CleanOrphanOutputs => "clean_orphan_outputs",
             ClientMessage::Undo => "undo",
             ClientMessage::Redo => "redo",
             ClientMessage::RestartKernel => "restart_kernel",
+            ClientMessage::SetEnv { .. } => "set_env",
+            ClientMessage::SetWorkingDirectory { .. } => "set_working_directory",
+            ClientMessage::ReloadUniverse => "reload_universe",
             ClientMessage::ClearOutputs => "clear_outputs",
+            ClientMessage::ClearCellOutput { .. } => "clear_cell_output",
+            ClientMessage::ReloadFromDisk => "reload_from_disk",
+            ClientMessage::SetBackend { .. } => "set_backend",
+            ClientMessage::SetOptLevel { .. } => "set_opt_level",
             ClientMessage::RenameCell { .. } => "rename_cell",
             ClientMessage::InsertMarkdownCell { .. } => "insert_markdown_cell",
             ClientMessage::EditMarkdownCell { .. } => "edit_markdown_cell",
@@ -101,8 +292,13 @@ fn test_all_client_messages_serialize() {
             ClientMessage::MoveMarkdownCell { .. } => "move_markdown_cell",
             ClientMessage::InsertDefinitionCell { .. } => "insert_definition_cell",
             ClientMessage::EditDefinitionCell { .. } => "edit_definition_cell",
+            ClientMessage::EditUnparsedRegion { .. } => "edit_unparsed_region",
             ClientMessage::DeleteDefinitionCell { .. } => "delete_definition_cell",
             ClientMessage::MoveDefinitionCell { .. } => "move_definition_cell",
+            ClientMessage::AcquireCellLock { .. } => "acquire_cell_lock",
+            ClientMessage::ReleaseCellLock { .. } => "release_cell_lock",
+            ClientMessage::ConvertCell { .. } => "convert_cell",
+            ClientMessage::SetMetadata { .. } => "set_metadata",
         };
 
         assert!(
@@ -126,6 +322,29 @@ fn test_all_client_messages_serialize() {
 fn test_all_server_messages_serialize() {
     // Test all ServerMessage variants
     let messages = vec![
+        ServerMessage::Welcome {
+            protocol_version: PROTOCOL_VERSION,
+            accepted: true,
+            client_id: 1,
+        },
+        ServerMessage::Lsp {
+            payload: serde_json::json!({ "jsonrpc": "2.0", "id": 1, "result": null }),
+        },
+        ServerMessage::Pong {
+            nonce: 42,
+            server_time: 1_700_000_000_000,
+        },
+        ServerMessage::Resync { missed: 12 },
+        ServerMessage::Presence {
+            clients: vec![ClientInfo {
+                id: 1,
+                connected_at: 1_700_000_000_000,
+            }],
+        },
+        ServerMessage::CellLocked {
+            cell_id: CellId::new(1),
+            client_id: Some(2),
+        },
         ServerMessage::NotebookState {
             path: "/test/notebook.rs".to_string(),
             cells: vec![],
@@ -133,38 +352,251 @@ fn test_all_server_messages_serialize() {
             execution_order: vec![],
             workspace_root: Some("/test".to_string()),
             cargo_toml_path: Some("/test/Cargo.toml".to_string()),
+            metadata: NotebookMetadata::default(),
+            in_flight: vec![InFlightCell {
+                cell_id: CellId::new(1),
+                status: CellStatus::Compiling,
+                elapsed_ms: 12_000,
+            }],
         },
         ServerMessage::CellStarted {
             cell_id: CellId::new(1),
         },
+        ServerMessage::CellExecuting {
+            cell_id: CellId::new(1),
+            elapsed_ms: 3000,
+        },
+        ServerMessage::ResourceUsage {
+            cell_id: CellId::new(1),
+            rss_bytes: 52_428_800,
+            cpu_ms: 1250,
+        },
         ServerMessage::CellCompleted {
             cell_id: CellId::new(1),
             duration_ms: 100,
             output: None,
+            from_cache: false,
+        },
+        ServerMessage::CellProfile {
+            cell_id: CellId::new(1),
+            compile_ms: 850.0,
+            ipc_ms: 1.2,
+            exec_ms: 40.5,
+            serialize_ms: 0.3,
+        },
+        ServerMessage::CellOutputAppended {
+            cell_id: CellId::new(1),
+            chunk: "row 1\n".to_string(),
+            seq: 0,
+            is_final: false,
         },
         ServerMessage::CellError {
             cell_id: CellId::new(1),
             error: "Test error".to_string(),
             location: None,
         },
+        ServerMessage::CellPanic {
+            cell_id: CellId::new(1),
+            message: "index out of bounds at src/lib.rs:3:5".to_string(),
+            backtrace: Some("0: notebook::data".to_string()),
+        },
+        ServerMessage::CellOutputStale {
+            cell_id: CellId::new(1),
+        },
+        ServerMessage::SelectionResult {
+            output: None,
+            error: Some("Missing dependencies: config".to_string()),
+        },
         ServerMessage::CompileError {
             cell_id: CellId::new(1),
             errors: vec![],
         },
+        ServerMessage::OrphanOutputs {
+            names: vec!["cell_4".to_string(), "old_name".to_string()],
+            removed: false,
+        },
+        ServerMessage::BuildPaths {
+            build_dir: "/nb/.venus/build".to_string(),
+            cache_dir: "/nb/.venus/cache".to_string(),
+            state_dir: "/nb/.venus/state".to_string(),
+            universe_path: None,
+            virtual_notebook: "/nb/.venus/build/universe/src/notebook.rs".to_string(),
+        },
+        ServerMessage::OutputInspection {
+            cell_id: CellId::new(1),
+            type_name: "Vec<i32>".to_string(),
+            decoded_json: serde_json::json!([1, 2, 3]),
+        },
+        ServerMessage::CompileOutput {
+            cell_id: CellId::new(1),
+            errors: vec![],
+            warnings: vec![],
+        },
         ServerMessage::GraphUpdated {
             edges: vec![],
             levels: vec![],
         },
+        ServerMessage::ParseError {
+            message: "expected expression".to_string(),
+            line: 12,
+            column: 5,
+        },
         ServerMessage::FileChanged {
             modified_cells: vec![],
             added_cells: vec![],
             removed_cells: vec![],
         },
+        ServerMessage::DependenciesChangedOnDisk,
+        ServerMessage::Warning {
+            message: "`fn main` at line 9 is never run".to_string(),
+        },
+        ServerMessage::NotebookStats {
+            code_cells: 3,
+            markdown_cells: 1,
+            definition_cells: 1,
+            total_deps: 2,
+            executed: 2,
+            failed: 1,
+            total_exec_time_ms: 150,
+        },
+        ServerMessage::ServerInfo {
+            venus_version: "0.1.0".to_string(),
+            protocol_version: PROTOCOL_VERSION,
+            toolchain_version: "rustc 1.90.0-nightly".to_string(),
+            features: vec!["cranelift".to_string()],
+        },
+        ServerMessage::ExecutionOrder {
+            steps: vec![ExecutionStep {
+                cell_id: CellId::new(1),
+                name: "load".to_string(),
+                level: 0,
+            }],
+        },
+        ServerMessage::SubgraphSource {
+            source: "#[venus::cell]\npub fn a() -> i32 {\n    1\n}\n".to_string(),
+        },
+        ServerMessage::DataExport {
+            exported_at: 1_700_000_000_000,
+            cells: vec![
+                ExportedOutput {
+                    cell_id: CellId::new(1),
+                    name: "total".to_string(),
+                    type_name: "i32".to_string(),
+                    size: 4,
+                    bytes: Some("KgAAAA==".to_string()),
+                    json: Some(serde_json::json!(42)),
+                    truncated: false,
+                },
+                ExportedOutput {
+                    cell_id: CellId::new(2),
+                    name: "frame".to_string(),
+                    type_name: "Vec<u8>".to_string(),
+                    size: 64 * 1024 * 1024,
+                    bytes: None,
+                    json: None,
+                    truncated: true,
+                },
+            ],
+        },
+        ServerMessage::ExportReady {
+            html: "<!DOCTYPE html>".to_string(),
+        },
+        ServerMessage::GarbageCollected {
+            bytes_reclaimed: 1_048_576,
+        },
+        ServerMessage::References {
+            cells: vec![CellId::new(2)],
+        },
+        ServerMessage::LintReport {
+            warnings: vec![LintWarning {
+                cell_id: CellId::new(1),
+                rule: LintRule::SharedOwnedOutput,
+                message: "`data` returns an owned `Vec`".to_string(),
+            }],
+        },
+        ServerMessage::Logs {
+            lines: vec![LogLine {
+                timestamp: 1_700_000_000_000,
+                level: LogLevel::Info,
+                target: "venus_server::session".to_string(),
+                message: "Notebook reloaded".to_string(),
+            }],
+        },
+        ServerMessage::Definitions {
+            blocks: vec![DefinitionBlock {
+                id: CellId::new(2),
+                definition_type: DefinitionType::Import,
+                content: "use std::collections::HashMap;".to_string(),
+                doc: None,
+            }],
+        },
+        ServerMessage::CellDoc {
+            cell_id: CellId::new(1),
+            html: "<p>Doc</p>\n".to_string(),
+        },
+        ServerMessage::CellType {
+            cell_id: CellId::new(1),
+            kind: CellKind::Markdown,
+        },
+        ServerMessage::UnparsedRegionEdited {
+            cell_id: CellId::new(4),
+            error: None,
+        },
+        ServerMessage::CellSignature {
+            cell_id: CellId::new(1),
+            name: "total".to_string(),
+            params: vec![CellParam {
+                name: "sales".to_string(),
+                param_type: "Vec<f64>".to_string(),
+                is_ref: true,
+                is_mut: false,
+            }],
+            return_type: "f64".to_string(),
+            is_async: false,
+        },
         ServerMessage::SyncCompleted {
             ipynb_path: "/test/notebook.ipynb".to_string(),
         },
         ServerMessage::ExecutionAborted {
             cell_id: Some(CellId::new(1)),
+            reason: None,
+        },
+        ServerMessage::SweepResults {
+            cell_id: CellId::new(1),
+            results: vec![
+                SweepRun {
+                    value: venus_core::widgets::WidgetValue::Number(0.1),
+                    output: Some(CellOutput {
+                        text: Some("12".to_string()),
+                        html: None,
+                        image: None,
+                        json: None,
+                        widgets: Vec::new(),
+                        stale: false,
+                    }),
+                    duration_ms: 3,
+                    error: None,
+                },
+                SweepRun {
+                    value: venus_core::widgets::WidgetValue::Number(0.5),
+                    output: None,
+                    duration_ms: 1,
+                    error: Some("panicked".to_string()),
+                },
+            ],
+            interrupted: false,
+        },
+        ServerMessage::CompileAborted {
+            cell_id: Some(CellId::new(1)),
+        },
+        ServerMessage::LspReady,
+        ServerMessage::LspRestarted { attempt: 1 },
+        ServerMessage::Paused {
+            at: CellId::new(2),
+            next: Some(CellId::new(3)),
+        },
+        ServerMessage::Breakpoints {
+            cell_ids: vec![CellId::new(2)],
         },
         ServerMessage::Error {
             message: "Test error".to_string(),
@@ -193,6 +625,35 @@ fn test_all_server_messages_serialize() {
             output: None,
             dirty_cells: vec![],
         },
+        ServerMessage::CellHistory {
+            cell_id: CellId::new(1),
+            entries: vec![HistoryEntryInfo {
+                index: 0,
+                timestamp: 1_700_000_000_000,
+                preview: "42".to_string(),
+                duration_ms: 12,
+            }],
+        },
+        ServerMessage::WidgetState {
+            per_cell: vec![CellWidgetState {
+                cell_id: CellId::new(1),
+                defs: vec![venus_core::widgets::WidgetDef::Checkbox {
+                    id: "enabled".to_string(),
+                    label: "Enabled".to_string(),
+                    value: true,
+                }],
+                values: [(
+                    "enabled".to_string(),
+                    venus_core::widgets::WidgetValue::Bool(true),
+                )]
+                .into_iter()
+                .collect(),
+            }],
+        },
+        ServerMessage::WidgetReactive {
+            cell_id: CellId::new(1),
+            enabled: true,
+        },
         ServerMessage::UndoResult {
             success: true,
             error: None,
@@ -210,7 +671,25 @@ fn test_all_server_messages_serialize() {
             redo_description: None,
         },
         ServerMessage::KernelRestarted { error: None },
+        ServerMessage::UniverseBuildProgress {
+            crate_name: "serde".to_string(),
+            current: 3,
+            total: Some(40),
+        },
+        ServerMessage::UniverseReloaded {
+            dirty_cells: vec![CellId::new(1)],
+            error: None,
+        },
         ServerMessage::OutputsCleared { error: None },
+        ServerMessage::NotebookReloaded { error: None },
+        ServerMessage::BackendChanged {
+            release: true,
+            error: None,
+        },
+        ServerMessage::OptLevelChanged {
+            level: 2,
+            error: None,
+        },
         ServerMessage::CellRenamed {
             cell_id: CellId::new(1),
             new_display_name: "New Name".to_string(),
@@ -232,6 +711,23 @@ fn test_all_server_messages_serialize() {
             cell_id: CellId::new(10),
             error: None,
         },
+        ServerMessage::CellConverted {
+            cell_id: CellId::new(1),
+            to: CellType::Code,
+            error: None,
+        },
+        ServerMessage::WorkingDirectorySet {
+            path: "/home/user/notebooks/data".to_string(),
+        },
+        ServerMessage::EnvSet {
+            names: vec!["API_KEY".to_string()],
+        },
+        ServerMessage::MetadataSet { error: None },
+        ServerMessage::NotebookReordered {
+            moved: 3,
+            applied: true,
+            error: None,
+        },
     ];
 
     // Serialize and deserialize each message
@@ -259,6 +755,7 @@ fn test_cell_state_variants() {
         description: Some("A test function".to_string()),
         return_type: "i32".to_string(),
         dependencies: vec!["dep1".to_string(), "dep2".to_string()],
+        output_format: None,
         status: CellStatus::Success,
         output: Some(CellOutput {
             text: Some("42".to_string()),
@@ -266,6 +763,7 @@ fn test_cell_state_variants() {
             image: None,
             json: None,
             widgets: vec![],
+            stale: false,
         }),
         dirty: false,
     };
@@ -336,6 +834,7 @@ fn test_cell_output_with_widgets() {
                 value: true,
             },
         ],
+        stale: false,
     };
 
     let json = serde_json::to_string(&output).unwrap();
@@ -414,6 +913,7 @@ fn test_cell_state_methods() {
         description: None,
         return_type: "()".to_string(),
         dependencies: vec![],
+        output_format: None,
         status: CellStatus::Idle,
         output: None,
         dirty: false,
@@ -436,6 +936,7 @@ fn test_cell_state_methods() {
         image: None,
         json: None,
         widgets: vec![],
+        stale: false,
     }));
     assert!(matches!(
         &cell,
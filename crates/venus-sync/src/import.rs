@@ -0,0 +1,321 @@
+//! Conversion from Jupyter notebooks (.ipynb) back to `.rs`.
+//!
+//! Notebooks generated by [`IpynbGenerator`](crate::IpynbGenerator) carry
+//! each cell's signature in `cell.metadata.venus` and the source before the
+//! first cell in the notebook metadata, so converting them back yields the
+//! original cells. Items between cells that are not part of a cell are not
+//! carried over.
+
+use std::path::Path;
+
+use crate::error::SyncResult;
+use crate::ipynb::{JupyterCell, JupyterNotebook};
+use crate::metadata::NotebookMetadata;
+use crate::parser::{CellType, NotebookCell, is_cell_attribute, parse_cell_signature};
+
+/// Name of the markdown cell generated from the module doc comment.
+const HEADER_CELL: &str = "_header";
+
+/// Parser for Jupyter notebooks.
+pub struct IpynbParser {
+    // Reserved for future configuration
+}
+
+impl IpynbParser {
+    /// Create a new parser.
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Parse an `.ipynb` file into notebook metadata and cells.
+    pub fn parse_file(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> SyncResult<(NotebookMetadata, Vec<NotebookCell>)> {
+        let notebook = JupyterNotebook::read_from_file(path)?;
+        Ok(self.parse_notebook(&notebook))
+    }
+
+    /// Convert a Jupyter notebook into notebook metadata and cells.
+    ///
+    /// Cell names and signatures come from the Venus cell metadata. Without
+    /// it, code cells are named after the function they define, or
+    /// `cell_<n>` if they don't define one.
+    pub fn parse_notebook(
+        &self,
+        notebook: &JupyterNotebook,
+    ) -> (NotebookMetadata, Vec<NotebookCell>) {
+        let mut metadata = NotebookMetadata {
+            title: notebook.metadata.title.clone(),
+            authors: notebook
+                .metadata
+                .authors
+                .iter()
+                .map(|author| author.name.clone())
+                .collect(),
+            ..Default::default()
+        };
+        if let Some(venus) = &notebook.metadata.venus {
+            metadata.description = venus.description.clone();
+            metadata.created = venus.created.clone();
+            metadata.tags = venus.tags.clone();
+            metadata.preamble = venus.preamble.clone();
+        }
+
+        let cells = notebook
+            .cells
+            .iter()
+            .enumerate()
+            .filter_map(|(index, cell)| self.convert_cell(index, cell))
+            .collect();
+
+        (metadata, cells)
+    }
+
+    /// Convert a Jupyter cell, skipping raw cells.
+    fn convert_cell(&self, index: usize, cell: &JupyterCell) -> Option<NotebookCell> {
+        let source = cell.source.concat();
+        let source = source.trim_end_matches('\n').to_string();
+
+        match cell.cell_type.as_str() {
+            "markdown" => Some(NotebookCell {
+                name: cell
+                    .metadata
+                    .venus_cell
+                    .clone()
+                    .unwrap_or_else(|| format!("markdown_{}", index)),
+                cell_type: CellType::Markdown,
+                markdown: Some(source),
+                source: None,
+                has_dependencies: false,
+                dependencies: Vec::new(),
+                return_type: None,
+            }),
+            "code" => {
+                let (name, dependencies, return_type) = match &cell.metadata.venus {
+                    Some(venus) => (
+                        venus.name.clone(),
+                        venus.dependencies.clone(),
+                        venus.return_type.clone(),
+                    ),
+                    None => match parse_cell_signature(&source) {
+                        Some((name, deps, ret)) => (name, deps, Some(ret)),
+                        None => (
+                            cell.metadata
+                                .venus_cell
+                                .clone()
+                                .unwrap_or_else(|| format!("cell_{}", index)),
+                            Vec::new(),
+                            None,
+                        ),
+                    },
+                };
+
+                Some(NotebookCell {
+                    name,
+                    cell_type: CellType::Code,
+                    markdown: None,
+                    source: Some(source),
+                    has_dependencies: !dependencies.is_empty(),
+                    dependencies,
+                    return_type,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Default for IpynbParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Generator for `.rs` notebooks from cells.
+pub struct RsGenerator {
+    // Reserved for future configuration
+}
+
+impl RsGenerator {
+    /// Create a new generator.
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Generate `.rs` notebook source from metadata and cells.
+    ///
+    /// A markdown cell named `<cell>_doc` directly before code cell `<cell>`
+    /// becomes its doc comment; other markdown cells become `//` comment
+    /// blocks. Code cells whose source lost the `#[venus::cell]` function
+    /// are wrapped in one built from their signature.
+    pub fn generate(&self, metadata: &NotebookMetadata, cells: &[NotebookCell]) -> String {
+        let mut blocks = Vec::new();
+
+        match &metadata.preamble {
+            Some(preamble) => blocks.push(preamble.clone()),
+            None => {
+                if let Some(header) = cells
+                    .iter()
+                    .find(|c| c.cell_type == CellType::Markdown && c.name == HEADER_CELL)
+                {
+                    blocks.push(comment_lines(
+                        "//!",
+                        header.markdown.as_deref().unwrap_or(""),
+                    ));
+                }
+            }
+        }
+
+        let mut doc: Option<&str> = None;
+        for (i, cell) in cells.iter().enumerate() {
+            match cell.cell_type {
+                CellType::Markdown => {
+                    let markdown = cell.markdown.as_deref().unwrap_or("");
+                    let documents_next = cells.get(i + 1).is_some_and(|next| {
+                        next.cell_type == CellType::Code
+                            && cell.name == format!("{}_doc", next.name)
+                    });
+
+                    if cell.name == HEADER_CELL {
+                        continue;
+                    } else if documents_next {
+                        doc = Some(markdown);
+                    } else {
+                        blocks.push(comment_lines("//", markdown));
+                    }
+                }
+                CellType::Code => {
+                    let mut block = String::new();
+                    if let Some(doc) = doc.take() {
+                        block.push_str(&comment_lines("///", doc));
+                        block.push('\n');
+                    }
+                    block.push_str(&self.cell_source(cell));
+                    blocks.push(block);
+                }
+            }
+        }
+
+        let mut source = blocks.join("\n\n");
+        source.push('\n');
+        source
+    }
+
+    /// Source of a code cell, wrapped in a cell function if it has none.
+    fn cell_source(&self, cell: &NotebookCell) -> String {
+        let source = cell.source.as_deref().unwrap_or("");
+        let has_attribute = source.lines().any(|line| is_cell_attribute(line.trim()));
+        let Some(return_type) = cell.return_type.as_deref().filter(|_| !has_attribute) else {
+            return source.to_string();
+        };
+
+        let params = cell
+            .dependencies
+            .iter()
+            .map(|dep| format!("{}: {}", dep.name, dep.ty))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let ret = if return_type == "()" {
+            String::new()
+        } else {
+            format!(" -> {}", return_type)
+        };
+        let body = source
+            .lines()
+            .map(|line| {
+                if line.is_empty() {
+                    String::new()
+                } else {
+                    format!("    {}", line)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        format!(
+            "#[venus::cell]\npub fn {}({}){} {{\n{}\n}}",
+            cell.name, params, ret, body
+        )
+    }
+}
+
+impl Default for RsGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Prefix each line of `text` with a comment marker.
+fn comment_lines(marker: &str, text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            if line.is_empty() {
+                marker.to_string()
+            } else {
+                format!("{} {}", marker, line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ipynb::CellMetadata;
+    use crate::parser::CellDependency;
+
+    fn code_cell(source: &str, metadata: CellMetadata) -> JupyterCell {
+        JupyterCell {
+            cell_type: "code".to_string(),
+            metadata,
+            source: source.lines().map(|l| format!("{}\n", l)).collect(),
+            outputs: Some(Vec::new()),
+            execution_count: None,
+        }
+    }
+
+    #[test]
+    fn test_infers_cell_name_without_metadata() {
+        let mut notebook = JupyterNotebook::new();
+        notebook.cells.push(code_cell(
+            "#[venus::cell]\npub(crate) fn total<T>(items: &Vec<i32>) -> i64 {\n    0\n}",
+            CellMetadata::default(),
+        ));
+        notebook
+            .cells
+            .push(code_cell("let x = 1;", CellMetadata::default()));
+
+        let (_, cells) = IpynbParser::new().parse_notebook(&notebook);
+
+        assert_eq!(cells[0].name, "total");
+        assert_eq!(cells[0].dependencies.len(), 1);
+        assert_eq!(cells[0].dependencies[0].name, "items");
+        assert!(cells[0].has_dependencies);
+        assert_eq!(cells[1].name, "cell_1");
+    }
+
+    #[test]
+    fn test_wraps_body_with_signature() {
+        let cell = NotebookCell {
+            name: "doubled".to_string(),
+            cell_type: CellType::Code,
+            markdown: None,
+            source: Some("value * 2".to_string()),
+            has_dependencies: true,
+            dependencies: vec![CellDependency {
+                name: "value".to_string(),
+                ty: "&i32".to_string(),
+            }],
+            return_type: Some("i32".to_string()),
+        };
+
+        let source = RsGenerator::new().generate(&NotebookMetadata::default(), &[cell]);
+
+        assert_eq!(
+            source,
+            "#[venus::cell]\npub fn doubled(value: &i32) -> i32 {\n    value * 2\n}\n"
+        );
+    }
+}
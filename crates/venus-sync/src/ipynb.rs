@@ -8,8 +8,9 @@ use std::path::Path;
 use serde::{Deserialize, Serialize};
 
 use crate::error::{SyncError, SyncResult};
+use crate::metadata::NotebookMetadata;
 use crate::outputs::OutputCache;
-use crate::parser::{CellType, NotebookCell, NotebookMetadata};
+use crate::parser::{CellDependency, CellType, NotebookCell};
 
 /// A Jupyter notebook.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,6 +37,14 @@ pub struct JupyterMetadata {
     /// Language info
     pub language_info: LanguageInfo,
 
+    /// Notebook title
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+
+    /// Notebook authors
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub authors: Vec<JupyterAuthor>,
+
     /// Venus-specific metadata for round-trip
     #[serde(skip_serializing_if = "Option::is_none")]
     pub venus: Option<VenusMetadata>,
@@ -70,6 +79,13 @@ pub struct LanguageInfo {
     pub version: String,
 }
 
+/// Notebook author, as in the nbformat `authors` field.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JupyterAuthor {
+    /// Author name
+    pub name: String,
+}
+
 /// Venus-specific metadata.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VenusMetadata {
@@ -79,6 +95,22 @@ pub struct VenusMetadata {
 
     /// Venus version
     pub version: String,
+
+    /// Notebook description
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Notebook creation date
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created: Option<String>,
+
+    /// Notebook tags
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
+    /// Source before the first cell, verbatim
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preamble: Option<String>,
 }
 
 /// A Jupyter cell.
@@ -116,6 +148,25 @@ pub struct CellMetadata {
     /// Tags
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tags: Option<Vec<String>>,
+
+    /// Venus cell signature, for converting back to `.rs`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub venus: Option<VenusCellMetadata>,
+}
+
+/// Signature of a Venus code cell, stored in `cell.metadata.venus`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VenusCellMetadata {
+    /// Function name
+    pub name: String,
+
+    /// Parameters, one per upstream cell
+    #[serde(default)]
+    pub dependencies: Vec<CellDependency>,
+
+    /// Return type
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub return_type: Option<String>,
 }
 
 /// Cell output.
@@ -228,9 +279,15 @@ impl Default for JupyterMetadata {
                 name: "rust".to_string(),
                 version: "1.0".to_string(),
             },
+            title: None,
+            authors: Vec::new(),
             venus: Some(VenusMetadata {
                 source_file: None,
                 version: env!("CARGO_PKG_VERSION").to_string(),
+                description: None,
+                created: None,
+                tags: Vec::new(),
+                preamble: None,
             }),
         }
     }
@@ -258,12 +315,20 @@ impl IpynbGenerator {
         let mut notebook = JupyterNotebook::new();
 
         // Set metadata
-        if let Some(title) = &metadata.title {
-            notebook.metadata.venus = Some(VenusMetadata {
-                source_file: Some(title.clone()),
-                version: env!("CARGO_PKG_VERSION").to_string(),
-            });
-        }
+        notebook.metadata.title = metadata.title.clone();
+        notebook.metadata.authors = metadata
+            .authors
+            .iter()
+            .map(|name| JupyterAuthor { name: name.clone() })
+            .collect();
+        notebook.metadata.venus = Some(VenusMetadata {
+            source_file: metadata.title.clone(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            description: metadata.description.clone(),
+            created: metadata.created.clone(),
+            tags: metadata.tags.clone(),
+            preamble: metadata.preamble.clone(),
+        });
 
         // Convert cells
         for cell in cells {
@@ -289,6 +354,7 @@ impl IpynbGenerator {
                         venus_cell: Some(cell.name.clone()),
                         editable: Some(true),
                         tags: None,
+                        venus: None,
                     },
                     source: source.lines().map(|l| format!("{}\n", l)).collect(),
                     outputs: None,
@@ -317,6 +383,11 @@ impl IpynbGenerator {
                         } else {
                             None
                         },
+                        venus: Some(VenusCellMetadata {
+                            name: cell.name.clone(),
+                            dependencies: cell.dependencies.clone(),
+                            return_type: cell.return_type.clone(),
+                        }),
                     },
                     source: source.lines().map(|l| format!("{}\n", l)).collect(),
                     outputs: Some(outputs.unwrap_or_default()),
@@ -344,6 +415,25 @@ mod tests {
         assert!(notebook.cells.is_empty());
     }
 
+    #[test]
+    fn test_generate_sets_notebook_metadata() {
+        let metadata = NotebookMetadata {
+            title: Some("Sales".to_string()),
+            authors: vec!["Ada".to_string()],
+            tags: vec!["q3".to_string()],
+            ..Default::default()
+        };
+
+        let notebook = IpynbGenerator::new()
+            .generate(&metadata, &[], None)
+            .unwrap();
+        let json = serde_json::to_value(&notebook).unwrap();
+
+        assert_eq!(json["metadata"]["title"], "Sales");
+        assert_eq!(json["metadata"]["authors"][0]["name"], "Ada");
+        assert_eq!(json["metadata"]["venus"]["tags"][0], "q3");
+    }
+
     #[test]
     fn test_generate_markdown_cell() {
         let mut generator = IpynbGenerator::new();
@@ -354,6 +444,8 @@ mod tests {
             markdown: Some("# Hello\n\nThis is a test.".to_string()),
             source: None,
             has_dependencies: false,
+            dependencies: Vec::new(),
+            return_type: None,
         };
 
         let jupyter_cell = generator.convert_cell(&cell, None).unwrap();
@@ -373,11 +465,16 @@ mod tests {
             markdown: None,
             source: Some("#[venus::cell]\npub fn compute() -> i32 { 42 }".to_string()),
             has_dependencies: false,
+            dependencies: Vec::new(),
+            return_type: Some("i32".to_string()),
         };
 
         let jupyter_cell = generator.convert_cell(&cell, None).unwrap();
 
         assert_eq!(jupyter_cell.cell_type, "code");
+        let venus = jupyter_cell.metadata.venus.as_ref().unwrap();
+        assert_eq!(venus.name, "compute");
+        assert_eq!(venus.return_type.as_deref(), Some("i32"));
         assert!(jupyter_cell.execution_count.is_some());
         assert!(jupyter_cell.outputs.is_some());
     }
@@ -10,17 +10,26 @@
 //!                                           ▼
 //!                                     OutputCache
 //!                                    (.venus/outputs/)
+//!
+//! notebook.ipynb ──► IpynbParser ──► CellsWithMeta ─────► RsGenerator ─────► notebook.rs
 //! ```
+//!
+//! Generated `.ipynb` files store each cell's name, dependencies and return
+//! type in `cell.metadata.venus`, so converting back reproduces the cells.
 
 mod error;
+mod import;
 mod ipynb;
+mod metadata;
 mod outputs;
 mod parser;
 
 pub use error::{SyncError, SyncResult};
-pub use ipynb::{IpynbGenerator, JupyterNotebook};
+pub use import::{IpynbParser, RsGenerator};
+pub use ipynb::{IpynbGenerator, JupyterNotebook, VenusCellMetadata};
+pub use metadata::{NotebookMetadata, parse_metadata_block, write_metadata_block};
 pub use outputs::OutputCache;
-pub use parser::{NotebookCell, NotebookMetadata, RsParser};
+pub use parser::{CellDependency, CellType, NotebookCell, RsParser};
 
 use std::path::Path;
 
@@ -54,6 +63,29 @@ pub fn sync_to_ipynb(
     Ok(())
 }
 
+/// Convert an `.ipynb` notebook back to a `.rs` notebook.
+pub fn sync_to_rs(ipynb_path: impl AsRef<Path>, rs_path: impl AsRef<Path>) -> SyncResult<()> {
+    let ipynb_path = ipynb_path.as_ref();
+    let rs_path = rs_path.as_ref();
+
+    let (metadata, cells) = IpynbParser::new().parse_file(ipynb_path)?;
+    let source = RsGenerator::new().generate(&metadata, &cells);
+
+    std::fs::write(rs_path, source).map_err(|e| SyncError::WriteError {
+        path: rs_path.to_path_buf(),
+        message: e.to_string(),
+    })?;
+
+    tracing::info!(
+        "Synced {} → {} ({} cells)",
+        ipynb_path.display(),
+        rs_path.display(),
+        cells.len()
+    );
+
+    Ok(())
+}
+
 /// Get the default `.ipynb` path for a `.rs` notebook.
 pub fn default_ipynb_path(rs_path: impl AsRef<Path>) -> std::path::PathBuf {
     let rs_path = rs_path.as_ref();
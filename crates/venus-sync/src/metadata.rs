@@ -0,0 +1,351 @@
+//! Notebook-level metadata.
+//!
+//! Title and description default to the module doc heading and the text
+//! after it. They, along with authors, creation date and tags, can be set
+//! explicitly in a `metadata` fenced block in the module doc:
+//!
+//! ```text
+//! //! ```metadata
+//! //! title = "Sales Analysis"
+//! //! authors = ["Ada", "Grace"]
+//! //! created = "2026-01-05"
+//! //! tags = ["sales", "q3"]
+//! //! ```
+//! ```
+//!
+//! Values use TOML string and string-array syntax. Unknown keys and
+//! malformed lines are ignored.
+
+use serde::{Deserialize, Serialize};
+
+/// Opening line of the metadata block (after `//!`).
+const BLOCK_START: &str = "```metadata";
+
+/// Metadata extracted from the notebook header.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotebookMetadata {
+    /// Notebook title (from the metadata block, or first `# Title` in doc comment)
+    #[serde(default)]
+    pub title: Option<String>,
+
+    /// Notebook authors
+    #[serde(default)]
+    pub authors: Vec<String>,
+
+    /// Notebook description (from the metadata block, or doc comment after title)
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// Creation date, as written in the metadata block
+    #[serde(default)]
+    pub created: Option<String>,
+
+    /// Free-form tags
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    /// Dependencies (parsed from `//! ```cargo` block)
+    #[serde(skip)]
+    pub dependencies: Vec<String>,
+
+    /// Source before the first cell: module docs, `cargo` block and `use`
+    /// items. Kept so a notebook converted from `.ipynb` matches the original.
+    #[serde(skip)]
+    pub preamble: Option<String>,
+}
+
+impl NotebookMetadata {
+    /// Check if no metadata field is set.
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none()
+            && self.authors.is_empty()
+            && self.description.is_none()
+            && self.created.is_none()
+            && self.tags.is_empty()
+    }
+
+    /// Override fields with those set in `other`.
+    pub fn merge(&mut self, other: NotebookMetadata) {
+        if other.title.is_some() {
+            self.title = other.title;
+        }
+        if !other.authors.is_empty() {
+            self.authors = other.authors;
+        }
+        if other.description.is_some() {
+            self.description = other.description;
+        }
+        if other.created.is_some() {
+            self.created = other.created;
+        }
+        if !other.tags.is_empty() {
+            self.tags = other.tags;
+        }
+    }
+}
+
+/// Check whether a module doc line (after `//!`) opens the metadata block.
+pub(crate) fn is_block_start(content: &str) -> bool {
+    content.trim() == BLOCK_START
+}
+
+/// Find the metadata block as a 0-indexed inclusive line range.
+fn find_block(lines: &[&str]) -> Option<(usize, usize)> {
+    let start = lines
+        .iter()
+        .position(|line| line.trim().strip_prefix("//!").is_some_and(is_block_start))?;
+
+    let end = lines[start + 1..]
+        .iter()
+        .position(|line| {
+            line.trim()
+                .strip_prefix("//!")
+                .is_some_and(|content| content.trim() == "```")
+        })
+        .map(|offset| start + 1 + offset)?;
+
+    Some((start, end))
+}
+
+/// Parse the explicit metadata block, if the source has one.
+///
+/// Only fields written in the block are set; title and description are not
+/// derived from the module doc heading here.
+pub fn parse_metadata_block(source: &str) -> Option<NotebookMetadata> {
+    let lines: Vec<&str> = source.lines().collect();
+    let (start, end) = find_block(&lines)?;
+
+    let mut metadata = NotebookMetadata::default();
+    for line in &lines[start + 1..end] {
+        let content = line.trim().trim_start_matches("//!").trim();
+        let Some((key, value)) = content.split_once('=') else {
+            continue;
+        };
+
+        match key.trim() {
+            "title" => metadata.title = parse_string(value.trim()),
+            "description" => metadata.description = parse_string(value.trim()),
+            "created" => metadata.created = parse_string(value.trim()),
+            "authors" => metadata.authors = parse_string_array(value.trim()).unwrap_or_default(),
+            "tags" => metadata.tags = parse_string_array(value.trim()).unwrap_or_default(),
+            _ => {}
+        }
+    }
+
+    Some(metadata)
+}
+
+/// Replace the metadata block in `source`, returning the new source.
+///
+/// An existing block is replaced in place; otherwise the block is appended
+/// to the leading module doc, or inserted at the top of the file if there
+/// is none. Passing `None` or empty metadata removes the block.
+pub fn write_metadata_block(source: &str, metadata: Option<&NotebookMetadata>) -> String {
+    let lines: Vec<&str> = source.lines().collect();
+    let block = metadata
+        .filter(|m| !m.is_empty())
+        .map(render_block)
+        .unwrap_or_default();
+
+    let mut result: Vec<String> = Vec::with_capacity(lines.len() + block.len() + 1);
+
+    if let Some((mut start, mut end)) = find_block(&lines) {
+        // When removing, also drop the separator added on insertion
+        if block.is_empty() {
+            if start > 0 && lines[start - 1].trim() == "//!" {
+                start -= 1;
+            } else if start == 0 && lines.get(end + 1).is_some_and(|l| l.trim().is_empty()) {
+                end += 1;
+            }
+        }
+
+        result.extend(lines[..start].iter().map(|l| l.to_string()));
+        result.extend(block);
+        result.extend(lines[end + 1..].iter().map(|l| l.to_string()));
+    } else if !block.is_empty() {
+        let header_end = lines
+            .iter()
+            .take_while(|line| line.trim().starts_with("//!"))
+            .count();
+
+        result.extend(lines[..header_end].iter().map(|l| l.to_string()));
+        if header_end > 0 {
+            result.push("//!".to_string());
+        }
+        result.extend(block);
+        if header_end == 0 && !lines.is_empty() {
+            result.push(String::new());
+        }
+        result.extend(lines[header_end..].iter().map(|l| l.to_string()));
+    } else {
+        return source.to_string();
+    }
+
+    let mut output = result.join("\n");
+    if source.ends_with('\n') || source.is_empty() {
+        output.push('\n');
+    }
+    output
+}
+
+/// Render metadata as module doc lines.
+fn render_block(metadata: &NotebookMetadata) -> Vec<String> {
+    let mut lines = vec![format!("//! {}", BLOCK_START)];
+
+    if let Some(title) = &metadata.title {
+        lines.push(format!("//! title = {}", quote(title)));
+    }
+    if !metadata.authors.is_empty() {
+        lines.push(format!("//! authors = {}", quote_array(&metadata.authors)));
+    }
+    if let Some(description) = &metadata.description {
+        lines.push(format!("//! description = {}", quote(description)));
+    }
+    if let Some(created) = &metadata.created {
+        lines.push(format!("//! created = {}", quote(created)));
+    }
+    if !metadata.tags.is_empty() {
+        lines.push(format!("//! tags = {}", quote_array(&metadata.tags)));
+    }
+
+    lines.push("//! ```".to_string());
+    lines
+}
+
+/// Quote a string with TOML basic-string escapes.
+fn quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for ch in value.chars() {
+        match ch {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            _ => quoted.push(ch),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Quote a list of strings as a TOML array.
+fn quote_array(values: &[String]) -> String {
+    let items: Vec<String> = values.iter().map(|v| quote(v)).collect();
+    format!("[{}]", items.join(", "))
+}
+
+/// Parse a quoted string, returning it and the remaining input.
+fn parse_quoted(input: &str) -> Option<(String, &str)> {
+    let mut chars = input.strip_prefix('"')?.char_indices();
+    let mut value = String::new();
+
+    while let Some((i, ch)) = chars.next() {
+        match ch {
+            '"' => return Some((value, &input[i + 2..])),
+            '\\' => match chars.next()?.1 {
+                'n' => value.push('\n'),
+                't' => value.push('\t'),
+                other => value.push(other),
+            },
+            _ => value.push(ch),
+        }
+    }
+
+    None
+}
+
+/// Parse a value that must be exactly one quoted string.
+fn parse_string(input: &str) -> Option<String> {
+    let (value, rest) = parse_quoted(input)?;
+    rest.trim().is_empty().then_some(value)
+}
+
+/// Parse an array of quoted strings.
+fn parse_string_array(input: &str) -> Option<Vec<String>> {
+    let mut rest = input.strip_prefix('[')?.trim_start();
+    let mut values = Vec::new();
+
+    loop {
+        if let Some(after) = rest.strip_prefix(']') {
+            return after.trim().is_empty().then_some(values);
+        }
+
+        let (value, after) = parse_quoted(rest)?;
+        values.push(value);
+        rest = after.trim_start();
+        if let Some(after) = rest.strip_prefix(',') {
+            rest = after.trim_start();
+        } else if !rest.starts_with(']') {
+            return None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> NotebookMetadata {
+        NotebookMetadata {
+            title: Some("Sales \"Q3\"".to_string()),
+            authors: vec!["Ada".to_string(), "Grace".to_string()],
+            description: None,
+            created: Some("2026-01-05".to_string()),
+            tags: vec!["sales".to_string()],
+            dependencies: Vec::new(),
+            preamble: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_block() {
+        let source = r#"//! # Heading
+//!
+//! ```metadata
+//! title = "Sales"
+//! authors = ["Ada", "Grace"]
+//! tags = []
+//! unknown = "ignored"
+//! ```
+
+use venus::prelude::*;
+"#;
+        let metadata = parse_metadata_block(source).unwrap();
+        assert_eq!(metadata.title.as_deref(), Some("Sales"));
+        assert_eq!(metadata.authors, vec!["Ada", "Grace"]);
+        assert!(metadata.tags.is_empty());
+        assert!(metadata.description.is_none());
+
+        assert!(parse_metadata_block("//! # Heading\n").is_none());
+    }
+
+    #[test]
+    fn test_write_inserts_after_module_doc_and_round_trips() {
+        let source = "//! # Heading\n//! Intro.\n\nuse venus::prelude::*;\n";
+        let written = write_metadata_block(source, Some(&sample()));
+
+        assert!(written.starts_with("//! # Heading\n//! Intro.\n//!\n//! ```metadata\n"));
+        assert!(written.ends_with("//! ```\n\nuse venus::prelude::*;\n"));
+        assert_eq!(parse_metadata_block(&written), Some(sample()));
+    }
+
+    #[test]
+    fn test_write_replaces_and_removes_block() {
+        let source = "use venus::prelude::*;\n";
+        let written = write_metadata_block(source, Some(&sample()));
+        assert!(written.starts_with("//! ```metadata\n"));
+
+        let mut updated = sample();
+        updated.tags.clear();
+        let rewritten = write_metadata_block(&written, Some(&updated));
+        assert_eq!(parse_metadata_block(&rewritten), Some(updated));
+        assert_eq!(rewritten.matches("```metadata").count(), 1);
+
+        assert_eq!(write_metadata_block(&rewritten, None), source);
+
+        let doc = "//! # Heading\n\nuse venus::prelude::*;\n";
+        let with_block = write_metadata_block(doc, Some(&sample()));
+        assert_eq!(write_metadata_block(&with_block, None), doc);
+    }
+}
@@ -1,14 +1,42 @@
 //! Output cache for storing cell outputs.
 //!
 //! Caches cell outputs (text, HTML, images) for embedding in `.ipynb` files.
+//!
+//! Each output is a `<cell>.json` file in the cache directory. When the
+//! files exceed the cache's maximum size on save, the least recently used
+//! outputs are evicted. Access times are kept in an index file alongside.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::error::{SyncError, SyncResult};
 use crate::ipynb::{CellOutput, OutputData};
 
+/// Default maximum total size of cached outputs on disk (100 MiB).
+pub const DEFAULT_MAX_CACHE_SIZE: u64 = 100 * 1024 * 1024;
+
+/// Environment variable overriding the maximum cache size, in MiB.
+pub const MAX_CACHE_SIZE_ENV: &str = "VENUS_OUTPUT_CACHE_MAX_MB";
+
+/// File holding the last access time of each output.
+const ACCESS_INDEX_FILE: &str = "access.idx";
+
+/// Size of an output cache on disk.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Number of cached outputs
+    pub entries: usize,
+
+    /// Total size of the cached outputs in bytes
+    pub total_bytes: u64,
+
+    /// Size above which outputs are evicted
+    pub max_bytes: u64,
+}
+
 /// Cache for cell outputs.
 pub struct OutputCache {
     /// Cache directory path
@@ -17,19 +45,36 @@ pub struct OutputCache {
     /// In-memory cache
     outputs: HashMap<String, CellOutput>,
 
+    /// Last access of each output, in milliseconds since the Unix epoch.
+    /// Behind a mutex so reads through `&self` can record access.
+    accessed: Mutex<HashMap<String, u64>>,
+
+    /// Maximum total size of the outputs on disk
+    max_size: u64,
+
     /// Execution counter for proper Jupyter numbering
     execution_count: u32,
 }
 
 impl OutputCache {
     /// Create a new output cache.
+    ///
+    /// The maximum size is [`DEFAULT_MAX_CACHE_SIZE`], unless overridden in
+    /// MiB by the `VENUS_OUTPUT_CACHE_MAX_MB` environment variable.
     pub fn new(cache_dir: impl AsRef<Path>) -> SyncResult<Self> {
         let cache_dir = cache_dir.as_ref().to_path_buf();
         fs::create_dir_all(&cache_dir)?;
 
+        let max_size = std::env::var(MAX_CACHE_SIZE_ENV)
+            .ok()
+            .and_then(|mb| mb.trim().parse::<u64>().ok())
+            .map_or(DEFAULT_MAX_CACHE_SIZE, |mb| mb * 1024 * 1024);
+
         let mut cache = Self {
             cache_dir,
             outputs: HashMap::new(),
+            accessed: Mutex::new(HashMap::new()),
+            max_size,
             execution_count: 0,
         };
 
@@ -37,6 +82,30 @@ impl OutputCache {
         Ok(cache)
     }
 
+    /// Set the maximum total size of the outputs on disk.
+    pub fn with_max_size(mut self, max_size: u64) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Record that a cell's output was used.
+    fn touch(&self, cell_name: &str) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        let mut accessed = self.accessed.lock().unwrap_or_else(|e| e.into_inner());
+        // Strictly increasing, so accesses within the same millisecond keep their order
+        let latest = accessed.values().copied().max().unwrap_or(0);
+        accessed.insert(cell_name.to_string(), now.max(latest + 1));
+    }
+
+    /// Store an output and mark it as used.
+    fn insert(&mut self, cell_name: &str, output: CellOutput) {
+        self.outputs.insert(cell_name.to_string(), output);
+        self.touch(cell_name);
+    }
+
     /// Get the next execution count.
     fn next_execution_count(&mut self) -> u32 {
         self.execution_count += 1;
@@ -52,9 +121,13 @@ impl OutputCache {
         }
     }
 
-    /// Get cached output for a cell.
+    /// Get cached output for a cell, marking it as used.
     pub fn get_output(&self, cell_name: &str) -> Option<CellOutput> {
-        self.outputs.get(cell_name).cloned()
+        let output = self.outputs.get(cell_name).cloned();
+        if output.is_some() {
+            self.touch(cell_name);
+        }
+        output
     }
 
     /// Store text output for a cell.
@@ -64,7 +137,7 @@ impl OutputCache {
             ..Default::default()
         };
         let output = self.make_execute_result(data);
-        self.outputs.insert(cell_name.to_string(), output);
+        self.insert(cell_name, output);
     }
 
     /// Store HTML output for a cell.
@@ -75,7 +148,7 @@ impl OutputCache {
             ..Default::default()
         };
         let output = self.make_execute_result(data);
-        self.outputs.insert(cell_name.to_string(), output);
+        self.insert(cell_name, output);
     }
 
     /// Store PNG image output for a cell.
@@ -89,7 +162,7 @@ impl OutputCache {
             ..Default::default()
         };
         let output = self.make_execute_result(data);
-        self.outputs.insert(cell_name.to_string(), output);
+        self.insert(cell_name, output);
     }
 
     /// Store SVG output for a cell.
@@ -100,7 +173,7 @@ impl OutputCache {
             ..Default::default()
         };
         let output = self.make_execute_result(data);
-        self.outputs.insert(cell_name.to_string(), output);
+        self.insert(cell_name, output);
     }
 
     /// Store JSON output for a cell.
@@ -112,7 +185,7 @@ impl OutputCache {
             ..Default::default()
         };
         let output = self.make_execute_result(data);
-        self.outputs.insert(cell_name.to_string(), output);
+        self.insert(cell_name, output);
     }
 
     /// Store error output for a cell.
@@ -122,16 +195,38 @@ impl OutputCache {
             evalue: error.to_string(),
             traceback: error.lines().map(String::from).collect(),
         };
-        self.outputs.insert(cell_name.to_string(), output);
+        self.insert(cell_name, output);
     }
 
     /// Clear all cached outputs.
     pub fn clear(&mut self) {
         self.outputs.clear();
+        self.accessed_mut().clear();
+    }
+
+    /// Remove all cached outputs, in memory and on disk.
+    ///
+    /// Returns the number of bytes reclaimed on disk.
+    pub fn remove_all(&mut self) -> SyncResult<u64> {
+        self.clear();
+
+        let mut reclaimed = 0;
+        for (_, path, size) in self.files_on_disk()? {
+            fs::remove_file(&path)?;
+            reclaimed += size;
+        }
+        let index = self.cache_dir.join(ACCESS_INDEX_FILE);
+        if index.exists() {
+            reclaimed += fs::metadata(&index)?.len();
+            fs::remove_file(&index)?;
+        }
+
+        Ok(reclaimed)
     }
 
-    /// Save cache to disk.
-    pub fn save_to_disk(&self) -> SyncResult<()> {
+    /// Save cache to disk, then evict least recently used outputs if the
+    /// cache exceeds its maximum size.
+    pub fn save_to_disk(&mut self) -> SyncResult<()> {
         for (name, output) in &self.outputs {
             let path = self.output_path(name);
             let json = serde_json::to_string_pretty(output)?;
@@ -140,7 +235,106 @@ impl OutputCache {
                 message: e.to_string(),
             })?;
         }
-        Ok(())
+        self.evict()?;
+        self.save_access_times()
+    }
+
+    /// Write the access times of the outputs to disk.
+    ///
+    /// [`save_to_disk`](Self::save_to_disk) does this too; call it after
+    /// only reading outputs so eviction sees them as recently used.
+    pub fn save_access_times(&self) -> SyncResult<()> {
+        let path = self.cache_dir.join(ACCESS_INDEX_FILE);
+        let json = {
+            let accessed = self.accessed.lock().unwrap_or_else(|e| e.into_inner());
+            serde_json::to_string(&*accessed)?
+        };
+        fs::write(&path, json).map_err(|e| SyncError::WriteError {
+            path,
+            message: e.to_string(),
+        })
+    }
+
+    /// Remove least recently used outputs until the cache fits its maximum size.
+    ///
+    /// Returns the number of bytes reclaimed on disk.
+    pub fn evict(&mut self) -> SyncResult<u64> {
+        let mut files = self.files_on_disk()?;
+        let mut total: u64 = files.iter().map(|(_, _, size)| size).sum();
+        if total <= self.max_size {
+            return Ok(0);
+        }
+
+        // Outputs never accessed sort first
+        {
+            let accessed = self.accessed_mut();
+            files.sort_by_key(|(name, _, _)| accessed.get(name).copied().unwrap_or(0));
+        }
+
+        let mut reclaimed = 0;
+        for (name, path, size) in files {
+            if total <= self.max_size {
+                break;
+            }
+            fs::remove_file(&path)?;
+            self.outputs.remove(&name);
+            self.accessed_mut().remove(&name);
+            total -= size;
+            reclaimed += size;
+        }
+
+        tracing::debug!("Evicted {} bytes of cached outputs", reclaimed);
+        Ok(reclaimed)
+    }
+
+    /// Number and total size of the outputs on disk.
+    pub fn stats(&self) -> SyncResult<CacheStats> {
+        let files = self.files_on_disk()?;
+        Ok(CacheStats {
+            entries: files.len(),
+            total_bytes: files.iter().map(|(_, _, size)| size).sum(),
+            max_bytes: self.max_size,
+        })
+    }
+
+    /// Remove cached outputs for cells not in `live_cell_names`.
+    ///
+    /// Returns the number of bytes reclaimed on disk.
+    pub fn gc(&mut self, live_cell_names: &[String]) -> SyncResult<u64> {
+        let live: HashSet<&str> = live_cell_names.iter().map(String::as_str).collect();
+        self.outputs.retain(|name, _| live.contains(name.as_str()));
+        self.accessed_mut()
+            .retain(|name, _| live.contains(name.as_str()));
+
+        let mut reclaimed = 0;
+        for (name, path, size) in self.files_on_disk()? {
+            if !live.contains(name.as_str()) {
+                fs::remove_file(&path)?;
+                reclaimed += size;
+            }
+        }
+
+        Ok(reclaimed)
+    }
+
+    /// Cached output files on disk, as (cell name, path, size).
+    fn files_on_disk(&self) -> SyncResult<Vec<(String, PathBuf, u64)>> {
+        let mut files = Vec::new();
+        for entry in fs::read_dir(&self.cache_dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|e| e == "json")
+                && let Some(name) = path.file_stem().and_then(|s| s.to_str())
+            {
+                let size = fs::metadata(&path)?.len();
+                files.push((name.to_string(), path, size));
+            }
+        }
+        Ok(files)
+    }
+
+    /// Access times, without locking since `self` is borrowed mutably.
+    fn accessed_mut(&mut self) -> &mut HashMap<String, u64> {
+        self.accessed.get_mut().unwrap_or_else(|e| e.into_inner())
     }
 
     /// Load cache from disk.
@@ -162,6 +356,12 @@ impl OutputCache {
             }
         }
 
+        if let Ok(content) = fs::read_to_string(self.cache_dir.join(ACCESS_INDEX_FILE))
+            && let Ok(accessed) = serde_json::from_str(&content)
+        {
+            *self.accessed_mut() = accessed;
+        }
+
         Ok(())
     }
 
@@ -225,4 +425,78 @@ mod tests {
             assert!(cache.get_output("test").is_some());
         }
     }
+
+    #[test]
+    fn test_gc_removes_outputs_of_missing_cells() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let mut cache = OutputCache::new(temp.path()).unwrap();
+        cache.store_text("kept", "1");
+        cache.store_text("renamed", "2");
+        cache.save_to_disk().unwrap();
+
+        let reclaimed = cache.gc(&["kept".to_string()]).unwrap();
+
+        assert!(reclaimed > 0);
+        assert!(cache.get_output("kept").is_some());
+        assert!(cache.get_output("renamed").is_none());
+        assert!(temp.path().join("kept.json").exists());
+        assert!(!temp.path().join("renamed.json").exists());
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let mut cache = OutputCache::new(temp.path()).unwrap();
+        cache.store_text("old", &"a".repeat(100));
+        cache.store_text("recent", &"b".repeat(100));
+        cache.save_to_disk().unwrap();
+
+        // Reading "old" makes "recent" the least recently used
+        cache.get_output("old").unwrap();
+        let one_entry = cache.stats().unwrap().total_bytes / 2;
+        let mut cache = cache.with_max_size(one_entry + 10);
+        cache.save_to_disk().unwrap();
+
+        assert!(cache.get_output("old").is_some());
+        assert!(cache.get_output("recent").is_none());
+        assert!(!temp.path().join("recent.json").exists());
+        assert_eq!(cache.stats().unwrap().entries, 1);
+    }
+
+    #[test]
+    fn test_access_times_persist() {
+        let temp = tempfile::TempDir::new().unwrap();
+        {
+            let mut cache = OutputCache::new(temp.path()).unwrap();
+            cache.store_text("first", "1");
+            cache.store_text("second", "2");
+            cache.save_to_disk().unwrap();
+        }
+
+        // Only read in this session, so the access must be saved explicitly
+        {
+            let cache = OutputCache::new(temp.path()).unwrap();
+            cache.get_output("first").unwrap();
+            cache.save_access_times().unwrap();
+        }
+
+        let cache = OutputCache::new(temp.path()).unwrap();
+        let total = cache.stats().unwrap().total_bytes;
+        let mut cache = cache.with_max_size(total - 1);
+        assert!(cache.evict().unwrap() > 0);
+        assert!(cache.get_output("first").is_some());
+        assert!(cache.get_output("second").is_none());
+    }
+
+    #[test]
+    fn test_remove_all() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let mut cache = OutputCache::new(temp.path()).unwrap();
+        cache.store_text("a", "1");
+        cache.save_to_disk().unwrap();
+
+        assert!(cache.remove_all().unwrap() > 0);
+        assert_eq!(cache.stats().unwrap().entries, 0);
+        assert!(cache.get_output("a").is_none());
+    }
 }
@@ -5,20 +5,11 @@
 use std::fs;
 use std::path::Path;
 
-use crate::error::{SyncError, SyncResult};
-
-/// Metadata extracted from the notebook header.
-#[derive(Debug, Clone, Default)]
-pub struct NotebookMetadata {
-    /// Notebook title (from first `# Title` in doc comment)
-    pub title: Option<String>,
-
-    /// Notebook description (from doc comment after title)
-    pub description: Option<String>,
+use serde::{Deserialize, Serialize};
+use venus_core::graph::CellParser;
 
-    /// Dependencies (parsed from `//! ```cargo` block)
-    pub dependencies: Vec<String>,
-}
+use crate::error::{SyncError, SyncResult};
+use crate::metadata::{self, NotebookMetadata};
 
 /// A cell extracted from the notebook.
 #[derive(Debug, Clone)]
@@ -37,6 +28,23 @@ pub struct NotebookCell {
 
     /// Whether this cell has dependencies
     pub has_dependencies: bool,
+
+    /// Cells this cell depends on (code cells only)
+    pub dependencies: Vec<CellDependency>,
+
+    /// Return type as written in the signature (code cells only)
+    pub return_type: Option<String>,
+}
+
+/// A parameter of a code cell, naming the cell it depends on.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CellDependency {
+    /// Parameter name (the name of the upstream cell)
+    pub name: String,
+
+    /// Parameter type, e.g. `&Config`
+    #[serde(rename = "type")]
+    pub ty: String,
 }
 
 /// Type of cell.
@@ -75,11 +83,37 @@ impl RsParser {
 
     /// Parse source code into notebook metadata and cells.
     pub fn parse_source(&self, source: &str) -> SyncResult<(NotebookMetadata, Vec<NotebookCell>)> {
-        let mut metadata = NotebookMetadata::default();
+        let mut metadata = self.parse_metadata(source);
+        metadata.preamble = self.extract_preamble(source);
         let mut cells = Vec::new();
 
-        // First pass: extract module-level doc comments for metadata
+        // Create a markdown cell for the header if there's content
+        let header_md = self.extract_header_markdown(source);
+        if let Some(md) = header_md {
+            cells.push(NotebookCell {
+                name: "_header".to_string(),
+                cell_type: CellType::Markdown,
+                markdown: Some(md),
+                source: None,
+                has_dependencies: false,
+                dependencies: Vec::new(),
+                return_type: None,
+            });
+        }
+
+        // Extract cells
+        self.extract_cells(source, &mut cells)?;
+
+        Ok((metadata, cells))
+    }
+
+    /// Parse notebook metadata from the module doc comments.
+    pub fn parse_metadata(&self, source: &str) -> NotebookMetadata {
+        let mut metadata = NotebookMetadata::default();
+
+        // Collect module-level doc comment lines
         let mut in_cargo_block = false;
+        let mut in_metadata_block = false;
         let mut header_lines = Vec::new();
 
         for line in source.lines() {
@@ -98,6 +132,17 @@ impl RsParser {
                     continue;
                 }
 
+                // Metadata block is parsed separately below
+                if metadata::is_block_start(content) {
+                    in_metadata_block = true;
+                    continue;
+                }
+
+                if in_metadata_block {
+                    in_metadata_block = content != "```";
+                    continue;
+                }
+
                 if in_cargo_block {
                     // Skip cargo block content for markdown
                     if content.starts_with('[') || content.contains('=') {
@@ -117,11 +162,14 @@ impl RsParser {
             if first.starts_with("# ") {
                 metadata.title = Some(first.trim_start_matches("# ").to_string());
                 if header_lines.len() > 1 {
-                    // Skip empty lines after title
-                    let desc_lines: Vec<&String> = header_lines[1..]
+                    // Skip empty lines around the description
+                    let mut desc_lines: Vec<&String> = header_lines[1..]
                         .iter()
                         .skip_while(|l| l.is_empty())
                         .collect();
+                    while desc_lines.last().is_some_and(|l| l.is_empty()) {
+                        desc_lines.pop();
+                    }
                     if !desc_lines.is_empty() {
                         metadata.description = Some(
                             desc_lines
@@ -135,28 +183,36 @@ impl RsParser {
             }
         }
 
-        // Create a markdown cell for the header if there's content
-        let header_md = self.extract_header_markdown(source);
-        if let Some(md) = header_md {
-            cells.push(NotebookCell {
-                name: "_header".to_string(),
-                cell_type: CellType::Markdown,
-                markdown: Some(md),
-                source: None,
-                has_dependencies: false,
-            });
+        // Explicit metadata block overrides the heading-derived fields
+        if let Some(explicit) = metadata::parse_metadata_block(source) {
+            metadata.merge(explicit);
         }
 
-        // Second pass: extract cells
-        self.extract_cells(source, &mut cells)?;
+        metadata
+    }
 
-        Ok((metadata, cells))
+    /// Extract the source before the first cell and its doc comment.
+    ///
+    /// This holds the module docs, `cargo` block and `use` items, which are
+    /// not part of any cell.
+    fn extract_preamble(&self, source: &str) -> Option<String> {
+        let lines: Vec<&str> = source.lines().collect();
+        let end = lines
+            .iter()
+            .position(|line| is_cell_attribute(line.trim()))
+            .map(|attr| self.find_doc_comment_start(&lines, attr))
+            .unwrap_or(lines.len());
+
+        let preamble = lines[..end].join("\n");
+        let preamble = preamble.trim_end();
+        (!preamble.is_empty()).then(|| preamble.to_string())
     }
 
     /// Extract the header markdown from module doc comments.
     fn extract_header_markdown(&self, source: &str) -> Option<String> {
         let mut lines = Vec::new();
         let mut in_cargo_block = false;
+        let mut in_metadata_block = false;
 
         for line in source.lines() {
             let trimmed = line.trim();
@@ -174,6 +230,16 @@ impl RsParser {
                     continue;
                 }
 
+                if metadata::is_block_start(content) {
+                    in_metadata_block = true;
+                    continue;
+                }
+
+                if in_metadata_block {
+                    in_metadata_block = content.trim() != "```";
+                    continue;
+                }
+
                 if !in_cargo_block {
                     lines.push(content.to_string());
                 }
@@ -202,7 +268,7 @@ impl RsParser {
             let line = lines[i].trim();
 
             // Look for #[venus::cell] attribute
-            if line == "#[venus::cell]" || line.starts_with("#[venus::cell(") {
+            if is_cell_attribute(line) {
                 // Collect doc comment before the attribute
                 let doc_start = self.find_doc_comment_start(&lines, i);
                 let doc_comment = if doc_start < i {
@@ -223,14 +289,11 @@ impl RsParser {
 
                 // Extract function
                 let fn_line = lines[i].trim();
-                if !fn_line.starts_with("pub fn ") && !fn_line.starts_with("fn ") {
+                if !is_function_line(fn_line) {
                     i += 1;
                     continue;
                 }
 
-                // Get function name and check for dependencies
-                let (name, has_deps) = self.parse_function_signature(fn_line);
-
                 // Find the end of the function (matching braces)
                 let fn_start = i;
                 let fn_end = self.find_function_end(&lines, i);
@@ -248,6 +311,17 @@ impl RsParser {
                 }
                 let source_code = source_lines.join("\n");
 
+                // Name, dependencies and return type from the parsed signature,
+                // falling back to the signature line if the cell doesn't parse
+                let (name, has_deps, dependencies, return_type) =
+                    match parse_cell_signature(&source_code) {
+                        Some((name, deps, ret)) => (name, !deps.is_empty(), deps, Some(ret)),
+                        None => {
+                            let (name, has_deps) = self.parse_function_signature(fn_line);
+                            (name, has_deps, Vec::new(), None)
+                        }
+                    };
+
                 // Add markdown cell for doc comment if present
                 if let Some(md) = &doc_comment
                     && !md.is_empty()
@@ -258,6 +332,8 @@ impl RsParser {
                         markdown: Some(md.clone()),
                         source: None,
                         has_dependencies: false,
+                        dependencies: Vec::new(),
+                        return_type: None,
                     });
                 }
 
@@ -268,6 +344,8 @@ impl RsParser {
                     markdown: None,
                     source: Some(source_code),
                     has_dependencies: has_deps,
+                    dependencies,
+                    return_type,
                 });
 
                 i = fn_end + 1;
@@ -320,14 +398,11 @@ impl RsParser {
     /// Parse function signature to extract name and check for dependencies.
     fn parse_function_signature(&self, line: &str) -> (String, bool) {
         // Remove visibility and fn keyword
-        let stripped = line
-            .trim_start_matches("pub ")
-            .trim_start_matches("fn ")
-            .trim();
+        let stripped = strip_visibility(line).trim_start_matches("fn ").trim();
 
-        // Extract name (before the '(')
+        // Extract name (up to generics or the parameter list)
         let name = stripped
-            .split('(')
+            .split(['(', '<'])
             .next()
             .unwrap_or("unknown")
             .trim()
@@ -381,6 +456,56 @@ impl Default for RsParser {
     }
 }
 
+/// Whether a trimmed line is a `#[venus::cell]` attribute.
+pub(crate) fn is_cell_attribute(line: &str) -> bool {
+    line == "#[venus::cell]" || line.starts_with("#[venus::cell(")
+}
+
+/// Whether a trimmed line starts a function, with any visibility.
+fn is_function_line(line: &str) -> bool {
+    strip_visibility(line).starts_with("fn ")
+}
+
+/// Strip a leading `pub` or `pub(...)` from a trimmed line.
+fn strip_visibility(line: &str) -> &str {
+    let Some(rest) = line.strip_prefix("pub") else {
+        return line;
+    };
+    let rest = match rest.strip_prefix('(') {
+        Some(restricted) => restricted.split_once(')').map_or(rest, |(_, after)| after),
+        None => rest,
+    };
+    rest.trim_start()
+}
+
+/// Parse a cell's source with the Venus cell parser.
+///
+/// Returns the cell name, dependencies and return type, or `None` if the
+/// source is not a single parseable cell.
+pub(crate) fn parse_cell_signature(source: &str) -> Option<(String, Vec<CellDependency>, String)> {
+    let result = CellParser::new()
+        .parse_str(source, Path::new("cell.rs"))
+        .ok()?;
+    let [cell] = result.code_cells.as_slice() else {
+        return None;
+    };
+
+    let dependencies = cell
+        .dependencies
+        .iter()
+        .map(|dep| CellDependency {
+            name: dep.param_name.clone(),
+            ty: match (dep.is_ref, dep.is_mut) {
+                (true, true) => format!("&mut {}", dep.param_type),
+                (true, false) => format!("&{}", dep.param_type),
+                (false, _) => dep.param_type.clone(),
+            },
+        })
+        .collect();
+
+    Some((cell.name.clone(), dependencies, cell.return_type.clone()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -418,6 +543,33 @@ pub fn hello() -> String {
         assert!(!code_cell.has_dependencies);
     }
 
+    #[test]
+    fn test_parse_metadata_block() {
+        let source = r#"//! # My Notebook
+//!
+//! A test notebook.
+//!
+//! ```metadata
+//! title = "Explicit Title"
+//! authors = ["Ada"]
+//! ```
+
+use venus::prelude::*;
+"#;
+
+        let parser = RsParser::new();
+        let (metadata, cells) = parser.parse_source(source).unwrap();
+
+        assert_eq!(metadata.title, Some("Explicit Title".to_string()));
+        assert_eq!(metadata.description, Some("A test notebook.".to_string()));
+        assert_eq!(metadata.authors, vec!["Ada".to_string()]);
+
+        // The block is not part of the header markdown
+        let header = cells[0].markdown.as_deref().unwrap();
+        assert!(!header.contains("metadata"));
+        assert!(!header.contains("authors"));
+    }
+
     #[test]
     fn test_parse_cell_with_dependencies() {
         let source = r#"
@@ -4,7 +4,9 @@
 
 use std::fs;
 use tempfile::TempDir;
-use venus_sync::{JupyterNotebook, OutputCache, RsParser, sync_to_ipynb};
+use venus_sync::{
+    CellType, JupyterNotebook, NotebookCell, OutputCache, RsParser, sync_to_ipynb, sync_to_rs,
+};
 
 // =============================================================================
 // Test Helpers
@@ -225,6 +227,140 @@ fn test_roundtrip_with_unicode() {
     assert!(has_unicode, "Should preserve unicode characters");
 }
 
+/// Convert `source` to `.ipynb` and back, returning the regenerated `.rs`.
+fn rs_ipynb_rs(source: &str) -> String {
+    let temp = temp_dir();
+    let rs_path = temp.path().join("original.rs");
+    let ipynb_path = temp.path().join("converted.ipynb");
+    let back_path = temp.path().join("back.rs");
+
+    fs::write(&rs_path, source).expect("Failed to write RS file");
+    sync_to_ipynb(&rs_path, &ipynb_path, None).expect("Failed to sync to ipynb");
+    sync_to_rs(&ipynb_path, &back_path).expect("Failed to sync to rs");
+
+    fs::read_to_string(&back_path).expect("Failed to read RS file")
+}
+
+/// A code cell as (name, source, dependencies, return type, doc).
+type CodeCell = (
+    String,
+    String,
+    Vec<(String, String)>,
+    Option<String>,
+    Option<String>,
+);
+
+/// Code cells of a notebook.
+fn code_cells(source: &str) -> Vec<CodeCell> {
+    let (_, cells) = RsParser::new()
+        .parse_source(source)
+        .expect("Failed to parse RS source");
+
+    let doc = |cell: &NotebookCell| {
+        cells
+            .iter()
+            .find(|c| c.cell_type == CellType::Markdown && c.name == format!("{}_doc", cell.name))
+            .and_then(|c| c.markdown.clone())
+    };
+
+    cells
+        .iter()
+        .filter(|c| c.cell_type == CellType::Code)
+        .map(|c| {
+            (
+                c.name.clone(),
+                c.source.clone().unwrap_or_default(),
+                c.dependencies
+                    .iter()
+                    .map(|d| (d.name.clone(), d.ty.clone()))
+                    .collect(),
+                c.return_type.clone(),
+                doc(c),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_roundtrip_rs_ipynb_rs_is_exact() {
+    let source = create_simple_notebook();
+    assert_eq!(rs_ipynb_rs(source), source);
+}
+
+#[test]
+fn test_roundtrip_rs_ipynb_rs_preserves_cells() {
+    for source in [create_simple_notebook(), create_edge_case_notebook()] {
+        let back = rs_ipynb_rs(source);
+        assert_eq!(code_cells(&back), code_cells(source));
+    }
+}
+
+#[test]
+fn test_roundtrip_preserves_dependency_structure() {
+    let source = r#"//! # Pipeline
+
+use venus::prelude::*;
+
+#[venus::cell]
+pub fn config() -> Config {
+    Config::default()
+}
+
+/// Loads the data.
+#[venus::cell]
+pub fn data(config: &Config) -> Vec<i32> {
+    vec![config.size]
+}
+
+#[venus::cell]
+pub fn total(config: &Config, data: &Vec<i32>) -> i64 {
+    data.iter().map(|&x| x as i64).sum::<i64>() * config.scale
+}
+"#;
+
+    let back = rs_ipynb_rs(source);
+    let cells = code_cells(&back);
+
+    assert_eq!(cells, code_cells(source));
+    assert_eq!(
+        cells[2].2,
+        vec![
+            ("config".to_string(), "&Config".to_string()),
+            ("data".to_string(), "&Vec < i32 >".to_string()),
+        ]
+    );
+    assert!(back.contains("use venus::prelude::*;"));
+}
+
+#[test]
+fn test_ipynb_stores_cell_signature_metadata() {
+    let temp = temp_dir();
+    let rs_path = temp.path().join("meta.rs");
+    let ipynb_path = temp.path().join("meta.ipynb");
+
+    fs::write(&rs_path, create_simple_notebook()).expect("Failed to write RS file");
+    sync_to_ipynb(&rs_path, &ipynb_path, None).expect("Failed to sync");
+
+    let content = fs::read_to_string(&ipynb_path).expect("Failed to read IPYNB");
+    let json: serde_json::Value = serde_json::from_str(&content).expect("Invalid JSON");
+
+    let process = json["cells"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|c| c["metadata"]["venus"]["name"] == "process")
+        .expect("process cell should carry venus metadata");
+    assert_eq!(process["metadata"]["venus"]["return_type"], "String");
+    assert_eq!(
+        process["metadata"]["venus"]["dependencies"][0]["name"],
+        "greeting"
+    );
+    assert_eq!(
+        process["metadata"]["venus"]["dependencies"][0]["type"],
+        "&String"
+    );
+}
+
 // =============================================================================
 // Output Cache Tests
 // =============================================================================
@@ -1,11 +1,11 @@
 //! Build command implementation for Venus CLI.
 //!
-//! Compiles a notebook to a standalone binary.
+//! Compiles a notebook to a standalone binary, or generates a library crate.
 
 use std::path::Path;
 use std::time::Instant;
 
-use venus_core::compile::{CompilerConfig, ProductionBuilder};
+use venus_core::compile::{CompilerConfig, ProductionBuilder, ToolchainManager};
 use venus_core::paths::NotebookDirs;
 
 use crate::colors;
@@ -14,7 +14,19 @@ use crate::colors;
 pub type CliResult = anyhow::Result<()>;
 
 /// Build a notebook to a standalone binary.
-pub fn execute(notebook_path: &str, output: Option<&str>, release: bool) -> CliResult {
+///
+/// With `embed_outputs`, the outputs cached by `venus run` are baked into
+/// the binary. With `target`, the binary is cross-compiled for that triple.
+/// With `lib`, a library crate exposing the cells is generated instead.
+pub fn execute(
+    notebook_path: &str,
+    output: Option<&str>,
+    release: bool,
+    embed_outputs: bool,
+    target: Option<&str>,
+    lib: bool,
+    verbose: bool,
+) -> CliResult {
     let path = Path::new(notebook_path);
     if !path.exists() {
         anyhow::bail!(
@@ -38,6 +50,11 @@ pub fn execute(notebook_path: &str, output: Option<&str>, release: bool) -> CliR
         colors::RESET
     );
 
+    // Fail early if the target's standard library is missing
+    if let Some(target) = target {
+        ToolchainManager::new()?.ensure_target(target)?;
+    }
+
     // Determine output path
     let output_path = if let Some(out) = output {
         Path::new(out).to_path_buf()
@@ -49,11 +66,17 @@ pub fn execute(notebook_path: &str, output: Option<&str>, release: bool) -> CliR
             .to_string_lossy()
             .replace('-', "_");
 
-        #[cfg(target_os = "windows")]
-        let output_name = format!("{}.exe", name);
-
-        #[cfg(not(target_os = "windows"))]
-        let output_name = name;
+        let windows = match target {
+            Some(target) => target.contains("windows"),
+            None => cfg!(target_os = "windows"),
+        };
+        let output_name = if lib {
+            format!("{}_lib", name)
+        } else if windows {
+            format!("{}.exe", name)
+        } else {
+            name
+        };
 
         Path::new(&output_name).to_path_buf()
     };
@@ -71,6 +94,12 @@ pub fn execute(notebook_path: &str, output: Option<&str>, release: bool) -> CliR
     colors::flush_stdout();
 
     let mut builder = ProductionBuilder::new(config);
+    if embed_outputs {
+        builder = builder.with_embedded_outputs(&dirs.state_dir);
+    }
+    if let Some(target) = target {
+        builder = builder.with_target(target);
+    }
     builder.load(&abs_path)?;
 
     println!(
@@ -81,11 +110,30 @@ pub fn execute(notebook_path: &str, output: Option<&str>, release: bool) -> CliR
         builder.dependency_count()
     );
 
+    if lib {
+        print!("{}  ◆ Generating crate{} ... ", colors::BLUE, colors::RESET);
+        colors::flush_stdout();
+
+        builder.build_library(&output_path)?;
+
+        println!("{}✓{}", colors::GREEN, colors::RESET);
+        println!();
+        println!(
+            "{}Generated:{} {} (build with `cargo build`)",
+            colors::GREEN,
+            colors::RESET,
+            output_path.display()
+        );
+        return Ok(());
+    }
+
     // Build
     print!("{}  ◆ Compiling binary{} ... ", colors::BLUE, colors::RESET);
     colors::flush_stdout();
 
-    builder.build(&output_path, release)?;
+    let mut progress = colors::BuildProgressLine::new("Compiling binary", verbose);
+    builder.build_with_progress(&output_path, release, |event| progress.handle(event))?;
+    progress.finish();
 
     let duration = start.elapsed();
 
@@ -109,6 +157,16 @@ pub fn execute(notebook_path: &str, output: Option<&str>, release: bool) -> CliR
             "debug"
         }
     );
+    if let Some(target) = target {
+        println!("{}Target:{} {}", colors::DIM, colors::RESET, target);
+    }
+    if embed_outputs {
+        println!(
+            "{}Outputs:{} embedded (run with --cached)",
+            colors::DIM,
+            colors::RESET
+        );
+    }
     println!(
         "{}Time:{} {:.2}s",
         colors::DIM,
@@ -0,0 +1,58 @@
+//! Cache command implementation for Venus CLI.
+//!
+//! Reports on and clears the notebook's output cache (`.venus/outputs`),
+//! which holds the cell outputs embedded by `venus sync`.
+
+use std::path::Path;
+
+use venus_core::paths::NotebookDirs;
+use venus_sync::OutputCache;
+
+use crate::clean::format_bytes;
+use crate::colors;
+
+/// Open the output cache of a notebook.
+fn open_cache(notebook_path: &str) -> anyhow::Result<OutputCache> {
+    let path = Path::new(notebook_path);
+    if !path.exists() {
+        anyhow::bail!("Notebook not found: {}", notebook_path);
+    }
+
+    let dirs = NotebookDirs::from_notebook_path(&path.canonicalize()?)?;
+    Ok(OutputCache::new(dirs.venus_dir.join("outputs"))?)
+}
+
+/// Execute `venus cache info`.
+pub fn info(notebook_path: &str) -> anyhow::Result<()> {
+    let stats = open_cache(notebook_path)?.stats()?;
+
+    println!(
+        "{}Output cache{}: {} {} ({} of {} max)",
+        colors::BOLD,
+        colors::RESET,
+        stats.entries,
+        if stats.entries == 1 {
+            "entry"
+        } else {
+            "entries"
+        },
+        format_bytes(stats.total_bytes),
+        format_bytes(stats.max_bytes)
+    );
+
+    Ok(())
+}
+
+/// Execute `venus cache clear`.
+pub fn clear(notebook_path: &str) -> anyhow::Result<()> {
+    let reclaimed = open_cache(notebook_path)?.remove_all()?;
+
+    println!(
+        "{}✓{} Cleared output cache, reclaimed {}",
+        colors::GREEN,
+        colors::RESET,
+        format_bytes(reclaimed)
+    );
+
+    Ok(())
+}
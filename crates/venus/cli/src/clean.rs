@@ -0,0 +1,102 @@
+//! Clean command implementation for Venus CLI.
+//!
+//! Removes a notebook's `.venus` artifacts, or with `--cache` only those left
+//! behind by cells that were renamed or deleted.
+
+use std::fs;
+use std::path::Path;
+
+use venus_core::compile::{CellCompiler, CompilerConfig, ToolchainManager};
+use venus_core::graph::{CellId, CellParser, GraphEngine};
+use venus_core::paths::NotebookDirs;
+use venus_core::state::StateManager;
+use venus_sync::OutputCache;
+
+use crate::colors;
+
+/// Execute the clean command.
+pub fn execute(notebook_path: &str, cache_only: bool) -> anyhow::Result<()> {
+    let path = Path::new(notebook_path);
+    if !path.exists() {
+        anyhow::bail!("Notebook not found: {}", notebook_path);
+    }
+
+    let abs_path = path.canonicalize()?;
+    let dirs = NotebookDirs::from_notebook_path(&abs_path)?;
+
+    let reclaimed = if cache_only {
+        collect_garbage(&abs_path, &dirs)?
+    } else {
+        let size = dir_size(&dirs.venus_dir);
+        dirs.clean()?;
+        size
+    };
+
+    println!(
+        "{}✓{} Reclaimed {}",
+        colors::GREEN,
+        colors::RESET,
+        format_bytes(reclaimed)
+    );
+
+    Ok(())
+}
+
+/// Remove outputs and build artifacts of cells that are no longer in the notebook.
+fn collect_garbage(notebook: &Path, dirs: &NotebookDirs) -> anyhow::Result<u64> {
+    let parsed = CellParser::new().parse_file(notebook)?;
+
+    // Assign cell IDs the same way the executors do
+    let mut graph = GraphEngine::new();
+    let mut ids: Vec<CellId> = Vec::new();
+    let mut names: Vec<String> = Vec::new();
+    for cell in parsed.code_cells {
+        names.push(cell.name.clone());
+        ids.push(graph.add_cell(cell));
+    }
+
+    let mut reclaimed = StateManager::new(&dirs.state_dir)?.gc(&ids)?;
+
+    let compiler = CellCompiler::new(CompilerConfig::for_notebook(dirs), ToolchainManager::new()?);
+    reclaimed += compiler.prune_artifacts(&names);
+
+    let outputs_dir = dirs.venus_dir.join("outputs");
+    if outputs_dir.exists() {
+        reclaimed += OutputCache::new(&outputs_dir)?.gc(&names)?;
+    }
+
+    Ok(reclaimed)
+}
+
+/// Total size of the files under a directory.
+fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(t) if t.is_dir() => dir_size(&entry.path()),
+            _ => entry.metadata().map(|m| m.len()).unwrap_or(0),
+        })
+        .sum()
+}
+
+/// Format a byte count for display.
+pub(crate) fn format_bytes(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "KB", "MB", "GB"];
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
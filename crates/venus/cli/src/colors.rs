@@ -2,6 +2,8 @@
 
 use std::io::{self, Write};
 
+use venus_core::compile::BuildEvent;
+
 pub const RESET: &str = "\x1b[0m";
 pub const BOLD: &str = "\x1b[1m";
 pub const DIM: &str = "\x1b[2m";
@@ -11,6 +13,9 @@ pub const BLUE: &str = "\x1b[34m";
 pub const CYAN: &str = "\x1b[36m";
 pub const RED: &str = "\x1b[31m";
 
+/// Erase the current terminal line.
+pub const CLEAR_LINE: &str = "\x1b[2K";
+
 /// Flush stdout to ensure progress output is visible immediately.
 ///
 /// This is useful when printing progress indicators without a trailing newline.
@@ -18,3 +23,63 @@ pub const RED: &str = "\x1b[31m";
 pub fn flush_stdout() {
     io::stdout().flush().ok();
 }
+
+/// Live status line for a step that runs a cargo build.
+///
+/// Rewrites the step line with the crate currently being compiled, so long
+/// dependency builds visibly make progress. In verbose mode, cargo's output
+/// is printed in full instead.
+pub struct BuildProgressLine {
+    step: String,
+    verbose: bool,
+    dirty: bool,
+}
+
+impl BuildProgressLine {
+    /// Create a progress line for a step that has already been printed.
+    pub fn new(step: impl Into<String>, verbose: bool) -> Self {
+        Self {
+            step: step.into(),
+            verbose,
+            dirty: false,
+        }
+    }
+
+    /// Update the line for a cargo build event.
+    pub fn handle(&mut self, event: &BuildEvent) {
+        match event {
+            BuildEvent::Output(line) if self.verbose => {
+                if !self.dirty {
+                    println!();
+                    self.dirty = true;
+                }
+                eprintln!("{}    {}{}", DIM, line, RESET);
+            }
+            BuildEvent::Compiling {
+                name,
+                current,
+                total,
+            } if !self.verbose => {
+                let count = match total {
+                    Some(total) => format!("{}/{}", current, total),
+                    None => current.to_string(),
+                };
+                print!(
+                    "\r{}{}  ◆ {}{} ... {}Compiling {} ({}){}",
+                    CLEAR_LINE, BLUE, self.step, RESET, DIM, name, count, RESET
+                );
+                flush_stdout();
+                self.dirty = true;
+            }
+            _ => {}
+        }
+    }
+
+    /// Restore the plain step line so a result can be printed after it.
+    pub fn finish(&self) {
+        if self.dirty {
+            print!("\r{}{}  ◆ {}{} ... ", CLEAR_LINE, BLUE, self.step, RESET);
+            flush_stdout();
+        }
+    }
+}
@@ -17,7 +17,7 @@ use venus_core::compile::{
 use venus_core::execute::{ExecutionCallback, LinearExecutor};
 use venus_core::graph::{CellId, CellInfo, CellParser, GraphEngine};
 use venus_core::paths::NotebookDirs;
-use venus_core::state::{BoxedOutput, StateManager};
+use venus_core::state::{BoxedOutput, OutputIndex, StateManager, output_fingerprints};
 
 use crate::colors;
 
@@ -120,7 +120,6 @@ pub struct NotebookExecutor {
     /// Absolute path to the notebook file.
     pub notebook_path: PathBuf,
     /// Notebook source code.
-    #[allow(dead_code)]
     pub source: String,
     /// Notebook directories.
     pub dirs: NotebookDirs,
@@ -134,6 +133,8 @@ pub struct NotebookExecutor {
     pub order: Vec<CellId>,
     /// Dependency map (cell_id -> dependencies).
     pub deps: HashMap<CellId, Vec<CellId>>,
+    /// Output fingerprints by cell name, recorded with persisted outputs.
+    pub fingerprints: HashMap<String, u64>,
     /// Compiler configuration.
     pub config: CompilerConfig,
     /// Universe builder (for dependency hash).
@@ -149,7 +150,9 @@ impl NotebookExecutor {
     /// Create a new notebook executor.
     ///
     /// This performs the setup phase: parsing, dependency resolution, and universe building.
-    pub fn new(notebook_path: &str, release: bool) -> anyhow::Result<Self> {
+    ///
+    /// With `verbose`, cargo's full output is shown while building the universe.
+    pub fn new(notebook_path: &str, release: bool, verbose: bool) -> anyhow::Result<Self> {
         let path = Path::new(notebook_path);
         if !path.exists() {
             anyhow::bail!("Notebook not found: {}", notebook_path);
@@ -182,6 +185,7 @@ impl NotebookExecutor {
         }
         graph.resolve_dependencies()?;
         let order = graph.topological_order()?;
+        let fingerprints = output_fingerprints(&graph)?;
         Self::print_success(None);
 
         // Build dependency map
@@ -189,12 +193,7 @@ impl NotebookExecutor {
             .iter()
             .map(|cell| {
                 let real_id = cell_ids[&cell.name];
-                let dep_ids: Vec<CellId> = cell
-                    .dependencies
-                    .iter()
-                    .filter_map(|dep| cell_ids.get(&dep.param_name).copied())
-                    .collect();
-                (real_id, dep_ids)
+                (real_id, graph.dependencies(real_id))
             })
             .collect();
 
@@ -208,7 +207,9 @@ impl NotebookExecutor {
 
         let mut universe_builder = UniverseBuilder::new(config.clone(), toolchain.clone(), None);
         universe_builder.parse_dependencies(&source, &definition_cells)?;
-        let universe_path = universe_builder.build()?;
+        let mut progress = colors::BuildProgressLine::new("Building universe", verbose);
+        let universe_path = universe_builder.build_with_progress(|event| progress.handle(event))?;
+        progress.finish();
 
         if universe_builder.dependencies().is_empty() {
             Self::print_success(Some("runtime only"));
@@ -225,6 +226,7 @@ impl NotebookExecutor {
             cell_ids,
             order,
             deps,
+            fingerprints,
             config,
             universe_builder,
             universe_path,
@@ -355,6 +357,8 @@ impl NotebookExecutor {
         executor.execute_in_order(&execution_order, &self.deps)?;
         let execution_time = exec_start.elapsed();
 
+        self.persist_outputs(executor.state_mut(), &execution_order);
+
         // Collect outputs
         let mut outputs = HashMap::new();
         for &cell_id in &execution_order {
@@ -411,6 +415,38 @@ impl NotebookExecutor {
         })
     }
 
+    /// Save outputs to the state directory for later reuse.
+    ///
+    /// Outputs are tagged with the cell's declared return type and indexed
+    /// by cell name and fingerprint, so that `venus build --embed-outputs`
+    /// can find them after IDs change and refuse stale ones. Failure to
+    /// persist is not fatal.
+    fn persist_outputs(&self, state: &mut StateManager, executed: &[CellId]) {
+        let mut index = match OutputIndex::load(&self.dirs.state_dir) {
+            Ok(index) => index,
+            Err(e) => {
+                tracing::warn!("Failed to load the output index: {}", e);
+                return;
+            }
+        };
+
+        for &cell_id in executed {
+            if let Some(cell) = self.cell_by_id(cell_id)
+                && let Some(output) = state.get_output(cell_id)
+            {
+                let typed = (*output).clone().with_type_name(cell.return_type.clone());
+                state.store_output(cell_id, typed);
+                if let Some(&fingerprint) = self.fingerprints.get(&cell.name) {
+                    index.record(&cell.name, cell_id, fingerprint);
+                }
+            }
+        }
+
+        if let Err(e) = state.flush().and_then(|()| index.save()) {
+            tracing::warn!("Failed to persist cell outputs: {}", e);
+        }
+    }
+
     /// Filter execution order based on cell filter.
     fn filter_execution_order(&self, cell_filter: Option<&str>) -> anyhow::Result<Vec<CellId>> {
         if let Some(cell_name) = cell_filter {
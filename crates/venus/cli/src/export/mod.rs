@@ -2,9 +2,7 @@
 //!
 //! Generates standalone HTML files from notebook execution.
 
-mod html;
-
-pub use html::{CellExport, generate_html};
+pub use venus_server::export::{CellExport, generate_html};
 
 use std::collections::HashMap;
 use std::fs;
@@ -12,6 +10,7 @@ use std::path::Path;
 use std::time::Instant;
 
 use venus_core::graph::CellId;
+use venus_sync::RsParser;
 
 use crate::colors;
 use crate::executor::NotebookExecutor;
@@ -27,7 +26,7 @@ pub fn execute(
     let start = Instant::now();
 
     // Create executor (handles parsing, graph building, universe)
-    let executor = NotebookExecutor::new(notebook_path, release)?;
+    let executor = NotebookExecutor::new(notebook_path, release, false)?;
 
     // Print header
     println!(
@@ -135,7 +134,14 @@ pub fn execute(
         .filter_map(|id| cell_exports.remove(id))
         .collect();
 
-    let html = generate_html(&executor.notebook_name(), &ordered_exports, dark_theme);
+    let source = fs::read_to_string(&executor.notebook_path)?;
+    let metadata = RsParser::new().parse_metadata(&source);
+    let html = generate_html(
+        &executor.notebook_name(),
+        &metadata,
+        &ordered_exports,
+        dark_theme,
+    );
 
     // Determine output path
     let path = Path::new(notebook_path);
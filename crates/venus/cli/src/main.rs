@@ -1,7 +1,9 @@
 //! Venus CLI - Reactive notebook environment for Rust.
 
 mod build;
+mod cache;
 mod cargo_manager;
+mod clean;
 mod colors;
 mod executor;
 mod export;
@@ -9,9 +11,14 @@ mod output;
 mod run;
 mod serve;
 mod sync;
+mod warm;
 mod watch;
 
+use std::io::IsTerminal;
+
 use clap::{Parser, Subcommand};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::prelude::*;
 
 #[derive(Parser)]
 #[command(name = "venus")]
@@ -40,6 +47,10 @@ enum Commands {
         /// Use release mode (LLVM backend, optimized)
         #[arg(long)]
         release: bool,
+
+        /// Load environment variables for cells from a dotenv file
+        #[arg(long)]
+        env_file: Option<String>,
     },
 
     /// Start the interactive notebook server
@@ -50,6 +61,22 @@ enum Commands {
         /// Port to listen on
         #[arg(short, long, default_value = "8080")]
         port: u16,
+
+        /// Open the notebook in a browser (default when run from a terminal)
+        #[arg(long, overrides_with = "no_open")]
+        open: bool,
+
+        /// Do not open a browser
+        #[arg(long, overrides_with = "open")]
+        no_open: bool,
+
+        /// Report CPU and memory usage of running cells
+        #[arg(long)]
+        resource_usage: bool,
+
+        /// Load environment variables for cells from a dotenv file
+        #[arg(long)]
+        env_file: Option<String>,
     },
 
     /// Sync .rs notebook to .ipynb format
@@ -57,9 +84,21 @@ enum Commands {
         /// Path to the notebook (.rs file)
         notebook: String,
 
+        /// Output path for the .ipynb file (default: next to the notebook)
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Create the output directory if it does not exist
+        #[arg(long, requires = "output")]
+        mkdir: bool,
+
         /// Watch for changes
         #[arg(short, long)]
         watch: bool,
+
+        /// Run the notebook on each change to keep outputs current
+        #[arg(long, requires = "watch")]
+        run: bool,
     },
 
     /// Build notebook as standalone binary
@@ -74,6 +113,18 @@ enum Commands {
         /// Build with optimizations
         #[arg(long)]
         release: bool,
+
+        /// Embed cached outputs from the last `venus run` (print with `--cached`)
+        #[arg(long)]
+        embed_outputs: bool,
+
+        /// Cross-compile for a target triple (uses LLVM)
+        #[arg(long)]
+        target: Option<String>,
+
+        /// Generate a library crate exposing the cells instead of a binary
+        #[arg(long, conflicts_with_all = ["embed_outputs", "target", "release"])]
+        lib: bool,
     },
 
     /// Create a new notebook from template
@@ -120,6 +171,47 @@ enum Commands {
         /// Clear screen before each run
         #[arg(long, default_value = "true")]
         clear: bool,
+
+        /// Load environment variables for cells from a dotenv file
+        #[arg(long)]
+        env_file: Option<String>,
+    },
+
+    /// Compile the universe and all cells without running them
+    Warm {
+        /// Path to the notebook (.rs file)
+        notebook: String,
+    },
+
+    /// Remove the notebook's build artifacts and cached outputs
+    Clean {
+        /// Path to the notebook (.rs file)
+        notebook: String,
+
+        /// Only remove artifacts of cells that no longer exist
+        #[arg(long)]
+        cache: bool,
+    },
+
+    /// Inspect or clear the notebook's output cache
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum CacheAction {
+    /// Show the number and total size of cached outputs
+    Info {
+        /// Path to the notebook (.rs file)
+        notebook: String,
+    },
+
+    /// Remove all cached outputs
+    Clear {
+        /// Path to the notebook (.rs file)
+        notebook: String,
     },
 }
 
@@ -135,9 +227,14 @@ async fn main() -> anyhow::Result<()> {
         tracing_subscriber::EnvFilter::from_default_env().add_directive(tracing::Level::WARN.into())
     };
 
-    tracing_subscriber::fmt()
-        .with_env_filter(filter)
-        .with_target(false)
+    // Recent records are also kept in memory for `GetLogs`
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_target(false)
+                .with_filter(filter),
+        )
+        .with(venus_server::logs::layer().with_filter(LevelFilter::INFO))
         .init();
 
     // Helper to format venus-core errors with recovery hints
@@ -154,22 +251,65 @@ async fn main() -> anyhow::Result<()> {
             notebook,
             cell,
             release,
-        } => run::execute(&notebook, cell.as_deref(), release).map_err(format_error)?,
+            env_file,
+        } => {
+            if let Some(path) = env_file {
+                apply_env_file(&path).map_err(format_error)?;
+            }
+            run::execute(&notebook, cell.as_deref(), release, cli.verbose).map_err(format_error)?
+        }
 
-        Commands::Serve { path, port } => {
-            serve::execute(&path, port).await.map_err(format_error)?;
+        Commands::Serve {
+            path,
+            port,
+            open,
+            no_open,
+            resource_usage,
+            env_file,
+        } => {
+            let open_browser = if open || no_open {
+                open
+            } else {
+                std::io::stdout().is_terminal()
+            };
+            let env = match env_file {
+                Some(path) => venus_core::env_file::load_env_file(&path)
+                    .map_err(|e| format_error(e.into()))?,
+                None => Vec::new(),
+            };
+            serve::execute(&path, port, open_browser, resource_usage, env)
+                .await
+                .map_err(format_error)?;
         }
 
-        Commands::Sync { notebook, watch } => {
-            sync::execute(&notebook, watch).map_err(format_error)?;
+        Commands::Sync {
+            notebook,
+            output,
+            mkdir,
+            watch,
+            run,
+        } => {
+            sync::execute(&notebook, output.as_deref(), mkdir, watch, run).map_err(format_error)?;
         }
 
         Commands::Build {
             notebook,
             output,
             release,
+            embed_outputs,
+            target,
+            lib,
         } => {
-            build::execute(&notebook, output.as_deref(), release).map_err(format_error)?;
+            build::execute(
+                &notebook,
+                output.as_deref(),
+                release,
+                embed_outputs,
+                target.as_deref(),
+                lib,
+                cli.verbose,
+            )
+            .map_err(format_error)?;
         }
 
         Commands::New { name, workspace } => {
@@ -190,16 +330,50 @@ async fn main() -> anyhow::Result<()> {
             cell,
             release,
             clear,
+            env_file,
         } => {
+            if let Some(path) = env_file {
+                apply_env_file(&path).map_err(format_error)?;
+            }
             watch::execute(&notebook, cell.as_deref(), release, clear)
                 .await
                 .map_err(format_error)?;
         }
+
+        Commands::Warm { notebook } => {
+            warm::execute(&notebook, cli.verbose).map_err(format_error)?;
+        }
+
+        Commands::Clean { notebook, cache } => {
+            clean::execute(&notebook, cache).map_err(format_error)?;
+        }
+
+        Commands::Cache { action } => match action {
+            CacheAction::Info { notebook } => {
+                cache::info(&notebook).map_err(format_error)?;
+            }
+            CacheAction::Clear { notebook } => {
+                cache::clear(&notebook).map_err(format_error)?;
+            }
+        },
     }
 
     Ok(())
 }
 
+/// Load a dotenv file into this process's environment.
+///
+/// `run` and `watch` execute cells in-process, so the variables must be set
+/// here rather than on a worker.
+fn apply_env_file(path: &str) -> anyhow::Result<()> {
+    for (key, value) in venus_core::env_file::load_env_file(path)? {
+        // SAFETY: called before the notebook is loaded or any cell runs, while
+        // no other thread is reading the environment.
+        unsafe { std::env::set_var(key, value) };
+    }
+    Ok(())
+}
+
 /// Create a new notebook from template.
 fn create_new_notebook(name: &str, workspace: bool) -> anyhow::Result<()> {
     use cargo_manager::{CargoManager, IntegrationMode};
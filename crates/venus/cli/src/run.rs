@@ -13,11 +13,12 @@ pub fn execute(
     notebook_path: &str,
     cell_filter: Option<&str>,
     release: bool,
+    verbose: bool,
 ) -> anyhow::Result<()> {
     let start = Instant::now();
 
     // Create executor (handles parsing, graph building, universe)
-    let executor = NotebookExecutor::new(notebook_path, release)?;
+    let executor = NotebookExecutor::new(notebook_path, release, verbose)?;
     executor.print_header("Running");
 
     // Handle empty notebooks
@@ -9,7 +9,16 @@ use venus_server::ServerConfig;
 use crate::colors;
 
 /// Start the interactive notebook server.
-pub async fn execute(notebook_path: &str, port: u16) -> anyhow::Result<()> {
+///
+/// With `resource_usage`, clients receive periodic CPU and memory reports
+/// for running cells. `env` is passed to the processes that run cells.
+pub async fn execute(
+    notebook_path: &str,
+    port: u16,
+    open_browser: bool,
+    resource_usage: bool,
+    env: Vec<(String, String)>,
+) -> anyhow::Result<()> {
     let path = Path::new(notebook_path);
     if !path.exists() {
         anyhow::bail!("Notebook not found: {}", notebook_path);
@@ -32,29 +41,35 @@ pub async fn execute(notebook_path: &str, port: u16) -> anyhow::Result<()> {
     let config = ServerConfig {
         host: "127.0.0.1".to_string(),
         port,
-        open_browser: false,
+        open_browser,
+        port_fallback: true,
+        resource_sample_interval: resource_usage
+            .then_some(venus_server::DEFAULT_RESOURCE_SAMPLE_INTERVAL),
+        env,
+        ..Default::default()
     };
 
-    println!(
-        "{}  ◆ Server:{} http://{}:{}",
-        colors::CYAN,
-        colors::RESET,
-        config.host,
-        config.port
-    );
-    println!(
-        "{}  ◆ WebSocket:{} ws://{}:{}/ws",
-        colors::CYAN,
-        colors::RESET,
-        config.host,
-        config.port
-    );
-    println!("{}", "─".repeat(50));
-    println!("{}Press Ctrl+C to stop{}", colors::GREEN, colors::RESET);
-    println!();
-
-    // Run server (handles Ctrl+C internally with graceful shutdown)
-    venus_server::serve(path, config).await?;
+    // Run server (handles Ctrl+C internally with graceful shutdown). The
+    // port can differ from the requested one, so the URLs are printed once
+    // the server is listening.
+    venus_server::serve_with(path, config, |addr| {
+        println!(
+            "{}  ◆ Server:{} http://{}",
+            colors::CYAN,
+            colors::RESET,
+            addr
+        );
+        println!(
+            "{}  ◆ WebSocket:{} ws://{}/ws",
+            colors::CYAN,
+            colors::RESET,
+            addr
+        );
+        println!("{}", "─".repeat(50));
+        println!("{}Press Ctrl+C to stop{}", colors::GREEN, colors::RESET);
+        println!();
+    })
+    .await?;
 
     // Clean up any remaining LSP processes
     venus_server::kill_all_lsp_processes().await;
@@ -1,23 +1,42 @@
 //! Sync command implementation for Venus CLI.
 //!
-//! Converts .rs notebooks to .ipynb format.
+//! Converts .rs notebooks to .ipynb format. With `--watch --run`, the
+//! notebook is also executed on each change so the `.ipynb` carries
+//! current outputs.
 
-use std::path::Path;
-use std::time::Instant;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
+use venus_core::paths::NotebookDirs;
 use venus_sync::{OutputCache, default_ipynb_path, sync_to_ipynb};
 
 use crate::colors;
+use crate::executor::NotebookExecutor;
+use crate::output::decoder::try_decode_value;
+
+/// How long the notebook must stay unchanged before a change is synced.
+const DEBOUNCE: Duration = Duration::from_millis(300);
 
 /// Execute the sync command.
-pub fn execute(notebook_path: &str, watch: bool) -> anyhow::Result<()> {
+///
+/// The `.ipynb` is written to `output_path`, or next to the notebook if not
+/// given. Its directory is created if `mkdir` is set. If `run` is set (only
+/// valid with `watch`), cells are executed before each sync and their
+/// outputs stored in the output cache.
+pub fn execute(
+    notebook_path: &str,
+    output_path: Option<&str>,
+    mkdir: bool,
+    watch: bool,
+    run: bool,
+) -> anyhow::Result<()> {
     let path = Path::new(notebook_path);
     if !path.exists() {
         anyhow::bail!("Notebook not found: {}", notebook_path);
     }
 
     let abs_path = path.canonicalize()?;
-    let ipynb_path = default_ipynb_path(&abs_path);
+    let ipynb_path = resolve_output_path(&abs_path, output_path, mkdir)?;
 
     println!(
         "\n{}Venus Sync{} - Converting to Jupyter format",
@@ -27,9 +46,13 @@ pub fn execute(notebook_path: &str, watch: bool) -> anyhow::Result<()> {
     println!("{}", "─".repeat(50));
 
     // Set up output cache
-    let notebook_dir = abs_path.parent().unwrap_or(Path::new("."));
-    let cache_dir = notebook_dir.join(".venus").join("outputs");
-    let cache = OutputCache::new(&cache_dir).ok();
+    let cache_dir = NotebookDirs::from_notebook_path(&abs_path)?
+        .venus_dir
+        .join("outputs");
+    let mut cache = OutputCache::new(&cache_dir).ok();
+    if run && cache.is_none() {
+        anyhow::bail!("Failed to create output cache at {}", cache_dir.display());
+    }
 
     if watch {
         println!(
@@ -41,11 +64,14 @@ pub fn execute(notebook_path: &str, watch: bool) -> anyhow::Result<()> {
         println!("Press Ctrl+C to stop.\n");
 
         // Initial sync
+        if run && let Some(cache) = cache.as_mut() {
+            run_into_cache(&abs_path, cache);
+        }
         sync_file(&abs_path, &ipynb_path, cache.as_ref())?;
 
         // Watch for changes using simple polling
         // TODO: Use notify crate for proper file watching
-        watch_and_sync(&abs_path, &ipynb_path, cache.as_ref())?;
+        watch_and_sync(&abs_path, &ipynb_path, cache.as_mut(), run)?;
     } else {
         sync_file(&abs_path, &ipynb_path, cache.as_ref())?;
     }
@@ -53,6 +79,36 @@ pub fn execute(notebook_path: &str, watch: bool) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Determine where the `.ipynb` is written.
+///
+/// Fails if the directory of `output_path` does not exist, unless `mkdir` is
+/// set, in which case it is created.
+fn resolve_output_path(
+    rs_path: &Path,
+    output_path: Option<&str>,
+    mkdir: bool,
+) -> anyhow::Result<PathBuf> {
+    let Some(output_path) = output_path else {
+        return Ok(default_ipynb_path(rs_path));
+    };
+
+    let ipynb_path = PathBuf::from(output_path);
+    if let Some(parent) = ipynb_path.parent()
+        && !parent.as_os_str().is_empty()
+        && !parent.is_dir()
+    {
+        if !mkdir {
+            anyhow::bail!(
+                "Output directory does not exist: {} (pass --mkdir to create it)",
+                parent.display()
+            );
+        }
+        std::fs::create_dir_all(parent)?;
+    }
+
+    Ok(ipynb_path)
+}
+
 /// Sync a single file.
 fn sync_file(rs_path: &Path, ipynb_path: &Path, cache: Option<&OutputCache>) -> anyhow::Result<()> {
     let start = Instant::now();
@@ -65,6 +121,10 @@ fn sync_file(rs_path: &Path, ipynb_path: &Path, cache: Option<&OutputCache>) ->
     std::io::Write::flush(&mut std::io::stdout()).ok();
 
     sync_to_ipynb(rs_path, ipynb_path, cache)?;
+    if let Some(cache) = cache {
+        // Outputs embedded in the notebook count as used for eviction
+        cache.save_access_times()?;
+    }
 
     let elapsed = start.elapsed();
     println!(
@@ -77,30 +137,126 @@ fn sync_file(rs_path: &Path, ipynb_path: &Path, cache: Option<&OutputCache>) ->
     Ok(())
 }
 
+/// Execute the notebook and store each cell's output in the cache.
+///
+/// Failures are reported and recorded as error outputs; cells that did not
+/// run keep their previous output.
+fn run_into_cache(rs_path: &Path, cache: &mut OutputCache) {
+    let executor = match NotebookExecutor::new(&rs_path.to_string_lossy(), false, false) {
+        Ok(executor) => executor,
+        Err(e) => {
+            eprintln!("  {}Error:{} {}", colors::RED, colors::RESET, e);
+            return;
+        }
+    };
+
+    let compilation = match executor.compile() {
+        Ok(compilation) => compilation,
+        Err(e) => {
+            eprintln!("  {}Error:{} {}", colors::RED, colors::RESET, e);
+            return;
+        }
+    };
+
+    for (name, errors) in &compilation.errors {
+        let message = errors
+            .iter()
+            .map(|e| e.rendered.clone().unwrap_or_else(|| e.format_terminal()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        cache.store_error(name, &message);
+    }
+
+    if compilation.errors.is_empty() {
+        match executor.execute_silent(&compilation, None) {
+            Ok(execution) => {
+                for &cell_id in &execution.executed_cells {
+                    if let Some(cell) = executor.cell_by_id(cell_id)
+                        && let Some(output) = execution.outputs.get(&cell_id)
+                    {
+                        let text = output
+                            .display_text()
+                            .map(|s| s.to_string())
+                            .or_else(|| try_decode_value(&cell.return_type, output.bytes()))
+                            .unwrap_or_else(|| format!("<{}>", cell.return_type));
+                        cache.store_text(&cell.name, &text);
+                    }
+                }
+            }
+            Err(e) => eprintln!("  {}Error:{} {}", colors::RED, colors::RESET, e),
+        }
+    }
+
+    if let Err(e) = cache.save_to_disk() {
+        tracing::warn!("Failed to save output cache: {}", e);
+    }
+}
+
 /// Watch a file and sync on changes.
+///
+/// A change is synced once the file has been quiet for [`DEBOUNCE`], so a
+/// burst of saves results in a single sync (and run).
 fn watch_and_sync(
     rs_path: &Path,
     ipynb_path: &Path,
-    cache: Option<&OutputCache>,
+    mut cache: Option<&mut OutputCache>,
+    run: bool,
 ) -> anyhow::Result<()> {
     use std::fs;
     use std::thread;
-    use std::time::Duration;
 
     let mut last_modified = fs::metadata(rs_path)?.modified()?;
+    let mut pending_since: Option<Instant> = None;
 
     loop {
-        thread::sleep(Duration::from_millis(500));
+        thread::sleep(Duration::from_millis(100));
 
         let current_modified = fs::metadata(rs_path)?.modified()?;
 
         if current_modified != last_modified {
             last_modified = current_modified;
-            println!("\nFile changed, syncing...");
+            pending_since = Some(Instant::now());
+            continue;
+        }
+
+        if pending_since.is_some_and(|since| since.elapsed() >= DEBOUNCE) {
+            pending_since = None;
+
+            if run && let Some(cache) = cache.as_deref_mut() {
+                println!("\nFile changed, running...");
+                run_into_cache(rs_path, cache);
+            } else {
+                println!("\nFile changed, syncing...");
+            }
 
-            if let Err(e) = sync_file(rs_path, ipynb_path, cache) {
+            if let Err(e) = sync_file(rs_path, ipynb_path, cache.as_deref()) {
                 eprintln!("  Error: {}", e);
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_output_path() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let rs_path = temp.path().join("notebook.rs");
+
+        assert_eq!(
+            resolve_output_path(&rs_path, None, false).unwrap(),
+            temp.path().join("notebook.ipynb")
+        );
+
+        let nested = temp.path().join("exports").join("notebook.ipynb");
+        let nested_str = nested.to_str().unwrap();
+        assert!(resolve_output_path(&rs_path, Some(nested_str), false).is_err());
+        assert_eq!(
+            resolve_output_path(&rs_path, Some(nested_str), true).unwrap(),
+            nested
+        );
+        assert!(temp.path().join("exports").is_dir());
+    }
+}
@@ -0,0 +1,118 @@
+//! Warm command implementation for Venus CLI.
+//!
+//! Builds the universe and compiles every cell without executing anything,
+//! so the next `run` or `serve` starts from a populated compile cache.
+
+use std::time::Instant;
+
+use venus_core::salsa_db::cache::CachePersistence;
+use venus_core::salsa_db::{CompilationStatus, VenusDatabase};
+
+use crate::colors;
+use crate::executor::NotebookExecutor;
+
+/// File name of the compile cache snapshot inside `.venus/cache`.
+const SNAPSHOT_FILE: &str = "salsa.bin";
+
+/// Execute the warm command.
+pub fn execute(notebook_path: &str, verbose: bool) -> anyhow::Result<()> {
+    let start = Instant::now();
+
+    // Create executor (handles parsing, graph building, universe)
+    let executor = NotebookExecutor::new(notebook_path, false, verbose)?;
+    executor.print_header("Warming");
+
+    if executor.cells.is_empty() {
+        println!(
+            "\n{}No cells found in notebook.{}",
+            colors::YELLOW,
+            colors::RESET
+        );
+        return Ok(());
+    }
+
+    println!("\n{}Compiling cells...{}", colors::BOLD, colors::RESET);
+
+    let db = VenusDatabase::new();
+    let source = db.set_source(executor.notebook_path.clone(), executor.source.clone());
+    let settings = db.create_compiler_settings(
+        executor.config.build_dir.clone(),
+        executor.config.cache_dir.clone(),
+        Some(executor.universe_path.clone()),
+        Some(executor.universe_builder.deps_hash()),
+        executor.config.use_cranelift,
+        executor.config.opt_level,
+    );
+
+    let names = db.get_cell_names(source);
+    let order = db.get_execution_order(source);
+    let results = db.compile_all(source, settings);
+
+    let mut entries = Vec::with_capacity(results.len());
+    let mut failed = 0;
+    for (&idx, status) in order.iter().zip(results.iter()) {
+        let name = names[idx].clone();
+        let source_hash = match status {
+            CompilationStatus::Success(data) => {
+                println!(
+                    "  {} {} ... {}✓{} ({}ms)",
+                    colors::DIM,
+                    name,
+                    colors::GREEN,
+                    colors::RESET,
+                    data.compile_time_ms
+                );
+                data.source_hash
+            }
+            CompilationStatus::Cached(data) => {
+                println!(
+                    "  {} {} ... {}✓{} {}(cached){}",
+                    colors::DIM,
+                    name,
+                    colors::GREEN,
+                    colors::RESET,
+                    colors::DIM,
+                    colors::RESET
+                );
+                data.source_hash
+            }
+            CompilationStatus::Failed(error) => {
+                println!(
+                    "  {} {} ... {}✗{}",
+                    colors::DIM,
+                    name,
+                    colors::RED,
+                    colors::RESET
+                );
+                eprintln!("{}    Error:{} {}", colors::RED, colors::RESET, error);
+                failed += 1;
+                0
+            }
+        };
+        entries.push((name, source_hash, status.clone()));
+    }
+
+    let compiled = entries.len() - failed;
+    let snapshot = db.create_cache_snapshot(
+        executor.toolchain.version().to_string(),
+        executor.universe_builder.deps_hash(),
+        entries,
+    );
+    CachePersistence::save(&executor.dirs.cache_dir.join(SNAPSHOT_FILE), &snapshot)?;
+
+    println!("\n{}", "─".repeat(50));
+    println!(
+        "{}Cached{} {} of {} cells in {:.2}s",
+        colors::GREEN,
+        colors::RESET,
+        compiled,
+        order.len(),
+        start.elapsed().as_secs_f64()
+    );
+
+    if failed > 0 {
+        anyhow::bail!("{} cell(s) failed to compile", failed);
+    }
+
+    Ok(())
+}
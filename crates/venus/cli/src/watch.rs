@@ -116,7 +116,7 @@ fn run_notebook(abs_path: &Path, cell_filter: Option<&str>, release: bool) -> an
     let start = Instant::now();
 
     // Create executor
-    let executor = NotebookExecutor::new(abs_path.to_str().unwrap(), release)?;
+    let executor = NotebookExecutor::new(abs_path.to_str().unwrap(), release, false)?;
     executor.print_header("Running");
 
     // Compile cells
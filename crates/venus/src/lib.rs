@@ -51,13 +51,20 @@
 //!
 //! The parameter name must match the producing cell's function name.
 
-pub use venus_macros::cell;
+// Lets `#[derive(Render)]` output, which names `::venus`, work inside this crate
+extern crate self as venus;
+
+pub use venus_macros::{Render, cell};
 
 pub mod render;
 
 // Re-export widgets from venus-core (moved there to break circular dependency)
 pub use venus_core::widgets;
 
+// Panic reporting used by the generated cell wrappers
+#[doc(hidden)]
+pub use venus_core::panic_report;
+
 pub mod prelude {
     //! Common imports for Venus notebooks.
     //!
@@ -67,6 +74,7 @@ pub mod prelude {
 
     pub use crate::cell;
     pub use crate::render::Render;
+    pub use venus_macros::Render;
 
     // Re-export rkyv derives for user structs (all cell return types need serialization)
     pub use rkyv::{Archive, Deserialize as RkyvDeserialize, Serialize as RkyvSerialize};
@@ -88,3 +96,29 @@ pub use widgets::{
     input_slider_labeled, input_slider_with_step, input_text, input_text_labeled,
     input_text_with_default,
 };
+
+#[cfg(test)]
+mod tests {
+    use crate::cell;
+
+    /// Base value
+    #[cell]
+    pub fn base() -> i32 {
+        2
+    }
+
+    pub struct Stats;
+
+    impl Stats {
+        /// Triples the base value
+        #[cell]
+        pub fn tripled(base: &i32) -> i32 {
+            *base * 3
+        }
+    }
+
+    #[test]
+    fn test_cell_macro_expands_free_and_impl_cells() {
+        assert_eq!(Stats::tripled(&base()), 6);
+    }
+}
@@ -77,8 +77,29 @@ pub trait Render {
     fn render_data(&self) -> Option<serde_json::Value> {
         None
     }
+
+    /// Column names when a collection of this type is shown as a table.
+    ///
+    /// Return `None` (the default) to render collections as a list instead.
+    /// `#[derive(Render)]` returns the struct's field labels.
+    fn render_header() -> Option<Vec<String>>
+    where
+        Self: Sized,
+    {
+        None
+    }
+
+    /// Cells of this value's table row, matching [`Render::render_header`].
+    ///
+    /// Cells are HTML; escape any text they contain.
+    fn render_row(&self) -> Option<Vec<String>> {
+        None
+    }
 }
 
+/// Maximum number of items rendered for a collection.
+pub const MAX_RENDERED_ITEMS: usize = 100;
+
 // Blanket implementations for common types
 
 impl Render for String {
@@ -123,23 +144,101 @@ impl Render for bool {
     }
 }
 
+/// Render each item as text, one per line.
+fn render_items_text<T: Render + std::fmt::Debug>(items: &[T]) -> String {
+    let mut lines: Vec<String> = items
+        .iter()
+        .take(MAX_RENDERED_ITEMS)
+        .map(|item| item.render_text())
+        .collect();
+    if items.len() > MAX_RENDERED_ITEMS {
+        lines.push(format!("... {} more", items.len() - MAX_RENDERED_ITEMS));
+    }
+    lines.join("\n")
+}
+
+/// Render items as an HTML table if the item type has a header, otherwise
+/// as a list using each item's HTML when it has one.
+fn render_items_html<T: Render + std::fmt::Debug>(items: &[T]) -> String {
+    let shown = &items[..items.len().min(MAX_RENDERED_ITEMS)];
+    let remaining = items.len() - shown.len();
+
+    if let Some(header) = T::render_header() {
+        let mut html = String::from("<table class=\"venus-dataframe\">\n<thead><tr>");
+        for name in &header {
+            html.push_str(&format!("<th>{}</th>", html_escape(name)));
+        }
+        html.push_str("</tr></thead>\n<tbody>\n");
+        for item in shown {
+            html.push_str("<tr>");
+            for cell in item.render_row().unwrap_or_default() {
+                html.push_str(&format!("<td>{}</td>", cell));
+            }
+            html.push_str("</tr>\n");
+        }
+        html.push_str("</tbody>\n");
+        if remaining > 0 {
+            html.push_str(&format!(
+                "<tfoot><tr><td colspan=\"{}\">... {} more rows</td></tr></tfoot>\n",
+                header.len(),
+                remaining
+            ));
+        }
+        html.push_str("</table>");
+        return html;
+    }
+
+    let mut list_items: String = shown
+        .iter()
+        .map(|item| {
+            let content = item
+                .render_html()
+                .unwrap_or_else(|| html_escape(&item.render_text()));
+            format!("<li>{}</li>", content)
+        })
+        .collect();
+    if remaining > 0 {
+        list_items.push_str(&format!("<li>... {} more</li>", remaining));
+    }
+    format!("<ul>{}</ul>", list_items)
+}
+
+impl<T: Render> Render for [T]
+where
+    T: std::fmt::Debug,
+{
+    fn render_text(&self) -> String {
+        render_items_text(self)
+    }
+
+    fn render_html(&self) -> Option<String> {
+        Some(render_items_html(self))
+    }
+}
+
+impl<T: Render, const N: usize> Render for [T; N]
+where
+    T: std::fmt::Debug,
+{
+    fn render_text(&self) -> String {
+        render_items_text(self)
+    }
+
+    fn render_html(&self) -> Option<String> {
+        Some(render_items_html(self))
+    }
+}
+
 impl<T: Render> Render for Vec<T>
 where
     T: std::fmt::Debug,
 {
     fn render_text(&self) -> String {
-        format!("{:?}", self)
+        render_items_text(self)
     }
 
     fn render_html(&self) -> Option<String> {
-        let items: Vec<String> = self.iter().map(|item| item.render_text()).collect();
-        Some(format!(
-            "<ul>{}</ul>",
-            items
-                .iter()
-                .map(|s| format!("<li>{}</li>", s))
-                .collect::<String>()
-        ))
+        Some(render_items_html(self))
     }
 }
 
@@ -184,6 +283,106 @@ impl Render for serde_json::Value {
     }
 }
 
+/// Rich HTML for any value, used by generated cell wrappers.
+///
+/// `(&HtmlProbe(&value)).probe_html()` resolves to [`Render::render_html`]
+/// when the value's type implements [`Render`], and to `None` otherwise.
+#[doc(hidden)]
+pub struct HtmlProbe<'a, T>(pub &'a T);
+
+#[doc(hidden)]
+pub trait RenderHtmlProbe {
+    fn probe_html(&self) -> Option<String>;
+}
+
+impl<T: Render> RenderHtmlProbe for HtmlProbe<'_, T> {
+    fn probe_html(&self) -> Option<String> {
+        self.0.render_html()
+    }
+}
+
+#[doc(hidden)]
+pub trait NoHtmlProbe {
+    fn probe_html(&self) -> Option<String>;
+}
+
+impl<T> NoHtmlProbe for &HtmlProbe<'_, T> {
+    fn probe_html(&self) -> Option<String> {
+        None
+    }
+}
+
+/// Plain text for any value, used by generated cell wrappers for cells with
+/// a text or CSV output hint.
+///
+/// `(&TextProbe(&value)).probe_text()` resolves to [`Render::render_text`]
+/// when the value's type implements [`Render`], and to `None` otherwise.
+#[doc(hidden)]
+pub struct TextProbe<'a, T>(pub &'a T);
+
+#[doc(hidden)]
+pub trait RenderTextProbe {
+    fn probe_text(&self) -> Option<String>;
+}
+
+impl<T: Render + std::fmt::Debug> RenderTextProbe for TextProbe<'_, T> {
+    fn probe_text(&self) -> Option<String> {
+        Some(self.0.render_text())
+    }
+}
+
+#[doc(hidden)]
+pub trait NoTextProbe {
+    fn probe_text(&self) -> Option<String>;
+}
+
+impl<T> NoTextProbe for &TextProbe<'_, T> {
+    fn probe_text(&self) -> Option<String> {
+        None
+    }
+}
+
+/// Structured data for any value, used by generated cell wrappers for cells
+/// with a JSON output hint.
+///
+/// `(&DataProbe(&value)).probe_data()` resolves to [`Render::render_data`]
+/// when the value's type implements [`Render`], and to `None` otherwise.
+#[doc(hidden)]
+pub struct DataProbe<'a, T>(pub &'a T);
+
+#[doc(hidden)]
+pub trait RenderDataProbe {
+    fn probe_data(&self) -> Option<serde_json::Value>;
+}
+
+impl<T: Render> RenderDataProbe for DataProbe<'_, T> {
+    fn probe_data(&self) -> Option<serde_json::Value> {
+        self.0.render_data()
+    }
+}
+
+#[doc(hidden)]
+pub trait NoDataProbe {
+    fn probe_data(&self) -> Option<serde_json::Value>;
+}
+
+impl<T> NoDataProbe for &DataProbe<'_, T> {
+    fn probe_data(&self) -> Option<serde_json::Value> {
+        None
+    }
+}
+
+/// Escape text for inclusion in HTML.
+///
+/// Public for code generated by `#[derive(Render)]`.
+#[doc(hidden)]
+pub fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
 /// Convenience wrapper for types that implement Serialize.
 ///
 /// Wrapping a value in `Json` will render it as formatted JSON.
@@ -214,14 +413,7 @@ impl<T: Serialize + std::fmt::Debug> Render for Json<T> {
 /// Render implementation for polars DataFrame.
 #[cfg(feature = "polars")]
 mod polars_impl {
-    use super::{ImageOutput, Render};
-
-    fn html_escape(s: &str) -> String {
-        s.replace('&', "&amp;")
-            .replace('<', "&lt;")
-            .replace('>', "&gt;")
-            .replace('"', "&quot;")
-    }
+    use super::{ImageOutput, Render, html_escape};
 
     impl Render for polars::frame::DataFrame {
         fn render_text(&self) -> String {
@@ -387,8 +579,122 @@ mod tests {
     #[test]
     fn test_vec_render() {
         let v = vec![1, 2, 3];
-        assert_eq!(v.render_text(), "[1, 2, 3]");
-        assert!(v.render_html().unwrap().contains("<ul>"));
+        assert_eq!(v.render_text(), "1\n2\n3");
+        assert_eq!(
+            v.render_html().unwrap(),
+            "<ul><li>1</li><li>2</li><li>3</li></ul>"
+        );
+    }
+
+    #[test]
+    fn test_collection_render_uses_item_html() {
+        let items = [Json(serde_json::json!(1)), Json(serde_json::json!(2))];
+        let html = items.render_html().unwrap();
+        assert_eq!(html.matches("<li><pre>").count(), 2);
+
+        let words = ["a<b".to_string(), "c".to_string()];
+        assert_eq!(words[..].render_text(), "a<b\nc");
+        assert_eq!(
+            words[..].render_html().unwrap(),
+            "<ul><li>a&lt;b</li><li>c</li></ul>"
+        );
+    }
+
+    #[derive(Debug, crate::Render)]
+    struct Summary {
+        #[render(label = "Row count")]
+        rows: usize,
+        mean: f64,
+        #[render(skip)]
+        #[allow(dead_code)]
+        raw: Vec<f64>,
+    }
+
+    #[derive(Debug, crate::Render)]
+    struct Pair(i32, &'static str);
+
+    #[test]
+    fn test_derive_render() {
+        let summary = Summary {
+            rows: 3,
+            mean: 2.5,
+            raw: vec![1.0, 2.5, 4.0],
+        };
+        assert_eq!(summary.render_text(), "Row count: 3\nmean: 2.5");
+        let html = summary.render_html().unwrap();
+        assert!(html.contains("<tr><th>Row count</th><td>3</td></tr>"));
+        assert!(!html.contains("raw"));
+
+        let pair = Pair(1, "<a>");
+        assert_eq!(pair.render_text(), "0: 1\n1: \"<a>\"");
+        assert!(
+            pair.render_html()
+                .unwrap()
+                .contains("&quot;&lt;a&gt;&quot;")
+        );
+    }
+
+    #[test]
+    fn test_struct_vec_renders_as_table() {
+        let rows = vec![
+            Summary {
+                rows: 1,
+                mean: 0.5,
+                raw: vec![],
+            },
+            Summary {
+                rows: 2,
+                mean: 1.5,
+                raw: vec![],
+            },
+        ];
+        let html = rows.render_html().unwrap();
+        assert!(html.contains("<th>Row count</th><th>mean</th>"));
+        assert!(html.contains("<tr><td>2</td><td>1.5</td></tr>"));
+        assert!(!html.contains("<ul>"));
+    }
+
+    #[test]
+    fn test_collection_render_is_truncated() {
+        let v: Vec<i32> = (0..150).collect();
+        assert!(v.render_text().ends_with("99\n... 50 more"));
+        assert!(
+            v.render_html()
+                .unwrap()
+                .ends_with("<li>... 50 more</li></ul>")
+        );
+    }
+
+    #[test]
+    // The explicit borrow is what selects between the two probe traits
+    #[allow(clippy::needless_borrow)]
+    fn test_html_probe() {
+        use super::{NoHtmlProbe as _, RenderHtmlProbe as _};
+
+        struct Opaque;
+        assert!((&HtmlProbe(&vec![1])).probe_html().is_some());
+        assert!((&HtmlProbe(&1)).probe_html().is_none());
+        assert!((&HtmlProbe(&Opaque)).probe_html().is_none());
+    }
+
+    #[test]
+    #[allow(clippy::needless_borrow)]
+    fn test_text_and_data_probes() {
+        use super::{
+            NoDataProbe as _, NoTextProbe as _, RenderDataProbe as _, RenderTextProbe as _,
+        };
+
+        struct Opaque;
+        let csv = "a,b\n1,2".to_string();
+        assert_eq!((&TextProbe(&csv)).probe_text().as_deref(), Some("a,b\n1,2"));
+        assert!((&TextProbe(&Opaque)).probe_text().is_none());
+
+        let data = Json(serde_json::json!({"key": 1}));
+        assert_eq!(
+            (&DataProbe(&data)).probe_data(),
+            Some(serde_json::json!({"key": 1}))
+        );
+        assert!((&DataProbe(&Opaque)).probe_data().is_none());
     }
 
     #[test]
@@ -166,7 +166,7 @@ macro_rules! impl_call_cell_n_deps {
             symbol_name: &str,
             inputs: &[Vec<u8>],
             widget_values_json: &[u8],
-        ) -> Result<(Vec<u8>, Vec<u8>), String> {
+        ) -> Result<(Vec<u8>, Vec<u8>), super::CellFailure> {
             let func: Symbol<$fn_type> = unsafe { cell.library.get(symbol_name.as_bytes()) }
                 .map_err(|e| format!("Failed to get symbol: {}", e))?;
 
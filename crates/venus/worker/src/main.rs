@@ -6,10 +6,14 @@
 use std::io::{BufReader, BufWriter, stdin, stdout};
 use std::panic::{self, AssertUnwindSafe};
 use std::path::PathBuf;
+use std::time::Instant;
 
 use libloading::{Library, Symbol};
 
-use venus_core::ipc::{WorkerCommand, WorkerResponse, read_message, write_message};
+use venus_core::ipc::{
+    OUTPUT_CHUNK_SIZE, WorkerCommand, WorkerResponse, read_message, write_message,
+};
+use venus_core::panic_report::{self, PanicReport};
 
 mod ffi;
 
@@ -30,6 +34,20 @@ struct LoadedCell {
     name: String,
 }
 
+/// Why calling a cell produced no output.
+enum CellFailure {
+    /// The call failed with an error message.
+    Failed(String),
+    /// The cell panicked.
+    Panicked(PanicReport),
+}
+
+impl From<String> for CellFailure {
+    fn from(message: String) -> Self {
+        Self::Failed(message)
+    }
+}
+
 fn main() {
     // Initialize tracing for debugging (stderr so it doesn't interfere with IPC)
     tracing_subscriber::fmt()
@@ -42,6 +60,9 @@ fn main() {
 
     tracing::info!("Venus worker starting (pid={})", std::process::id());
 
+    // Record backtraces of panics in the worker itself; cells record their own
+    panic_report::install_hook();
+
     let stdin = stdin();
     let stdout = stdout();
     let mut reader = BufReader::new(stdin.lock());
@@ -109,8 +130,13 @@ fn main() {
             },
         };
 
-        // Send response
-        if let Err(e) = write_message(&mut writer, &response) {
+        // Send response, splitting large outputs into segments the host can
+        // show as they arrive
+        let sent = response
+            .into_chunks(OUTPUT_CHUNK_SIZE)
+            .iter()
+            .try_for_each(|part| write_message(&mut writer, part));
+        if let Err(e) = sent {
             tracing::error!("Failed to send response: {}", e);
             break;
         }
@@ -164,25 +190,41 @@ fn execute_cell(
 
     // Catch panics during execution
     // Note: Widget context is now set up inside the cell itself (via FFI parameter)
+    let start = Instant::now();
     let result = panic::catch_unwind(AssertUnwindSafe(|| {
         call_cell_ffi(cell, &inputs, &widget_values_json)
     }));
+    let exec_us = start.elapsed().as_micros() as u64;
 
     match result {
         Ok(Ok((output_bytes, widgets_json))) => WorkerResponse::Output {
             bytes: output_bytes,
             widgets_json,
+            exec_us,
+        },
+        Ok(Err(CellFailure::Failed(message))) => WorkerResponse::Error { message },
+        Ok(Err(CellFailure::Panicked(report))) => WorkerResponse::Panic {
+            message: report.message,
+            backtrace: report.backtrace,
         },
-        Ok(Err(e)) => WorkerResponse::Error { message: e },
         Err(panic_info) => {
-            let message = if let Some(s) = panic_info.downcast_ref::<&str>() {
-                s.to_string()
-            } else if let Some(s) = panic_info.downcast_ref::<String>() {
-                s.clone()
-            } else {
-                "Unknown panic".to_string()
-            };
-            WorkerResponse::Panic { message }
+            let report = panic_report::take_report().unwrap_or_else(|| {
+                let message = if let Some(s) = panic_info.downcast_ref::<&str>() {
+                    s.to_string()
+                } else if let Some(s) = panic_info.downcast_ref::<String>() {
+                    s.clone()
+                } else {
+                    "Unknown panic".to_string()
+                };
+                PanicReport {
+                    message,
+                    backtrace: None,
+                }
+            });
+            WorkerResponse::Panic {
+                message: report.message,
+                backtrace: report.backtrace,
+            }
         }
     }
 }
@@ -192,7 +234,7 @@ fn call_cell_ffi(
     cell: &LoadedCell,
     inputs: &[Vec<u8>],
     widget_values_json: &[u8],
-) -> Result<(Vec<u8>, Vec<u8>), String> {
+) -> Result<(Vec<u8>, Vec<u8>), CellFailure> {
     let symbol_name = format!("{}\0", cell.entry_symbol);
 
     // For cells with no dependencies
@@ -209,7 +251,7 @@ fn call_cell_no_deps(
     cell: &LoadedCell,
     symbol_name: &str,
     widget_values_json: &[u8],
-) -> Result<(Vec<u8>, Vec<u8>), String> {
+) -> Result<(Vec<u8>, Vec<u8>), CellFailure> {
     let func: Symbol<EntryFn0> = unsafe { cell.library.get(symbol_name.as_bytes()) }
         .map_err(|e| format!("Failed to get symbol: {}", e))?;
 
@@ -234,7 +276,7 @@ fn call_cell_with_deps(
     symbol_name: &str,
     inputs: &[Vec<u8>],
     widget_values_json: &[u8],
-) -> Result<(Vec<u8>, Vec<u8>), String> {
+) -> Result<(Vec<u8>, Vec<u8>), CellFailure> {
     // Dynamic dispatch based on dependency count
     match inputs.len() {
         1 => ffi::call_cell_1_deps(cell, symbol_name, inputs, widget_values_json),
@@ -245,10 +287,7 @@ fn call_cell_with_deps(
         6 => ffi::call_cell_6_deps(cell, symbol_name, inputs, widget_values_json),
         7 => ffi::call_cell_7_deps(cell, symbol_name, inputs, widget_values_json),
         8 => ffi::call_cell_8_deps(cell, symbol_name, inputs, widget_values_json),
-        n => Err(format!(
-            "Cells with {} dependencies not yet supported (max 8)",
-            n
-        )),
+        n => Err(format!("Cells with {} dependencies not yet supported (max 8)", n).into()),
     }
 }
 
@@ -265,13 +304,13 @@ fn process_ffi_result(
     out_ptr: *mut u8,
     out_len: usize,
     cell_name: &str,
-) -> Result<(Vec<u8>, Vec<u8>), String> {
+) -> Result<(Vec<u8>, Vec<u8>), CellFailure> {
     let result = ExecutionResult::from(result_code);
 
     match result {
         ExecutionResult::Success => {
             if out_ptr.is_null() || out_len == 0 {
-                return Err(format!("Cell {} returned null output", cell_name));
+                return Err(format!("Cell {} returned null output", cell_name).into());
             }
 
             // Take ownership of the output bytes
@@ -290,7 +329,8 @@ fn process_ffi_result(
                     "Cell {} output too short: {} bytes",
                     cell_name,
                     raw_bytes.len()
-                ));
+                )
+                .into());
             }
 
             // Read display_len
@@ -301,10 +341,7 @@ fn process_ffi_result(
             let display_end = 8 + display_len;
 
             if raw_bytes.len() < display_end + 8 {
-                return Err(format!(
-                    "Cell {} output too short for display data",
-                    cell_name
-                ));
+                return Err(format!("Cell {} output too short for display data", cell_name).into());
             }
 
             // Read widgets_len
@@ -318,10 +355,7 @@ fn process_ffi_result(
             let widgets_end = widgets_start + widgets_len;
 
             if raw_bytes.len() < widgets_end {
-                return Err(format!(
-                    "Cell {} output too short for widgets data",
-                    cell_name
-                ));
+                return Err(format!("Cell {} output too short for widgets data", cell_name).into());
             }
 
             // Extract widgets_json
@@ -338,12 +372,28 @@ fn process_ffi_result(
             Ok((bytes, widgets_json))
         }
         ExecutionResult::DeserializationError => {
-            Err(format!("Cell {} failed to deserialize input", cell_name))
+            Err(format!("Cell {} failed to deserialize input", cell_name).into())
         }
-        ExecutionResult::CellError => Err(format!("Cell {} returned an error", cell_name)),
+        ExecutionResult::CellError => Err(format!("Cell {} returned an error", cell_name).into()),
         ExecutionResult::SerializationError => {
-            Err(format!("Cell {} failed to serialize output", cell_name))
+            Err(format!("Cell {} failed to serialize output", cell_name).into())
+        }
+        ExecutionResult::Panic => {
+            // The cell hands over its panic report, if it has one
+            let report = (!out_ptr.is_null() && out_len > 0)
+                .then(|| unsafe {
+                    let slice = std::slice::from_raw_parts(out_ptr, out_len);
+                    let report = PanicReport::from_bytes(slice);
+                    libc::free(out_ptr as *mut libc::c_void);
+                    report
+                })
+                .flatten();
+            Err(CellFailure::Panicked(report.unwrap_or_else(|| {
+                PanicReport {
+                    message: format!("Cell {} panicked during execution", cell_name),
+                    backtrace: None,
+                }
+            })))
         }
-        ExecutionResult::Panic => Err(format!("Cell {} panicked during execution", cell_name)),
     }
 }